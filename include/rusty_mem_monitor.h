@@ -0,0 +1,41 @@
+/* C interface to the rusty_mem_monitor sampler, see src/ffi.rs.
+ * Link against the cdylib built by `cargo build --release --lib --no-default-features`. */
+#ifndef RUSTY_MEM_MONITOR_H
+#define RUSTY_MEM_MONITOR_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* All sizes in bytes */
+typedef struct {
+    uint64_t timestamp_ms;
+    uint64_t total_memory;
+    uint64_t used_memory;
+    uint64_t used_memory_min;
+    uint64_t used_memory_max;
+    uint64_t total_swap;
+    uint64_t used_swap;
+    uint64_t free_memory;
+    uint64_t buffers_memory;
+    uint64_t compressed_memory;
+    bool cgroup_limited;
+} RmmSample;
+
+typedef struct RmmMonitor RmmMonitor;
+
+RmmMonitor *rmm_new(size_t capacity);
+void rmm_free(RmmMonitor *monitor);
+bool rmm_sample(RmmMonitor *monitor, RmmSample *out);
+size_t rmm_history_len(const RmmMonitor *monitor);
+size_t rmm_history_range(const RmmMonitor *monitor, size_t start, RmmSample *out, size_t max);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
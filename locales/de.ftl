@@ -0,0 +1,364 @@
+# Deutsch
+
+title = SPEICHERMONITOR
+dashboard-title = SPEICHERMONITOR [{ $hosts } HOSTS]
+badge-offline = OFFLINE
+badge-linked = VERBUNDEN
+badge-paused = PAUSIERT
+badge-snoozed = STUMM
+badge-ack = QUITTIERT
+badge-lean = SPARMODUS
+badge-safe-mode = ABGESICHERTER MODUS
+badge-dry-run = PROBELAUF
+
+memory-usage = Speicherauslastung: { $percent }
+health-score = Zustand: { $score }/100
+not-available = k. A.
+total-memory = Gesamtspeicher: { $bytes }
+total-memory-cgroup = Gesamtspeicher: { $bytes } (cgroup-Limit)
+used-memory = Belegt:         { $bytes }
+compressed-memory = Komprimiert:    { $bytes }
+
+critical-banner = WARNUNG: KRITISCHE SPEICHERAUSLASTUNG!
+critical-acknowledged = KRITISCHE SPEICHERAUSLASTUNG (quittiert)
+critical-snoozed = KRITISCHE SPEICHERAUSLASTUNG (stummgeschaltet)
+escalation-level = Eskalationsstufe { $level }
+snooze-alerts = Alarme stummschalten
+snoozed-for = Alarme stumm, noch { $minutes } min
+
+updated-ago = aktualisiert vor { $age }
+age-ago = vor { $age }
+stale-data = VERALTETE DATEN: seit { $age } keine neuen Messwerte
+backend-degraded = Antwortet nicht, neuer Versuch in { $age }
+
+dashboard-waiting = warte auf Daten
+dashboard-readout = RAM { $memory }  SWAP { $swap }
+dashboard-clock = Uhr { $offset }
+dashboard-clock-skewed = Uhr { $offset } ABWEICHEND
+dashboard-critical = KRITISCH
+dashboard-critical-ack = KRITISCH (QUITTIERT)
+dashboard-ack = QUITTIEREN
+dashboard-compare = Vergleichen
+dashboard-versus = gegen
+dashboard-compare-now = Δ jetzt { $delta } Pkt. ({ $bytes })
+dashboard-compare-mean = Δ Mittel { $delta } Pkt.
+dashboard-compare-waiting = warte auf Daten beider Hosts
+
+button-ack = [ QUITTIEREN ]
+button-pause = [ PAUSE ]
+button-resume = [ WEITER ]
+button-snapshot = [ SCHNAPPSCHUSS ]
+button-record = [ AUFNAHME ]
+button-stop-recording = [ AUFNAHME STOPP ]
+button-export = [ EXPORT ]
+button-report = [ BERICHT ]
+button-mark = [ MARKE ]
+button-unsnooze = [ ALARME AN ]
+button-selftest = [ SELBSTTEST ]
+button-test = TEST
+hint-snapshot-name = Name des Schnappschusses
+hint-bookmark = Lesezeichen
+bookmark-default = Lesezeichen
+hover-export = Den dargestellten Verlauf als CSV speichern
+hover-report = HTML-Bericht speichern: Diagramm, Statistik, Ereignisse und größte Prozesse
+hover-image = Diagramm und Statistik als Bild speichern
+hover-mark = B: diesen Moment auf der Zeitachse markieren, in jedem offenen Monitor
+keep-awake = Während der Aufnahme wach bleiben
+
+plot-lines = RAM / Swap
+plot-composition = Zusammensetzung
+plot-top-processes = Top-Prozesse
+plot-top-count = Anzahl
+legend-other = Sonstige
+plot-bytes = Bytes
+legend-events = Ereignisse
+legend-range = RAM min/max
+legend-ram = RAM
+legend-swap = Swap
+legend-ram-smoothed = RAM (geglättet)
+legend-swap-smoothed = Swap (geglättet)
+
+panel-statistics = STATISTIK
+panel-processes = PROZESSE
+panel-focus = FOKUS
+panel-budgets = SPEICHERBUDGETS
+panel-agent = AGENT-STEUERUNG
+agent-no-token = Kein Operator-Token gesetzt; der Agent lehnt Befehle ab
+panel-containers = CONTAINER
+panel-offenders = GRÖSSTE VERBRAUCHER
+panel-hugepages = HUGEPAGES
+panel-accounting = SPEICHERBILANZ
+panel-temperatures = TEMPERATUREN
+panel-energy = ENERGIE
+panel-pagefile = AUSLAGERUNGSDATEI & RUHEZUSTAND
+panel-pressure = SPEICHERDRUCK
+panel-actions = AKTIONEN
+channels-title = Alarmkanäle
+channels-none = Keine Webhooks, E-Mails oder Alarmbefehle eingerichtet
+channel-sending = wird gesendet...
+channel-webhook = Webhook { $url }
+channel-email = E-Mail { $to }
+channel-command-warning = Warnungsbefehl: { $command }
+channel-command-critical = Befehl bei kritischem Alarm: { $command }
+channel-command-recovery = Entwarnungsbefehl: { $command }
+channel-mqtt = MQTT { $broker }
+panel-plugins = PLUGINS
+panel-registry = PLUGIN-VERZEICHNIS
+panel-snapshots = SCHNAPPSCHÜSSE ({ $count })
+diff-now = jetzt
+diff-compare = Vergleichen
+panel-events = EREIGNISSE ({ $count })
+
+settings = Einstellungen
+settings-appearance = Darstellung
+settings-thresholds = Schwellenwerte
+settings-sampling = Messung
+settings-highlighting = Prozesse hervorheben
+settings-performance = Leistung
+settings-profiles = Profile
+profiles-compare = Vergleichen
+profiles-same = Diese Profile haben dasselbe Layout
+profiles-setting = Einstellung
+profiles-copy-right = In das rechte Profil übernehmen
+profiles-copy-left = In das linke Profil übernehmen
+
+theme = Farbschema
+theme-export = Exportieren
+theme-import = Importieren
+hover-theme-export = Farbschema und Effekte zum Teilen speichern und kopieren
+hint-theme-path = Pfad zu einer Farbschema-.toml
+language = Sprache
+language-system = System ({ $language })
+ui-scale = Skalierung
+reduced-motion = Weniger Bewegung
+hover-reduced-motion = Keine Glitches, kein Flackern, kein Regen, keine Animationen; Alarmfarben bleiben
+rain = Digitaler Regen
+hover-rain = Fallende Zeichen hinter dem Diagramm: ruhig bei wenig Auslastung, schnell und rot bei Alarm
+crt = Röhrenmonitor
+hover-crt = Zeilenraster und Phosphorschimmer über dem Fenster; gestörte Bilder flackern
+glitch = Glitch-Effekt
+glitch-frames = gestörte Bilder
+glitch-characters-replaced = ersetzte Zeichen
+glitch-characters = Zeichen
+glitch-numbers = Auch Zahlen stören
+
+lean-under-pressure = Bei kritischem Druck sparsam arbeiten
+show-self-stats = Eigenen Ressourcenverbrauch anzeigen
+soak = Dauerbetrieb
+hover-soak = Begrenzt Speicher- und Plattenverbrauch des Monitors für lange Läufe; wirkt nach Neustart
+soak-memory-cap = Speichergrenze
+soak-disk-cap = Plattengrenze
+
+status-recording = Aufnahme nach { $path }
+status-recording-saved = { $count } Messwerte in { $path } gespeichert
+status-recording-failed = Aufnahme konnte nicht starten: { $error }
+status-recording-stopped = Aufnahme beendet: { $error }
+status-inhibit-failed = Ruhezustand ließ sich nicht verhindern: { $error }
+status-exported = { $count } Messwerte nach { $path } exportiert
+status-export-failed = Export fehlgeschlagen: { $error }
+status-report-saved = Bericht in { $path } gespeichert
+status-report-failed = Bericht fehlgeschlagen: { $error }
+status-screenshot-saved = Diagramm in { $path } gespeichert
+status-screenshot-failed = Bildschirmfoto fehlgeschlagen: { $error }
+status-bookmark-failed = Lesezeichen konnte nicht gespeichert werden: { $error }
+status-config-failed = Konfiguration konnte nicht gespeichert werden: { $error }
+status-agent-reply = Agent: { $message }
+status-agent-unreachable = Agent nicht erreichbar: { $error }
+status-load = Last { $load }
+status-uptime = läuft seit { $uptime }
+compact-mem = RAM
+key-space = Leertaste
+
+snapshot-line = { $name } { $ram } RAM  { $used }  { $swap } Swap  (vor { $age }s)
+snapshot-versus = ggü. { $name }: RAM { $ram }  Swap { $swap }  in { $secs }s
+snapshot-diff = { $from } → { $to }: RAM { $ram }  Swap { $swap }  in { $secs }s
+stats-growth = Wachstum: { $bytes }/min
+stats-span = { $count } Messwerte über { $secs }s
+
+smoothing = Glättung
+smoothing-off = Aus
+smoothing-average = Mittel
+smoothing-ema = EMA
+unit-samples = Messwerte
+hover-ema-alpha = Gewicht jedes neuen Messwerts
+hide-raw = Rohwerte ausblenden
+steps = Stufen
+hover-steps = Jeden Messwert bis zum nächsten halten
+band-used = Belegt
+band-compressed = Komprimiert
+band-buffers = Puffer
+band-cached = Cache
+band-free = Frei
+
+column-pid = PID
+column-name = NAME
+column-user = BENUTZER
+column-command = BEFEHL
+column-process = PROZESS
+column-procs = PROZ.
+column-rss = RSS
+column-pss = PSS
+column-swap = SWAP
+column-cpu = CPU
+column-io = E/A
+column-threads = THREADS
+column-started = GESTARTET
+column-before = VORHER
+column-after = NACHHER
+column-delta = DIFFERENZ
+column-min = MIN
+column-max = MAX
+column-avg = MITTEL
+column-p95 = P95
+column-usage = BELEGT
+column-limit = LIMIT
+column-percent = %
+column-oom-kills = OOM-KILLS
+column-memory-hours = SPEICHERSTUNDEN
+column-soft = WEICH
+column-hard = HART
+column-state = ZUSTAND
+
+sort-by = Sortieren nach
+sort-rss = RSS
+sort-swap = Swap
+group-by = Gruppieren nach
+group-process = Prozess
+group-user = Benutzer
+group-command = Befehl
+columns = Spalten
+columns-reset = Zurücksetzen
+button-focus = Fokus
+hover-what-if = Was, wenn ich das schließe?
+hover-what-if-group = Was, wenn ich diese schließe?
+what-if = { $name } zu schließen gibt ~{ $bytes } frei → { $percent }
+what-if-group = { $name } ({ $count } Prozesse)
+what-if-gone = Die Auswahl läuft nicht mehr
+restricted-summary = Die aufgeführten Prozesse belegen { $listed } von { $used }; { $hidden } Prozesse anderer Benutzer haben ohne Administratorrechte keine Größe
+restricted-users = Andere Benutzer (Größen unbekannt)
+restricted-user = { $user }: { $count } Prozesse
+restricted-sudo = mit sudo starten, um sie zu sehen
+button-run-as-admin = [ ALS ADMIN STARTEN ]
+hover-run-as-admin = Den Monitor mit Administratorrechten neu starten
+
+focus-pid = PID { $pid }
+focus-title = { $name } (PID { $pid })
+button-unfocus = Fokus lösen
+focus-exited = Der Prozess wurde beendet
+focus-readout = RSS { $rss }  VIRT { $virtual }  { $share } des RAM
+focus-growth = Seit Fokus: { $bytes }
+focus-rss = RSS
+focus-virtual = Virtuell
+focus-share = % des RAM
+focus-memory-map = SPEICHERBELEGUNG
+focus-map-unreadable = Speicherbelegung nicht lesbar: { $error }
+focus-map-rss = RSS { $bytes } (gemeinsame Seiten voll gezählt)
+budget-not-running = { $name } (läuft nicht)
+budget-under = darunter
+budget-soft = weich
+budget-hard = hart
+
+containers-none = Keine Docker- oder Podman-Container gefunden
+limit-none = keins
+period-day = Heute
+period-week = 7 Tage
+offenders-none = Noch nichts aufgezeichnet
+zram-device = { $name } { $stored } von { $size } in { $ram } RAM ({ $ratio }, { $algorithm })
+zswap-pool = { $name } { $stored } in { $ram } RAM ({ $ratio })
+hugepages-unreadable = /proc/meminfo ist nicht lesbar
+hugepages-none = Kein Hugepage-Pool reserviert
+hugepages-pool = Pool: { $bytes } ({ $percent } des RAM), { $count } x { $size } Seiten
+hugepages-usage = Belegt { $used }  frei { $free }  reserviert { $reserved }  Überschuss { $surplus } Seiten
+hugepages-outside = RAM außerhalb des Pools belegt: { $bytes } ({ $percent })
+hugepages-thp = THP: { $bytes } (enabled { $enabled }, defrag { $defrag })
+hover-hugepages-thp = anonym { $anonymous }, shmem { $shmem }, Dateien { $file }
+button-reconcile = [ ABGLEICHEN ]
+hover-reconcile = Zusammenzählen, wo der RAM geblieben ist; liest die Speicherbelegung jedes Prozesses
+accounting-unexplained = Ungeklärt
+accounting-overlap = Posten überlappen sich
+accounting-remainder = Treiber, GPU, vmalloc, unlesbare Prozesse
+accounting-total = Gesamt
+sensors-none = Keine Temperatursensoren gefunden
+sensor-critical = krit. { $celsius }
+energy-power = CPU-Leistungsaufnahme: { $watts }
+energy-session = Diese Sitzung: { $wh } Wh
+energy-high-memory = Über { $threshold } RAM: { $wh } Wh in { $minutes } min ({ $share } davon)
+energy-carbon = ≈ { $grams } g CO₂, davon { $high } g bei hoher Speicherauslastung
+pagefile-querying = Wird abgefragt...
+pagefile-commit = Zugesicherter Speicher: { $committed } von { $limit } ({ $percent })
+pagefile-automatic-on = Automatische Verwaltung: an
+pagefile-automatic-off = Automatische Verwaltung: aus
+pagefile-file = { $name }  { $size } MB  belegt { $used } MB  Spitze { $peak } MB
+hiberfil = hiberfil.sys: { $size }
+hiberfil-absent = nicht vorhanden
+button-refresh = Aktualisieren
+pressure-level = Druck: { $level }
+pressure-unknown = unbekannt
+pressure-compressed = Komprimiert: { $stored } in { $ram } RAM ({ $ratio })
+
+confirm-title = Aktion bestätigen
+confirm-kill = Eine Eskalationsstufe will { $name } beenden (PID { $pid }, { $bytes }).
+confirm-go-ahead = Ausführen
+confirm-cancel = Abbrechen
+actions-dry-run = Probelauf: Aktionen nur protokollieren
+actions-confirm-kill = Nachfragen, bevor eine Eskalation einen Prozess beendet
+actions-confirm-system = Vor Änderungen an Auslagerungsdatei und Ruhezustand nachfragen
+channel-ok = OK: { $message }
+channel-failed = FEHLGESCHLAGEN: { $error }
+
+button-report-problem = [ PROBLEM MELDEN ]
+report-problem-title = Problem melden
+report-problem-hint = In ein neues GitHub-Issue einfügen, zusammen mit dem, was Sie gerade getan haben.
+button-copy = In die Zwischenablage kopieren
+button-reload = [ NEU LADEN ]
+hover-reload-plugins = Das Plugin-Verzeichnis neu laden und abgeschaltete Plugins wieder aktivieren
+plugins-none = Keine Plugins installiert
+plugin-disabled = deaktiviert: { $error }
+plugin-error = Fehler: { $error }
+plugin-no-capabilities = keine
+hover-plugin-capabilities = Berechtigungen: { $capabilities }
+plugin-rejected = { $name }: nicht geladen, { $error }
+registry-network = Netzwerkzugriff erlauben
+hint-registry-url = URL des Verzeichnisindex
+button-registry-refresh = [ AKTUALISIEREN ]
+registry-plugin = Plugin
+registry-theme = Schema
+registry-installed = installiert
+registry-needs-api = braucht Plugin-API { $version }
+registry-install = installieren
+registry-fetching = Verzeichnis wird abgerufen...
+registry-available = { $count } Einträge verfügbar
+registry-error = Verzeichnisfehler: { $error }
+registry-no-config-dir = Kein Konfigurationsverzeichnis zum Installieren
+registry-downloading = { $name } wird heruntergeladen...
+registry-installed-to = { $name } in { $path } installiert
+registry-install-failed = { $name } konnte nicht installiert werden: { $error }
+
+theme-exported = In { $path } gespeichert und kopiert
+theme-export-failed = Kopiert; Speichern fehlgeschlagen: { $error }
+theme-imported = „{ $name }“ importiert
+threshold-warning = Warnung
+threshold-critical = kritischer Alarm
+threshold-sustained = anhaltend für
+hover-threshold-sustained = Alarm erst auslösen, wenn die Auslastung so lange darüber lag, damit eine kurze Spitze wie ein Compiler-Fork ihn nicht auslöst
+quiet-presenting = Während Präsentationen ruhig
+hover-quiet-presenting = Warn- und Entwarnungsbefehle zurückhalten, solange ein Video, eine Präsentation oder eine Vollbildanwendung läuft, und danach senden; der kritische Alarm kommt trotzdem
+alarm-sound = Alarmton
+hover-alarm-sound = Piepen, solange der kritische Alarm anliegt, bis er bestätigt oder pausiert wird
+sound-volume = Lautstärke
+sound-repeat = wiederholen alle
+sampling-memory = Speicher
+sampling-processes = Prozesstabelle
+sampling-system = Systemwerte
+sampling-span = Diagrammspanne
+sampling-kept = { $count } Messwerte vorgehalten
+sampling-remote = Ein Daemon oder Agent misst, in seinem eigenen Takt
+hint-rule-pattern = Namens-Regex
+hint-rule-color = Farbe
+rule-bold = fett
+rule-add = Regel hinzufügen
+rule-error = Regel { $index }: { $error }
+
+self-rss = RSS { $bytes }
+self-frame = Bild { $mean } ms (max. { $max })
+self-allocations = { $count } Allokationen/Bild
@@ -0,0 +1,365 @@
+# English, the fallback for messages other catalogs leave out.
+# Placeholders look like { $name } and are filled in by the monitor.
+
+title = MEMORY MONITOR
+dashboard-title = MEMORY MONITOR [{ $hosts } HOSTS]
+badge-offline = OFFLINE
+badge-linked = LINKED
+badge-paused = PAUSED
+badge-snoozed = SNOOZED
+badge-ack = ACK
+badge-lean = LEAN
+badge-safe-mode = SAFE MODE
+badge-dry-run = DRY RUN
+
+memory-usage = Memory Usage: { $percent }
+health-score = Health: { $score }/100
+not-available = N/A
+total-memory = Total Memory: { $bytes }
+total-memory-cgroup = Total Memory: { $bytes } (cgroup limit)
+used-memory = Used Memory:  { $bytes }
+compressed-memory = Compressed:   { $bytes }
+
+critical-banner = WARNING: CRITICAL MEMORY USAGE!
+critical-acknowledged = CRITICAL MEMORY USAGE (acknowledged)
+critical-snoozed = CRITICAL MEMORY USAGE (snoozed)
+escalation-level = Escalation level { $level }
+snooze-alerts = Snooze alerts
+snoozed-for = Alerts snoozed, { $minutes } min left
+
+updated-ago = updated { $age } ago
+age-ago = { $age } ago
+stale-data = STALE DATA: no new samples for { $age }
+backend-degraded = Not responding, retrying in { $age }
+
+dashboard-waiting = waiting for data
+dashboard-readout = MEM { $memory }  SWP { $swap }
+dashboard-clock = clock { $offset }
+dashboard-clock-skewed = clock { $offset } SKEWED
+dashboard-critical = CRITICAL
+dashboard-critical-ack = CRITICAL (ACK)
+dashboard-ack = ACK
+dashboard-compare = Compare
+dashboard-versus = vs
+dashboard-compare-now = Δ now { $delta } pts ({ $bytes })
+dashboard-compare-mean = Δ mean { $delta } pts
+dashboard-compare-waiting = waiting for data from both hosts
+
+button-ack = [ ACK ]
+button-pause = [ PAUSE ]
+button-resume = [ RESUME ]
+button-snapshot = [ SNAPSHOT ]
+button-record = [ REC ]
+button-stop-recording = [ STOP REC ]
+button-export = [ EXPORT ]
+button-report = [ REPORT ]
+button-mark = [ MARK ]
+button-unsnooze = [ UNSNOOZE ]
+button-selftest = [ SELFTEST ]
+button-test = TEST
+hint-snapshot-name = snapshot name
+hint-bookmark = bookmark
+bookmark-default = Bookmark
+hover-export = Save the plotted history as CSV
+hover-report = Save an HTML report: chart, statistics, events and top processes
+hover-image = Save the plot and its statistics as an image
+hover-mark = B: mark this moment on the timeline, in every open monitor
+keep-awake = Keep awake while recording
+
+plot-lines = RAM / Swap
+plot-composition = Composition
+plot-top-processes = Top processes
+plot-top-count = Show top
+legend-other = Other
+plot-bytes = Bytes
+legend-events = Events
+legend-range = RAM min/max
+legend-ram = RAM
+legend-swap = Swap
+legend-ram-smoothed = RAM (smoothed)
+legend-swap-smoothed = Swap (smoothed)
+
+panel-statistics = STATISTICS
+panel-processes = PROCESSES
+panel-focus = FOCUS
+panel-budgets = MEMORY BUDGETS
+panel-agent = AGENT CONTROL
+agent-no-token = No operator token set; the agent will refuse commands
+panel-containers = CONTAINERS
+panel-offenders = TOP OFFENDERS
+panel-hugepages = HUGEPAGES
+panel-accounting = ACCOUNTING
+panel-temperatures = TEMPERATURES
+panel-energy = ENERGY
+panel-pagefile = PAGEFILE & HIBERNATION
+panel-pressure = MEMORY PRESSURE
+panel-actions = ACTIONS
+channels-title = Alert channels
+channels-none = No webhooks, emails or alert commands configured
+channel-sending = sending...
+channel-webhook = Webhook { $url }
+channel-email = Email { $to }
+channel-command-warning = warning command: { $command }
+channel-command-critical = critical command: { $command }
+channel-command-recovery = recovery command: { $command }
+channel-mqtt = MQTT { $broker }
+panel-plugins = PLUGINS
+panel-registry = PLUGIN REGISTRY
+panel-snapshots = SNAPSHOTS ({ $count })
+diff-now = now
+diff-compare = Diff
+panel-events = EVENTS ({ $count })
+
+settings = Settings
+settings-appearance = Appearance
+settings-thresholds = Thresholds
+settings-sampling = Sampling
+settings-highlighting = Process highlighting
+settings-performance = Performance
+settings-profiles = Profiles
+profiles-compare = Compare
+profiles-same = These profiles have the same layout
+profiles-setting = Setting
+profiles-copy-right = Copy to the right-hand profile
+profiles-copy-left = Copy to the left-hand profile
+
+theme = Theme
+theme-export = Export
+theme-import = Import
+hover-theme-export = Save the theme and effects to share, and copy them
+hint-theme-path = path to a theme .toml
+language = Language
+language-system = System ({ $language })
+ui-scale = UI scale
+reduced-motion = Reduced motion
+hover-reduced-motion = No glitches, flicker, digital rain or animations; alarm colors still change
+rain = Digital rain
+hover-rain = Falling characters behind the plot: calm at low usage, fast and red during an alarm
+crt = CRT mode
+hover-crt = Scanlines and phosphor glow over the window; glitched frames flicker
+glitch = Glitch effect
+glitch-frames = frames glitched
+glitch-characters-replaced = characters replaced
+glitch-characters = Characters
+glitch-numbers = Glitch numeric readouts
+
+lean-under-pressure = Go lean under critical pressure
+show-self-stats = Show the monitor's own footprint
+soak = Soak mode
+hover-soak = Caps the monitor's own memory and disk use for long runs; applies on restart
+soak-memory-cap = memory cap
+soak-disk-cap = disk cap
+
+status-recording = Recording to { $path }
+status-recording-saved = Saved { $count } samples to { $path }
+status-recording-failed = Could not start recording: { $error }
+status-recording-stopped = Recording stopped: { $error }
+status-inhibit-failed = Could not inhibit sleep: { $error }
+status-exported = Exported { $count } samples to { $path }
+status-export-failed = Export failed: { $error }
+status-report-saved = Report saved to { $path }
+status-report-failed = Report failed: { $error }
+status-screenshot-saved = Plot saved to { $path }
+status-screenshot-failed = Screenshot failed: { $error }
+status-bookmark-failed = Could not save bookmark: { $error }
+status-config-failed = Could not save config: { $error }
+status-agent-reply = Agent: { $message }
+status-agent-unreachable = Could not reach the agent: { $error }
+status-load = load { $load }
+status-uptime = up { $uptime }
+compact-mem = MEM
+key-space = Space
+
+snapshot-line = { $name } { $ram } RAM  { $used }  { $swap } Swap  ({ $age }s ago)
+snapshot-versus = vs { $name }: RAM { $ram }  Swap { $swap }  over { $secs }s
+snapshot-diff = { $from } → { $to }: RAM { $ram }  Swap { $swap }  over { $secs }s
+stats-growth = Growth: { $bytes }/min
+stats-span = { $count } samples over { $secs }s
+
+smoothing = Smoothing
+smoothing-off = Off
+smoothing-average = Avg
+smoothing-ema = EMA
+unit-samples = samples
+hover-ema-alpha = Weight of each new sample
+hide-raw = hide raw
+steps = Steps
+hover-steps = Hold each reading until the next
+band-used = Used
+band-compressed = Compressed
+band-buffers = Buffers
+band-cached = Cached
+band-free = Free
+
+column-pid = PID
+column-name = NAME
+column-user = USER
+column-command = COMMAND
+column-process = PROCESS
+column-procs = PROCS
+column-rss = RSS
+column-pss = PSS
+column-swap = SWAP
+column-cpu = CPU
+column-io = I/O
+column-threads = THREADS
+column-started = STARTED
+column-before = BEFORE
+column-after = AFTER
+column-delta = DELTA
+column-min = MIN
+column-max = MAX
+column-avg = AVG
+column-p95 = P95
+column-usage = USAGE
+column-limit = LIMIT
+column-percent = %
+column-oom-kills = OOM KILLS
+column-memory-hours = MEMORY-HOURS
+column-soft = SOFT
+column-hard = HARD
+column-state = STATE
+
+sort-by = Sort by
+sort-rss = RSS
+sort-swap = Swap
+group-by = Group by
+group-process = Process
+group-user = User
+group-command = Command
+columns = Columns
+columns-reset = Reset
+button-focus = focus
+hover-what-if = What if I close this?
+hover-what-if-group = What if I close these?
+what-if = Closing { $name } frees ~{ $bytes } → { $percent }
+what-if-group = { $name } ({ $count } processes)
+what-if-gone = Selection is no longer running
+restricted-summary = Listed processes add up to { $listed } of { $used } used; { $hidden } of other users' have no sizes without admin rights
+restricted-users = Other users (sizes unavailable)
+restricted-user = { $user }: { $count } processes
+restricted-sudo = run with sudo to see them
+button-run-as-admin = [ RUN AS ADMIN ]
+hover-run-as-admin = Restart the monitor with administrator rights
+
+focus-pid = PID { $pid }
+focus-title = { $name } (PID { $pid })
+button-unfocus = unfocus
+focus-exited = Process has exited
+focus-readout = RSS { $rss }  VIRT { $virtual }  { $share } of RAM
+focus-growth = Since focus: { $bytes }
+focus-rss = RSS
+focus-virtual = Virtual
+focus-share = % of RAM
+focus-memory-map = MEMORY MAP
+focus-map-unreadable = Can't read memory map: { $error }
+focus-map-rss = RSS { $bytes } (shared pages counted in full)
+budget-not-running = { $name } (not running)
+budget-under = under
+budget-soft = soft
+budget-hard = hard
+
+containers-none = No Docker or Podman containers found
+limit-none = none
+period-day = Today
+period-week = 7 days
+offenders-none = Nothing recorded yet
+zram-device = { $name } { $stored } of { $size } in { $ram } RAM ({ $ratio }, { $algorithm })
+zswap-pool = { $name } { $stored } in { $ram } RAM ({ $ratio })
+hugepages-unreadable = /proc/meminfo isn't readable
+hugepages-none = No hugepage pool reserved
+hugepages-pool = Pool: { $bytes } ({ $percent } of RAM), { $count } x { $size } pages
+hugepages-usage = In use { $used }  free { $free }  reserved { $reserved }  surplus { $surplus } pages
+hugepages-outside = RAM used outside the pool: { $bytes } ({ $percent })
+hugepages-thp = THP: { $bytes } (enabled { $enabled }, defrag { $defrag })
+hover-hugepages-thp = anonymous { $anonymous }, shmem { $shmem }, file { $file }
+button-reconcile = [ RECONCILE ]
+hover-reconcile = Add up where RAM went; reads every process's memory map
+accounting-unexplained = Unexplained
+accounting-overlap = buckets overlap
+accounting-remainder = drivers, GPU, vmalloc, unreadable processes
+accounting-total = Total
+sensors-none = No temperature sensors found
+sensor-critical = crit { $celsius }
+energy-power = CPU package power: { $watts }
+energy-session = This session: { $wh } Wh
+energy-high-memory = Above { $threshold } RAM: { $wh } Wh over { $minutes } min ({ $share } of it)
+energy-carbon = ≈ { $grams } g CO₂, { $high } g of it while memory was high
+pagefile-querying = Querying...
+pagefile-commit = Commit charge: { $committed } of { $limit } ({ $percent })
+pagefile-automatic-on = Automatic management: on
+pagefile-automatic-off = Automatic management: off
+pagefile-file = { $name }  { $size } MB  in use { $used } MB  peak { $peak } MB
+hiberfil = hiberfil.sys: { $size }
+hiberfil-absent = not present
+button-refresh = Refresh
+pressure-level = Pressure: { $level }
+pressure-unknown = unknown
+pressure-compressed = Compressed: { $stored } in { $ram } RAM ({ $ratio })
+
+confirm-title = Confirm action
+confirm-kill = An escalation stage wants to kill { $name } (pid { $pid }, { $bytes }).
+confirm-go-ahead = Go ahead
+confirm-cancel = Cancel
+actions-dry-run = Dry run: only log what actions would do
+actions-confirm-kill = Ask before an escalation kills a process
+actions-confirm-system = Ask before pagefile/hibernation changes
+channel-ok = OK: { $message }
+channel-failed = FAILED: { $error }
+
+button-report-problem = [ REPORT A PROBLEM ]
+report-problem-title = Report a problem
+report-problem-hint = Paste this into a new GitHub issue along with what you were doing.
+button-copy = Copy to clipboard
+button-reload = [ RELOAD ]
+hover-reload-plugins = Load the plugins directory again, re-enabling plugins that were switched off
+plugins-none = No plugins installed
+plugin-disabled = disabled: { $error }
+plugin-error = error: { $error }
+plugin-no-capabilities = none
+hover-plugin-capabilities = Capabilities: { $capabilities }
+plugin-rejected = { $name }: not loaded, { $error }
+registry-network = Allow network access
+hint-registry-url = registry index URL
+button-registry-refresh = [ REFRESH ]
+registry-plugin = plugin
+registry-theme = theme
+registry-installed = installed
+registry-needs-api = needs plugin API { $version }
+registry-install = install
+registry-fetching = Fetching registry...
+registry-available = { $count } entries available
+registry-error = Registry error: { $error }
+registry-no-config-dir = No config directory to install into
+registry-downloading = Downloading { $name }...
+registry-installed-to = Installed { $name } to { $path }
+registry-install-failed = Failed to install { $name }: { $error }
+
+theme-exported = Saved to { $path } and copied
+theme-export-failed = Copied; saving failed: { $error }
+theme-imported = Imported "{ $name }"
+threshold-warning = warning
+threshold-critical = critical alarm
+threshold-sustained = sustained for
+hover-threshold-sustained = Only raise the alarm once usage has stayed above it this long, so a short spike such as a compiler fork doesn't
+quiet-presenting = Quiet during presentations
+hover-quiet-presenting = Hold the warning and recovery commands while a video, presentation or fullscreen app is running, and send them once it ends; the critical alarm still goes off
+alarm-sound = Alarm sound
+hover-alarm-sound = Beep while the critical alarm is up, until it's acknowledged or snoozed
+sound-volume = volume
+sound-repeat = repeat every
+sampling-memory = memory
+sampling-processes = process table
+sampling-system = system stats
+sampling-span = plot span
+sampling-kept = { $count } samples kept
+sampling-remote = A daemon or agent is sampling, at its own rate
+hint-rule-pattern = name regex
+hint-rule-color = color
+rule-bold = bold
+rule-add = Add rule
+rule-error = Rule { $index }: { $error }
+
+self-rss = RSS { $bytes }
+self-frame = frame { $mean } ms (max { $max })
+self-allocations = { $count } allocs/frame
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use sysinfo::{ProcessExt, ProcessRefreshKind, System, SystemExt};
+
+use rusty_mem_monitor::meminfo;
+use rusty_mem_monitor::units::format_bytes;
+use rusty_mem_monitor::zram::SwapCompression;
+
+pub struct Bucket {
+    pub label: &'static str,
+    pub bytes: u64,
+    pub detail: String,
+}
+
+// Total RAM split into where the kernel and the processes say it went, plus
+// whatever none of them explain: "where did my RAM go?" answered as far as the
+// platform allows.
+pub struct Reconciliation {
+    pub total: u64,
+    pub buckets: Vec<Bucket>,
+    // Negative when the buckets overlap and add up to more than there is
+    pub unexplained: i64,
+}
+
+impl Reconciliation {
+    fn new(total: u64, buckets: Vec<Bucket>) -> Self {
+        let accounted: u64 = buckets.iter().map(|bucket| bucket.bytes).sum();
+        Self {
+            total,
+            buckets,
+            unexplained: total as i64 - accounted as i64,
+        }
+    }
+}
+
+// Walks every process's memory map on Linux, so it's a one-off on request, not a live readout
+pub fn reconcile(sys: &mut System) -> Result<Reconciliation, String> {
+    if cfg!(target_os = "linux") {
+        let info = meminfo::read().ok_or("/proc/meminfo isn't readable")?;
+        let anon = ProcessAnon::read();
+        let compressed = SwapCompression::read();
+        let compressed = compressed.zram.iter().map(|device| device.used).sum::<u64>()
+            + compressed.zswap.map_or(0, |zswap| zswap.pool);
+        Ok(linux(&info, &anon, compressed))
+    } else {
+        Ok(other(sys))
+    }
+}
+
+// Anonymous memory per process, shared pages split proportionally (Pss_Anon, or
+// Anonymous on kernels before 5.7). File and shmem pages are left to the page cache.
+#[derive(Default)]
+struct ProcessAnon {
+    bytes: u64,
+    processes: usize,
+    unreadable: usize,
+}
+
+impl ProcessAnon {
+    fn read() -> Self {
+        let mut anon = Self::default();
+        let Ok(entries) = std::fs::read_dir("/proc") else {
+            return anon;
+        };
+        for entry in entries.flatten() {
+            if !entry.file_name().to_string_lossy().bytes().all(|b| b.is_ascii_digit()) {
+                continue;
+            }
+            // Kernel threads have an empty rollup; other users' processes aren't readable unprivileged
+            match std::fs::read_to_string(entry.path().join("smaps_rollup")) {
+                Ok(rollup) if rollup.trim().is_empty() => {}
+                Ok(rollup) => {
+                    let fields = meminfo::parse(&rollup);
+                    anon.bytes += fields.get("Pss_Anon").or(fields.get("Anonymous")).copied().unwrap_or(0);
+                    anon.processes += 1;
+                }
+                Err(_) => anon.unreadable += 1,
+            }
+        }
+        anon
+    }
+}
+
+fn linux(info: &HashMap<String, u64>, anon: &ProcessAnon, compressed: u64) -> Reconciliation {
+    let field = |name: &str| info.get(name).copied().unwrap_or(0);
+    let kernel_anon = format_bytes(field("AnonPages"));
+    let mut process_detail = format!("{} processes; kernel counts {} anonymous", anon.processes, kernel_anon);
+    if anon.unreadable > 0 {
+        process_detail.push_str(&format!(", {} processes unreadable (run as root)", anon.unreadable));
+    }
+    let buckets = vec![
+        Bucket {
+            label: "Free",
+            bytes: field("MemFree"),
+            detail: String::new(),
+        },
+        Bucket {
+            label: "Process memory",
+            bytes: anon.bytes,
+            detail: process_detail,
+        },
+        Bucket {
+            label: "Page cache",
+            bytes: field("Cached") + field("Buffers"),
+            detail: format!("includes {} shmem/tmpfs", format_bytes(field("Shmem"))),
+        },
+        Bucket {
+            label: "Kernel slab",
+            bytes: field("SReclaimable") + field("SUnreclaim"),
+            detail: format!("{} reclaimable", format_bytes(field("SReclaimable"))),
+        },
+        Bucket {
+            label: "Kernel other",
+            bytes: field("KernelStack") + field("PageTables") + field("SecPageTables") + field("Percpu"),
+            detail: "stacks, page tables, per-CPU".to_string(),
+        },
+        Bucket {
+            label: "Hugepage pool",
+            bytes: field("Hugetlb"),
+            detail: String::new(),
+        },
+        Bucket {
+            label: "Compressed swap",
+            bytes: compressed,
+            detail: "zram and zswap pools".to_string(),
+        },
+    ];
+    let mut reconciliation = Reconciliation::new(field("MemTotal"), buckets);
+    reconciliation.buckets.retain(|bucket| bucket.bytes > 0 || bucket.label == "Process memory");
+    reconciliation
+}
+
+// Elsewhere there's only free memory and per-process resident sizes, which count
+// shared pages once per process; the remainder is kernel, cache and driver memory
+fn other(sys: &mut System) -> Reconciliation {
+    sys.refresh_memory();
+    sys.refresh_processes_specifics(ProcessRefreshKind::new());
+    let processes = sys.processes().len();
+    let unreadable = sys.processes().values().filter(|process| process.memory() == 0).count();
+    let rss: u64 = sys.processes().values().map(|process| process.memory()).sum();
+    let mut detail = format!("RSS of {} processes; shared pages count once per process", processes);
+    if unreadable > 0 {
+        detail.push_str(&format!(", {} reported no size (run as admin)", unreadable));
+    }
+    let buckets = vec![
+        Bucket {
+            label: "Free",
+            bytes: sys.free_memory(),
+            detail: String::new(),
+        },
+        Bucket {
+            label: "Process memory",
+            bytes: rss,
+            detail,
+        },
+    ];
+    Reconciliation::new(sys.total_memory(), buckets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remainder_is_what_no_bucket_explains() {
+        let info = meminfo::parse(
+            "MemTotal: 1000 kB\nMemFree: 200 kB\nCached: 250 kB\nBuffers: 50 kB\n\
+             SReclaimable: 60 kB\nSUnreclaim: 40 kB\nKernelStack: 10 kB\nPageTables: 20 kB\n",
+        );
+        let anon = ProcessAnon { bytes: 300 * 1024, processes: 12, unreadable: 3 };
+        let reconciliation = linux(&info, &anon, 0);
+        assert_eq!(reconciliation.total, 1000 * 1024);
+        assert_eq!(reconciliation.unexplained, 70 * 1024);
+        let labels: Vec<_> = reconciliation.buckets.iter().map(|bucket| bucket.label).collect();
+        assert_eq!(labels, ["Free", "Process memory", "Page cache", "Kernel slab", "Kernel other"]);
+        assert!(reconciliation.buckets[1].detail.contains("3 processes unreadable"));
+    }
+}
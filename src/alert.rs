@@ -0,0 +1,404 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use crate::sample::Sample;
+
+pub const CRITICAL_THRESHOLD: f32 = 90.0;
+pub const WARNING_THRESHOLD: f32 = 70.0;
+// Once raised, the critical alarm only clears this far below the threshold so a
+// reading hovering around 90% doesn't flap it on and off every sample
+pub const CRITICAL_HYSTERESIS: f32 = 5.0;
+const MB: f64 = 1024.0 * 1024.0;
+
+/// Usage, in percent, above which readouts turn to the warning color and the
+/// critical alarm is raised. The alarm waits until usage has stayed above
+/// `critical` for `critical_secs`, so a momentary spike doesn't raise it.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct Thresholds {
+    pub warning: f32,
+    pub critical: f32,
+    pub critical_secs: f64,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            warning: WARNING_THRESHOLD,
+            critical: CRITICAL_THRESHOLD,
+            critical_secs: 0.0,
+        }
+    }
+}
+
+/// What an [`AlertRule`] watches: RAM or swap in percent of its total, or how
+/// fast available memory is falling in MB/s. The rate catches a runaway
+/// allocation on a machine with lots of RAM long before a percentage would.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    #[default]
+    Ram,
+    Swap,
+    AvailableDecline,
+}
+
+impl Metric {
+    /// None when the samples have no reading for it. Only the rate needs the
+    /// sample before.
+    pub fn value(self, sample: &Sample, previous: Option<&Sample>) -> Option<f32> {
+        match self {
+            Metric::Ram => sample.has_memory().then(|| sample.memory_percentage()),
+            Metric::Swap => sample.has_swap().then(|| sample.swap_percentage()),
+            Metric::AvailableDecline => {
+                let previous = previous.filter(|previous| previous.has_memory() && sample.has_memory())?;
+                let elapsed_ms = sample.timestamp_ms.checked_sub(previous.timestamp_ms).filter(|&ms| ms > 0)?;
+                let secs = elapsed_ms as f64 / 1000.0;
+                let available = |sample: &Sample| sample.total_memory.saturating_sub(sample.used_memory) as f64;
+                Some(((available(previous) - available(sample)) / MB / secs) as f32)
+            }
+        }
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            Metric::Ram | Metric::Swap => "%",
+            Metric::AvailableDecline => " MB/s",
+        }
+    }
+
+    // Where a raised rule clears: a few points under a percentage, and half the
+    // rate, since a falling rate is noisy from sample to sample
+    fn clear_line(self, above: f32) -> f32 {
+        match self {
+            Metric::Ram | Metric::Swap => above - CRITICAL_HYSTERESIS,
+            Metric::AvailableDecline => above / 2.0,
+        }
+    }
+}
+
+/// A named condition that holds once `metric` has stayed above `above` for
+/// `for_secs`, e.g. swap over 50% for five minutes.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct AlertRule {
+    pub name: String,
+    pub metric: Metric,
+    pub above: f32,
+    pub for_secs: f64,
+    pub webhook: Option<String>,
+}
+
+/// A rule that was raised or cleared by the latest sample.
+#[derive(PartialEq, Debug)]
+pub struct RuleChange {
+    pub index: usize,
+    pub raised: bool,
+    pub value: f32,
+}
+
+/// Tracks which [`AlertRule`]s hold. Like the critical alarm, a raised rule only
+/// clears some way below its line: [`CRITICAL_HYSTERESIS`] points for a
+/// percentage, half the rate for a rate.
+#[derive(Default)]
+pub struct RuleState {
+    // Per rule: when its metric went above the line, and whether it's raised
+    states: Vec<(Option<Instant>, bool)>,
+    previous: Option<Sample>,
+}
+
+impl RuleState {
+    /// Returns the rules that changed with this sample. Rules whose metric has
+    /// no reading stay as they were.
+    pub fn update(&mut self, rules: &[AlertRule], sample: &Sample, now: Instant) -> Vec<RuleChange> {
+        self.states.resize(rules.len(), (None, false));
+        let mut changes = Vec::new();
+        for (index, (rule, (since, raised))) in rules.iter().zip(&mut self.states).enumerate() {
+            let Some(value) = rule.metric.value(sample, self.previous.as_ref()) else { continue };
+            let line = if *raised { rule.metric.clear_line(rule.above) } else { rule.above };
+            if value <= line {
+                *since = None;
+                if std::mem::take(raised) {
+                    changes.push(RuleChange { index, raised: false, value });
+                }
+                continue;
+            }
+            let held = now.saturating_duration_since(*since.get_or_insert(now));
+            if !*raised && held >= Duration::from_secs_f64(rule.for_secs.max(0.0)) {
+                *raised = true;
+                changes.push(RuleChange { index, raised: true, value });
+            }
+        }
+        self.previous = Some(*sample);
+        changes
+    }
+
+    pub fn is_raised(&self, index: usize) -> bool {
+        self.states.get(index).is_some_and(|(_, raised)| *raised)
+    }
+}
+
+/// One step of the escalation chain, fired once the critical alarm has gone
+/// unacknowledged for `after_minutes`. `sound_volume` (0-1) makes the alarm
+/// sound louder from this stage on.
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct EscalationStage {
+    pub after_minutes: f64,
+    pub sound_volume: Option<f32>,
+    pub webhook: Option<String>,
+    pub email: Option<String>,
+    pub kill_top_offender: bool,
+}
+
+/// Tracks the critical alarm and how far it has escalated. Escalation only advances
+/// while nobody has acknowledged the alarm; it resets once usage recovers.
+/// A snooze silences escalation until it expires, whether or not the alarm is up.
+#[derive(Default)]
+pub struct AlertState {
+    pub thresholds: Thresholds,
+    pub critical: bool,
+    pub acknowledged: bool,
+    // When usage went above the threshold, while the alarm waits out critical_secs
+    above_since: Option<Instant>,
+    critical_since: Option<Instant>,
+    stages_fired: usize,
+    snoozed_until: Option<Instant>,
+}
+
+impl AlertState {
+    /// Returns the indices of escalation stages that became due with this sample.
+    pub fn update(&mut self, memory_percentage: f32, now: Instant, stages: &[EscalationStage]) -> Vec<usize> {
+        let above = if self.critical {
+            memory_percentage > self.thresholds.critical - CRITICAL_HYSTERESIS
+        } else {
+            memory_percentage > self.thresholds.critical
+        };
+        if above && !self.critical {
+            let held = now.saturating_duration_since(*self.above_since.get_or_insert(now));
+            if held < Duration::from_secs_f64(self.thresholds.critical_secs.max(0.0)) {
+                return Vec::new();
+            }
+        }
+        self.critical = above;
+        if !self.critical {
+            *self = Self {
+                thresholds: self.thresholds,
+                snoozed_until: self.snoozed_until,
+                ..Self::default()
+            };
+            return Vec::new();
+        }
+        // Escalation starts over once the snooze runs out rather than firing
+        // everything that fell due in the meantime
+        if self.is_snoozed(now) {
+            self.critical_since = None;
+            return Vec::new();
+        }
+        let since = *self.critical_since.get_or_insert(now);
+        if self.acknowledged {
+            return Vec::new();
+        }
+
+        let elapsed = now.saturating_duration_since(since);
+        let mut due = Vec::new();
+        while let Some(stage) = stages.get(self.stages_fired) {
+            if elapsed < Duration::from_secs_f64(stage.after_minutes.max(0.0) * 60.0) {
+                break;
+            }
+            due.push(self.stages_fired);
+            self.stages_fired += 1;
+        }
+        due
+    }
+
+    pub fn acknowledge(&mut self) {
+        if self.critical {
+            self.acknowledged = true;
+        }
+    }
+
+    /// Whether the alarm sound should be going: the alarm is up, and nobody has
+    /// acknowledged or snoozed it. The badge stays after an acknowledgement.
+    pub fn is_sounding(&self, now: Instant) -> bool {
+        self.critical && !self.acknowledged && !self.is_snoozed(now)
+    }
+
+    pub fn escalation_level(&self) -> usize {
+        self.stages_fired
+    }
+
+    pub fn snooze(&mut self, until: Instant) {
+        self.snoozed_until = Some(until);
+    }
+
+    pub fn unsnooze(&mut self) {
+        self.snoozed_until = None;
+    }
+
+    pub fn is_snoozed(&self, now: Instant) -> bool {
+        self.snoozed_until.is_some_and(|until| now < until)
+    }
+
+    /// Time left on the snooze, if one is running.
+    pub fn snooze_remaining(&self, now: Instant) -> Option<Duration> {
+        self.snoozed_until
+            .filter(|&until| now < until)
+            .map(|until| until - now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stage(after_minutes: f64) -> EscalationStage {
+        EscalationStage {
+            after_minutes,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn critical_only_above_threshold() {
+        let mut alerts = AlertState::default();
+        let now = Instant::now();
+        alerts.update(CRITICAL_THRESHOLD, now, &[]);
+        assert!(!alerts.critical);
+        alerts.update(CRITICAL_THRESHOLD + 0.1, now, &[]);
+        assert!(alerts.critical);
+
+        let mut lowered = AlertState::default();
+        lowered.thresholds.critical = 80.0;
+        lowered.update(85.0, now, &[]);
+        assert!(lowered.critical);
+        lowered.update(50.0, now, &[]);
+        assert_eq!(lowered.thresholds.critical, 80.0);
+    }
+
+    #[test]
+    fn alarm_waits_out_the_duration() {
+        let mut alerts = AlertState::default();
+        alerts.thresholds.critical_secs = 30.0;
+        let start = Instant::now();
+        let secs = |s: u64| start + Duration::from_secs(s);
+        alerts.update(95.0, secs(0), &[]);
+        alerts.update(95.0, secs(20), &[]);
+        assert!(!alerts.critical);
+        // A dip below the line starts the wait over
+        alerts.update(50.0, secs(25), &[]);
+        alerts.update(95.0, secs(40), &[]);
+        alerts.update(95.0, secs(60), &[]);
+        assert!(!alerts.critical);
+        alerts.update(95.0, secs(70), &[]);
+        assert!(alerts.critical);
+    }
+
+    #[test]
+    fn rules_hold_for_their_duration() {
+        let rules = [AlertRule { metric: Metric::Swap, above: 50.0, for_secs: 300.0, ..AlertRule::default() }];
+        let swap = |used| Sample { total_memory: 100, total_swap: 100, used_swap: used, ..Sample::default() };
+        let mut state = RuleState::default();
+        let start = Instant::now();
+        let minutes = |m: u64| start + Duration::from_secs(m * 60);
+        assert!(state.update(&rules, &swap(60), minutes(0)).is_empty());
+        assert!(state.update(&rules, &swap(60), minutes(4)).is_empty());
+        let raised = state.update(&rules, &swap(60), minutes(5));
+        assert_eq!(raised, [RuleChange { index: 0, raised: true, value: 60.0 }]);
+        assert!(state.update(&rules, &swap(48), minutes(6)).is_empty());
+        // No swap reading says nothing either way
+        assert!(state.update(&rules, &Sample { total_swap: 0, ..swap(0) }, minutes(7)).is_empty());
+        assert!(state.is_raised(0));
+        assert!(!state.update(&rules, &swap(40), minutes(8))[0].raised);
+        assert!(!state.is_raised(0));
+    }
+
+    #[test]
+    fn decline_rate_rule() {
+        let mb = 1024 * 1024;
+        let rule = AlertRule { metric: Metric::AvailableDecline, above: 100.0, for_secs: 2.0, ..AlertRule::default() };
+        let rules = [rule];
+        let used = |secs: u64, used_mb: u64| Sample {
+            timestamp_ms: secs * 1000,
+            total_memory: 64 * 1024 * mb,
+            used_memory: used_mb * mb,
+            ..Sample::default()
+        };
+        let mut state = RuleState::default();
+        let start = Instant::now();
+        let secs = |s: u64| start + Duration::from_secs(s);
+        // Only a level, nothing to compare with yet
+        assert!(state.update(&rules, &used(0, 1000), secs(0)).is_empty());
+        assert!(state.update(&rules, &used(1, 1200), secs(1)).is_empty());
+        assert!(state.update(&rules, &used(2, 1400), secs(2)).is_empty());
+        let raised = state.update(&rules, &used(3, 1600), secs(3));
+        assert_eq!(raised, [RuleChange { index: 0, raised: true, value: 200.0 }]);
+        assert!(state.update(&rules, &used(4, 1680), secs(4)).is_empty());
+        assert!(!state.update(&rules, &used(5, 1700), secs(5))[0].raised);
+    }
+
+    #[test]
+    fn hysteresis_keeps_alarm_until_usage_recovers() {
+        let mut alerts = AlertState::default();
+        let now = Instant::now();
+        alerts.update(95.0, now, &[]);
+        alerts.acknowledge();
+        alerts.update(CRITICAL_THRESHOLD - CRITICAL_HYSTERESIS + 0.5, now, &[]);
+        assert!(alerts.critical);
+        assert!(alerts.acknowledged);
+        alerts.update(CRITICAL_THRESHOLD - CRITICAL_HYSTERESIS, now, &[]);
+        assert!(!alerts.critical);
+        assert!(!alerts.acknowledged);
+    }
+
+    #[test]
+    fn stages_fire_once_in_order() {
+        let mut alerts = AlertState::default();
+        let stages = [stage(0.0), stage(5.0), stage(15.0)];
+        let start = Instant::now();
+        assert_eq!(alerts.update(95.0, start, &stages), [0]);
+        assert!(alerts.update(95.0, start + Duration::from_secs(60), &stages).is_empty());
+        assert_eq!(alerts.update(95.0, start + Duration::from_secs(20 * 60), &stages), [1, 2]);
+        assert_eq!(alerts.escalation_level(), 3);
+    }
+
+    #[test]
+    fn acknowledged_alarm_does_not_escalate() {
+        let mut alerts = AlertState::default();
+        let stages = [stage(1.0)];
+        let start = Instant::now();
+        alerts.update(95.0, start, &stages);
+        alerts.acknowledge();
+        assert!(alerts.update(95.0, start + Duration::from_secs(120), &stages).is_empty());
+        assert_eq!(alerts.escalation_level(), 0);
+    }
+
+    #[test]
+    fn acknowledging_or_snoozing_silences_the_alarm() {
+        let mut alerts = AlertState::default();
+        let start = Instant::now();
+        alerts.update(95.0, start, &[]);
+        assert!(alerts.is_sounding(start));
+        alerts.snooze(start + Duration::from_secs(60));
+        assert!(!alerts.is_sounding(start));
+        assert!(alerts.is_sounding(start + Duration::from_secs(60)));
+        alerts.acknowledge();
+        assert!(alerts.critical && !alerts.is_sounding(start + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn snooze_outlives_the_alarm_and_restarts_escalation() {
+        let mut alerts = AlertState::default();
+        let stages = [stage(1.0)];
+        let start = Instant::now();
+        let minutes = |m: u64| start + Duration::from_secs(m * 60);
+        alerts.snooze(minutes(30));
+        assert!(alerts.update(95.0, minutes(5), &stages).is_empty());
+        alerts.update(50.0, minutes(10), &stages);
+        assert!(alerts.is_snoozed(minutes(10)));
+        assert!(alerts.update(95.0, minutes(29), &stages).is_empty());
+        assert!(alerts.update(95.0, minutes(30), &stages).is_empty());
+        assert_eq!(alerts.update(95.0, minutes(31), &stages), [0]);
+        assert_eq!(alerts.snooze_remaining(minutes(31)), None);
+    }
+}
@@ -0,0 +1,219 @@
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use sysinfo::{System, SystemExt};
+
+use rusty_mem_monitor::alert::Thresholds;
+use rusty_mem_monitor::History;
+
+use crate::diag;
+use crate::jsonl::{self, Alarm};
+use crate::processes;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_REQUEST: usize = 8 * 1024;
+// A tool polling /processes in a loop shouldn't have us walking /proc for each call
+const PROCESS_CACHE: Duration = Duration::from_secs(2);
+
+// What the window shares with the API thread: its history itself, and the alarm
+// and rule states as of the last sample
+#[derive(Clone)]
+pub struct ApiSource {
+    pub history: Arc<Mutex<History>>,
+    pub alerts: Arc<Mutex<AlertsView>>,
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct AlertsView {
+    pub alarm: Alarm,
+    pub thresholds: Thresholds,
+    pub rules: Vec<RuleView>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct RuleView {
+    pub name: String,
+    pub raised: bool,
+}
+
+#[derive(Serialize)]
+struct ProcessView {
+    pid: u32,
+    name: String,
+    user: Option<String>,
+    memory: u64,
+    swap: Option<u64>,
+    start_time: u64,
+}
+
+// `--api-port`: read-only JSON over HTTP on localhost, so other tools can ask
+// the monitor instead of sampling on their own. One request at a time on a
+// thread of its own; nothing here is slow enough to need more.
+pub fn serve(port: u16, source: ApiSource) -> std::io::Result<()> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+    diag::info(format!("API listening on http://127.0.0.1:{}", port));
+    std::thread::Builder::new().name("api".to_string()).spawn(move || {
+        let mut processes = Processes::default();
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else { continue };
+            if let Err(e) = respond(stream, &source, &mut processes) {
+                diag::log(format!("API request failed: {}", e));
+            }
+        }
+    })?;
+    Ok(())
+}
+
+#[derive(Default)]
+struct Processes {
+    sys: Option<System>,
+    listed: Option<(Instant, String)>,
+}
+
+impl Processes {
+    fn json(&mut self) -> String {
+        if let Some((at, json)) = &self.listed {
+            if at.elapsed() < PROCESS_CACHE {
+                return json.clone();
+            }
+        }
+        let sys = self.sys.get_or_insert_with(System::new);
+        let mut rows = processes::collect(sys);
+        rows.sort_by_key(|row| std::cmp::Reverse(row.memory));
+        let views: Vec<ProcessView> = rows
+            .into_iter()
+            .map(|row| ProcessView {
+                pid: row.pid,
+                name: row.name,
+                user: row.user,
+                memory: row.memory,
+                swap: row.swap,
+                start_time: row.start_time,
+            })
+            .collect();
+        let json = serde_json::to_string(&views).unwrap_or_default();
+        self.listed = Some((Instant::now(), json.clone()));
+        json
+    }
+}
+
+fn respond(mut stream: TcpStream, source: &ApiSource, processes: &mut Processes) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|end| end == b"\r\n\r\n") && request.len() < MAX_REQUEST {
+        let read = stream.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..read]);
+    }
+    let request = String::from_utf8_lossy(&request);
+    let mut words = request.lines().next().unwrap_or_default().split_whitespace();
+    let (status, body) = match (words.next(), words.next()) {
+        (Some("GET"), Some(target)) => route(target, source, processes),
+        (Some(_), Some(_)) => error(405, "only GET is supported"),
+        _ => error(400, "malformed request"),
+    };
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Service Unavailable",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    )
+}
+
+fn route(target: &str, source: &ApiSource, processes: &mut Processes) -> (u16, String) {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    match path.trim_end_matches('/') {
+        "/current" => {
+            let latest = source.history.lock().unwrap().last().copied();
+            let alarm = source.alerts.lock().unwrap().alarm;
+            match latest {
+                Some(latest) => (200, jsonl::line(&latest, alarm)),
+                None => error(503, "no samples yet"),
+            }
+        }
+        "/history" => {
+            let window = query.split('&').find_map(|pair| pair.strip_prefix("window="));
+            let window_ms = match window.map(parse_window) {
+                Some(Some(window_ms)) => Some(window_ms),
+                Some(None) => return error(400, "window is a number of seconds, or like 90s, 10m or 2h"),
+                None => None,
+            };
+            let history = source.history.lock().unwrap();
+            let since = match (window_ms, history.last()) {
+                (Some(window_ms), Some(latest)) => latest.timestamp_ms.saturating_sub(window_ms),
+                _ => 0,
+            };
+            let samples: Vec<_> = history.iter().filter(|sample| sample.timestamp_ms >= since).collect();
+            (200, serde_json::to_string(&samples).unwrap_or_default())
+        }
+        "/processes" => (200, processes.json()),
+        "/alerts" => (200, serde_json::to_string(&*source.alerts.lock().unwrap()).unwrap_or_default()),
+        _ => error(404, "try /current, /history, /processes or /alerts"),
+    }
+}
+
+fn error(status: u16, message: &str) -> (u16, String) {
+    (status, serde_json::json!({ "error": message }).to_string())
+}
+
+// Milliseconds in "90", "90s", "10m" or "2h"
+fn parse_window(text: &str) -> Option<u64> {
+    let (number, unit_ms) = match text.char_indices().last()? {
+        (at, 's') => (&text[..at], 1000),
+        (at, 'm') => (&text[..at], 60_000),
+        (at, 'h') => (&text[..at], 3_600_000),
+        _ => (text, 1000),
+    };
+    number.parse::<u64>().ok()?.checked_mul(unit_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusty_mem_monitor::Sample;
+
+    #[test]
+    fn routes_answer_from_the_shared_history() {
+        let source = ApiSource {
+            history: Arc::new(Mutex::new(History::new(10))),
+            alerts: Arc::new(Mutex::new(AlertsView::default())),
+        };
+        let mut processes = Processes::default();
+        assert_eq!(route("/current", &source, &mut processes).0, 503);
+
+        for minute in 0..5 {
+            let timestamp_ms = minute * 60_000;
+            let sample = Sample { timestamp_ms, total_memory: 200, used_memory: 50, ..Sample::default() };
+            source.history.lock().unwrap().push(&sample);
+        }
+        let (status, body) = route("/current", &source, &mut processes);
+        assert_eq!(status, 200);
+        assert!(body.contains("\"timestamp_ms\":240000"));
+
+        let mut history = |target| {
+            let (_, body) = route(target, &source, &mut processes);
+            serde_json::from_str::<Vec<Sample>>(&body).map(|samples| samples.len())
+        };
+        assert_eq!(history("/history").unwrap(), 5);
+        assert_eq!(history("/history?window=2m").unwrap(), 3);
+        assert_eq!(history("/history?window=60").unwrap(), 2);
+        assert_eq!(route("/history?window=soon", &source, &mut processes).0, 400);
+        assert_eq!(route("/nothing", &source, &mut processes).0, 404);
+    }
+}
@@ -0,0 +1,3729 @@
+use eframe::egui;
+use egui_plot::{Bar, BarChart, Legend, Line, LineStyle, Plot, PlotBounds, PlotPoints, VLine};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use sysinfo::SystemExt;
+
+use rusty_mem_monitor::alert::RuleState;
+use rusty_mem_monitor::health::{self, Health};
+use rusty_mem_monitor::hugepages::HugePages;
+use rusty_mem_monitor::sample::now_ms;
+use rusty_mem_monitor::stats;
+use rusty_mem_monitor::units::{format_bytes, format_bytes_delta};
+use rusty_mem_monitor::zram::SwapCompression;
+use rusty_mem_monitor::{AlertState, History, Sample, Sampler};
+
+use crate::accounting::{self, Reconciliation};
+use crate::api::{self, AlertsView, ApiSource, RuleView};
+use crate::bookmarks::{self, Bookmark, BookmarkReader};
+use crate::budget::Budgets;
+use crate::cli::{self, OutputFormat};
+use crate::config::{BudgetConfig, Config, GlitchConfig, HighlightRule, SamplingConfig, Smoothing};
+use crate::containers::{Container, ContainerWatcher};
+use crate::control::Command;
+use crate::crt;
+use crate::daemon::{self, DaemonClient};
+use crate::dashboard::{self, Dashboard};
+#[cfg(all(feature = "dbus", target_os = "linux"))]
+use crate::dbus::{self, DbusService};
+use crate::energy::EnergyMeter;
+use crate::highlight::Highlighter;
+use crate::events::{EventKind, EventLog};
+use crate::focus::ProcessFocus;
+use crate::i18n::{self, tr, tr_args};
+use crate::influx::InfluxSink;
+use crate::jsonl::{Alarm, JsonlOutput};
+use crate::inhibit::SleepInhibitor;
+use crate::layout::{self, Layout};
+#[cfg(feature = "mqtt")]
+use crate::mqtt::MqttSink;
+use crate::logging;
+use crate::offenders::{Leaderboard, Period};
+use crate::presentation::Presentation;
+use crate::processes::{
+    self, Privileges, ProcessColumn, ProcessGrouping, ProcessRow, ProcessScanner, ProcessSort, Selection,
+};
+use crate::profiles::Comparison;
+use crate::rain::Rain;
+use crate::recorder::{self, Recorder};
+use crate::plugins::{self, PluginHost};
+use crate::registry::{self, RegistryClient};
+use crate::report;
+use crate::screenshot;
+use crate::selfstats::SelfStats;
+use crate::series::{relative_secs, PlotSeries};
+use crate::sensors::{Sensor, SensorReader};
+use crate::smaps;
+use crate::snapshot::{Snapshot, SnapshotDiff};
+use crate::soak::Soak;
+#[cfg(feature = "audio")]
+use crate::sound::AlarmSound;
+use crate::source::Source;
+use crate::theme::{self, Theme};
+use crate::topn::TopProcesses;
+use crate::watchdog::Backend;
+#[cfg(feature = "web")]
+use crate::web::WebServer;
+use crate::{bugreport, diag, notify, pagefile, pressure};
+
+// Waiting on the confirmation window
+enum PendingAction {
+    Kill(notify::KillTarget),
+    SystemChange(pagefile::Action),
+}
+
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum PlotMode {
+    Lines,
+    Stacked,
+    TopProcesses,
+}
+
+pub const FULL_SIZE: [f32; 2] = [500.0, 700.0];
+pub const COMPACT_SIZE: [f32; 2] = [240.0, 56.0];
+// How often the bookmark file is checked for marks added from other sessions
+const BOOKMARK_POLL: Duration = Duration::from_secs(2);
+const WEBHOOK_RETRY: Duration = Duration::from_secs(60);
+const REPAINT_INTERVAL: Duration = Duration::from_millis(500);
+// Frame pacing and process walks slow down this much while memory is critical
+const LEAN_REPAINT_INTERVAL: Duration = Duration::from_secs(2);
+// Minimized: samples keep queueing from the sampler thread and are caught up on the next frame
+const HIDDEN_REPAINT_INTERVAL: Duration = Duration::from_secs(10);
+const LEAN_SLOWDOWN: u32 = 4;
+// Samples count as stale once this many intervals, and at least STALE_AFTER, pass without one
+const STALE_SAMPLES: u32 = 5;
+const STALE_AFTER: Duration = Duration::from_secs(5);
+const PROCESS_ROWS: usize = 15;
+const EVENT_LIST_HEIGHT: f32 = 150.0;
+const OFFENDER_ROWS: usize = 10;
+const SNOOZE_MINUTES: [u64; 3] = [15, 60, 120];
+// Points under the warning threshold from which presentation detection runs
+const PRESENTATION_MARGIN: f32 = 10.0;
+// The window reports its old size for a few frames after switching modes
+const WINDOW_SETTLE: Duration = Duration::from_millis(500);
+// Soak mode halves the history when over its memory cap, but never below this
+const SOAK_MIN_HISTORY: usize = 120;
+const MIN_UI_SCALE: f32 = 0.5;
+const MAX_UI_SCALE: f32 = 3.0;
+
+pub struct MemoryMonitor {
+    sampler: Sampler,
+    // Shared with the --api-port thread
+    history: Arc<Mutex<History>>,
+    plot_mode: PlotMode,
+    glitch_effect: bool,
+    alerts: AlertState,
+    // [[alerts.rules]], raised and cleared alongside the critical alarm
+    rules: RuleState,
+    // What /alerts answers with, refreshed after every sample
+    api_alerts: Option<Arc<Mutex<AlertsView>>>,
+    #[cfg(all(feature = "dbus", target_os = "linux"))]
+    dbus: Option<DbusService>,
+    alert_commands: notify::AlertCommands,
+    pending_action: Option<PendingAction>,
+    // Test alerts by channel label: still on their way, or how they went
+    channel_tests: Vec<(String, notify::ChannelTest)>,
+    settings_open: bool,
+    #[cfg(feature = "audio")]
+    alarm_sound: AlarmSound,
+    paused: bool,
+    compact: bool,
+    snapshots: Vec<Snapshot>,
+    snapshot_name: String,
+    // Snapshot indices picked for the diff table; None on the right means now
+    diff_pick: (usize, Option<usize>),
+    snapshot_diff: Option<(String, String, SnapshotDiff)>,
+    events: EventLog,
+    bookmark_text: String,
+    bookmarks: BookmarkReader,
+    bookmarks_polled: Option<Instant>,
+    webhooks_retried: Option<Instant>,
+    config: Config,
+    registry: RegistryClient,
+    plugins: PluginHost,
+    daemon: Option<DaemonClient>,
+    // Started on first use when there's no daemon to attach to
+    local: Option<DaemonClient>,
+    // Shared with the local sampling thread, which picks up changes on its next sample
+    sample_interval_ms: Arc<AtomicU64>,
+    // Local-only panels are hidden for anything else
+    source: Source,
+    // Set instead of a remote source when several agents were given
+    dashboard: Option<Dashboard>,
+    latest: Sample,
+    recorder: Option<Recorder>,
+    inhibitor: Option<SleepInhibitor>,
+    recording_status: String,
+    bug_report: Option<String>,
+    themes: Vec<Theme>,
+    theme: Theme,
+    safe_mode: bool,
+    profile: String,
+    layout: Layout,
+    // Applied on the first frame the main plot is drawn
+    restore_plot_bounds: Option<[f64; 4]>,
+    compact_switched: Option<Instant>,
+    // Set when the y units change, since zoomed bounds in the old units mean nothing
+    reset_plot: bool,
+    // Critical pressure on this machine: glitches off, fewer frames, caches dropped
+    lean: bool,
+    focus: Option<ProcessFocus>,
+    // Recorded only while its chart is shown
+    top_processes: TopProcesses,
+    budgets: Budgets,
+    // Commands sent to the remote agent and not yet answered
+    agent_requests: Vec<(u64, Command)>,
+    agent_recording: bool,
+    process_rows: Vec<ProcessRow>,
+    process_sort: ProcessSort,
+    process_grouping: ProcessGrouping,
+    processes_refreshed: Option<Instant>,
+    process_scanner: ProcessScanner,
+    highlighter: Highlighter,
+    // "What if I close this?" pick and its (process count, bytes freed), recomputed on refresh
+    what_if: Option<Selection>,
+    what_if_freed: Option<(usize, u64)>,
+    // Panel backends run off the UI thread, so a hung one only degrades its panel
+    containers_backend: Backend<ContainerWatcher, Vec<Container>>,
+    containers: Vec<Container>,
+    containers_refreshed: Option<Instant>,
+    hugepages: Option<HugePages>,
+    hugepages_refreshed: Option<Instant>,
+    hugepages_backend: Backend<(), Option<HugePages>>,
+    // zram and zswap, shown under the readouts since they change what "swap" means
+    swap_compression: SwapCompression,
+    swap_compression_refreshed: Option<Instant>,
+    // Not kept in safe mode, which writes nothing to disk
+    offenders: Option<Leaderboard>,
+    offender_period: Period,
+    // Hostname, load and uptime above the title; local machine only
+    status_strip: Option<String>,
+    status_strip_refreshed: Option<Instant>,
+    sensors_backend: Backend<SensorReader, Vec<Sensor>>,
+    self_stats: SelfStats,
+    plot_series: PlotSeries,
+    privileges: Option<Privileges>,
+    reconciliation: Option<Result<Reconciliation, String>>,
+    presentation: Presentation,
+    rain: Rain,
+    // Warning and recovery commands kept back until the presentation ends
+    held_alerts: Vec<(notify::Transition, f32)>,
+    // When the sampler last delivered anything
+    sampled: Instant,
+    soak: Option<Soak>,
+    // Only fed samples taken in this process; a daemon pushes its own
+    influx: Option<InfluxSink>,
+    // Same, and held until the alarm has caught up with them
+    #[cfg(feature = "mqtt")]
+    mqtt: Option<MqttSink>,
+    #[cfg(feature = "mqtt")]
+    mqtt_pending: Vec<Sample>,
+    // --web gets every sample shown, a remote host's included
+    #[cfg(feature = "web")]
+    web: Option<WebServer>,
+    theme_import_path: String,
+    theme_status: String,
+    // Profiles picked for comparison in the settings, and what was found
+    profile_names: Vec<String>,
+    compared: (String, String),
+    comparison: Option<Result<Comparison, String>>,
+    // The zoom last set from `appearance.ui_scale`
+    applied_scale: Option<f32>,
+    usage_bar: UsageBar,
+    compact_bar: UsageBar,
+    sensors: Vec<Sensor>,
+    sensors_refreshed: Option<Instant>,
+    // Local machine only; RAPL says nothing about a remote host's power draw
+    energy: Option<EnergyMeter>,
+    pagefile_query: Option<std::sync::mpsc::Receiver<Result<pagefile::PagefileInfo, String>>>,
+    pagefile_info: Option<Result<pagefile::PagefileInfo, String>>,
+    mac_memory: Option<Result<pressure::MacMemory, String>>,
+    mac_memory_refreshed: Option<Instant>,
+    // --output jsonl, and the samples it's still to get, written once the alarm
+    // state they led to is known
+    jsonl: Option<JsonlOutput>,
+    jsonl_pending: Vec<Sample>,
+    // The headline score, reassessed with every batch of samples
+    health: Option<Health>,
+    // Linux PSI, read at the system stats interval; local machine only
+    psi: Option<f64>,
+    psi_read: Option<Instant>,
+    mac_memory_backend: Backend<(), Result<pressure::MacMemory, String>>,
+}
+
+impl MemoryMonitor {
+    pub fn new(args: &cli::Args, layout: Layout) -> Self {
+        // Starts out with memory counters only; the process list and anything else
+        // sysinfo knows about is first loaded when a panel or action asks for it
+        let mut sampler = Sampler::new();
+        let source = Source::of(args);
+        // This machine's own memory, rather than an agent's, a dashboard's or a file's
+        let local = source.is_local() && args.connect.is_empty();
+        let latest = if local { sampler.sample() } else { Sample::default() };
+        // Safe mode ignores everything on disk so a bad config or theme can't
+        // keep the app from starting, and never writes back over it
+        let config = if args.safe_mode { Config::default() } else { Config::load() };
+        let themes = if args.safe_mode { theme::presets() } else { theme::available() };
+        i18n::init(&config.appearance.language, !args.safe_mode);
+        let theme = theme::find(&themes, &config.appearance.theme);
+        let sample_interval_ms = Arc::new(AtomicU64::new(config.sampling.interval().as_millis() as u64));
+        let highlighter = Highlighter::new(&config.processes.highlight);
+        let soak = (args.soak || config.soak.enabled).then(|| Soak::start(&config.soak));
+        let history = Arc::new(Mutex::new(History::new(config.sampling.history_len())));
+        let api_alerts = args.api_port.and_then(|port| {
+            let alerts = Arc::new(Mutex::new(AlertsView::default()));
+            let shared = ApiSource { history: Arc::clone(&history), alerts: Arc::clone(&alerts) };
+            match api::serve(port, shared) {
+                Ok(()) => Some(alerts),
+                Err(e) => {
+                    diag::log(format!("Could not serve the API on port {}: {}", port, e));
+                    None
+                }
+            }
+        });
+        let host = sampler.system_mut().host_name().unwrap_or_default();
+        #[cfg(feature = "mqtt")]
+        let mqtt = if local { MqttSink::start(&config.mqtt, &host) } else { None };
+        #[cfg(feature = "web")]
+        let web = args.web.as_deref().and_then(|addr| {
+            // A single remote host or a replay is what's on screen, so it's what the page is named after
+            let host = source.label().unwrap_or_else(|| host.clone());
+            WebServer::start(addr, &host)
+                .map_err(|e| diag::log(format!("Could not serve the web view on {}: {}", addr, e)))
+                .ok()
+        });
+        let influx = if local { InfluxSink::start(&config.influx, host) } else { None };
+        let daemon = source.client(args, &config.agent);
+        let dashboard = (args.connect.len() > 1).then(|| Dashboard::new(&args.connect, &config.agent));
+        let mut budgets = config.budgets.clone();
+        budgets.extend(args.budgets.iter().map(|&(pid, soft_mb, hard_mb)| BudgetConfig {
+            pid,
+            soft_mb,
+            hard_mb,
+            ..BudgetConfig::default()
+        }));
+        let budgets = Budgets::new(budgets);
+        Self {
+            sampler,
+            history,
+            // Only this machine's processes are listed
+            plot_mode: match layout.plot_mode {
+                PlotMode::TopProcesses if !local => PlotMode::Lines,
+                mode => mode,
+            },
+            glitch_effect: false,
+            alerts: AlertState::default(),
+            rules: RuleState::default(),
+            api_alerts,
+            #[cfg(all(feature = "dbus", target_os = "linux"))]
+            dbus: if args.safe_mode {
+                None
+            } else {
+                DbusService::start()
+                    .map_err(|e| diag::log(format!("Could not register on the session bus: {}", e)))
+                    .ok()
+            },
+            alert_commands: notify::AlertCommands::default(),
+            pending_action: None,
+            channel_tests: Vec::new(),
+            settings_open: false,
+            #[cfg(feature = "audio")]
+            alarm_sound: AlarmSound::default(),
+            paused: false,
+            compact: args.compact || layout.compact,
+            snapshots: Vec::new(),
+            diff_pick: (0, None),
+            snapshot_diff: None,
+            snapshot_name: String::new(),
+            events: EventLog::default(),
+            bookmark_text: String::new(),
+            bookmarks: BookmarkReader::new(),
+            bookmarks_polled: None,
+            webhooks_retried: None,
+            config,
+            registry: RegistryClient::new(),
+            plugins: if args.safe_mode { PluginHost::default() } else { PluginHost::load() },
+            daemon,
+            local: None,
+            sample_interval_ms,
+            source,
+            dashboard,
+            latest,
+            recorder: None,
+            inhibitor: None,
+            recording_status: String::new(),
+            bug_report: None,
+            themes,
+            theme,
+            safe_mode: args.safe_mode,
+            profile: args.profile.clone().unwrap_or_else(|| layout::DEFAULT_PROFILE.to_string()),
+            restore_plot_bounds: layout.plot_bounds,
+            compact_switched: None,
+            reset_plot: false,
+            lean: false,
+            focus: args.pid.map(ProcessFocus::new),
+            top_processes: TopProcesses::default(),
+            budgets,
+            agent_requests: Vec::new(),
+            agent_recording: false,
+            process_rows: Vec::new(),
+            process_sort: layout.process_sort,
+            process_grouping: layout.process_grouping,
+            processes_refreshed: None,
+            process_scanner: ProcessScanner::default(),
+            highlighter,
+            what_if: None,
+            what_if_freed: None,
+            containers_backend: Backend::new("containers"),
+            containers: Vec::new(),
+            containers_refreshed: None,
+            hugepages: None,
+            hugepages_refreshed: None,
+            hugepages_backend: Backend::new("hugepages"),
+            swap_compression: SwapCompression::default(),
+            swap_compression_refreshed: None,
+            offenders: (!args.safe_mode && local).then(Leaderboard::load),
+            offender_period: Period::Day,
+            status_strip: None,
+            status_strip_refreshed: None,
+            sensors_backend: Backend::new("sensors"),
+            self_stats: SelfStats::default(),
+            plot_series: PlotSeries::default(),
+            privileges: None,
+            reconciliation: None,
+            presentation: Presentation::default(),
+            rain: Rain::default(),
+            held_alerts: Vec::new(),
+            sampled: Instant::now(),
+            soak,
+            influx,
+            #[cfg(feature = "mqtt")]
+            mqtt,
+            #[cfg(feature = "mqtt")]
+            mqtt_pending: Vec::new(),
+            #[cfg(feature = "web")]
+            web,
+            theme_import_path: String::new(),
+            theme_status: String::new(),
+            profile_names: Vec::new(),
+            compared: (String::new(), String::new()),
+            comparison: None,
+            applied_scale: None,
+            usage_bar: UsageBar::default(),
+            compact_bar: UsageBar::default(),
+            sensors: Vec::new(),
+            sensors_refreshed: None,
+            energy: local.then(EnergyMeter::new),
+            pagefile_query: None,
+            pagefile_info: None,
+            mac_memory: None,
+            mac_memory_refreshed: None,
+            health: None,
+            jsonl: match args.output {
+                Some(OutputFormat::Jsonl) => match JsonlOutput::open(args.output_file.as_deref()) {
+                    Ok(output) => Some(output),
+                    Err(e) => {
+                        diag::log(format!("Could not open the JSONL output: {}", e));
+                        None
+                    }
+                },
+                None => None,
+            },
+            jsonl_pending: Vec::new(),
+            psi: None,
+            psi_read: None,
+            mac_memory_backend: Backend::new("memory pressure"),
+            layout,
+        }
+    }
+
+    fn start_recording(&mut self) {
+        match Recorder::start() {
+            Ok(recorder) => {
+                self.set_output_status(Ok(tr_args("status-recording", &[("path", &recorder.path().display())])));
+                self.recorder = Some(recorder);
+                self.update_inhibitor();
+            }
+            Err(e) => self.set_recording_error(tr_args("status-recording-failed", &[("error", &e)])),
+        }
+    }
+
+    fn stop_recording(&mut self) {
+        if let Some(recorder) = self.recorder.take() {
+            self.set_output_status(Ok(tr_args(
+                "status-recording-saved",
+                &[("count", &recorder.samples()), ("path", &recorder.path().display())],
+            )));
+        }
+        self.update_inhibitor();
+    }
+
+    fn set_recording_error(&mut self, message: String) {
+        self.set_output_status(Err(message));
+    }
+
+    // Shown under the controls and logged, failures as warnings
+    fn set_output_status(&mut self, status: Result<String, String>) {
+        match &status {
+            Ok(message) => diag::info(message),
+            Err(message) => diag::log(message),
+        }
+        self.recording_status = status.unwrap_or_else(|message| message);
+    }
+
+    fn update_inhibitor(&mut self) {
+        let wanted = self.recorder.is_some() && self.config.recording.inhibit_sleep;
+        if !wanted {
+            self.inhibitor = None;
+        } else if self.inhibitor.is_none() {
+            match SleepInhibitor::acquire("Recording memory samples") {
+                Ok(inhibitor) => self.inhibitor = Some(inhibitor),
+                Err(e) => self.set_recording_error(tr_args("status-inhibit-failed", &[("error", &e)])),
+            }
+        }
+    }
+
+    // Attached instances consume the daemon's stream; if the daemon goes away we
+    // quietly fall back to sampling on our own. A remote agent is waited for instead,
+    // and a replay that couldn't be read, or has ended, leaves the plot where it is.
+    fn collect_samples(&mut self) -> Vec<Sample> {
+        if let Some(daemon) = &self.daemon {
+            if daemon.is_connected() || !self.source.is_local() {
+                return daemon.poll();
+            }
+            self.daemon = None;
+            diag::log("Lost connection to sampler daemon, sampling locally");
+        }
+        if !self.source.is_local() {
+            return Vec::new();
+        }
+        let interval_ms = Arc::clone(&self.sample_interval_ms);
+        let samples = self.local.get_or_insert_with(|| DaemonClient::in_process(interval_ms)).poll();
+        if let Some(influx) = &self.influx {
+            samples.iter().for_each(|sample| influx.push(sample));
+        }
+        #[cfg(feature = "mqtt")]
+        if self.mqtt.is_some() {
+            self.mqtt_pending.extend_from_slice(&samples);
+        }
+        samples
+    }
+
+    fn push_sample(&mut self, sample: Sample) {
+        logging::sample(&sample);
+        if self.jsonl.is_some() {
+            self.jsonl_pending.push(sample);
+        }
+        self.history.lock().unwrap().push(&sample);
+        #[cfg(feature = "web")]
+        if let Some(web) = &self.web {
+            web.push(&sample);
+        }
+        if self.events.observe(&sample, &self.config.alerts.thresholds) {
+            self.fire_alert_command(notify::Transition::Warning, sample.memory_percentage());
+        }
+        self.plugins.update(&sample);
+        self.latest = sample;
+
+        if let Some(recorder) = &mut self.recorder {
+            if let Err(e) = recorder.record(&sample) {
+                self.set_recording_error(tr_args("status-recording-stopped", &[("error", &e)]));
+                self.recorder = None;
+                self.update_inhibitor();
+            }
+        }
+    }
+
+    fn write_jsonl(&mut self) {
+        let Some(output) = &mut self.jsonl else { return };
+        let alarm = Alarm::of(&self.alerts);
+        let written = self.jsonl_pending.drain(..).try_for_each(|sample| output.write(&sample, alarm));
+        if let Err(e) = written {
+            // A closed pipe means the reader is done; the window carries on
+            diag::log(format!("JSONL output stopped: {}", e));
+            self.jsonl = None;
+        }
+    }
+
+    #[cfg(feature = "mqtt")]
+    fn publish_mqtt(&mut self) {
+        let Some(mqtt) = &self.mqtt else { return };
+        let alarm = Alarm::of(&self.alerts);
+        self.mqtt_pending.drain(..).for_each(|sample| mqtt.push(&sample, alarm));
+    }
+
+    fn share_alerts(&self) {
+        let Some(shared) = &self.api_alerts else { return };
+        let rules = self.config.alerts.rules.iter().enumerate().map(|(index, rule)| RuleView {
+            name: rule.name.clone(),
+            raised: self.rules.is_raised(index),
+        });
+        *shared.lock().unwrap() = AlertsView {
+            alarm: Alarm::of(&self.alerts),
+            thresholds: self.config.alerts.thresholds,
+            rules: rules.collect(),
+        };
+    }
+
+    #[cfg(all(feature = "dbus", target_os = "linux"))]
+    fn serve_dbus(&mut self) {
+        let Some(service) = &self.dbus else { return };
+        service.update(&self.latest, self.alerts.critical);
+        for request in service.requests() {
+            match request {
+                dbus::Request::Snapshot(name) => {
+                    // Whatever's typed in the snapshot field stays there
+                    let typed = std::mem::replace(&mut self.snapshot_name, name);
+                    self.take_snapshot();
+                    self.snapshot_name = typed;
+                }
+            }
+        }
+    }
+
+    fn fire_alert_command(&mut self, transition: notify::Transition, memory_percentage: f32) {
+        // Automation hears about every transition; snoozing and presentations
+        // only hold back our own notifications
+        #[cfg(all(feature = "dbus", target_os = "linux"))]
+        if let Some(service) = &self.dbus {
+            service.alarm_changed(transition, memory_percentage);
+        }
+        if self.alerts.is_snoozed(Instant::now()) {
+            return;
+        }
+        let holdable = transition != notify::Transition::Critical && self.config.alerts.hold_while_presenting;
+        if holdable && self.presentation.is_active() {
+            // Only the latest of each kind is worth sending afterwards
+            self.held_alerts.retain(|(held, _)| *held != transition);
+            self.held_alerts.push((transition, memory_percentage));
+            let message = format!("Held {} notification during a presentation", transition.name());
+            self.events.push(now_ms(), EventKind::Threshold, message);
+            return;
+        }
+        let hostname = self.alert_hostname();
+        let dry_run = self.config.actions.dry_run;
+        self.alert_commands.fire(transition, &self.config.alerts, dry_run, &hostname, memory_percentage);
+    }
+
+    fn release_held_alerts(&mut self, memory_percentage: f32) {
+        let mut held = std::mem::take(&mut self.held_alerts);
+        // A warning that has since cleared would only be noise now
+        held.retain(|(transition, _)| {
+            *transition != notify::Transition::Warning || memory_percentage > self.config.alerts.thresholds.warning
+        });
+        if held.is_empty() {
+            return;
+        }
+        let message = format!("Presentation over: sending {} held notification(s)", held.len());
+        self.events.push(now_ms(), EventKind::Threshold, message);
+        let hostname = self.alert_hostname();
+        let dry_run = self.config.actions.dry_run;
+        for (transition, percentage) in held {
+            self.alert_commands.fire(transition, &self.config.alerts, dry_run, &hostname, percentage);
+        }
+    }
+
+    fn save_config(&self) -> std::io::Result<()> {
+        if self.safe_mode {
+            return Ok(());
+        }
+        self.config.save()
+    }
+
+    fn alert_hostname(&mut self) -> String {
+        match self.source.label() {
+            Some(label) => label,
+            None => self.sampler.system_mut().host_name().unwrap_or_else(|| "unknown host".to_string()),
+        }
+    }
+
+    fn process_refresh(&self) -> Duration {
+        self.slowed(self.config.sampling.process_interval())
+    }
+
+    fn system_refresh(&self) -> Duration {
+        self.slowed(self.config.sampling.system_interval())
+    }
+
+    // How long the sampler has been silent, once that's well past its interval: a
+    // hung backend or a daemon that stopped sending, rather than a quiet machine
+    fn stale_for(&self) -> Option<Duration> {
+        if self.paused {
+            return None;
+        }
+        let interval = if self.daemon.is_some() { daemon::SAMPLE_INTERVAL } else { self.config.sampling.interval() };
+        let age = self.sampled.elapsed();
+        (age > (interval * STALE_SAMPLES).max(STALE_AFTER)).then_some(age)
+    }
+
+    fn refreshed_ui(&self, ui: &mut egui::Ui, refreshed: Option<Instant>) {
+        if let Some(at) = refreshed {
+            let age = tr_args("updated-ago", &[("age", &format_duration(at.elapsed().as_secs()))]);
+            ui.label(egui::RichText::new(age).color(self.theme.dim).small().monospace());
+        }
+    }
+
+    // Percentages from a sample without totals would read as a reassuring 0%
+    fn percent_text(&self, percentage: f32) -> String {
+        if self.latest.has_memory() {
+            format!("{:.1}%", percentage)
+        } else {
+            tr("not-available")
+        }
+    }
+
+    // A panel whose backend hung or failed, with when it's tried again
+    fn degraded_ui(&self, ui: &mut egui::Ui, retry_in: Option<Duration>) {
+        if let Some(retry_in) = retry_in {
+            let text = tr_args("backend-degraded", &[("age", &format_duration(retry_in.as_secs().max(1)))]);
+            ui.label(egui::RichText::new(text).color(self.theme.warning).small().monospace());
+        }
+    }
+
+    fn slowed(&self, interval: Duration) -> Duration {
+        if self.lean { interval * LEAN_SLOWDOWN } else { interval }
+    }
+
+    // Nobody's reading the window between samples unless it has focus, so the frame
+    // rate only outpaces the sampling while it does
+    fn repaint_interval(&self, ctx: &egui::Context) -> Duration {
+        let (focused, minimized) = ctx.input(|i| (i.focused, i.viewport().minimized.unwrap_or(false)));
+        // A remote agent samples at a rate we don't know
+        let sampling = if !self.source.is_local() || self.dashboard.is_some() {
+            REPAINT_INTERVAL
+        } else {
+            self.config.sampling.interval()
+        };
+        let interval = if minimized {
+            HIDDEN_REPAINT_INTERVAL
+        } else if focused {
+            REPAINT_INTERVAL.min(sampling)
+        } else {
+            REPAINT_INTERVAL.max(sampling)
+        };
+        if self.lean {
+            return interval.max(LEAN_REPAINT_INTERVAL);
+        }
+        match self.rain_intensity() {
+            Some(intensity) if focused && !minimized => interval.min(Rain::frame(intensity)),
+            _ => interval,
+        }
+    }
+
+    // None when the rain is off, including in the compact widget and in lean mode
+    fn rain_intensity(&self) -> Option<f32> {
+        let appearance = &self.config.appearance;
+        if !appearance.rain || appearance.reduced_motion || self.lean || self.compact || self.dashboard.is_some() {
+            return None;
+        }
+        if self.alerts.critical {
+            return Some(1.0);
+        }
+        let critical = self.config.alerts.thresholds.critical.max(1.0);
+        Some((self.latest.memory_percentage() / critical).clamp(0.0, 1.0) * 0.85)
+    }
+
+    // Gives back what's cheap to rebuild: the process table sysinfo keeps, cached
+    // rows and the last bug report. They're filled in again on demand.
+    fn shed_memory(&mut self) {
+        self.sampler = Sampler::new();
+        self.process_rows = Vec::new();
+        self.processes_refreshed = None;
+        self.process_scanner.reset();
+        self.sensors_backend = Backend::new("sensors");
+        self.what_if_freed = None;
+        self.containers = Vec::new();
+        self.containers_refreshed = None;
+        self.bug_report = None;
+    }
+
+    fn apply_accessibility(&mut self, ctx: &egui::Context) {
+        let zoom = ctx.zoom_factor();
+        let scale = self.config.appearance.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+        match self.applied_scale {
+            // Ctrl +/- zoomed since: keep that as the setting
+            Some(applied) if zoom != applied => {
+                self.config.appearance.ui_scale = zoom;
+                self.applied_scale = Some(zoom);
+                if let Err(e) = self.save_config() {
+                    diag::log(format!("Could not save config: {}", e));
+                }
+            }
+            Some(applied) if applied == scale => {}
+            // Waits for the slider to be let go, or the window would rescale under the pointer
+            _ if !ctx.is_using_pointer() => {
+                ctx.set_zoom_factor(scale);
+                self.applied_scale = Some(scale);
+            }
+            _ => {}
+        }
+        let reduced_motion = self.config.appearance.reduced_motion;
+        let animation_time = if reduced_motion { 0.0 } else { egui::Style::default().animation_time };
+        if ctx.style().animation_time != animation_time {
+            let mut style = (*ctx.style()).clone();
+            style.animation_time = animation_time;
+            ctx.set_style(style);
+        }
+    }
+
+    fn check_soak(&mut self) {
+        let Some(soak) = &mut self.soak else { return };
+        let recording = self.recorder.as_ref().map(|recorder| recorder.path().as_path());
+        let keep: Vec<&std::path::Path> = recording.into_iter().collect();
+        let footprint = soak.check(self.sampler.system_mut(), self.history.lock().unwrap().len(), &keep);
+        if !footprint.is_some_and(|footprint| footprint.over_memory) {
+            return;
+        }
+        self.shed_memory();
+        let mut history = self.history.lock().unwrap();
+        let capacity = (history.capacity() / 2).max(SOAK_MIN_HISTORY);
+        if capacity < history.capacity() {
+            history.set_capacity(capacity);
+            self.plot_series = PlotSeries::default();
+        }
+        diag::info(format!("Soak mode: over the memory cap, keeping {} samples of history", capacity));
+    }
+
+    fn take_snapshot(&mut self) {
+        let name = if self.snapshot_name.trim().is_empty() {
+            format!("snapshot-{}", self.snapshots.len() + 1)
+        } else {
+            std::mem::take(&mut self.snapshot_name).trim().to_string()
+        };
+        let snapshot = if !self.source.is_local() {
+            Snapshot::from_sample(&self.latest, name)
+        } else {
+            Snapshot::capture(self.sampler.system_mut(), name)
+        };
+        self.events.push(now_ms(), EventKind::Snapshot, format!("Snapshot \"{}\"", snapshot.name));
+        self.snapshots.push(snapshot);
+    }
+
+    // Safe mode writes nothing, so the mark stays in this session's event log
+    fn add_bookmark(&mut self) {
+        let text = std::mem::take(&mut self.bookmark_text);
+        let text = if text.trim().is_empty() { tr("bookmark-default") } else { text.trim().to_string() };
+        if self.safe_mode {
+            self.push_bookmark(Bookmark { timestamp_ms: now_ms(), text });
+            return;
+        }
+        match bookmarks::add(&text) {
+            Ok(_) => self.poll_bookmarks(),
+            Err(e) => self.set_output_status(Err(tr_args("status-bookmark-failed", &[("error", &e)]))),
+        }
+    }
+
+    // Picks up marks from this window and from `--bookmark` runs alike
+    fn poll_bookmarks(&mut self) {
+        self.bookmarks_polled = Some(Instant::now());
+        for bookmark in self.bookmarks.poll() {
+            self.push_bookmark(bookmark);
+        }
+    }
+
+    fn export_report(&mut self) {
+        let host = self.alert_hostname();
+        let mut processes = Vec::new();
+        if self.source.is_local() {
+            processes = processes::collect(self.sampler.system_mut());
+            processes::sort(&mut processes, ProcessSort::Memory);
+            processes.truncate(PROCESS_ROWS);
+        }
+        let offenders = self.offenders.as_ref().map(|offenders| offenders.top(Period::Day, now_ms(), PROCESS_ROWS));
+        let history = self.history.lock().unwrap();
+        let report = report::Report {
+            host,
+            history: &history,
+            events: self.events.iter().collect(),
+            processes,
+            offenders: offenders.unwrap_or_default(),
+        };
+        let written = report::write(&report);
+        drop(history);
+        self.set_output_status(match written {
+            Ok(path) => Ok(tr_args("status-report-saved", &[("path", &path.display())])),
+            Err(e) => Err(tr_args("status-report-failed", &[("error", &e)])),
+        });
+    }
+
+    fn export_screenshot(&mut self, format: screenshot::Format) {
+        let host = self.alert_hostname();
+        let path = match recorder::output_path("screenshots", "plot", format.extension()) {
+            Ok(path) => path,
+            Err(e) => {
+                self.set_output_status(Err(tr_args("status-screenshot-failed", &[("error", &e)])));
+                return;
+            }
+        };
+        let written = screenshot::write(&path, &host, &self.history.lock().unwrap());
+        self.set_output_status(match written {
+            Ok(()) => Ok(tr_args("status-screenshot-saved", &[("path", &path.display())])),
+            Err(e) => Err(tr_args("status-screenshot-failed", &[("error", &e)])),
+        });
+    }
+
+    fn push_bookmark(&mut self, bookmark: Bookmark) {
+        self.events.push(bookmark.timestamp_ms, EventKind::Bookmark, format!("Mark: {}", bookmark.text));
+    }
+
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        // Don't steal keys while the snapshot name field has focus
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let (toggle_pause, snapshot, toggle_compact, bookmark) = ctx.input(|i| {
+            (
+                i.key_pressed(egui::Key::Space),
+                i.key_pressed(egui::Key::S),
+                i.key_pressed(egui::Key::C),
+                i.key_pressed(egui::Key::B),
+            )
+        });
+        if toggle_pause {
+            self.paused = !self.paused;
+        }
+        if snapshot {
+            self.take_snapshot();
+        }
+        if toggle_compact {
+            self.set_compact(ctx, !self.compact);
+        }
+        if bookmark {
+            self.add_bookmark();
+        }
+    }
+
+    fn set_compact(&mut self, ctx: &egui::Context, compact: bool) {
+        self.compact = compact;
+        self.compact_switched = Some(Instant::now());
+        let (size, position, level) = if compact {
+            (COMPACT_SIZE, self.layout.compact_position, egui::WindowLevel::AlwaysOnTop)
+        } else {
+            (self.layout.window_size.unwrap_or(FULL_SIZE), self.layout.window_position, egui::WindowLevel::Normal)
+        };
+        ctx.send_viewport_cmd(egui::ViewportCommand::Maximized(!compact && self.layout.maximized));
+        ctx.send_viewport_cmd(egui::ViewportCommand::Decorations(!compact));
+        ctx.send_viewport_cmd(egui::ViewportCommand::WindowLevel(level));
+        ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(size.into()));
+        if let Some(position) = position {
+            ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(position.into()));
+        }
+    }
+
+    // Remembers where the window is for whichever mode it's in, so each comes back where it was left
+    fn track_window(&mut self, ctx: &egui::Context) {
+        if self.compact_switched.is_some_and(|at| at.elapsed() < WINDOW_SETTLE) {
+            return;
+        }
+        let (outer, inner, maximized) = ctx.input(|i| {
+            let viewport = i.viewport();
+            (viewport.outer_rect, viewport.inner_rect, viewport.maximized == Some(true))
+        });
+        let (Some(outer), Some(inner)) = (outer, inner) else {
+            return;
+        };
+        if self.compact {
+            self.layout.compact_position = Some(outer.min.into());
+            return;
+        }
+        self.layout.maximized = maximized;
+        if !maximized {
+            self.layout.window_position = Some(outer.min.into());
+            self.layout.window_size = Some(inner.size().into());
+        }
+    }
+
+    fn save_layout(&mut self) {
+        if self.safe_mode {
+            return;
+        }
+        self.layout.compact = self.compact;
+        self.layout.plot_mode = self.plot_mode;
+        self.layout.process_sort = self.process_sort;
+        self.layout.process_grouping = self.process_grouping;
+        if let Err(e) = self.layout.save(&self.profile) {
+            diag::log(format!("Could not save layout: {}", e));
+        }
+    }
+
+    // Frameless widget: drag anywhere to move, double-click to go back to the full view
+    fn compact_ui(&mut self, ctx: &egui::Context, memory_percentage: f32) {
+        let panel_frame = egui::Frame::central_panel(&ctx.style())
+            .fill(self.theme.background)
+            .inner_margin(6.0);
+        egui::CentralPanel::default().frame(panel_frame).show(ctx, |ui| {
+            let color = if self.stale_for().is_some() || !self.latest.has_memory() {
+                self.theme.dim
+            } else {
+                self.theme.level(memory_percentage, &self.config.alerts.thresholds)
+            };
+            let percent = self.percent_text(memory_percentage);
+            let bar = self.compact_bar.get((memory_percentage / 4.0) as usize, |bar| format!("[{:<25}]", bar));
+            ui.vertical_centered(|ui| {
+                ui.label(
+                    egui::RichText::new(format!("{} {:>6}", tr("compact-mem"), percent))
+                        .color(color)
+                        .strong()
+                        .monospace()
+                );
+                ui.label(
+                    egui::RichText::new(bar)
+                        .color(color)
+                        .monospace()
+                );
+            });
+
+            let response = ui.interact(ui.max_rect(), ui.id().with("compact_drag"), egui::Sense::click_and_drag());
+            if response.double_clicked() {
+                self.set_compact(ctx, false);
+            } else if response.drag_started() {
+                ctx.send_viewport_cmd(egui::ViewportCommand::StartDrag);
+            }
+        });
+    }
+
+    fn controls_ui(&mut self, ui: &mut egui::Ui) {
+        let primary = self.theme.primary;
+        ui.horizontal(|ui| {
+            let pause_label = tr(if self.paused { "button-resume" } else { "button-pause" });
+            if ui.button(egui::RichText::new(pause_label).color(primary).monospace())
+                .on_hover_text(tr("key-space"))
+                .clicked()
+            {
+                self.paused = !self.paused;
+            }
+            ui.add(
+                egui::TextEdit::singleline(&mut self.snapshot_name)
+                    .hint_text(tr("hint-snapshot-name"))
+                    .desired_width(160.0)
+                    .font(egui::TextStyle::Monospace)
+            );
+            if ui.button(egui::RichText::new(tr("button-snapshot")).color(primary).monospace())
+                .on_hover_text("S")
+                .clicked()
+            {
+                self.take_snapshot();
+            }
+            if ui.button(egui::RichText::new("[ ⚙ ]").color(primary).monospace())
+                .on_hover_text(tr("settings"))
+                .clicked()
+            {
+                self.settings_open = !self.settings_open;
+            }
+        });
+        ui.horizontal(|ui| {
+            let recording = self.recorder.is_some();
+            let rec_label = tr(if recording { "button-stop-recording" } else { "button-record" });
+            let rec_color = if recording { self.theme.critical } else { primary };
+            if ui.button(egui::RichText::new(rec_label).color(rec_color).monospace()).clicked() {
+                if recording {
+                    self.stop_recording();
+                } else {
+                    self.start_recording();
+                }
+            }
+            if ui.button(egui::RichText::new(tr("button-export")).color(primary).monospace())
+                .on_hover_text(tr("hover-export"))
+                .clicked()
+            {
+                let history = self.history.lock().unwrap();
+                let exported = recorder::export_history(&history).map(|path| (path, history.len()));
+                drop(history);
+                self.set_output_status(match exported {
+                    Ok((path, len)) => Ok(tr_args("status-exported", &[("count", &len), ("path", &path.display())])),
+                    Err(e) => Err(tr_args("status-export-failed", &[("error", &e)])),
+                });
+            }
+            if ui.button(egui::RichText::new(tr("button-report")).color(primary).monospace())
+                .on_hover_text(tr("hover-report"))
+                .clicked()
+            {
+                self.export_report();
+            }
+            for (label, format) in [("[ PNG ]", screenshot::Format::Png), ("[ SVG ]", screenshot::Format::Svg)] {
+                if ui.button(egui::RichText::new(label).color(primary).monospace())
+                    .on_hover_text(tr("hover-image"))
+                    .clicked()
+                {
+                    self.export_screenshot(format);
+                }
+            }
+            ui.add(
+                egui::TextEdit::singleline(&mut self.bookmark_text)
+                    .hint_text(tr("hint-bookmark"))
+                    .desired_width(120.0)
+                    .font(egui::TextStyle::Monospace)
+            );
+            if ui.button(egui::RichText::new(tr("button-mark")).color(primary).monospace())
+                .on_hover_text(tr("hover-mark"))
+                .clicked()
+            {
+                self.add_bookmark();
+            }
+            if ui.checkbox(&mut self.config.recording.inhibit_sleep, tr("keep-awake"))
+                .changed()
+            {
+                self.update_inhibitor();
+                if let Err(e) = self.save_config() {
+                    self.set_recording_error(tr_args("status-config-failed", &[("error", &e)]));
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            match self.alerts.snooze_remaining(Instant::now()) {
+                Some(remaining) => {
+                    ui.label(
+                        egui::RichText::new(tr_args("snoozed-for", &[("minutes", &(remaining.as_secs() / 60 + 1))]))
+                            .color(self.theme.dim)
+                            .monospace()
+                    );
+                    if ui.button(egui::RichText::new(tr("button-unsnooze")).color(primary).monospace()).clicked() {
+                        self.alerts.unsnooze();
+                    }
+                }
+                None => {
+                    ui.label(egui::RichText::new(tr("snooze-alerts")).color(self.theme.dim).monospace());
+                    for minutes in SNOOZE_MINUTES {
+                        let label = if minutes < 60 { format!("{}m", minutes) } else { format!("{}h", minutes / 60) };
+                        if ui.small_button(label).clicked() {
+                            self.alerts.snooze(Instant::now() + Duration::from_secs(minutes * 60));
+                            diag::info(format!("Alerts snoozed for {} minutes", minutes));
+                        }
+                    }
+                }
+            }
+        });
+        if !self.recording_status.is_empty() {
+            ui.label(
+                egui::RichText::new(&self.recording_status)
+                    .color(self.theme.dim)
+                    .small()
+                    .monospace()
+            );
+        }
+    }
+
+    fn snapshots_ui(&mut self, ui: &mut egui::Ui) {
+        if self.snapshots.is_empty() {
+            return;
+        }
+        let info = self.theme.info;
+        let mut remove = None;
+
+        let response = egui::CollapsingHeader::new(
+            egui::RichText::new(tr_args("panel-snapshots", &[("count", &self.snapshots.len())]))
+                .color(info)
+                .monospace()
+        )
+        .default_open(self.layout.is_open("snapshots", true))
+        .show(ui, |ui| {
+            for (i, snap) in self.snapshots.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(tr_args("snapshot-line", &[
+                            ("name", &format!("{:<16}", snap.name)),
+                            ("ram", &format!("{:>5.1}%", snap.memory_percentage())),
+                            ("used", &format!("{:>9}", format_bytes(snap.used_memory))),
+                            ("swap", &format!("{:>5.1}%", snap.swap_percentage())),
+                            ("age", &snap.age_secs()),
+                        ]))
+                        .color(info)
+                        .monospace()
+                    );
+                    if ui.small_button("x").clicked() {
+                        remove = Some(i);
+                    }
+                });
+
+                if i > 0 {
+                    let diff = snap.diff(&self.snapshots[i - 1]);
+                    ui.label(
+                        egui::RichText::new(format!("  {}", tr_args("snapshot-versus", &[
+                            ("name", &self.snapshots[i - 1].name),
+                            ("ram", &format_bytes_delta(diff.used_memory)),
+                            ("swap", &format_bytes_delta(diff.used_swap)),
+                            ("secs", &format!("{:.0}", diff.seconds)),
+                        ])))
+                        .color(if diff.used_memory > 0 {
+                            self.theme.warning
+                        } else {
+                            self.theme.primary
+                        })
+                        .monospace()
+                    );
+                }
+
+                for process in snap.processes.iter().take(3) {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "  {:>7} {:<20} {:>9}",
+                            process.pid,
+                            process.name,
+                            format_bytes(process.memory),
+                        ))
+                        .color(self.theme.dim)
+                        .monospace()
+                    );
+                }
+            }
+            ui.separator();
+            self.snapshot_diff_ui(ui);
+        });
+        self.layout.set_open("snapshots", !response.fully_closed());
+
+        if let Some(i) = remove {
+            self.snapshots.remove(i);
+            self.diff_pick = (0, None);
+        }
+    }
+
+    // Two snapshots, or one against the system as it is now, side by side:
+    // the leak hunter's "what grew between before and after the test run"
+    fn snapshot_diff_ui(&mut self, ui: &mut egui::Ui) {
+        let mut compare = false;
+        ui.horizontal(|ui| {
+            let name = |pick: Option<usize>| match pick {
+                Some(i) => self.snapshots[i].name.clone(),
+                None => tr("diff-now"),
+            };
+            egui::ComboBox::from_id_source("diff_from")
+                .selected_text(name(Some(self.diff_pick.0)))
+                .show_ui(ui, |ui| {
+                    for (i, snap) in self.snapshots.iter().enumerate() {
+                        compare |= ui.selectable_value(&mut self.diff_pick.0, i, &snap.name).changed();
+                    }
+                });
+            ui.label("→");
+            egui::ComboBox::from_id_source("diff_to")
+                .selected_text(name(self.diff_pick.1))
+                .show_ui(ui, |ui| {
+                    for (i, snap) in self.snapshots.iter().enumerate() {
+                        compare |= ui.selectable_value(&mut self.diff_pick.1, Some(i), &snap.name).changed();
+                    }
+                    compare |= ui.selectable_value(&mut self.diff_pick.1, None, tr("diff-now")).changed();
+                });
+            compare |= ui.button(tr("diff-compare")).clicked();
+        });
+        if compare {
+            let earlier = &self.snapshots[self.diff_pick.0];
+            let later = match self.diff_pick.1 {
+                Some(i) => self.snapshots[i].clone(),
+                None if !self.source.is_local() => Snapshot::from_sample(&self.latest, tr("diff-now")),
+                None => Snapshot::capture(self.sampler.system_mut(), tr("diff-now")),
+            };
+            let diff = later.diff(earlier);
+            self.snapshot_diff = Some((earlier.name.clone(), later.name, diff));
+        }
+
+        let Some((from, to, diff)) = &self.snapshot_diff else { return };
+        let (warning, primary, dim) = (self.theme.warning, self.theme.primary, self.theme.dim);
+        let delta_color = |delta: i64| if delta > 0 { warning } else { primary };
+        ui.label(
+            egui::RichText::new(tr_args("snapshot-diff", &[
+                ("from", from),
+                ("to", to),
+                ("ram", &format_bytes_delta(diff.used_memory)),
+                ("swap", &format_bytes_delta(diff.used_swap)),
+                ("secs", &format!("{:.0}", diff.seconds.abs())),
+            ]))
+            .color(delta_color(diff.used_memory))
+            .monospace()
+        );
+        if diff.processes.is_empty() {
+            return;
+        }
+        egui::ScrollArea::vertical().id_source("snapshot_diff").max_height(240.0).show(ui, |ui| {
+            egui::Grid::new("snapshot_diff_table").striped(true).show(ui, |ui| {
+                for header in ["column-pid", "column-name", "column-before", "column-after", "column-delta"] {
+                    ui.label(egui::RichText::new(tr(header)).color(self.theme.info).monospace());
+                }
+                ui.end_row();
+                for process in diff.processes.iter().filter(|process| process.delta() != 0) {
+                    let bytes = |bytes: Option<u64>| bytes.map_or_else(|| "-".to_string(), format_bytes);
+                    ui.label(egui::RichText::new(process.pid.to_string()).color(dim).monospace());
+                    ui.label(egui::RichText::new(&process.name).color(primary).monospace());
+                    ui.label(egui::RichText::new(bytes(process.before)).color(dim).monospace());
+                    ui.label(egui::RichText::new(bytes(process.after)).color(dim).monospace());
+                    let delta = format_bytes_delta(process.delta());
+                    ui.label(egui::RichText::new(delta).color(delta_color(process.delta())).monospace());
+                    ui.end_row();
+                }
+            });
+        });
+    }
+
+    // Over the whole plotted history, for pasting into test write-ups
+    fn statistics_ui(&mut self, ui: &mut egui::Ui) {
+        let info = self.theme.info;
+        let primary = self.theme.primary;
+        let response = egui::CollapsingHeader::new(
+            egui::RichText::new(tr("panel-statistics")).color(info).monospace()
+        )
+            .default_open(self.layout.is_open("statistics", false))
+            .show(ui, |ui| {
+                let history = self.history.lock().unwrap();
+                let rows = [
+                    (tr("legend-ram"), stats::memory_summary(&history)),
+                    (tr("legend-swap"), stats::swap_summary(&history)),
+                ];
+                egui::Grid::new("statistics_table").striped(true).show(ui, |ui| {
+                    ui.label("");
+                    for header in ["column-min", "column-max", "column-avg", "column-p95"] {
+                        ui.label(egui::RichText::new(tr(header)).color(info).monospace());
+                    }
+                    ui.end_row();
+                    for (name, summary) in rows {
+                        let Some(summary) = summary else { continue };
+                        ui.label(egui::RichText::new(name).color(info).monospace());
+                        for value in [summary.min, summary.max, summary.mean, summary.p95] {
+                            ui.label(egui::RichText::new(format!("{:.1}%", value)).color(primary).monospace());
+                        }
+                        ui.end_row();
+                    }
+                });
+                if let Some(growth) = stats::growth_per_minute(&history) {
+                    let color = if growth > 0.0 { self.theme.warning } else { primary };
+                    ui.label(
+                        egui::RichText::new(tr_args("stats-growth", &[("bytes", &format_bytes_delta(growth as i64))]))
+                            .color(color)
+                            .monospace()
+                    );
+                }
+                let span_secs = match (history.first(), history.last()) {
+                    (Some(first), Some(last)) => (last.timestamp_ms - first.timestamp_ms) / 1000,
+                    _ => 0,
+                };
+                ui.label(
+                    egui::RichText::new(tr_args("stats-span", &[("count", &history.len()), ("secs", &span_secs)]))
+                        .color(self.theme.dim)
+                        .monospace()
+                );
+            });
+        self.layout.set_open("statistics", !response.fully_closed());
+    }
+
+    fn smoothing_ui(&mut self, ui: &mut egui::Ui) {
+        let plot = &mut self.config.plot;
+        let mut changed = false;
+        ui.label(tr("smoothing"));
+        let choices = [
+            (Smoothing::Off, "smoothing-off"),
+            (Smoothing::MovingAverage, "smoothing-average"),
+            (Smoothing::Ema, "smoothing-ema"),
+        ];
+        for (smoothing, key) in choices {
+            changed |= ui.selectable_value(&mut plot.smoothing, smoothing, tr(key)).changed();
+        }
+        match plot.smoothing {
+            Smoothing::Off => {}
+            Smoothing::MovingAverage => {
+                let window = egui::DragValue::new(&mut plot.window)
+                    .clamp_range(2..=60)
+                    .suffix(format!(" {}", tr("unit-samples")));
+                changed |= settled(&ui.add(window));
+            }
+            Smoothing::Ema => {
+                let alpha = egui::DragValue::new(&mut plot.ema_alpha).clamp_range(0.01..=1.0).speed(0.01);
+                changed |= settled(&ui.add(alpha).on_hover_text(tr("hover-ema-alpha")));
+            }
+        }
+        if plot.smoothing != Smoothing::Off {
+            changed |= ui.checkbox(&mut plot.hide_raw, tr("hide-raw")).changed();
+        }
+        ui.separator();
+        ui.label(tr("steps"));
+        let hint = tr("hover-steps");
+        changed |= ui.checkbox(&mut plot.step_memory, tr("legend-ram")).on_hover_text(&hint).changed();
+        changed |= ui.checkbox(&mut plot.step_swap, tr("legend-swap")).on_hover_text(&hint).changed();
+        if changed {
+            if let Err(e) = self.save_config() {
+                diag::log(format!("Could not save config: {}", e));
+            }
+        }
+    }
+
+    // Ticking a line off hides its smoothed twin too
+    fn legend_ui(&mut self, ui: &mut egui::Ui, lines: [(&str, String, egui::Color32); 4]) {
+        let hidden = &mut self.config.plot.hidden;
+        let mut changed = false;
+        ui.horizontal_wrapped(|ui| {
+            for (id, name, color) in lines {
+                let mut shown = !hidden.iter().any(|line| line == id);
+                if ui.checkbox(&mut shown, egui::RichText::new(name).color(color).monospace()).changed() {
+                    hidden.retain(|line| line != id);
+                    if !shown {
+                        hidden.push(id.to_string());
+                    }
+                    changed = true;
+                }
+            }
+        });
+        if changed {
+            if let Err(e) = self.save_config() {
+                diag::log(format!("Could not save config: {}", e));
+            }
+        }
+    }
+
+    fn events_ui(&mut self, ui: &mut egui::Ui) {
+        if self.events.is_empty() {
+            return;
+        }
+        let response = egui::CollapsingHeader::new(
+            egui::RichText::new(tr_args("panel-events", &[("count", &self.events.len())]))
+                .color(self.theme.info)
+                .monospace()
+        )
+        .default_open(self.layout.is_open("events", false))
+        .show(ui, |ui| {
+            egui::ScrollArea::vertical().max_height(EVENT_LIST_HEIGHT).show(ui, |ui| {
+                for event in self.events.iter().rev() {
+                    ui.label(
+                        egui::RichText::new(format!("{} UTC  {}", format_clock(event.timestamp_ms), event.message))
+                            .color(self.event_color(event.kind))
+                            .monospace()
+                    );
+                }
+            });
+        });
+        self.layout.set_open("events", !response.fully_closed());
+    }
+
+    fn event_color(&self, kind: EventKind) -> egui::Color32 {
+        match kind {
+            EventKind::Threshold => self.theme.warning,
+            EventKind::AlarmStart | EventKind::Oom => self.theme.critical,
+            EventKind::AlarmEnd => self.theme.primary,
+            EventKind::Snapshot | EventKind::Bookmark => self.theme.info,
+        }
+    }
+
+    // The process list is only walked while the panel is open, and at most every
+    // process interval, since it's far more expensive than the memory totals.
+    fn processes_ui(&mut self, ui: &mut egui::Ui) {
+        let info = self.theme.info;
+        let primary = self.theme.primary;
+        let response = egui::CollapsingHeader::new(egui::RichText::new(tr("panel-processes")).color(info).monospace())
+            .default_open(self.layout.is_open("processes", false))
+            .show(ui, |ui| {
+                let stale = self.processes_refreshed.is_none_or(|at| at.elapsed() >= self.process_refresh());
+                if stale && !self.paused {
+                    let shown = |column| self.layout.process_columns.iter().any(|c| c.column == column && c.visible);
+                    let (cpu, io) = (shown(ProcessColumn::Cpu), shown(ProcessColumn::Io));
+                    let pss = shown(ProcessColumn::Pss);
+                    self.process_rows = self.process_scanner.collect(self.sampler.system_mut(), cpu, io);
+                    self.processes_refreshed = Some(Instant::now());
+                    // Rights don't change while we run
+                    if self.privileges.is_none() {
+                        self.privileges = Some(Privileges::of(self.sampler.system_mut()));
+                    }
+                    self.what_if_freed = None;
+                    if pss {
+                        processes::sort(&mut self.process_rows, self.process_sort);
+                        for row in self.process_rows.iter_mut().take(PROCESS_ROWS) {
+                            row.pss = smaps::pss(row.pid);
+                        }
+                    }
+                }
+                self.refreshed_ui(ui, self.processes_refreshed);
+                processes::sort(&mut self.process_rows, self.process_sort);
+
+                let grouping = self.process_grouping;
+                ui.horizontal(|ui| {
+                    ui.label(tr("sort-by"));
+                    ui.selectable_value(&mut self.process_sort, ProcessSort::Memory, tr("sort-rss"));
+                    ui.selectable_value(&mut self.process_sort, ProcessSort::Swap, tr("sort-swap"));
+                    ui.separator();
+                    ui.label(tr("group-by"));
+                    ui.selectable_value(&mut self.process_grouping, ProcessGrouping::None, tr("group-process"));
+                    ui.selectable_value(&mut self.process_grouping, ProcessGrouping::User, tr("group-user"));
+                    ui.selectable_value(&mut self.process_grouping, ProcessGrouping::Command, tr("group-command"));
+                    ui.separator();
+                    ui.menu_button(tr("columns"), |ui| self.process_columns_ui(ui));
+                });
+                if self.process_grouping != grouping {
+                    self.what_if = None;
+                }
+                self.restricted_ui(ui);
+                let mut select = None;
+
+                if self.process_grouping != ProcessGrouping::None {
+                    let groups = processes::group(&self.process_rows, self.process_grouping, self.process_sort);
+                    let key_header = if self.process_grouping == ProcessGrouping::User {
+                        "column-user"
+                    } else {
+                        "column-command"
+                    };
+                    egui::Grid::new("process_groups").striped(true).show(ui, |ui| {
+                        for header in [key_header, "column-procs", "column-rss", "column-swap"] {
+                            ui.label(egui::RichText::new(tr(header)).color(info).monospace());
+                        }
+                        ui.end_row();
+                        for group in groups.iter().take(PROCESS_ROWS) {
+                            let selection = Selection::Group(group.key.clone());
+                            let selected = self.what_if.as_ref() == Some(&selection);
+                            let name = egui::RichText::new(&group.key).color(primary).monospace();
+                            let response = ui.selectable_label(selected, name);
+                            if response.on_hover_text(tr("hover-what-if-group")).clicked() {
+                                select = Some(selection);
+                            }
+                            ui.label(egui::RichText::new(group.count.to_string()).color(primary).monospace());
+                            ui.label(egui::RichText::new(format_bytes(group.memory)).color(primary).monospace());
+                            let swap = group.swap.map(format_bytes).unwrap_or_else(|| "-".to_string());
+                            ui.label(egui::RichText::new(swap).color(self.theme.swap).monospace());
+                            ui.end_row();
+                        }
+                    });
+                    self.what_if_ui(ui, select);
+                    return;
+                }
+
+                let columns: Vec<_> = self.layout.process_columns.iter().filter(|c| c.visible).copied().collect();
+                let now_secs = now_ms() / 1000;
+                egui::Grid::new("process_table").striped(true).show(ui, |ui| {
+                    for layout in &columns {
+                        sized(ui, layout.width, |ui| {
+                            ui.label(egui::RichText::new(layout.column.header()).color(info).monospace());
+                        });
+                    }
+                    ui.end_row();
+                    let mut focus = None;
+                    let privileges = self.privileges.clone().unwrap_or_default();
+                    let readable = self.process_rows.iter().filter(|row| !privileges.hides(row));
+                    for row in readable.take(PROCESS_ROWS) {
+                        let highlight = self.highlighter.style(row);
+                        let styled = |text: String, color| {
+                            let text = egui::RichText::new(text).color(highlight.color.unwrap_or(color)).monospace();
+                            if highlight.bold { text.strong() } else { text }
+                        };
+                        for layout in &columns {
+                            sized(ui, layout.width, |ui| {
+                                let selection = Selection::Process(row.pid);
+                                if layout.column == ProcessColumn::Name {
+                                    let selected = self.what_if.as_ref() == Some(&selection);
+                                    let response = ui.selectable_label(selected, styled(row.name.clone(), primary));
+                                    if response.on_hover_text(tr("hover-what-if")).clicked() {
+                                        select = Some(selection);
+                                    }
+                                    return;
+                                }
+                                let swap = layout.column == ProcessColumn::Swap;
+                                let color = if swap { self.theme.swap } else { primary };
+                                let text = styled(process_cell(row, layout.column, now_secs), color);
+                                ui.add(egui::Label::new(text).truncate(true));
+                            });
+                        }
+                        if ui.small_button(tr("button-focus")).clicked() {
+                            focus = Some(row.pid);
+                        }
+                        ui.end_row();
+                    }
+                    if let Some(pid) = focus {
+                        self.focus = Some(ProcessFocus::new(pid));
+                    }
+                });
+                self.what_if_ui(ui, select);
+            });
+        self.layout.set_open("processes", !response.fully_closed());
+    }
+
+    // Without administrator rights other users' processes come back without sizes;
+    // say so instead of letting the table quietly fall short of the used total
+    fn restricted_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(privileges) = self.privileges.as_ref().filter(|privileges| privileges.restricted) else {
+            return;
+        };
+        let mut users: BTreeMap<&str, usize> = BTreeMap::new();
+        for row in self.process_rows.iter().filter(|row| privileges.hides(row)) {
+            *users.entry(row.user.as_deref().unwrap_or("?")).or_default() += 1;
+        }
+        if users.is_empty() {
+            return;
+        }
+        let listed: u64 = self.process_rows.iter().map(|row| row.memory).sum();
+        let hidden: usize = users.values().sum();
+        let summary = tr_args("restricted-summary", &[
+            ("listed", &format_bytes(listed)),
+            ("used", &format_bytes(self.latest.used_memory)),
+            ("hidden", &hidden),
+        ]);
+        ui.label(egui::RichText::new(summary).color(self.theme.warning).monospace());
+        ui.horizontal(|ui| {
+            let title = egui::RichText::new(tr("restricted-users")).color(self.theme.dim).monospace();
+            ui.collapsing(title, |ui| {
+                for (user, count) in &users {
+                    let line = tr_args("restricted-user", &[("user", user), ("count", count)]);
+                    ui.label(egui::RichText::new(line).monospace());
+                }
+            });
+            if !cfg!(windows) {
+                ui.label(egui::RichText::new(tr("restricted-sudo")).color(self.theme.dim).monospace());
+            } else if ui.button(egui::RichText::new(tr("button-run-as-admin")).color(self.theme.primary).monospace())
+                .on_hover_text(tr("hover-run-as-admin"))
+                .clicked()
+            {
+                match processes::relaunch_elevated() {
+                    Ok(()) => ui.ctx().send_viewport_cmd(egui::ViewportCommand::Close),
+                    Err(e) => diag::log(format!("Could not restart elevated: {}", e)),
+                }
+            }
+        });
+    }
+
+    // Checkbox, move up/down and width for every column; the name can't be hidden
+    fn process_columns_ui(&mut self, ui: &mut egui::Ui) {
+        let columns = &mut self.layout.process_columns;
+        let mut swap = None;
+        egui::Grid::new("process_columns").show(ui, |ui| {
+            let last = columns.len() - 1;
+            for (index, layout) in columns.iter_mut().enumerate() {
+                let name = layout.column == ProcessColumn::Name;
+                ui.add_enabled(!name, egui::Checkbox::new(&mut layout.visible, layout.column.header()));
+                if ui.add_enabled(index > 0, egui::Button::new("\u{25b2}").small()).clicked() {
+                    swap = Some(index - 1);
+                }
+                if ui.add_enabled(index < last, egui::Button::new("\u{25bc}").small()).clicked() {
+                    swap = Some(index);
+                }
+                ui.add(egui::DragValue::new(&mut layout.width).clamp_range(30.0..=400.0).suffix(" px"));
+                ui.end_row();
+            }
+        });
+        if let Some(index) = swap {
+            columns.swap(index, index + 1);
+        }
+        if ui.button(tr("columns-reset")).clicked() {
+            *columns = processes::default_columns();
+        }
+    }
+
+    // Clicking a row picks it, clicking it again drops it
+    fn what_if_ui(&mut self, ui: &mut egui::Ui, clicked: Option<Selection>) {
+        if let Some(selection) = clicked {
+            self.what_if = if self.what_if.as_ref() == Some(&selection) { None } else { Some(selection) };
+            self.what_if_freed = None;
+        }
+        let Some(selection) = &self.what_if else {
+            return;
+        };
+        let (count, freed) = *self.what_if_freed.get_or_insert_with(|| {
+            processes::reclaimable(&self.process_rows, self.process_grouping, selection, smaps::pss)
+        });
+        if count == 0 {
+            ui.label(egui::RichText::new(tr("what-if-gone")).color(self.theme.dim).monospace());
+            return;
+        }
+        let name = match selection {
+            Selection::Process(pid) => self.process_rows.iter()
+                .find(|row| row.pid == *pid)
+                .map_or_else(|| pid.to_string(), |row| row.name.clone()),
+            Selection::Group(key) => tr_args("what-if-group", &[("name", key), ("count", &count)]),
+        };
+        let total = self.latest.total_memory.max(1);
+        let projected = self.latest.used_memory.saturating_sub(freed) as f64 / total as f64 * 100.0;
+        ui.label(
+            egui::RichText::new(tr_args("what-if", &[
+                ("name", &name),
+                ("bytes", &format_bytes(freed)),
+                ("percent", &format!("{:.1}%", projected)),
+            ]))
+                .color(self.theme.level(projected as f32, &self.config.alerts.thresholds))
+                .monospace()
+        );
+    }
+
+    fn focus_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(focus) = &mut self.focus else {
+            return;
+        };
+        let mut keep = true;
+        let response = egui::CollapsingHeader::new(
+            egui::RichText::new(tr("panel-focus")).color(self.theme.info).monospace()
+        )
+            .default_open(self.layout.is_open("focus", true))
+            .show(ui, |ui| keep = focus.ui(ui, &self.theme));
+        self.layout.set_open("focus", !response.fully_closed());
+        if !keep {
+            self.focus = None;
+        }
+    }
+
+    fn budgets_ui(&mut self, ui: &mut egui::Ui) {
+        if self.budgets.is_empty() {
+            return;
+        }
+        let response = egui::CollapsingHeader::new(
+            egui::RichText::new(tr("panel-budgets")).color(self.theme.info).monospace()
+        )
+            .default_open(self.layout.is_open("budgets", true))
+            .show(ui, |ui| self.budgets.ui(ui, &self.theme));
+        self.layout.set_open("budgets", !response.fully_closed());
+    }
+
+    // Commands for the remote agent, which runs them if our operator token matches
+    // its own. Replies are taken here, so this runs whenever an agent is shown.
+    fn agent_ui(&mut self, ui: &mut egui::Ui) {
+        let replies = self.daemon.as_ref().map(DaemonClient::replies).unwrap_or_default();
+        for reply in replies {
+            let Some(i) = self.agent_requests.iter().position(|(id, _)| *id == reply.id) else { continue };
+            let (_, command) = self.agent_requests.remove(i);
+            match command {
+                Command::StartRecording if reply.ok => self.agent_recording = true,
+                Command::StopRecording => self.agent_recording = false,
+                _ => {}
+            }
+            let message = tr_args("status-agent-reply", &[("message", &reply.message)]);
+            self.set_output_status(if reply.ok { Ok(message) } else { Err(message) });
+        }
+
+        let primary = self.theme.primary;
+        let connected = self.daemon.as_ref().is_some_and(DaemonClient::is_connected);
+        // Whatever was asked of a connection that dropped won't be answered
+        if !connected {
+            self.agent_requests.clear();
+        }
+        let response = egui::CollapsingHeader::new(
+            egui::RichText::new(tr("panel-agent")).color(self.theme.info).monospace()
+        )
+            .default_open(self.layout.is_open("agent", false))
+            .show(ui, |ui| {
+                if self.config.agent.operator_token.is_empty() {
+                    ui.label(egui::RichText::new(tr("agent-no-token")).color(self.theme.dim).monospace());
+                }
+                let record = if self.agent_recording {
+                    (Command::StopRecording, "button-stop-recording")
+                } else {
+                    (Command::StartRecording, "button-record")
+                };
+                let mut clicked = None;
+                ui.add_enabled_ui(connected && self.agent_requests.is_empty(), |ui| {
+                    ui.horizontal(|ui| {
+                        let snapshot = (Command::Snapshot, "button-snapshot");
+                        let commands = [snapshot, record, (Command::Selftest, "button-selftest")];
+                        for (command, key) in commands {
+                            if ui.button(egui::RichText::new(tr(key)).color(primary).monospace()).clicked() {
+                                clicked = Some(command);
+                            }
+                        }
+                    });
+                });
+                if let (Some(command), Some(daemon)) = (clicked, &self.daemon) {
+                    match daemon.send(command, &self.config.agent.operator_token) {
+                        Ok(id) => self.agent_requests.push((id, command)),
+                        Err(e) => self.set_output_status(Err(tr_args("status-agent-unreachable", &[("error", &e)]))),
+                    }
+                }
+            });
+        self.layout.set_open("agent", !response.fully_closed());
+    }
+
+    // Refreshed at the system stats interval, and only while open
+    fn containers_ui(&mut self, ui: &mut egui::Ui) {
+        let info = self.theme.info;
+        let primary = self.theme.primary;
+        let response = egui::CollapsingHeader::new(
+            egui::RichText::new(tr("panel-containers")).color(info).monospace()
+        )
+            .default_open(self.layout.is_open("containers", false))
+            .show(ui, |ui| {
+                let stale = self.containers_refreshed.is_none_or(|at| at.elapsed() >= self.system_refresh());
+                let due = stale && !self.paused;
+                if let Some(containers) = self.containers_backend.poll(due, ContainerWatcher::collect) {
+                    self.containers = containers;
+                    self.containers_refreshed = Some(Instant::now());
+                }
+                self.refreshed_ui(ui, self.containers_refreshed);
+                self.degraded_ui(ui, self.containers_backend.degraded());
+                if self.containers_refreshed.is_none() {
+                    return;
+                }
+
+                if self.containers.is_empty() {
+                    ui.label(egui::RichText::new(tr("containers-none")).color(self.theme.dim).monospace());
+                    return;
+                }
+
+                egui::Grid::new("container_table").striped(true).show(ui, |ui| {
+                    let headers =
+                        ["column-name", "column-usage", "column-limit", "column-percent", "column-oom-kills"];
+                    for header in headers {
+                        ui.label(egui::RichText::new(tr(header)).color(info).monospace());
+                    }
+                    ui.end_row();
+                    let thresholds = &self.config.alerts.thresholds;
+                    for container in &self.containers {
+                        let percentage = container.limit_percentage();
+                        let level = percentage.map_or(primary, |pct| self.theme.level(pct, thresholds));
+                        ui.label(egui::RichText::new(&container.name).color(primary).monospace())
+                            .on_hover_text(format!("{} {}", container.runtime, container.id));
+                        ui.label(egui::RichText::new(format_bytes(container.usage)).color(primary).monospace());
+                        let limit = container.limit.map(format_bytes).unwrap_or_else(|| tr("limit-none"));
+                        ui.label(egui::RichText::new(limit).color(primary).monospace());
+                        let percentage = percentage.map(|pct| format!("{:.1}%", pct)).unwrap_or_else(|| "-".to_string());
+                        ui.label(egui::RichText::new(percentage).color(level).monospace());
+                        let oom_color = if container.oom_kills > 0 { self.theme.critical } else { primary };
+                        ui.label(egui::RichText::new(container.oom_kills.to_string()).color(oom_color).monospace());
+                        ui.end_row();
+                    }
+                });
+            });
+        self.layout.set_open("containers", !response.fully_closed());
+    }
+
+    fn offenders_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(offenders) = &self.offenders else {
+            return;
+        };
+        let info = self.theme.info;
+        let primary = self.theme.primary;
+        let response = egui::CollapsingHeader::new(egui::RichText::new(tr("panel-offenders")).color(info).monospace())
+            .default_open(self.layout.is_open("offenders", false))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.offender_period, Period::Day, tr("period-day"));
+                    ui.selectable_value(&mut self.offender_period, Period::Week, tr("period-week"));
+                });
+                let top = offenders.top(self.offender_period, now_ms(), OFFENDER_ROWS);
+                if top.is_empty() {
+                    ui.label(egui::RichText::new(tr("offenders-none")).color(self.theme.dim).monospace());
+                    return;
+                }
+                egui::Grid::new("offender_table").striped(true).show(ui, |ui| {
+                    for header in ["column-command", "column-memory-hours"] {
+                        ui.label(egui::RichText::new(tr(header)).color(info).monospace());
+                    }
+                    ui.end_row();
+                    for (name, byte_hours) in top {
+                        ui.label(egui::RichText::new(name).color(primary).monospace());
+                        ui.label(egui::RichText::new(format!("{}·h", format_bytes(byte_hours as u64))).color(primary).monospace());
+                        ui.end_row();
+                    }
+                });
+            });
+        self.layout.set_open("offenders", !response.fully_closed());
+    }
+
+    // One line per zram device and for zswap: what's stored, the RAM it really
+    // takes, and the ratio between the two
+    fn swap_compression_ui(&mut self, ui: &mut egui::Ui) {
+        let stale = self.swap_compression_refreshed.is_none_or(|at| at.elapsed() >= self.system_refresh());
+        if stale && !self.paused {
+            self.swap_compression = SwapCompression::read();
+            self.swap_compression_refreshed = Some(Instant::now());
+        }
+        let ratio = |ratio: Option<f64>| ratio.map_or_else(|| "-".to_string(), |ratio| format!("{:.1}x", ratio));
+        let mut lines = Vec::new();
+        for device in &self.swap_compression.zram {
+            lines.push(tr_args("zram-device", &[
+                ("name", &format!("{:<13}", format!("{}:", device.name))),
+                ("stored", &format_bytes(device.original)),
+                ("size", &format_bytes(device.disk_size)),
+                ("ram", &format_bytes(device.used)),
+                ("ratio", &ratio(device.ratio())),
+                ("algorithm", &device.algorithm.as_deref().unwrap_or("?")),
+            ]));
+        }
+        if let Some(zswap) = &self.swap_compression.zswap {
+            lines.push(tr_args("zswap-pool", &[
+                ("name", &format!("{:<13}", "zswap:")),
+                ("stored", &format_bytes(zswap.stored)),
+                ("ram", &format_bytes(zswap.pool)),
+                ("ratio", &ratio(zswap.ratio())),
+            ]));
+        }
+        for line in lines {
+            ui.label(egui::RichText::new(line).color(self.theme.swap).monospace());
+        }
+    }
+
+    fn hugepages_ui(&mut self, ui: &mut egui::Ui) {
+        let info = self.theme.info;
+        let primary = self.theme.primary;
+        let response = egui::CollapsingHeader::new(egui::RichText::new(tr("panel-hugepages")).color(info).monospace())
+            .default_open(self.layout.is_open("hugepages", false))
+            .show(ui, |ui| {
+                let stale = self.hugepages_refreshed.is_none_or(|at| at.elapsed() >= self.system_refresh());
+                if let Some(pages) = self.hugepages_backend.poll(stale && !self.paused, |_| HugePages::read()) {
+                    self.hugepages = pages;
+                    self.hugepages_refreshed = Some(Instant::now());
+                }
+                self.refreshed_ui(ui, self.hugepages_refreshed);
+                self.degraded_ui(ui, self.hugepages_backend.degraded());
+                if self.hugepages_refreshed.is_none() {
+                    return;
+                }
+                let Some(pages) = &self.hugepages else {
+                    ui.label(egui::RichText::new(tr("hugepages-unreadable")).color(self.theme.dim).monospace());
+                    return;
+                };
+
+                let total_memory = self.latest.total_memory.max(1);
+                if pages.hugetlb == 0 {
+                    ui.label(egui::RichText::new(tr("hugepages-none")).color(self.theme.dim).monospace());
+                } else {
+                    ui.label(
+                        egui::RichText::new(tr_args("hugepages-pool", &[
+                            ("bytes", &format_bytes(pages.hugetlb)),
+                            ("percent", &format!("{:.1}%", pages.hugetlb as f64 / total_memory as f64 * 100.0)),
+                            ("count", &pages.total),
+                            ("size", &format_bytes(pages.page_size)),
+                        ]))
+                        .color(primary)
+                        .monospace()
+                    );
+                    ui.label(
+                        egui::RichText::new(tr_args("hugepages-usage", &[
+                            ("used", &pages.total.saturating_sub(pages.free)),
+                            ("free", &pages.free),
+                            ("reserved", &pages.reserved),
+                            ("surplus", &pages.surplus),
+                        ]))
+                        .color(primary)
+                        .monospace()
+                    );
+                    // The whole pool counts as used from the moment it's reserved
+                    let used = self.latest.used_memory.saturating_sub(pages.hugetlb);
+                    ui.label(
+                        egui::RichText::new(tr_args("hugepages-outside", &[
+                            ("bytes", &format_bytes(used)),
+                            ("percent", &format!("{:.1}%", used as f64 / total_memory as f64 * 100.0)),
+                        ]))
+                        .color(info)
+                        .monospace()
+                    );
+                }
+
+                let mode = |mode: &Option<String>| mode.clone().unwrap_or_else(|| "-".to_string());
+                ui.label(
+                    egui::RichText::new(tr_args("hugepages-thp", &[
+                        ("bytes", &format_bytes(pages.thp_bytes())),
+                        ("enabled", &mode(&pages.thp_enabled)),
+                        ("defrag", &mode(&pages.thp_defrag)),
+                    ]))
+                    .color(primary)
+                    .monospace()
+                )
+                .on_hover_text(tr_args("hover-hugepages-thp", &[
+                    ("anonymous", &format_bytes(pages.anon_thp)),
+                    ("shmem", &format_bytes(pages.shmem_thp)),
+                    ("file", &format_bytes(pages.file_thp)),
+                ]));
+            });
+        self.layout.set_open("hugepages", !response.fully_closed());
+    }
+
+    // Totals the kernel's buckets and every process's own memory against RAM, to
+    // show what's left unexplained. It walks all processes, so only on request.
+    fn accounting_ui(&mut self, ui: &mut egui::Ui) {
+        let info = self.theme.info;
+        let primary = self.theme.primary;
+        let response = egui::CollapsingHeader::new(
+            egui::RichText::new(tr("panel-accounting")).color(info).monospace()
+        )
+            .default_open(self.layout.is_open("accounting", false))
+            .show(ui, |ui| {
+                if ui.button(egui::RichText::new(tr("button-reconcile")).color(primary).monospace())
+                    .on_hover_text(tr("hover-reconcile"))
+                    .clicked()
+                {
+                    self.reconciliation = Some(accounting::reconcile(self.sampler.system_mut()));
+                }
+                let reconciliation = match &self.reconciliation {
+                    None => return,
+                    Some(Err(e)) => {
+                        ui.label(egui::RichText::new(e).color(self.theme.critical).monospace());
+                        return;
+                    }
+                    Some(Ok(reconciliation)) => reconciliation,
+                };
+                let total = reconciliation.total.max(1) as f64;
+                let percent = |bytes: i64| bytes as f64 / total * 100.0;
+                egui::Grid::new("accounting").striped(true).show(ui, |ui| {
+                    for bucket in &reconciliation.buckets {
+                        ui.label(egui::RichText::new(bucket.label).color(primary).monospace());
+                        ui.label(egui::RichText::new(format_bytes(bucket.bytes)).color(primary).monospace());
+                        let share = format!("{:5.1}%", percent(bucket.bytes as i64));
+                        ui.label(egui::RichText::new(share).color(primary).monospace());
+                        ui.label(egui::RichText::new(&bucket.detail).color(self.theme.dim).monospace());
+                        ui.end_row();
+                    }
+                    let unexplained = reconciliation.unexplained;
+                    let color = if percent(unexplained.abs()) >= 5.0 { self.theme.warning } else { info };
+                    ui.label(egui::RichText::new(tr("accounting-unexplained")).color(color).strong().monospace());
+                    ui.label(egui::RichText::new(format_bytes_delta(unexplained)).color(color).monospace());
+                    ui.label(egui::RichText::new(format!("{:5.1}%", percent(unexplained))).color(color).monospace());
+                    let note = if unexplained < 0 { "accounting-overlap" } else { "accounting-remainder" };
+                    ui.label(egui::RichText::new(tr(note)).color(self.theme.dim).monospace());
+                    ui.end_row();
+                    ui.label(egui::RichText::new(tr("accounting-total")).color(info).monospace());
+                    ui.label(egui::RichText::new(format_bytes(reconciliation.total)).color(info).monospace());
+                    ui.end_row();
+                });
+            });
+        self.layout.set_open("accounting", !response.fully_closed());
+    }
+
+    // A remote agent only sends memory samples, so all there is to say about it is its address
+    fn status_strip_ui(&mut self, ui: &mut egui::Ui) {
+        let mut color = self.theme.dim;
+        let text = match self.source.label() {
+            Some(addr) => {
+                let daemon = self.daemon.as_ref().filter(|daemon| daemon.is_connected());
+                match daemon.and_then(|daemon| daemon.clock_skew_ms()) {
+                    Some(skew_ms) => {
+                        let (skew, skewed) = dashboard::skew_text(skew_ms);
+                        color = if skewed { self.theme.warning } else { color };
+                        format!("{}  {}", addr, skew)
+                    }
+                    None => addr.clone(),
+                }
+            }
+            None => {
+                let stale = self.status_strip_refreshed.is_none_or(|at| at.elapsed() >= self.system_refresh());
+                if stale || self.status_strip.is_none() {
+                    self.status_strip = Some(self.local_status());
+                    self.status_strip_refreshed = Some(Instant::now());
+                }
+                self.status_strip.clone().unwrap_or_default()
+            }
+        };
+        ui.label(egui::RichText::new(text).color(color).monospace());
+    }
+
+    fn local_status(&mut self) -> String {
+        let hostname = self.alert_hostname();
+        let sys = self.sampler.system_mut();
+        // Windows has no load average; sysinfo reports zeros there
+        let load = if cfg!(windows) {
+            String::new()
+        } else {
+            let load = sys.load_average();
+            let load = format!("{:.2} {:.2} {:.2}", load.one, load.five, load.fifteen);
+            format!("  {}", tr_args("status-load", &[("load", &load)]))
+        };
+        let uptime = tr_args("status-uptime", &[("uptime", &format_duration(sys.uptime()))]);
+        format!("{}{}  {}", hostname, load, uptime)
+    }
+
+    // Heat and swap thrashing tend to come together on laptops; sensors are only
+    // read while this is open
+    fn temperatures_ui(&mut self, ui: &mut egui::Ui) {
+        let info = self.theme.info;
+        let response = egui::CollapsingHeader::new(
+            egui::RichText::new(tr("panel-temperatures")).color(info).monospace()
+        )
+            .default_open(self.layout.is_open("temperatures", false))
+            .show(ui, |ui| {
+                let stale = self.sensors_refreshed.is_none_or(|at| at.elapsed() >= self.system_refresh());
+                if let Some(sensors) = self.sensors_backend.poll(stale && !self.paused, SensorReader::read) {
+                    self.sensors = sensors;
+                    self.sensors_refreshed = Some(Instant::now());
+                }
+                self.refreshed_ui(ui, self.sensors_refreshed);
+                self.degraded_ui(ui, self.sensors_backend.degraded());
+                if self.sensors_refreshed.is_none() {
+                    return;
+                }
+                if self.sensors.is_empty() {
+                    ui.label(egui::RichText::new(tr("sensors-none")).color(self.theme.dim).monospace());
+                    return;
+                }
+                egui::Grid::new("sensor_table").striped(true).show(ui, |ui| {
+                    for sensor in &self.sensors {
+                        let color = match sensor.level() {
+                            0 => self.theme.primary,
+                            1 => self.theme.warning,
+                            _ => self.theme.critical,
+                        };
+                        ui.label(egui::RichText::new(&sensor.label).color(color).monospace());
+                        let celsius = format!("{:.0}\u{b0}C", sensor.celsius);
+                        ui.label(egui::RichText::new(celsius).color(color).monospace());
+                        let critical = sensor.critical.map_or_else(String::new, |c| {
+                            tr_args("sensor-critical", &[("celsius", &format!("{:.0}\u{b0}C", c))])
+                        });
+                        ui.label(egui::RichText::new(critical).color(self.theme.dim).monospace());
+                        ui.end_row();
+                    }
+                });
+            });
+        self.layout.set_open("temperatures", !response.fully_closed());
+    }
+
+    fn energy_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(energy) = &self.energy else {
+            return;
+        };
+        let info = self.theme.info;
+        let primary = self.theme.primary;
+        let response = egui::CollapsingHeader::new(egui::RichText::new(tr("panel-energy")).color(info).monospace())
+            .default_open(self.layout.is_open("energy", false))
+            .show(ui, |ui| {
+                if let Some(e) = energy.error() {
+                    ui.label(egui::RichText::new(e).color(self.theme.dim).monospace());
+                    return;
+                }
+                let watts = energy.watts.map_or_else(|| "-".to_string(), |watts| format!("{:.1} W", watts));
+                let power = tr_args("energy-power", &[("watts", &watts)]);
+                ui.label(egui::RichText::new(power).color(primary).monospace());
+                let wh = format!("{:.2}", energy.total_joules / 3600.0);
+                ui.label(
+                    egui::RichText::new(tr_args("energy-session", &[("wh", &wh)]))
+                        .color(primary)
+                        .monospace()
+                );
+                let share = energy.high_memory_joules / energy.total_joules.max(f64::EPSILON) * 100.0;
+                ui.label(
+                    egui::RichText::new(tr_args("energy-high-memory", &[
+                        ("threshold", &format!("{:.0}%", self.config.alerts.thresholds.warning)),
+                        ("wh", &format!("{:.2}", energy.high_memory_joules / 3600.0)),
+                        ("minutes", &format!("{:.0}", energy.high_memory_secs / 60.0)),
+                        ("share", &format!("{:.0}%", share)),
+                    ]))
+                    .color(self.theme.warning)
+                    .monospace()
+                );
+                if let Some(factor) = self.config.energy.grid_g_co2_per_kwh {
+                    let grams = |joules: f64| joules / 3_600_000.0 * factor;
+                    ui.label(
+                        egui::RichText::new(tr_args("energy-carbon", &[
+                            ("grams", &format!("{:.1}", grams(energy.total_joules))),
+                            ("high", &format!("{:.1}", grams(energy.high_memory_joules))),
+                        ]))
+                        .color(primary)
+                        .monospace()
+                    );
+                }
+            });
+        self.layout.set_open("energy", !response.fully_closed());
+    }
+
+    fn pagefile_ui(&mut self, ui: &mut egui::Ui) {
+        let info = self.theme.info;
+        let primary = self.theme.primary;
+        let response = egui::CollapsingHeader::new(
+            egui::RichText::new(tr("panel-pagefile")).color(info).monospace()
+        )
+            .default_open(self.layout.is_open("pagefile", false))
+            .show(ui, |ui| {
+                if let Some(result) = self.pagefile_query.as_ref().and_then(|rx| rx.try_recv().ok()) {
+                    if let Err(e) = &result {
+                        diag::log(format!("Pagefile query failed: {}", e));
+                    }
+                    self.pagefile_info = Some(result);
+                    self.pagefile_query = None;
+                }
+                if self.pagefile_info.is_none() && self.pagefile_query.is_none() {
+                    self.pagefile_query = Some(pagefile::query_async());
+                }
+                let mut requested = None;
+
+                match &self.pagefile_info {
+                    None => {
+                        ui.label(egui::RichText::new(tr("pagefile-querying")).color(self.theme.dim).monospace());
+                    }
+                    Some(Err(e)) => {
+                        ui.label(egui::RichText::new(e).color(self.theme.warning).monospace());
+                    }
+                    Some(Ok(details)) => {
+                        if let Some(commit) = details.commit {
+                            ui.label(
+                                egui::RichText::new(tr_args("pagefile-commit", &[
+                                    ("committed", &format_bytes(commit.committed_bytes)),
+                                    ("limit", &format_bytes(commit.commit_limit)),
+                                    ("percent", &format!("{:.1}%", commit.percentage())),
+                                ]))
+                                .color(primary)
+                                .monospace()
+                            );
+                        }
+                        ui.label(
+                            egui::RichText::new(tr(if details.automatic {
+                                "pagefile-automatic-on"
+                            } else {
+                                "pagefile-automatic-off"
+                            }))
+                            .color(primary)
+                            .monospace()
+                        );
+                        for file in &details.pagefiles {
+                            ui.label(
+                                egui::RichText::new(tr_args("pagefile-file", &[
+                                    ("name", &file.name),
+                                    ("size", &file.allocated_base_size),
+                                    ("used", &file.current_usage),
+                                    ("peak", &file.peak_usage),
+                                ]))
+                                .color(primary)
+                                .monospace()
+                            );
+                        }
+                        let hiberfil = details.hiberfil_bytes
+                            .map(format_bytes)
+                            .unwrap_or_else(|| tr("hiberfil-absent"));
+                        ui.label(
+                            egui::RichText::new(tr_args("hiberfil", &[("size", &hiberfil)]))
+                                .color(primary)
+                                .monospace(),
+                        );
+                        for advice in pagefile::guidance(details, self.latest.total_memory) {
+                            ui.label(egui::RichText::new(advice).color(self.theme.warning));
+                        }
+
+                        let mut actions = Vec::new();
+                        if !details.automatic {
+                            actions.push(pagefile::Action::EnableAutomaticPagefile);
+                        }
+                        if details.hiberfil_bytes.is_some() {
+                            actions.push(pagefile::Action::DisableHibernation);
+                        }
+                        for action in actions {
+                            if ui.button(action.label()).clicked() {
+                                requested = Some(action);
+                            }
+                        }
+                    }
+                }
+                if let Some(action) = requested {
+                    self.request_system_change(action);
+                }
+                // Elevated changes apply asynchronously (and pagefile ones only after a reboot)
+                if self.pagefile_query.is_none() && ui.button(tr("button-refresh")).clicked() {
+                    self.pagefile_info = None;
+                }
+            });
+        self.layout.set_open("pagefile", !response.fully_closed());
+    }
+
+    // Stalled share of time for the health score: PSI on Linux, and on macOS the
+    // kernel's pressure level from the MEMORY PRESSURE panel, when it's been read
+    fn memory_pressure(&mut self) -> Option<f64> {
+        if !self.source.is_local() {
+            return None;
+        }
+        if cfg!(target_os = "macos") {
+            let memory = self.mac_memory.as_ref().and_then(|memory| memory.as_ref().ok());
+            return memory.and_then(|memory| memory.level).map(|level| match level {
+                pressure::PressureLevel::Normal => 0.0,
+                pressure::PressureLevel::Warning => 12.5,
+                pressure::PressureLevel::Critical => 25.0,
+            });
+        }
+        if self.psi_read.is_none_or(|at| at.elapsed() >= self.system_refresh()) {
+            self.psi = health::read_pressure();
+            self.psi_read = Some(Instant::now());
+        }
+        self.psi
+    }
+
+    fn health_ui(&self, ui: &mut egui::Ui, stale: bool) {
+        let Some(health) = &self.health else { return };
+        let color = match health.score {
+            _ if stale => self.theme.dim,
+            70.. => self.theme.primary,
+            40.. => self.theme.warning,
+            _ => self.theme.critical,
+        };
+        let breakdown: Vec<String> = health
+            .factors
+            .iter()
+            .map(|factor| {
+                format!("{:<14} -{:>4.1} of {:>2.0}  {}", factor.name, factor.penalty, factor.weight, factor.detail)
+            })
+            .collect();
+        let text = tr_args("health-score", &[("score", &health.score)]);
+        ui.label(egui::RichText::new(text).color(color).monospace().strong())
+            .on_hover_text(egui::RichText::new(breakdown.join("\n")).monospace());
+    }
+
+    fn pressure_ui(&mut self, ui: &mut egui::Ui) {
+        let info = self.theme.info;
+        let primary = self.theme.primary;
+        let response = egui::CollapsingHeader::new(egui::RichText::new(tr("panel-pressure")).color(info).monospace())
+            .default_open(self.layout.is_open("pressure", false))
+            .show(ui, |ui| {
+                let stale = self.mac_memory_refreshed.is_none_or(|at| at.elapsed() >= self.system_refresh());
+                if let Some(memory) = self.mac_memory_backend.poll(stale && !self.paused, |_| pressure::query()) {
+                    self.mac_memory = Some(memory);
+                    self.mac_memory_refreshed = Some(Instant::now());
+                }
+                self.refreshed_ui(ui, self.mac_memory_refreshed);
+                self.degraded_ui(ui, self.mac_memory_backend.degraded());
+                let memory = match &self.mac_memory {
+                    Some(Ok(memory)) => *memory,
+                    Some(Err(e)) => {
+                        ui.label(egui::RichText::new(e).color(self.theme.warning).monospace());
+                        return;
+                    }
+                    None => return,
+                };
+                let color = match memory.level {
+                    Some(pressure::PressureLevel::Normal) => primary,
+                    Some(pressure::PressureLevel::Warning) => self.theme.warning,
+                    Some(pressure::PressureLevel::Critical) => self.theme.critical,
+                    None => self.theme.dim,
+                };
+                let level = memory.level.map_or_else(|| tr("pressure-unknown"), |level| level.label().to_string());
+                let level = tr_args("pressure-level", &[("level", &level)]);
+                ui.label(egui::RichText::new(level).color(color).monospace());
+                if let Some(compressor) = memory.compressor {
+                    let ratio = compressor.ratio().map_or_else(|| "-".to_string(), |ratio| format!("{:.1}x", ratio));
+                    ui.label(
+                        egui::RichText::new(tr_args("pressure-compressed", &[
+                            ("stored", &format_bytes(compressor.stored)),
+                            ("ram", &format_bytes(compressor.occupied)),
+                            ("ratio", &ratio),
+                        ]))
+                        .color(primary)
+                        .monospace()
+                    );
+                }
+            });
+        self.layout.set_open("pressure", !response.fully_closed());
+    }
+
+    fn request_system_change(&mut self, action: pagefile::Action) {
+        if self.config.actions.dry_run {
+            diag::info(format!("Dry run: would {}", action.label()));
+        } else if self.config.actions.confirm_system_changes {
+            self.pending_action = Some(PendingAction::SystemChange(action));
+        } else {
+            run_system_change(action);
+        }
+    }
+
+    fn confirm_ui(&mut self, ctx: &egui::Context) {
+        let Some(action) = &self.pending_action else {
+            return;
+        };
+        let question = match action {
+            PendingAction::Kill(target) => tr_args("confirm-kill", &[
+                ("name", &target.name),
+                ("pid", &target.pid),
+                ("bytes", &format_bytes(target.memory)),
+            ]),
+            PendingAction::SystemChange(action) => format!("{}?", action.label()),
+        };
+        let mut decision = None;
+        egui::Window::new(tr("confirm-title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(question);
+                ui.horizontal(|ui| {
+                    if ui.button(egui::RichText::new(tr("confirm-go-ahead")).color(self.theme.critical)).clicked() {
+                        decision = Some(true);
+                    }
+                    if ui.button(tr("confirm-cancel")).clicked() {
+                        decision = Some(false);
+                    }
+                });
+            });
+        let Some(confirmed) = decision else {
+            return;
+        };
+        match self.pending_action.take() {
+            Some(PendingAction::Kill(target)) if confirmed => notify::kill(self.sampler.system_mut(), &target),
+            Some(PendingAction::SystemChange(action)) if confirmed => run_system_change(action),
+            _ => diag::info("Action cancelled"),
+        }
+    }
+
+    fn actions_ui(&mut self, ui: &mut egui::Ui) {
+        let info = self.theme.info;
+        let response = egui::CollapsingHeader::new(egui::RichText::new(tr("panel-actions")).color(info).monospace())
+            .default_open(self.layout.is_open("actions", false))
+            .show(ui, |ui| {
+                let actions = &mut self.config.actions;
+                let mut changed = ui.checkbox(&mut actions.dry_run, tr("actions-dry-run")).changed();
+                changed |= ui.checkbox(&mut actions.confirm_kill, tr("actions-confirm-kill")).changed();
+                changed |= ui.checkbox(&mut actions.confirm_system_changes, tr("actions-confirm-system")).changed();
+                if changed {
+                    if let Err(e) = self.save_config() {
+                        diag::log(format!("Could not save config: {}", e));
+                    }
+                }
+                ui.separator();
+                self.channel_tests_ui(ui);
+            });
+        self.layout.set_open("actions", !response.fully_closed());
+    }
+
+    // A [TEST] button per configured alert channel, so a typo in a webhook URL
+    // turns up now rather than during the outage it was meant to report
+    fn channel_tests_ui(&mut self, ui: &mut egui::Ui) {
+        for (_, test) in &mut self.channel_tests {
+            test.poll();
+        }
+        ui.label(egui::RichText::new(tr("channels-title")).color(self.theme.info).monospace());
+        let channels = notify::channels(&self.config);
+        if channels.is_empty() {
+            ui.label(egui::RichText::new(tr("channels-none")).color(self.theme.dim).monospace());
+            return;
+        }
+        let mut start = None;
+        for channel in &channels {
+            let label = channel.label();
+            ui.horizontal(|ui| {
+                let test = self.channel_tests.iter().find(|(tested, _)| *tested == label).map(|(_, test)| test);
+                let running = matches!(test, Some(notify::ChannelTest::Sending(_)));
+                if ui.add_enabled(!running, egui::Button::new(tr("button-test")).small()).clicked() {
+                    start = Some(channel.clone());
+                }
+                ui.label(egui::RichText::new(&label).color(self.theme.primary).monospace());
+                let (status, color) = match test {
+                    None => return,
+                    Some(notify::ChannelTest::Sending(_)) => (tr("channel-sending"), self.theme.dim),
+                    Some(notify::ChannelTest::Done(Ok(message))) => {
+                        (tr_args("channel-ok", &[("message", message)]), self.theme.primary)
+                    }
+                    Some(notify::ChannelTest::Done(Err(e))) => {
+                        (tr_args("channel-failed", &[("error", e)]), self.theme.critical)
+                    }
+                };
+                ui.label(egui::RichText::new(status).color(color).monospace());
+            });
+        }
+        if let Some(channel) = start {
+            let label = channel.label();
+            let hostname = self.alert_hostname();
+            let test = notify::test_channel(&channel, &self.config, &hostname);
+            self.channel_tests.retain(|(tested, _)| *tested != label);
+            self.channel_tests.push((label, test));
+        }
+    }
+
+    // Everything here applies immediately, so the window doubles as a live preview
+    fn settings_ui(&mut self, ctx: &egui::Context) {
+        let mut open = self.settings_open;
+        let mut changed = false;
+        egui::Window::new(tr("settings"))
+            .open(&mut open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                ui.strong(tr("settings-appearance"));
+                changed |= self.appearance_settings(ui);
+                ui.separator();
+                ui.strong(tr("settings-thresholds"));
+                changed |= self.threshold_settings(ui);
+                ui.separator();
+                ui.strong(tr("settings-sampling"));
+                changed |= self.sampling_settings(ui);
+                ui.separator();
+                ui.strong(tr("settings-highlighting"));
+                changed |= self.highlight_settings(ui);
+                ui.separator();
+                ui.strong(tr("settings-profiles"));
+                self.profile_settings(ui);
+                ui.separator();
+                ui.strong(tr("settings-performance"));
+                let performance = &mut self.config.performance;
+                changed |= ui.checkbox(&mut performance.adapt_under_pressure, tr("lean-under-pressure"))
+                    .changed();
+                changed |= ui.checkbox(&mut performance.show_self_stats, tr("show-self-stats"))
+                    .changed();
+                let soak = &mut self.config.soak;
+                changed |= ui.checkbox(&mut soak.enabled, tr("soak"))
+                    .on_hover_text(tr("hover-soak"))
+                    .changed();
+                ui.add_enabled_ui(soak.enabled, |ui| {
+                    let memory = egui::Slider::new(&mut soak.max_memory_mb, 64..=2048)
+                        .text(tr("soak-memory-cap"))
+                        .suffix(" MB");
+                    changed |= settled(&ui.add(memory));
+                    let disk = egui::Slider::new(&mut soak.max_disk_mb, 64..=16384)
+                        .logarithmic(true)
+                        .text(tr("soak-disk-cap"))
+                        .suffix(" MB");
+                    changed |= settled(&ui.add(disk));
+                });
+            });
+        self.settings_open = open;
+        if changed {
+            if let Err(e) = self.save_config() {
+                diag::log(format!("Could not save config: {}", e));
+            }
+        }
+    }
+
+    // Layouts of two --profile names side by side; the arrows copy one setting
+    // across. The running profile's layout is changed in place, since it's saved
+    // over its file on exit.
+    fn profile_settings(&mut self, ui: &mut egui::Ui) {
+        if self.profile_names.is_empty() {
+            self.profile_names = layout::profiles();
+            if !self.profile_names.contains(&self.profile) {
+                self.profile_names.push(self.profile.clone());
+            }
+            if self.compared.0.is_empty() {
+                self.compared.0 = self.profile.clone();
+            }
+        }
+        let mut compare = false;
+        ui.horizontal(|ui| {
+            for (side, chosen) in [("compare_left", &mut self.compared.0), ("compare_right", &mut self.compared.1)] {
+                egui::ComboBox::from_id_source(side)
+                    .selected_text(chosen.as_str())
+                    .show_ui(ui, |ui| {
+                        for name in &self.profile_names {
+                            compare |= ui.selectable_value(chosen, name.clone(), name).changed();
+                        }
+                    });
+            }
+            compare |= ui.button(tr("profiles-compare")).clicked();
+        });
+        if compare && !self.compared.0.is_empty() && !self.compared.1.is_empty() {
+            self.compare_profiles();
+        }
+
+        let mut copy = None;
+        match &self.comparison {
+            Some(Ok(comparison)) if comparison.differences.is_empty() => {
+                ui.label(egui::RichText::new(tr("profiles-same")).color(self.theme.dim));
+            }
+            Some(Ok(comparison)) => {
+                egui::Grid::new("profile_diff").striped(true).show(ui, |ui| {
+                    ui.strong(tr("profiles-setting"));
+                    ui.strong(&comparison.left);
+                    ui.label("");
+                    ui.strong(&comparison.right);
+                    ui.end_row();
+                    for difference in &comparison.differences {
+                        let value = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+                        ui.monospace(&difference.key);
+                        ui.monospace(value(&difference.left));
+                        ui.horizontal(|ui| {
+                            if ui.small_button("→").on_hover_text(tr("profiles-copy-right")).clicked() {
+                                copy = Some((difference.key.clone(), true));
+                            }
+                            if ui.small_button("←").on_hover_text(tr("profiles-copy-left")).clicked() {
+                                copy = Some((difference.key.clone(), false));
+                            }
+                        });
+                        ui.monospace(value(&difference.right));
+                        ui.end_row();
+                    }
+                });
+            }
+            Some(Err(e)) => {
+                ui.label(egui::RichText::new(e).color(self.theme.warning));
+            }
+            None => {}
+        }
+        if let Some((key, to_right)) = copy {
+            self.copy_profile_setting(&key, to_right);
+        }
+    }
+
+    fn profile_layout(&self, profile: &str) -> Layout {
+        if profile == self.profile { self.layout.clone() } else { Layout::load(profile) }
+    }
+
+    fn compare_profiles(&mut self) {
+        self.profile_names.clear();
+        let (left, right) = &self.compared;
+        let (left_layout, right_layout) = (self.profile_layout(left), self.profile_layout(right));
+        self.comparison = Some(Comparison::new((left, &left_layout), (right, &right_layout)));
+    }
+
+    fn copy_profile_setting(&mut self, key: &str, to_right: bool) {
+        let Some(Ok(comparison)) = &self.comparison else { return };
+        let target = if to_right { comparison.right.clone() } else { comparison.left.clone() };
+        let result = comparison.copied(key, to_right).and_then(|layout| {
+            if target == self.profile {
+                self.layout = layout;
+                Ok(())
+            } else {
+                layout.save(&target).map_err(|e| e.to_string())
+            }
+        });
+        match result {
+            Ok(()) => diag::info(format!("Copied {} to profile {}", key, target)),
+            Err(e) => diag::log(format!("Could not copy {} to profile {}: {}", key, target, e)),
+        }
+        self.compare_profiles();
+    }
+
+    // Text is saved once the field loses focus, but the table follows every keystroke
+    fn highlight_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        let rules = &mut self.config.processes.highlight;
+        let mut changed = false;
+        let mut edited = false;
+        let mut remove = None;
+        egui::Grid::new("highlight_rules").show(ui, |ui| {
+            for (index, rule) in rules.iter_mut().enumerate() {
+                let fields =
+                    [(&mut rule.pattern, "hint-rule-pattern", 110.0), (&mut rule.color, "hint-rule-color", 70.0)];
+                for (text, hint, width) in fields {
+                    let response = ui.add(egui::TextEdit::singleline(text).hint_text(tr(hint)).desired_width(width));
+                    edited |= response.changed();
+                    changed |= response.lost_focus();
+                }
+                let response = ui.add(
+                    egui::DragValue::new(&mut rule.min_memory_mb).prefix("\u{2265} ").suffix(" MB")
+                );
+                edited |= response.changed();
+                changed |= settled(&response);
+                changed |= ui.checkbox(&mut rule.bold, tr("rule-bold")).changed();
+                if ui.small_button("x").clicked() {
+                    remove = Some(index);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(index) = remove {
+            rules.remove(index);
+            changed = true;
+        }
+        if ui.button(tr("rule-add")).clicked() {
+            rules.push(HighlightRule::default());
+            changed = true;
+        }
+        for (index, error) in &self.highlighter.errors {
+            let text = tr_args("rule-error", &[("index", &(index + 1)), ("error", error)]);
+            ui.label(egui::RichText::new(text).color(self.theme.warning));
+        }
+        if edited || changed {
+            self.highlighter = Highlighter::new(&self.config.processes.highlight);
+        }
+        changed
+    }
+
+    fn appearance_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut selected = self.theme.name.clone();
+        egui::ComboBox::from_label(tr("theme"))
+            .selected_text(&selected)
+            .show_ui(ui, |ui| {
+                for theme in &self.themes {
+                    ui.selectable_value(&mut selected, theme.name.clone(), &theme.name);
+                }
+            });
+        let mut changed = false;
+        if selected != self.theme.name {
+            self.theme = theme::find(&self.themes, &selected);
+            self.config.appearance.theme = selected;
+            changed = true;
+        }
+        let mut language = self.config.appearance.language.clone();
+        let detected = i18n::system_language().unwrap_or_else(|| "en".to_string());
+        let system = tr_args("language-system", &[("language", &detected)]);
+        let name = |code: &str| if code.is_empty() { system.clone() } else { code.to_string() };
+        egui::ComboBox::from_label(tr("language"))
+            .selected_text(name(&language))
+            .show_ui(ui, |ui| {
+                for code in std::iter::once(String::new()).chain(i18n::languages(!self.safe_mode)) {
+                    let label = name(&code);
+                    ui.selectable_value(&mut language, code, label);
+                }
+            });
+        if language != self.config.appearance.language {
+            i18n::init(&language, !self.safe_mode);
+            self.config.appearance.language = language;
+            changed = true;
+        }
+        ui.horizontal(|ui| {
+            if ui.button(tr("theme-export")).on_hover_text(tr("hover-theme-export")).clicked() {
+                self.theme_status = self.export_theme(ui.ctx());
+            }
+            ui.add_enabled_ui(!self.safe_mode, |ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.theme_import_path)
+                        .hint_text(tr("hint-theme-path"))
+                        .desired_width(160.0)
+                );
+                if ui.button(tr("theme-import")).clicked() {
+                    changed |= self.import_theme();
+                }
+            });
+        });
+        if !self.theme_status.is_empty() {
+            ui.label(egui::RichText::new(&self.theme_status).color(self.theme.dim).small());
+        }
+
+        let appearance = &mut self.config.appearance;
+        changed |= settled(&ui.add(
+            egui::Slider::new(&mut appearance.ui_scale, MIN_UI_SCALE..=MAX_UI_SCALE)
+                .step_by(0.05)
+                .text(tr("ui-scale"))
+        ));
+        changed |= ui.checkbox(&mut appearance.reduced_motion, tr("reduced-motion"))
+            .on_hover_text(tr("hover-reduced-motion"))
+            .changed();
+        changed |= ui.checkbox(&mut appearance.rain, tr("rain"))
+            .on_hover_text(tr("hover-rain"))
+            .changed();
+        changed |= ui.checkbox(&mut appearance.crt, tr("crt"))
+            .on_hover_text(tr("hover-crt"))
+            .changed();
+        let glitch = &mut self.config.glitch;
+        changed |= ui.checkbox(&mut glitch.enabled, tr("glitch")).changed();
+        ui.add_enabled_ui(glitch.enabled, |ui| {
+            changed |= settled(&ui.add(
+                egui::Slider::new(&mut glitch.probability, 0.0..=0.5).text(tr("glitch-frames"))
+            ));
+            changed |= settled(&ui.add(
+                egui::Slider::new(&mut glitch.char_probability, 0.0..=1.0).text(tr("glitch-characters-replaced"))
+            ));
+            ui.horizontal(|ui| {
+                ui.label(tr("glitch-characters"));
+                changed |= ui.add(
+                    egui::TextEdit::singleline(&mut glitch.charset).font(egui::TextStyle::Monospace)
+                ).lost_focus();
+            });
+            changed |= ui.checkbox(&mut glitch.affect_numbers, tr("glitch-numbers")).changed();
+        });
+        changed
+    }
+
+    fn export_theme(&self, ctx: &egui::Context) -> String {
+        let effects = theme::Effects {
+            glitch: self.config.glitch.clone(),
+            crt: self.config.appearance.crt,
+            rain: self.config.appearance.rain,
+        };
+        let text = theme::export(&self.theme, effects);
+        ctx.output_mut(|output| output.copied_text = text.clone());
+        let saved = recorder::output_path("exports", "theme", "toml")
+            .and_then(|path| std::fs::write(&path, &text).map(|()| path));
+        match saved {
+            Ok(path) => {
+                diag::info(format!("Exported theme {} to {}", self.theme.name, path.display()));
+                tr_args("theme-exported", &[("path", &path.display())])
+            }
+            Err(e) => {
+                diag::log(format!("Could not export theme: {}", e));
+                tr_args("theme-export-failed", &[("error", &e)])
+            }
+        }
+    }
+
+    // Installs the theme, switches to it and takes on the effects it came with
+    fn import_theme(&mut self) -> bool {
+        let path = std::path::PathBuf::from(self.theme_import_path.trim());
+        match theme::import(&path) {
+            Ok(imported) => {
+                self.themes = theme::available();
+                self.theme = theme::find(&self.themes, &imported.name);
+                self.config.appearance.theme = imported.name.clone();
+                if let Some(effects) = imported.effects {
+                    self.config.glitch = effects.glitch;
+                    self.config.appearance.crt = effects.crt;
+                    self.config.appearance.rain = effects.rain;
+                }
+                self.theme_status = tr_args("theme-imported", &[("name", &imported.name)]);
+                self.theme_import_path.clear();
+                true
+            }
+            Err(e) => {
+                self.theme_status = e;
+                false
+            }
+        }
+    }
+
+    fn threshold_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        let thresholds = &mut self.config.alerts.thresholds;
+        let mut changed = settled(&ui.add(
+            egui::Slider::new(&mut thresholds.warning, 10.0..=99.0).suffix("%").text(tr("threshold-warning"))
+        ));
+        changed |= settled(&ui.add(
+            egui::Slider::new(&mut thresholds.critical, 20.0..=99.0).suffix("%").text(tr("threshold-critical"))
+        ));
+        changed |= settled(&ui.add(
+            egui::Slider::new(&mut thresholds.critical_secs, 0.0..=600.0).suffix("s").text(tr("threshold-sustained"))
+        ).on_hover_text(tr("hover-threshold-sustained")));
+        // The warning level has to stay below the alarm, or the ladder makes no sense
+        thresholds.warning = thresholds.warning.min(thresholds.critical - 1.0);
+        changed |= ui.checkbox(&mut self.config.alerts.hold_while_presenting, tr("quiet-presenting"))
+            .on_hover_text(tr("hover-quiet-presenting"))
+            .changed();
+        #[cfg(feature = "audio")]
+        {
+            let sound = &mut self.config.alerts.sound;
+            changed |= ui.checkbox(&mut sound.enabled, tr("alarm-sound"))
+                .on_hover_text(tr("hover-alarm-sound"))
+                .changed();
+            ui.add_enabled_ui(sound.enabled, |ui| {
+                changed |= settled(&ui.add(egui::Slider::new(&mut sound.volume, 0.0..=1.0).text(tr("sound-volume"))));
+                changed |= settled(&ui.add(
+                    egui::Slider::new(&mut sound.repeat_secs, 5.0..=300.0).suffix("s").text(tr("sound-repeat"))
+                ));
+            });
+        }
+        changed
+    }
+
+    fn sampling_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        let sampling = &mut self.config.sampling;
+        let range = SamplingConfig::MIN_INTERVAL_MS..=SamplingConfig::MAX_INTERVAL_MS;
+        let mut changed = settled(&ui.add(
+            egui::Slider::new(&mut sampling.interval_ms, range.clone())
+                .logarithmic(true)
+                .suffix(" ms")
+                .text(tr("sampling-memory"))
+        ));
+        changed |= settled(&ui.add(
+            egui::Slider::new(&mut sampling.process_interval_ms, range.clone())
+                .logarithmic(true)
+                .suffix(" ms")
+                .text(tr("sampling-processes"))
+        ));
+        changed |= settled(&ui.add(
+            egui::Slider::new(&mut sampling.system_interval_ms, range)
+                .logarithmic(true)
+                .suffix(" ms")
+                .text(tr("sampling-system"))
+        ));
+        let span = SamplingConfig::MIN_HISTORY_SECS..=SamplingConfig::MAX_HISTORY_SECS;
+        changed |= settled(&ui.add(
+            egui::Slider::new(&mut sampling.history_secs, span)
+                .logarithmic(true)
+                .suffix(" s")
+                .text(tr("sampling-span"))
+        ));
+        ui.label(tr_args("sampling-kept", &[("count", &sampling.history_len())]));
+        if self.daemon.is_some() {
+            ui.label(egui::RichText::new(tr("sampling-remote")).weak());
+        }
+        if changed {
+            self.sample_interval_ms.store(sampling.interval().as_millis() as u64, Ordering::Relaxed);
+            self.history.lock().unwrap().set_capacity(sampling.history_len());
+        }
+        changed
+    }
+
+    fn bug_report_ui(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        let info = self.theme.info;
+        if ui.button(egui::RichText::new(tr("button-report-problem")).color(info).monospace()).clicked() {
+            self.bug_report = Some(bugreport::generate(&self.config, self.sampler.system_mut()));
+        }
+
+        let mut open = self.bug_report.is_some();
+        let mut copy = false;
+        if let Some(report) = &mut self.bug_report {
+            egui::Window::new(tr("report-problem-title"))
+                .open(&mut open)
+                .default_size([460.0, 420.0])
+                .show(ctx, |ui| {
+                    ui.label(tr("report-problem-hint"));
+                    if ui.button(tr("button-copy")).clicked() {
+                        copy = true;
+                    }
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(report)
+                                .font(egui::TextStyle::Monospace)
+                                .desired_width(f32::INFINITY)
+                        );
+                    });
+                });
+            if copy {
+                let text = report.clone();
+                ctx.output_mut(|o| o.copied_text = text);
+            }
+        }
+        if !open {
+            self.bug_report = None;
+        }
+    }
+
+    fn plugins_ui(&mut self, ui: &mut egui::Ui) {
+        let info = self.theme.info;
+        let primary = self.theme.primary;
+        let response = egui::CollapsingHeader::new(egui::RichText::new(tr("panel-plugins")).color(info).monospace())
+            .default_open(self.layout.is_open("plugins", false))
+            .show(ui, |ui| {
+                if ui.button(egui::RichText::new(tr("button-reload")).color(primary).monospace())
+                    .on_hover_text(tr("hover-reload-plugins"))
+                    .clicked()
+                {
+                    self.plugins = PluginHost::load();
+                }
+                if self.plugins.is_empty() {
+                    ui.label(egui::RichText::new(tr("plugins-none")).color(self.theme.dim).monospace());
+                    return;
+                }
+                for plugin in &self.plugins.plugins {
+                    let (text, color) = match (&plugin.value, &plugin.error) {
+                        (_, Some(error)) if plugin.disabled() => {
+                            (tr_args("plugin-disabled", &[("error", error)]), self.theme.critical)
+                        }
+                        (_, Some(error)) => (tr_args("plugin-error", &[("error", error)]), self.theme.warning),
+                        (Some(value), None) => (format!("{:.2}", value), primary),
+                        (None, None) => ("-".to_string(), self.theme.dim),
+                    };
+                    let mut capabilities = plugin.capability_names().join(", ");
+                    if capabilities.is_empty() {
+                        capabilities = tr("plugin-no-capabilities");
+                    }
+                    ui.label(egui::RichText::new(format!("{}: {}", plugin.name, text)).color(color).monospace())
+                        .on_hover_text(tr_args("hover-plugin-capabilities", &[("capabilities", &capabilities)]));
+                }
+                for (name, error) in &self.plugins.rejected {
+                    ui.label(
+                        egui::RichText::new(tr_args("plugin-rejected", &[("name", name), ("error", error)]))
+                            .color(self.theme.critical)
+                            .monospace()
+                    );
+                }
+            });
+        self.layout.set_open("plugins", !response.fully_closed());
+    }
+
+    fn registry_ui(&mut self, ui: &mut egui::Ui) {
+        let info = self.theme.info;
+        let primary = self.theme.primary;
+
+        let response = egui::CollapsingHeader::new(egui::RichText::new(tr("panel-registry")).color(info).monospace())
+            .default_open(self.layout.is_open("registry", false))
+            .show(ui, |ui| {
+                let mut changed = false;
+                ui.horizontal(|ui| {
+                    changed |= ui.checkbox(&mut self.config.registry.enabled, tr("registry-network")).changed();
+                    changed |= ui.add(
+                        egui::TextEdit::singleline(&mut self.config.registry.url)
+                            .hint_text(tr("hint-registry-url"))
+                            .font(egui::TextStyle::Monospace)
+                    ).lost_focus();
+                });
+                if changed {
+                    if let Err(e) = self.save_config() {
+                        self.registry.status = tr_args("status-config-failed", &[("error", &e)]);
+                        diag::log(&self.registry.status);
+                    }
+                }
+                if !self.config.registry.enabled || self.config.registry.url.is_empty() {
+                    return;
+                }
+
+                ui.horizontal(|ui| {
+                    let refresh = ui.add_enabled(
+                        !self.registry.busy(),
+                        egui::Button::new(
+                            egui::RichText::new(tr("button-registry-refresh")).color(primary).monospace(),
+                        ),
+                    );
+                    if refresh.clicked() {
+                        self.registry.refresh(&self.config.registry);
+                    }
+                    ui.label(egui::RichText::new(&self.registry.status).color(info).monospace());
+                });
+
+                let mut install = None;
+                for entry in &self.registry.entries {
+                    let kind = tr(match entry.kind {
+                        registry::EntryKind::Plugin => "registry-plugin",
+                        registry::EntryKind::Theme => "registry-theme",
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new(format!("{:<6} {} {}", kind, entry.name, entry.version))
+                                .color(primary)
+                                .monospace()
+                        )
+                        .on_hover_text(&entry.description);
+                        if registry::is_installed(entry) {
+                            ui.label(egui::RichText::new(tr("registry-installed")).color(info).monospace());
+                        } else if let Some(version) = entry.api_version.filter(|&v| v != plugins::ABI_VERSION) {
+                            let text = tr_args("registry-needs-api", &[("version", &version)]);
+                            ui.label(egui::RichText::new(text).color(self.theme.dim).monospace());
+                        } else if ui
+                            .add_enabled(!self.registry.busy(), egui::Button::new(tr("registry-install")))
+                            .clicked()
+                        {
+                            install = Some(entry.clone());
+                        }
+                    });
+                }
+                if let Some(entry) = install {
+                    self.registry.install(&entry);
+                }
+            });
+        self.layout.set_open("registry", !response.fully_closed());
+    }
+
+    // Stacked bars, one per sample, so "used" growth can be told apart from page cache
+    fn composition_charts(&self, latest_ms: u64, bar_width: f64) -> Vec<BarChart> {
+        let bands = [
+            (tr("band-used"), self.theme.primary),
+            (tr("band-compressed"), self.theme.swap),
+            (tr("band-buffers"), self.theme.buffers),
+            (tr("band-cached"), self.theme.info),
+            (tr("band-free"), self.theme.free),
+        ];
+
+        let history = self.history.lock().unwrap();
+        let mut charts: Vec<BarChart> = Vec::with_capacity(bands.len());
+        for (band, (name, color)) in bands.into_iter().enumerate() {
+            // Keep the legend free of categories this platform never reports
+            if history.iter().all(|point| point.composition().bands()[band] == 0.0) {
+                continue;
+            }
+            let bars = history.iter()
+                .map(|point| {
+                    Bar::new(relative_secs(point.timestamp_ms, latest_ms), point.composition().bands()[band] as f64)
+                        .width(bar_width)
+                        .fill(color)
+                        .stroke(egui::Stroke::NONE)
+                })
+                .collect();
+            let below: Vec<&BarChart> = charts.iter().collect();
+            let chart = BarChart::new(bars)
+                .name(name)
+                .color(color)
+                .stack_on(&below);
+            charts.push(chart);
+        }
+        charts
+    }
+
+    // Replaces the single-host view entirely; only pausing applies to it
+    fn dashboard_frame(&mut self, ctx: &egui::Context) {
+        let Some(dashboard) = &mut self.dashboard else {
+            return;
+        };
+        if !ctx.wants_keyboard_input() && ctx.input(|i| i.key_pressed(egui::Key::Space)) {
+            self.paused = !self.paused;
+        }
+        if !self.paused {
+            dashboard.poll();
+        }
+        dashboard.update_alerts(&self.config.alerts, &self.config.actions);
+
+        let panel_frame = egui::Frame::central_panel(&ctx.style()).fill(self.theme.background);
+        egui::CentralPanel::default().frame(panel_frame).show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                let mut title = tr_args("dashboard-title", &[("hosts", &dashboard.len())]);
+                if self.paused {
+                    title.push_str(" [PAUSED]");
+                }
+                ui.vertical_centered(|ui| {
+                    ui.heading(egui::RichText::new(title).color(self.theme.primary).monospace());
+                });
+                ui.add_space(10.0);
+                dashboard.ui(ui, &self.theme);
+            });
+        });
+    }
+
+    fn frame(&mut self, ctx: &egui::Context) {
+        self.track_window(ctx);
+        if self.webhooks_retried.is_none_or(|at| at.elapsed() >= WEBHOOK_RETRY) {
+            notify::retry_webhooks(&self.config.actions);
+            self.webhooks_retried = Some(Instant::now());
+        }
+        if self.dashboard.is_some() {
+            if ctx.style().visuals.dark_mode != self.theme.dark {
+                ctx.set_visuals(if self.theme.dark { egui::Visuals::dark() } else { egui::Visuals::light() });
+            }
+            self.dashboard_frame(ctx);
+            ctx.request_repaint_after(self.repaint_interval(ctx));
+            return;
+        }
+
+        self.handle_shortcuts(ctx);
+        self.registry.poll();
+        if self.config.alerts.hold_while_presenting {
+            // Only worth asking once a warning or recovery could be coming, so the
+            // answer is fresh by the time one fires
+            let warning = self.config.alerts.thresholds.warning - PRESENTATION_MARGIN;
+            let near = self.latest.has_memory() && self.latest.memory_percentage() >= warning;
+            self.presentation.poll(near || self.alerts.critical || !self.held_alerts.is_empty());
+        }
+
+        // Update history (frozen while paused so the evidence stays on screen)
+        if !self.paused {
+            let samples = self.collect_samples();
+            if !samples.is_empty() {
+                self.sampled = Instant::now();
+            }
+            let sampled = !samples.is_empty();
+            for sample in samples {
+                self.push_sample(sample);
+            }
+            if sampled {
+                let pressure = self.memory_pressure();
+                self.health = Health::assess(&self.history.lock().unwrap(), pressure);
+            }
+            // The leaderboard walks the process list once a minute even with the panel closed
+            if let Some(offenders) = self.offenders.as_mut().filter(|offenders| offenders.due() && !self.lean) {
+                let rows = processes::collect(self.sampler.system_mut());
+                if let Err(e) = offenders.record(&rows, now_ms()) {
+                    diag::log(format!("Could not save process history: {}", e));
+                }
+            }
+            let top_due = self.top_processes.due(self.system_refresh()) && !self.lean;
+            if self.plot_mode == PlotMode::TopProcesses && self.source.is_local() && top_due {
+                let rows = processes::collect(self.sampler.system_mut());
+                self.top_processes.record(&rows, self.latest.used_memory, self.latest.timestamp_ms);
+            }
+            if let Some(focus) = &mut self.focus {
+                let growth_alert_percent = self.config.focus.growth_alert_percent;
+                focus.update(self.sampler.system_mut(), self.latest.total_memory, growth_alert_percent);
+            }
+            if self.source.is_local() && !self.budgets.is_empty() {
+                for message in self.budgets.check(self.sampler.system_mut(), &self.config.actions) {
+                    self.events.push(now_ms(), EventKind::Threshold, message);
+                }
+            }
+        }
+        
+        let memory_percentage = self.latest.memory_percentage();
+
+        self.alerts.thresholds = self.config.alerts.thresholds;
+        let was_critical = self.alerts.critical;
+        // Without totals there's nothing to judge, so alarms stay as they were
+        let due = if self.latest.has_memory() {
+            self.alerts.update(memory_percentage, Instant::now(), &self.config.alerts.escalation)
+        } else {
+            Vec::new()
+        };
+        if self.alerts.critical != was_critical {
+            let (kind, transition, verb) = if self.alerts.critical {
+                (EventKind::AlarmStart, notify::Transition::Critical, "raised")
+            } else {
+                (EventKind::AlarmEnd, notify::Transition::Recovery, "cleared")
+            };
+            let message = format!("Critical alarm {} ({:.1}%)", verb, memory_percentage);
+            self.events.push(self.latest.timestamp_ms, kind, message);
+            self.fire_alert_command(transition, memory_percentage);
+        }
+        let changes = self.rules.update(&self.config.alerts.rules, &self.latest, Instant::now());
+        for change in changes {
+            let rule = &self.config.alerts.rules[change.index];
+            let (kind, verb) = if change.raised {
+                (EventKind::AlarmStart, "raised")
+            } else {
+                (EventKind::AlarmEnd, "cleared")
+            };
+            let message = format!("Rule \"{}\" {} ({:.1}{})", rule.name, verb, change.value, rule.metric.unit());
+            self.events.push(self.latest.timestamp_ms, kind, message);
+            if change.raised && !self.alerts.is_snoozed(Instant::now()) {
+                let hostname = self.alert_hostname();
+                let rule = &self.config.alerts.rules[change.index];
+                notify::fire_rule(rule, &hostname, change.value, &self.config.actions);
+            }
+        }
+        self.write_jsonl();
+        self.share_alerts();
+        #[cfg(all(feature = "dbus", target_os = "linux"))]
+        self.serve_dbus();
+        #[cfg(feature = "mqtt")]
+        self.publish_mqtt();
+        if !self.held_alerts.is_empty() && !self.presentation.is_active() {
+            self.release_held_alerts(memory_percentage);
+        }
+        if self.source.is_local() && !self.paused {
+            self.events.check_oom(self.latest.timestamp_ms);
+        }
+        self.check_soak();
+        if self.bookmarks_polled.is_none_or(|at| at.elapsed() >= BOOKMARK_POLL) {
+            self.poll_bookmarks();
+        }
+        // Power keeps flowing while the plot is paused, so this integrates regardless
+        if let Some(energy) = &mut self.energy {
+            energy.update(memory_percentage > self.config.alerts.thresholds.warning);
+        }
+        for index in due {
+            let hostname = self.alert_hostname();
+            let context = notify::AlertContext {
+                hostname,
+                memory_percentage,
+                level: index + 1,
+            };
+            let sys = if self.source.is_local() { Some(self.sampler.system_mut()) } else { None };
+            let stage = &self.config.alerts.escalation[index];
+            #[cfg(feature = "audio")]
+            if let Some(volume) = stage.sound_volume {
+                self.alarm_sound.escalate(volume);
+            }
+            if let Some(target) = notify::fire_stage(stage, context, sys, &self.config.actions) {
+                self.pending_action = Some(PendingAction::Kill(target));
+            }
+        }
+        // The banner still goes up in safe mode; only the beeping stays off
+        #[cfg(feature = "audio")]
+        if !self.safe_mode {
+            self.alarm_sound.update(self.alerts.is_sounding(Instant::now()), &self.config.alerts.sound);
+        }
+
+        // Only our own machine's pressure is worth backing off for
+        let lean = self.config.performance.adapt_under_pressure && self.alerts.critical && self.source.is_local();
+        if lean && !self.lean {
+            self.shed_memory();
+            diag::info("Critical memory pressure: switching to lean mode");
+        }
+        self.lean = lean;
+
+        self.glitch_effect = self.config.glitch.enabled
+            && !self.lean
+            && !self.config.appearance.reduced_motion
+            && rand::thread_rng().gen_bool(self.config.glitch.probability.clamp(0.0, 1.0));
+
+        if ctx.style().visuals.dark_mode != self.theme.dark {
+            ctx.set_visuals(if self.theme.dark { egui::Visuals::dark() } else { egui::Visuals::light() });
+        }
+
+        if self.compact {
+            self.compact_ui(ctx, memory_percentage);
+            ctx.request_repaint_after(self.repaint_interval(ctx));
+            return;
+        }
+
+        let panel_frame = egui::Frame::central_panel(&ctx.style()).fill(self.theme.background);
+        egui::CentralPanel::default().frame(panel_frame).show(ctx, |ui| {
+            if let Some(intensity) = self.rain_intensity() {
+                let color = if self.alerts.critical {
+                    self.theme.critical
+                } else {
+                    self.theme.level(memory_percentage, &self.config.alerts.thresholds)
+                };
+                self.rain.paint(ui.painter(), ui.max_rect(), intensity, color);
+            }
+            egui::ScrollArea::vertical().show(ui, |ui| ui.vertical_centered(|ui| {
+                self.status_strip_ui(ui);
+                let snoozed = self.alerts.is_snoozed(Instant::now());
+                let mut title = if self.glitch_effect {
+                    glitch_text(&self.config.glitch, &tr("title"))
+                } else {
+                    tr("title")
+                };
+                let mut badges = Vec::new();
+                if let Some(addr) = self.source.label() {
+                    title.push_str(&format!(" [{}]", addr));
+                    if !self.daemon.as_ref().is_some_and(|daemon| daemon.is_connected()) {
+                        badges.push("badge-offline");
+                    }
+                } else if self.daemon.is_some() {
+                    badges.push("badge-linked");
+                }
+                if self.paused {
+                    badges.push("badge-paused");
+                }
+                if snoozed {
+                    badges.push("badge-snoozed");
+                } else if self.alerts.acknowledged {
+                    badges.push("badge-ack");
+                }
+                if self.lean {
+                    badges.push("badge-lean");
+                }
+                if self.safe_mode {
+                    badges.push("badge-safe-mode");
+                }
+                if self.config.actions.dry_run {
+                    badges.push("badge-dry-run");
+                }
+                for badge in badges {
+                    title.push_str(&format!(" [{}]", tr(badge)));
+                }
+                
+                ui.heading(
+                    egui::RichText::new(title)
+                        .color(if self.alerts.critical {
+                            self.theme.critical
+                        } else {
+                            self.theme.primary
+                        })
+                        .monospace()
+                );
+
+                ui.add_space(10.0);
+                self.controls_ui(ui);
+                ui.add_space(10.0);
+                
+                let stale_for = self.stale_for();
+                let usage = tr_args("memory-usage", &[("percent", &self.percent_text(memory_percentage))]);
+                let usage_color = if stale_for.is_some() || !self.latest.has_memory() {
+                    self.theme.dim
+                } else {
+                    self.theme.level(memory_percentage, &self.config.alerts.thresholds)
+                };
+                ui.label(egui::RichText::new(usage).color(usage_color).monospace());
+                self.health_ui(ui, stale_for.is_some());
+
+                let bar = self.usage_bar.get((memory_percentage / 2.0) as usize, |bar| format!("[{:^50}]", bar));
+                let bar_text = if self.glitch_effect && self.config.glitch.affect_numbers {
+                    glitch_text(&self.config.glitch, bar)
+                } else {
+                    bar.to_string()
+                };
+                
+                ui.label(
+                    egui::RichText::new(bar_text)
+                        .color(if memory_percentage > self.config.alerts.thresholds.critical {
+                            self.theme.critical
+                        } else {
+                            self.theme.primary
+                        })
+                        .monospace()
+                );
+
+                match stale_for {
+                    Some(age) => {
+                        ui.add_space(10.0);
+                        let since = format_duration(age.as_secs());
+                        ui.label(
+                            egui::RichText::new(tr_args("stale-data", &[("age", &since)]))
+                                .color(self.theme.warning)
+                                .strong()
+                                .heading()
+                        );
+                    }
+                    None => self.refreshed_ui(ui, Some(self.sampled)),
+                }
+
+                ui.add_space(20.0);
+                
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.plot_mode, PlotMode::Lines, tr("plot-lines"));
+                    ui.selectable_value(&mut self.plot_mode, PlotMode::Stacked, tr("plot-composition"));
+                    if self.source.is_local() {
+                        ui.selectable_value(&mut self.plot_mode, PlotMode::TopProcesses, tr("plot-top-processes"));
+                    }
+                    if self.plot_mode == PlotMode::TopProcesses {
+                        ui.separator();
+                        ui.label(tr("plot-top-count"));
+                        let count = egui::DragValue::new(&mut self.config.plot.top_processes).clamp_range(1..=12);
+                        if settled(&ui.add(count)) {
+                            if let Err(e) = self.save_config() {
+                                diag::log(format!("Could not save config: {}", e));
+                            }
+                        }
+                    }
+                    if self.plot_mode == PlotMode::Lines {
+                        ui.separator();
+                        let absolute = self.config.plot.absolute;
+                        ui.selectable_value(&mut self.config.plot.absolute, false, "%");
+                        ui.selectable_value(&mut self.config.plot.absolute, true, tr("plot-bytes"));
+                        if self.config.plot.absolute != absolute {
+                            self.reset_plot = true;
+                            self.layout.plot_bounds = None;
+                            if let Err(e) = self.save_config() {
+                                diag::log(format!("Could not save config: {}", e));
+                            }
+                        }
+                        ui.separator();
+                        self.smoothing_ui(ui);
+                    }
+                });
+
+                // x is seconds relative to the newest sample so the hover readout can
+                // map straight back to a wall-clock time
+                let latest_ms = self.latest.timestamp_ms;
+                let history = self.history.lock().unwrap();
+                let bar_width = match (history.first(), history.last()) {
+                    (Some(first), Some(last)) if history.len() > 1 => {
+                        (last.timestamp_ms - first.timestamp_ms) as f64 / 1000.0 / (history.len() - 1) as f64
+                    }
+                    _ => 0.5,
+                };
+                let oldest_ms = history.first().map_or(latest_ms, |first| first.timestamp_ms);
+                drop(history);
+                let event_marks: Vec<(f64, egui::Color32, String)> = self.events.iter()
+                    .filter(|event| event.timestamp_ms >= oldest_ms)
+                    .map(|event| {
+                        (relative_secs(event.timestamp_ms, latest_ms), self.event_color(event.kind), event.message.clone())
+                    })
+                    .collect();
+                let event_labels: Vec<(f64, String)> = event_marks.iter()
+                    .map(|(x, _, message)| (*x, message.clone()))
+                    .collect();
+                // The composition chart is always in percent, the top processes in bytes
+                let absolute = match self.plot_mode {
+                    PlotMode::Lines => self.config.plot.absolute,
+                    PlotMode::Stacked => false,
+                    PlotMode::TopProcesses => true,
+                };
+                let format_y = move |y: f64| {
+                    if absolute { format_bytes(y.max(0.0) as u64) } else { format!("{:.1}%", y) }
+                };
+                let mut plot = Plot::new("memory_usage")
+                    .height(200.0)
+                    .show_axes([true, true])
+                    .show_background(false)
+                    .allow_zoom(true)
+                    .allow_drag(true)
+                    .allow_scroll(true)
+                    .allow_double_click_reset(true)
+                    .include_y(0.0)
+                    .x_axis_formatter(|x, _, _| format!("{:.0}s", x))
+                    .label_formatter(move |name, value| {
+                        let timestamp_ms = (latest_ms as f64 + value.x * 1000.0).max(0.0) as u64;
+                        let header = if name.is_empty() { String::new() } else { format!("{}\n", name) };
+                        // Markers are one pixel wide, so anything within a sample's width counts as hovering it
+                        let events: String = event_labels.iter()
+                            .filter(|(x, _)| (x - value.x).abs() <= bar_width)
+                            .map(|(_, message)| format!("\n{}", message))
+                            .collect();
+                        format!(
+                            "{}{}\n{:+.1}s  {} UTC{}",
+                            header,
+                            format_y(value.y),
+                            value.x,
+                            format_clock(timestamp_ms),
+                            events,
+                        )
+                    });
+                plot = if absolute {
+                    let top = self.latest.total_memory.max(self.latest.total_swap);
+                    plot.include_y(top as f64).y_axis_formatter(|y, _, _| format_bytes(y.max(0.0) as u64))
+                } else {
+                    plot.include_y(100.0).y_axis_formatter(|y, _, _| format!("{:.0}%", y))
+                };
+                if std::mem::take(&mut self.reset_plot) {
+                    plot = plot.reset();
+                }
+                // The lines get a legend of our own below, whose choices are saved
+                if self.plot_mode != PlotMode::Lines {
+                    plot = plot.legend(Legend::default());
+                }
+                
+                self.plot_series.update(&self.history.lock().unwrap(), latest_ms, absolute, &self.config.plot);
+                let series = &self.plot_series;
+                // Raw lines go thin behind the smoothed ones, or away entirely
+                let raw_width = if series.is_smoothed() { 1.0 } else { 2.0 };
+                let show_raw = !series.is_smoothed() || !self.config.plot.hide_raw;
+
+                let plot_mode = self.plot_mode;
+                let names = ["legend-events", "legend-range", "legend-ram", "legend-swap", "legend-ram-smoothed",
+                    "legend-swap-smoothed"].map(tr);
+                // Grayed out while stale, so the last numbers don't pass for current ones
+                let (ram_color, range_color, swap_color) = if stale_for.is_some() {
+                    (self.theme.dim, self.theme.dim.gamma_multiply(0.5), self.theme.dim.gamma_multiply(0.7))
+                } else {
+                    (self.theme.primary, self.theme.dim, self.theme.swap)
+                };
+                let bar_charts = match plot_mode {
+                    PlotMode::Lines => Vec::new(),
+                    PlotMode::Stacked => self.composition_charts(latest_ms, bar_width),
+                    PlotMode::TopProcesses => {
+                        let other = (tr("legend-other"), self.theme.dim);
+                        self.top_processes.charts(self.config.plot.top_processes, latest_ms, other)
+                    }
+                };
+                let restore_bounds = self.restore_plot_bounds.take();
+                let shown = |id: &str| !self.config.plot.hidden.iter().any(|hidden| hidden == id);
+                let (show_events, show_range) = (shown("events"), shown("range"));
+                let (show_ram, show_swap) = (shown("ram"), shown("swap"));
+
+                let response = plot.show(ui, |plot_ui| {
+                    if let Some([min_x, min_y, max_x, max_y]) = restore_bounds {
+                        plot_ui.set_plot_bounds(PlotBounds::from_min_max([min_x, min_y], [max_x, max_y]));
+                    }
+                    for (x, color, _) in event_marks.into_iter().filter(|_| show_events) {
+                        plot_ui.vline(VLine::new(x).color(color).style(LineStyle::dotted_dense()).name(&names[0]));
+                    }
+                    if plot_mode != PlotMode::Lines {
+                        for chart in bar_charts {
+                            plot_ui.bar_chart(chart);
+                        }
+                        return;
+                    }
+                    // egui_plot wants its points owned, so each line costs one copy of a cached buffer
+                    for range in [&series.range_min, &series.range_max].into_iter().filter(|_| show_range) {
+                        plot_ui.line(
+                            Line::new(PlotPoints::Owned(range.clone()))
+                                .color(range_color)
+                                .name(&names[1])
+                                .style(LineStyle::dashed_dense())
+                        );
+                    }
+                    if show_raw && show_ram {
+                        plot_ui.line(
+                            Line::new(PlotPoints::Owned(series.memory.clone()))
+                                .color(ram_color)
+                                .name(&names[2])
+                                .width(raw_width)
+                        );
+                    }
+                    if show_raw && show_swap {
+                        plot_ui.line(
+                            Line::new(PlotPoints::Owned(series.swap.clone()))
+                                .color(swap_color)
+                                .name(&names[3])
+                                .width(raw_width)
+                        );
+                    }
+                    if series.is_smoothed() {
+                        let smoothed = [
+                            (&series.smoothed_memory, ram_color, &names[4], show_ram),
+                            (&series.smoothed_swap, swap_color, &names[5], show_swap),
+                        ];
+                        for (points, color, name, _) in smoothed.into_iter().filter(|line| line.3) {
+                            plot_ui.line(Line::new(PlotPoints::Owned(points.clone())).color(color).name(name).width(2.0));
+                        }
+                    }
+                });
+                if plot_mode == PlotMode::Lines {
+                    let [events, range, ram, swap, ..] = names;
+                    let lines = [
+                        ("ram", ram, ram_color),
+                        ("swap", swap, swap_color),
+                        ("range", range, range_color),
+                        ("events", events, self.theme.info),
+                    ];
+                    self.legend_ui(ui, lines);
+                }
+                // Only a zoom or drag is worth restoring; double-click goes back to following the data
+                let zoomed = response.response.hovered()
+                    && ctx.input(|i| i.zoom_delta() != 1.0 || i.scroll_delta != egui::Vec2::ZERO);
+                if response.response.double_clicked() {
+                    self.layout.plot_bounds = None;
+                } else if response.response.dragged() || zoomed {
+                    let bounds = response.transform.bounds();
+                    let ([min_x, min_y], [max_x, max_y]) = (bounds.min(), bounds.max());
+                    self.layout.plot_bounds = Some([min_x, min_y, max_x, max_y]);
+                }
+
+                if self.alerts.critical {
+                    ui.add_space(10.0);
+                    // Once acknowledged or snoozed the banner shrinks to a badge
+                    if self.alerts.acknowledged || snoozed {
+                        let key = if snoozed { "critical-snoozed" } else { "critical-acknowledged" };
+                        ui.label(
+                            egui::RichText::new(tr(key))
+                                .color(self.theme.critical)
+                                .monospace()
+                        );
+                    } else {
+                        ui.label(
+                            egui::RichText::new(tr("critical-banner"))
+                                .color(self.theme.critical)
+                                .strong()
+                                .heading()
+                        );
+                        let level = self.alerts.escalation_level();
+                        if level > 0 {
+                            ui.label(
+                                egui::RichText::new(tr_args("escalation-level", &[("level", &level)]))
+                                    .color(self.theme.critical)
+                                    .monospace()
+                            );
+                        }
+                        let ack = egui::RichText::new(tr("button-ack")).color(self.theme.critical).monospace();
+                        if ui.button(ack).clicked() {
+                            self.alerts.acknowledge();
+                        }
+                    }
+                }
+
+                ui.add_space(20.0);
+                ui.label(
+                    egui::RichText::new(if !self.latest.has_memory() {
+                        tr_args("total-memory", &[("bytes", &tr("not-available"))])
+                    } else if self.latest.cgroup_limited {
+                        tr_args("total-memory-cgroup", &[("bytes", &format_bytes(self.latest.total_memory))])
+                    } else {
+                        tr_args("total-memory", &[("bytes", &format_bytes(self.latest.total_memory))])
+                    })
+                        .color(self.theme.info)
+                        .monospace()
+                );
+                ui.label(
+                    egui::RichText::new(tr_args("used-memory", &[("bytes", &format_bytes(self.latest.used_memory))]))
+                        .color(self.theme.info)
+                        .monospace()
+                );
+                if self.latest.compressed_memory > 0 {
+                    let compressed = format_bytes(self.latest.compressed_memory);
+                    ui.label(
+                        egui::RichText::new(tr_args("compressed-memory", &[("bytes", &compressed)]))
+                        .color(self.theme.info)
+                        .monospace()
+                    );
+                }
+                if self.source.is_local() {
+                    self.swap_compression_ui(ui);
+                }
+
+                ui.add_space(10.0);
+                if self.source.is_local() {
+                    self.focus_ui(ui);
+                    self.budgets_ui(ui);
+                    self.processes_ui(ui);
+                    if cfg!(target_os = "linux") {
+                        self.containers_ui(ui);
+                        self.hugepages_ui(ui);
+                    }
+                    self.offenders_ui(ui);
+                    self.accounting_ui(ui);
+                    self.energy_ui(ui);
+                    self.temperatures_ui(ui);
+                    if cfg!(windows) {
+                        self.pagefile_ui(ui);
+                    }
+                    if cfg!(target_os = "macos") {
+                        self.pressure_ui(ui);
+                    }
+                } else if matches!(self.source, Source::Remote(_)) {
+                    self.agent_ui(ui);
+                }
+                self.statistics_ui(ui);
+                self.events_ui(ui);
+                self.snapshots_ui(ui);
+                self.actions_ui(ui);
+                if !self.safe_mode {
+                    self.plugins_ui(ui);
+                    self.registry_ui(ui);
+                }
+                ui.add_space(10.0);
+                self.bug_report_ui(ctx, ui);
+            }));
+        });
+        self.settings_ui(ctx);
+        self.confirm_ui(ctx);
+
+        ctx.request_repaint_after(self.repaint_interval(ctx));
+    }
+
+    fn self_stats_ui(&mut self, ctx: &egui::Context) {
+        // Our own process is local even when the samples come from elsewhere
+        let every = self.system_refresh();
+        self.self_stats.refresh_rss(self.sampler.system_mut(), every);
+        let mut lines = vec![match self.self_stats.rss {
+            Some(rss) => tr_args("self-rss", &[("bytes", &format_bytes(rss))]),
+            None => tr_args("self-rss", &[("bytes", &tr("not-available"))]),
+        }];
+        if let Some((mean, max)) = self.self_stats.frame_time() {
+            let millis = |time: Duration| time.as_secs_f64() * 1000.0;
+            lines.push(tr_args("self-frame", &[
+                ("mean", &format!("{:.1}", millis(mean))),
+                ("max", &format!("{:.1}", millis(max))),
+            ]));
+        }
+        if let Some(allocations) = self.self_stats.allocations_per_frame() {
+            lines.push(tr_args("self-allocations", &[("count", &format!("{:.0}", allocations))]));
+        }
+        egui::Area::new("self_stats")
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-8.0, -8.0))
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    for line in lines {
+                        ui.label(egui::RichText::new(line).color(self.theme.dim).monospace().small());
+                    }
+                });
+            });
+    }
+}
+
+impl eframe::App for MemoryMonitor {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let frame = SelfStats::begin();
+        self.apply_accessibility(ctx);
+        self.frame(ctx);
+        if self.config.appearance.crt {
+            crt::paint(ctx, self.theme.primary, self.glitch_effect);
+        }
+        if self.config.performance.show_self_stats {
+            self.self_stats_ui(ctx);
+        }
+        self.self_stats.end(frame);
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.save_layout();
+    }
+}
+
+fn run_system_change(action: pagefile::Action) {
+    if let Err(e) = pagefile::run_elevated(action) {
+        diag::log(format!("Pagefile action failed: {}", e));
+    }
+}
+
+// A "[####    ]" bar, formatted again only when its fill changes
+#[derive(Default)]
+struct UsageBar {
+    filled: Option<usize>,
+    text: String,
+}
+
+impl UsageBar {
+    fn get(&mut self, filled: usize, format: impl FnOnce(&str) -> String) -> &str {
+        if self.filled != Some(filled) {
+            self.text = format(&"#".repeat(filled));
+            self.filled = Some(filled);
+        }
+        &self.text
+    }
+}
+
+fn glitch_text(glitch: &GlitchConfig, text: &str) -> String {
+    let charset_len = glitch.charset.chars().count();
+    if charset_len == 0 {
+        return text.to_string();
+    }
+    let probability = glitch.char_probability.clamp(0.0, 1.0);
+    let mut rng = rand::thread_rng();
+    let mut glitched = String::with_capacity(text.len());
+    for c in text.chars() {
+        if rng.gen_bool(probability) {
+            glitched.extend(glitch.charset.chars().nth(rng.gen_range(0..charset_len)));
+        } else {
+            glitched.push(c);
+        }
+    }
+    glitched
+}
+
+// True once a slider edit is finished, so config isn't rewritten on every drag frame
+fn settled(response: &egui::Response) -> bool {
+    response.drag_released() || (response.changed() && !response.dragged())
+}
+
+// Lays a cell out at the column's width, so columns line up and keep their size
+fn sized(ui: &mut egui::Ui, width: f32, add_contents: impl FnOnce(&mut egui::Ui)) {
+    let size = egui::vec2(width, ui.spacing().interact_size.y);
+    ui.allocate_ui_with_layout(size, egui::Layout::left_to_right(egui::Align::Center), |ui| {
+        ui.set_min_width(width);
+        add_contents(ui);
+    });
+}
+
+// Everything but the name, which is a selectable label of its own
+fn process_cell(row: &ProcessRow, column: ProcessColumn, now_secs: u64) -> String {
+    let bytes = |bytes: Option<u64>| bytes.map(format_bytes).unwrap_or_else(|| "-".to_string());
+    match column {
+        ProcessColumn::Pid => row.pid.to_string(),
+        ProcessColumn::Name => row.name.clone(),
+        ProcessColumn::User => row.user.clone().unwrap_or_else(|| "?".to_string()),
+        ProcessColumn::Rss => format_bytes(row.memory),
+        ProcessColumn::Pss => bytes(row.pss),
+        ProcessColumn::Swap => bytes(row.swap),
+        ProcessColumn::Cpu => format!("{:.1}%", row.cpu),
+        ProcessColumn::Io => row.io.map_or_else(|| "-".to_string(), |rate| format!("{}/s", format_bytes(rate))),
+        ProcessColumn::Threads => row.threads.map_or_else(|| "-".to_string(), |threads| threads.to_string()),
+        ProcessColumn::Started => format_age(now_secs.saturating_sub(row.start_time)),
+    }
+}
+
+fn format_age(secs: u64) -> String {
+    tr_args("age-ago", &[("age", &format_duration(secs))])
+}
+
+fn format_duration(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86_399 => format!("{}h{:02}m", secs / 3600, secs / 60 % 60),
+        _ => format!("{}d{}h", secs / 86_400, secs / 3600 % 24),
+    }
+}
+
+fn format_clock(timestamp_ms: u64) -> String {
+    let secs_of_day = (timestamp_ms / 1000) % 86_400;
+    format!(
+        "{:02}:{:02}:{:02}.{:01}",
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        timestamp_ms % 1000 / 100,
+    )
+}
+
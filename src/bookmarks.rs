@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+#[cfg(any(feature = "gui", test))]
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use rusty_mem_monitor::sample::now_ms;
+
+use crate::config;
+
+const FILE_NAME: &str = "bookmarks.jsonl";
+
+// Marks on the timeline, shared by every way of running the monitor through one
+// append-only file in the data directory: `--bookmark` from an SSH session, the
+// GUI's [ MARK ] button, and exports all read and write the same lines.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Bookmark {
+    pub timestamp_ms: u64,
+    pub text: String,
+}
+
+pub fn add(text: &str) -> std::io::Result<Bookmark> {
+    let path = path().ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no data directory"))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let bookmark = Bookmark {
+        timestamp_ms: now_ms(),
+        text: text.trim().to_string(),
+    };
+    append(&path, &bookmark)?;
+    Ok(bookmark)
+}
+
+// One write per line, so concurrent writers can't interleave within a bookmark
+fn append(path: &Path, bookmark: &Bookmark) -> std::io::Result<()> {
+    let mut line = serde_json::to_string(bookmark)?;
+    line.push('\n');
+    OpenOptions::new().create(true).append(true).open(path)?.write_all(line.as_bytes())
+}
+
+// Follows the file like `tail -f`: each poll returns only what was appended since
+// the last one, whoever appended it
+#[cfg(any(feature = "gui", test))]
+pub struct BookmarkReader {
+    path: Option<PathBuf>,
+    offset: u64,
+}
+
+#[cfg(any(feature = "gui", test))]
+impl BookmarkReader {
+    #[cfg(feature = "gui")]
+    pub fn new() -> Self {
+        Self { path: path(), offset: 0 }
+    }
+
+    pub fn poll(&mut self) -> Vec<Bookmark> {
+        let Some(path) = &self.path else {
+            return Vec::new();
+        };
+        let Ok(mut file) = std::fs::File::open(path) else {
+            return Vec::new();
+        };
+        if file.seek(SeekFrom::Start(self.offset)).is_err() {
+            return Vec::new();
+        }
+        let mut reader = BufReader::new(file);
+        let mut bookmarks = Vec::new();
+        let mut line = String::new();
+        // A line without its newline is still being written; it's read next time
+        while reader.read_line(&mut line).is_ok_and(|read| read > 0) && line.ends_with('\n') {
+            self.offset += line.len() as u64;
+            if let Ok(bookmark) = serde_json::from_str(&line) {
+                bookmarks.push(bookmark);
+            }
+            line.clear();
+        }
+        bookmarks
+    }
+}
+
+// Writes the marks that fall within an export next to it, as `<export>.bookmarks.jsonl`
+#[cfg(feature = "gui")]
+pub fn export_range(export: &Path, start_ms: u64, end_ms: u64) -> std::io::Result<Option<PathBuf>> {
+    let bookmarks = in_range(start_ms, end_ms);
+    if bookmarks.is_empty() {
+        return Ok(None);
+    }
+    let path = export.with_extension(FILE_NAME);
+    let _ = std::fs::remove_file(&path);
+    for bookmark in &bookmarks {
+        append(&path, bookmark)?;
+    }
+    Ok(Some(path))
+}
+
+#[cfg(feature = "gui")]
+pub fn in_range(start_ms: u64, end_ms: u64) -> Vec<Bookmark> {
+    BookmarkReader::new()
+        .poll()
+        .into_iter()
+        .filter(|bookmark| (start_ms..=end_ms).contains(&bookmark.timestamp_ms))
+        .collect()
+}
+
+fn path() -> Option<PathBuf> {
+    config::data_dir().map(|dir| dir.join(FILE_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reader_picks_up_only_complete_new_lines() {
+        let path = std::env::temp_dir().join(format!("rmm-bookmarks-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut reader = BookmarkReader { path: Some(path.clone()), offset: 0 };
+        assert!(reader.poll().is_empty());
+
+        let mark = |timestamp_ms, text: &str| Bookmark { timestamp_ms, text: text.to_string() };
+        append(&path, &mark(1, "deploy")).unwrap();
+        append(&path, &mark(2, "rollback")).unwrap();
+        assert_eq!(reader.poll(), [mark(1, "deploy"), mark(2, "rollback")]);
+
+        OpenOptions::new().append(true).open(&path).unwrap().write_all(b"{\"timestamp_ms\":3,").unwrap();
+        assert!(reader.poll().is_empty());
+        OpenOptions::new().append(true).open(&path).unwrap().write_all(b"\"text\":\"oom\"}\n").unwrap();
+        assert_eq!(reader.poll(), [mark(3, "oom")]);
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,296 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use eframe::egui;
+use sysinfo::{Pid, PidExt, ProcessExt, Signal, System, SystemExt};
+
+use rusty_mem_monitor::sample::now_ms;
+use rusty_mem_monitor::units::format_bytes;
+
+use crate::config::{ActionsConfig, BudgetConfig};
+use crate::diag;
+use crate::i18n::{tr, tr_args};
+use crate::theme::Theme;
+
+const MB: u64 = 1024 * 1024;
+// Budgets matched by name look for a new process this often while none is running
+const SEARCH_INTERVAL: Duration = Duration::from_secs(5);
+// A level is only left once usage is this far under its budget, so an app
+// hovering at the line isn't signalled on every sample
+const HYSTERESIS: f64 = 0.9;
+
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub enum Level {
+    Under,
+    Soft,
+    Hard,
+}
+
+impl Level {
+    fn label(&self) -> &'static str {
+        match self {
+            Level::Under => "under",
+            Level::Soft => "soft",
+            Level::Hard => "hard",
+        }
+    }
+}
+
+struct Budget {
+    config: BudgetConfig,
+    pid: Option<Pid>,
+    name: String,
+    rss: u64,
+    level: Level,
+    // Set while a write to the pipe waits for a reader, so they don't pile up
+    writing: Arc<AtomicBool>,
+}
+
+// Cooperative memory management for a developer's own app: it's told over a
+// signal or a named pipe when it crosses its soft or hard budget, and can shed
+// caches itself before the kernel's OOM killer has to step in.
+pub struct Budgets {
+    budgets: Vec<Budget>,
+    searched: Option<Instant>,
+}
+
+impl Budgets {
+    pub fn new(configs: Vec<BudgetConfig>) -> Self {
+        let budgets = configs
+            .into_iter()
+            .filter(|config| config.soft_mb > 0 && (config.pid > 0 || !config.name.is_empty()))
+            .map(|config| Budget {
+                pid: (config.pid > 0).then(|| Pid::from_u32(config.pid)),
+                name: config.name.clone(),
+                config,
+                rss: 0,
+                level: Level::Under,
+                writing: Arc::new(AtomicBool::new(false)),
+            })
+            .collect();
+        Self { budgets, searched: None }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.budgets.is_empty()
+    }
+
+    // Refreshes the watched processes and acts on every budget crossed since the
+    // last call; what happened comes back for the event log
+    pub fn check(&mut self, sys: &mut System, actions: &ActionsConfig) -> Vec<String> {
+        let searching = self.budgets.iter().any(|budget| budget.pid.is_none());
+        if searching && self.searched.is_none_or(|at| at.elapsed() >= SEARCH_INTERVAL) {
+            sys.refresh_processes();
+            self.searched = Some(Instant::now());
+            for budget in self.budgets.iter_mut().filter(|budget| budget.pid.is_none()) {
+                budget.pid = sys
+                    .processes()
+                    .values()
+                    .find(|process| process.name() == budget.config.name)
+                    .map(|process| process.pid());
+            }
+        }
+
+        let mut messages = Vec::new();
+        for budget in &mut self.budgets {
+            let Some(pid) = budget.pid else { continue };
+            let Some(process) = sys.refresh_process(pid).then(|| sys.process(pid)).flatten() else {
+                if budget.config.name.is_empty() {
+                    messages.push(format!("Budgeted process {} exited", pid));
+                    budget.config.soft_mb = 0;
+                }
+                budget.pid = None;
+                budget.rss = 0;
+                budget.level = Level::Under;
+                continue;
+            };
+            budget.name = process.name().to_string();
+            let previous = budget.level;
+            let Some(rising) = budget.advance(process.memory()) else { continue };
+            let message = format!(
+                "{} ({}) is {} its {} budget: {}",
+                budget.name,
+                pid,
+                if rising { "over" } else { "back under" },
+                if rising { budget.level.label() } else { previous.label() },
+                format_bytes(budget.rss),
+            );
+            budget.notify(process, rising, actions.dry_run);
+            diag::info(&message);
+            messages.push(message);
+        }
+        // A process budgeted by pid is done with once it's gone
+        self.budgets.retain(|budget| budget.config.soft_mb > 0);
+        messages
+    }
+
+    pub fn ui(&self, ui: &mut egui::Ui, theme: &Theme) {
+        egui::Grid::new("budget_table").striped(true).show(ui, |ui| {
+            for header in ["column-process", "column-rss", "column-soft", "column-hard", "column-state"] {
+                ui.label(egui::RichText::new(tr(header)).color(theme.info).monospace());
+            }
+            ui.end_row();
+            for budget in &self.budgets {
+                let color = match budget.level {
+                    Level::Under => theme.primary,
+                    Level::Soft => theme.warning,
+                    Level::Hard => theme.critical,
+                };
+                let name = match budget.pid {
+                    Some(pid) => format!("{} ({})", budget.name, pid),
+                    None => tr_args("budget-not-running", &[("name", &budget.config.name)]),
+                };
+                let hard = match budget.config.hard_mb {
+                    0 => "-".to_string(),
+                    hard => format_bytes(hard * MB),
+                };
+                ui.label(egui::RichText::new(name).color(theme.primary).monospace());
+                ui.label(egui::RichText::new(format_bytes(budget.rss)).color(color).monospace());
+                let soft = format_bytes(budget.config.soft_mb * MB);
+                ui.label(egui::RichText::new(soft).color(theme.primary).monospace());
+                ui.label(egui::RichText::new(hard).color(theme.primary).monospace());
+                let state = tr(match budget.level {
+                    Level::Under => "budget-under",
+                    Level::Soft => "budget-soft",
+                    Level::Hard => "budget-hard",
+                });
+                ui.label(egui::RichText::new(state).color(color).monospace());
+                ui.end_row();
+            }
+        });
+    }
+}
+
+impl Budget {
+    // Whether the level went up, or None if it stayed put
+    fn advance(&mut self, rss: u64) -> Option<bool> {
+        self.rss = rss;
+        let level = level_for(rss, self.level, &self.config);
+        let rising = level > self.level;
+        (std::mem::replace(&mut self.level, level) != level).then_some(rising)
+    }
+
+    // Only crossings upward are signalled; the pipe hears about every change
+    fn signal(&self, rising: bool) -> &str {
+        match self.level {
+            _ if !rising => "",
+            Level::Under => "",
+            Level::Soft => &self.config.soft_signal,
+            Level::Hard => &self.config.hard_signal,
+        }
+    }
+
+    fn notify(&self, process: &sysinfo::Process, rising: bool, dry_run: bool) {
+        let signal = self.signal(rising);
+        if !signal.is_empty() {
+            match parse_signal(signal) {
+                Some(_) if dry_run => diag::info(format!("Dry run: would send {} to {}", signal, process.pid())),
+                Some(parsed) => {
+                    if process.kill_with(parsed) != Some(true) {
+                        diag::log(format!("Could not send {} to {}", signal, process.pid()));
+                    }
+                }
+                None => diag::log(format!("Unknown signal in budget for {}: {}", self.name, signal)),
+            }
+        }
+        if !self.config.pipe.is_empty() {
+            self.write_pipe(process.pid());
+        }
+    }
+
+    // Opening a FIFO blocks until someone reads it, so this happens on its own
+    // thread; a change while it's still waiting is dropped
+    fn write_pipe(&self, pid: Pid) {
+        if self.writing.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let line = serde_json::json!({
+            "timestamp_ms": now_ms(),
+            "pid": pid.as_u32(),
+            "level": self.level.label(),
+            "rss": self.rss,
+            "soft": self.config.soft_mb * MB,
+            "hard": self.config.hard_mb * MB,
+        })
+        .to_string();
+        let path = self.config.pipe.clone();
+        let writing = Arc::clone(&self.writing);
+        std::thread::spawn(move || {
+            let result = OpenOptions::new().write(true).open(&path).and_then(|mut pipe| writeln!(pipe, "{}", line));
+            if let Err(e) = result {
+                diag::log(format!("Could not write to {}: {}", path, e));
+            }
+            writing.store(false, Ordering::Release);
+        });
+    }
+}
+
+fn level_for(rss: u64, current: Level, config: &BudgetConfig) -> Level {
+    let over = |mb: u64, factor: f64| mb > 0 && rss as f64 >= (mb * MB) as f64 * factor;
+    let factor = |level: Level| if current >= level { HYSTERESIS } else { 1.0 };
+    if over(config.hard_mb, factor(Level::Hard)) {
+        Level::Hard
+    } else if over(config.soft_mb, factor(Level::Soft)) {
+        Level::Soft
+    } else {
+        Level::Under
+    }
+}
+
+// "SIGUSR1", "USR1" and "usr1" all work
+pub fn parse_signal(name: &str) -> Option<Signal> {
+    let name = name.to_ascii_uppercase();
+    let signal = match name.strip_prefix("SIG").unwrap_or(&name) {
+        "USR1" => Signal::User1,
+        "USR2" => Signal::User2,
+        "HUP" => Signal::Hangup,
+        "INT" => Signal::Interrupt,
+        "TERM" => Signal::Term,
+        "QUIT" => Signal::Quit,
+        "KILL" => Signal::Kill,
+        _ => return None,
+    };
+    Some(signal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_step_with_hysteresis() {
+        let config = BudgetConfig { soft_mb: 100, hard_mb: 200, ..BudgetConfig::default() };
+        assert_eq!(level_for(50 * MB, Level::Under, &config), Level::Under);
+        assert_eq!(level_for(100 * MB, Level::Under, &config), Level::Soft);
+        assert_eq!(level_for(95 * MB, Level::Soft, &config), Level::Soft);
+        assert_eq!(level_for(85 * MB, Level::Soft, &config), Level::Under);
+        assert_eq!(level_for(250 * MB, Level::Under, &config), Level::Hard);
+        assert_eq!(level_for(190 * MB, Level::Hard, &config), Level::Hard);
+        assert_eq!(level_for(150 * MB, Level::Hard, &config), Level::Soft);
+        assert!(matches!(parse_signal("sigusr2"), Some(Signal::User2)));
+        assert!(parse_signal("SIGFOO").is_none());
+    }
+
+    #[test]
+    fn dropping_from_hard_to_soft_sends_nothing() {
+        let config = BudgetConfig {
+            soft_mb: 100,
+            hard_mb: 200,
+            soft_signal: "USR1".to_string(),
+            hard_signal: "USR2".to_string(),
+            ..BudgetConfig::default()
+        };
+        let mut budgets = Budgets::new(vec![BudgetConfig { pid: 1, ..config }]);
+        let budget = &mut budgets.budgets[0];
+        assert_eq!(budget.advance(100 * MB), Some(true));
+        assert_eq!(budget.signal(true), "USR1");
+        assert_eq!(budget.advance(250 * MB), Some(true));
+        assert_eq!(budget.signal(true), "USR2");
+        assert_eq!(budget.advance(150 * MB), Some(false));
+        assert_eq!(budget.level, Level::Soft);
+        assert_eq!(budget.signal(false), "");
+    }
+}
@@ -0,0 +1,58 @@
+use sysinfo::{System, SystemExt};
+
+use crate::config::Config;
+use crate::{diag, selftest};
+
+const LOG_LINES: usize = 50;
+
+// Markdown meant to be pasted straight into a GitHub issue
+pub fn generate(config: &Config, sys: &System) -> String {
+    let checks = selftest::run();
+    let config_text = toml::to_string_pretty(&sanitize(config))
+        .unwrap_or_else(|e| format!("<could not serialize config: {}>", e));
+
+    let mut report = String::new();
+    report.push_str("### Platform\n\n");
+    report.push_str(&format!("- App version: {}\n", env!("CARGO_PKG_VERSION")));
+    report.push_str(&format!(
+        "- OS: {} {}\n",
+        sys.name().unwrap_or_else(|| "unknown".to_string()),
+        sys.os_version().unwrap_or_default(),
+    ));
+    report.push_str(&format!("- Kernel: {}\n", sys.kernel_version().unwrap_or_default()));
+    report.push_str(&format!(
+        "- Arch: {} ({})\n",
+        std::env::consts::ARCH,
+        std::env::consts::FAMILY,
+    ));
+    report.push_str(&format!(
+        "- Memory: {} bytes RAM, {} bytes swap\n",
+        sys.total_memory(),
+        sys.total_swap(),
+    ));
+
+    report.push_str("\n### Self-test\n\n```\n");
+    report.push_str(&selftest::format(&checks));
+    report.push_str("```\n");
+
+    report.push_str("\n### Config (sanitized)\n\n```toml\n");
+    report.push_str(&config_text);
+    report.push_str("```\n");
+
+    report.push_str("\n<details><summary>Recent log</summary>\n\n```\n");
+    for line in diag::recent(LOG_LINES) {
+        report.push_str(&line);
+        report.push('\n');
+    }
+    report.push_str("```\n\n</details>\n");
+    report
+}
+
+// URLs may carry credentials or private hostnames; only whether one is set matters
+fn sanitize(config: &Config) -> Config {
+    let mut config = config.clone();
+    if !config.registry.url.is_empty() {
+        config.registry.url = "<redacted>".to_string();
+    }
+    config
+}
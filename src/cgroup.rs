@@ -0,0 +1,82 @@
+use std::path::{Path, PathBuf};
+
+const ROOT: &str = "/sys/fs/cgroup";
+
+/// Memory limit and usage of the cgroup this process runs in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CgroupMemory {
+    pub limit: u64,
+    pub usage: u64,
+    // Inactive page cache the kernel reclaims before it would OOM-kill anything
+    pub reclaimable: u64,
+}
+
+// The tightest limit anywhere between our cgroup and the root is the one that
+// applies. None outside Linux, and when nothing up the tree sets a limit.
+pub fn read() -> Option<CgroupMemory> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    let membership = std::fs::read_to_string("/proc/self/cgroup").ok()?;
+    read_v2(&membership).or_else(|| read_v1(&membership))
+}
+
+fn read_v2(membership: &str) -> Option<CgroupMemory> {
+    let relative = membership.lines().find_map(|line| line.strip_prefix("0::"))?;
+    let root = Path::new(ROOT);
+    let mut dir = root.join(relative.trim().trim_start_matches('/'));
+    let mut tightest: Option<(u64, PathBuf)> = None;
+    loop {
+        // "max" doesn't parse, which is exactly "no limit here"
+        if let Some(limit) = read_u64(&dir.join("memory.max")) {
+            if tightest.as_ref().is_none_or(|(best, _)| limit < *best) {
+                tightest = Some((limit, dir.clone()));
+            }
+        }
+        if dir == root || !dir.pop() {
+            break;
+        }
+    }
+    let (limit, dir) = tightest?;
+    Some(CgroupMemory {
+        limit,
+        usage: read_u64(&dir.join("memory.current"))?,
+        reclaimable: read_stat(&dir.join("memory.stat"), "inactive_file"),
+    })
+}
+
+fn read_v1(membership: &str) -> Option<CgroupMemory> {
+    let relative = membership.lines().find_map(|line| {
+        let mut parts = line.splitn(3, ':');
+        let controllers = parts.nth(1)?;
+        controllers.split(',').any(|c| c == "memory").then(|| parts.next())?
+    })?;
+    let mount = Path::new(ROOT).join("memory");
+    // Inside a container the hierarchy is usually mounted at the container's own cgroup
+    let dir = [mount.join(relative.trim().trim_start_matches('/')), mount]
+        .into_iter()
+        .find(|dir| dir.join("memory.limit_in_bytes").exists())?;
+    // v1 reports "no limit" as a huge page-aligned number
+    let limit = read_u64(&dir.join("memory.limit_in_bytes")).filter(|&limit| limit < i64::MAX as u64 / 2)?;
+    Some(CgroupMemory {
+        limit,
+        usage: read_u64(&dir.join("memory.usage_in_bytes"))?,
+        reclaimable: read_stat(&dir.join("memory.stat"), "total_inactive_file"),
+    })
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+fn read_stat(path: &Path, key: &str) -> u64 {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|text| {
+            text.lines().find_map(|line| {
+                let (name, value) = line.split_once(' ')?;
+                (name == key).then(|| value.trim().parse().ok())?
+            })
+        })
+        .unwrap_or(0)
+}
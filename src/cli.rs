@@ -0,0 +1,222 @@
+#[derive(Clone, Copy, PartialEq)]
+pub enum StatusbarFormat {
+    Plain,
+    Waybar,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum OutputFormat {
+    Jsonl,
+}
+
+pub struct Args {
+    pub daemon: bool,
+    pub agent: Option<String>,
+    pub connect: Vec<String>,
+    // --replay: a recording fed to the window in place of live samples
+    pub replay: Option<String>,
+    pub pid: Option<u32>,
+    pub standalone: bool,
+    pub selftest: bool,
+    pub safe_mode: bool,
+    pub compact: bool,
+    pub profile: Option<String>,
+    pub statusbar: Option<StatusbarFormat>,
+    // --output jsonl, to stdout or --output-file; alongside the GUI unless --headless
+    pub output: Option<OutputFormat>,
+    pub output_file: Option<String>,
+    pub headless: bool,
+    // --api-port: JSON over HTTP on localhost, served from the window's history
+    pub api_port: Option<u16>,
+    // --web: the browser view, from the daemon, an agent or the window
+    pub web: Option<String>,
+    pub version: bool,
+    pub features: bool,
+    pub export_alerts: Option<String>,
+    pub import_alerts: Option<String>,
+    pub bookmark: Option<String>,
+    pub screenshot: Option<String>,
+    pub generate: Option<usize>,
+    pub soak: bool,
+    pub log_level: Option<String>,
+    // --budget PID:SOFT[:HARD], sizes in MiB
+    pub budgets: Vec<(u32, u64, u64)>,
+}
+
+// Everything Cargo.toml can switch off, in the order `--version --features` lists them
+const FEATURES: &[(&str, bool)] = &[
+    ("gui", cfg!(feature = "gui")),
+    ("web", cfg!(feature = "web")),
+    ("mqtt", cfg!(feature = "mqtt")),
+    ("influx", cfg!(feature = "influx")),
+    ("audio", cfg!(feature = "audio")),
+    ("tls", cfg!(feature = "tls")),
+    ("dbus", cfg!(feature = "dbus")),
+    ("python", cfg!(feature = "python")),
+    ("wasm", cfg!(feature = "wasm")),
+];
+
+impl Args {
+    pub fn parse() -> Result<Self, String> {
+        let mut args = Self {
+            daemon: false,
+            agent: None,
+            connect: Vec::new(),
+            replay: None,
+            pid: None,
+            standalone: false,
+            selftest: false,
+            safe_mode: false,
+            compact: false,
+            profile: None,
+            statusbar: None,
+            output: None,
+            output_file: None,
+            headless: false,
+            api_port: None,
+            web: None,
+            version: false,
+            features: false,
+            export_alerts: None,
+            import_alerts: None,
+            bookmark: None,
+            screenshot: None,
+            generate: None,
+            soak: false,
+            log_level: None,
+            budgets: Vec::new(),
+        };
+        let mut argv = std::env::args().skip(1);
+        while let Some(arg) = argv.next() {
+            match arg.as_str() {
+                "--daemon" => args.daemon = true,
+                "--agent" => args.agent = Some(value(&mut argv, "--agent")?),
+                "--connect" => args.connect.push(value(&mut argv, "--connect")?),
+                "--replay" => args.replay = Some(value(&mut argv, "--replay")?),
+                "--pid" => {
+                    let pid = value(&mut argv, "--pid")?;
+                    args.pid = Some(pid.parse().map_err(|_| format!("invalid --pid: {}", pid))?);
+                }
+                "--budget" => {
+                    let budget = value(&mut argv, "--budget")?;
+                    args.budgets.push(parse_budget(&budget).ok_or_else(|| format!("invalid --budget: {}", budget))?);
+                }
+                "--standalone" => args.standalone = true,
+                "--selftest" => args.selftest = true,
+                "--safe-mode" => args.safe_mode = true,
+                "--soak" => args.soak = true,
+                "--log-level" => args.log_level = Some(value(&mut argv, "--log-level")?),
+                "--compact" => args.compact = true,
+                "--profile" => args.profile = Some(value(&mut argv, "--profile")?),
+                "--statusbar" => args.statusbar = Some(StatusbarFormat::Plain),
+                "--format" => {
+                    let format = match value(&mut argv, "--format")?.as_str() {
+                        "plain" => StatusbarFormat::Plain,
+                        "waybar" => StatusbarFormat::Waybar,
+                        other => return Err(format!("unknown --format: {}", other)),
+                    };
+                    args.statusbar = Some(format);
+                }
+                "--output" => {
+                    let format = match value(&mut argv, "--output")?.as_str() {
+                        "jsonl" => OutputFormat::Jsonl,
+                        other => return Err(format!("unknown --output: {}", other)),
+                    };
+                    args.output = Some(format);
+                }
+                "--output-file" => args.output_file = Some(value(&mut argv, "--output-file")?),
+                "--headless" => args.headless = true,
+                "--web" => args.web = Some(value(&mut argv, "--web")?),
+                "--api-port" => {
+                    let port = value(&mut argv, "--api-port")?;
+                    args.api_port = Some(port.parse().map_err(|_| format!("invalid --api-port: {}", port))?);
+                }
+                "--export-alerts" => args.export_alerts = Some(value(&mut argv, "--export-alerts")?),
+                "--import-alerts" => args.import_alerts = Some(value(&mut argv, "--import-alerts")?),
+                "--bookmark" => args.bookmark = Some(value(&mut argv, "--bookmark")?),
+                "--screenshot" => args.screenshot = Some(value(&mut argv, "--screenshot")?),
+                "--generate" => {
+                    let count = value(&mut argv, "--generate")?;
+                    args.generate = Some(count.parse().map_err(|_| format!("invalid --generate: {}", count))?);
+                }
+                "-V" | "--version" => args.version = true,
+                "--features" => args.features = true,
+                "-h" | "--help" => {
+                    print_help();
+                    std::process::exit(0);
+                }
+                other => return Err(format!("unknown argument: {}", other)),
+            }
+        }
+        if args.output.is_none() && (args.output_file.is_some() || args.headless) {
+            return Err("--output-file and --headless go with --output jsonl".to_string());
+        }
+        if args.replay.is_some() && !cfg!(feature = "gui") {
+            return Err("--replay plays into the window, which this build doesn't have".to_string());
+        }
+        if args.replay.is_some() && !args.connect.is_empty() {
+            return Err("--replay and --connect are two different sources; pick one".to_string());
+        }
+        if args.web.is_some() && !cfg!(feature = "web") {
+            return Err("--web needs a build with the web feature".to_string());
+        }
+        Ok(args)
+    }
+}
+
+fn parse_budget(text: &str) -> Option<(u32, u64, u64)> {
+    let mut parts = text.split(':');
+    let pid = parts.next()?.parse().ok()?;
+    let soft = parts.next()?.parse().ok()?;
+    let hard = parts.next().map_or(Some(0), |hard| hard.parse().ok())?;
+    (parts.next().is_none() && soft > 0 && (hard == 0 || hard >= soft)).then_some((pid, soft, hard))
+}
+
+fn value(argv: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    argv.next().ok_or_else(|| format!("{} needs a value", flag))
+}
+
+pub fn print_version(with_features: bool) {
+    println!("hacknet-memory-monitor {}", env!("CARGO_PKG_VERSION"));
+    if with_features {
+        for (name, enabled) in FEATURES {
+            println!("    {} {}", if *enabled { '+' } else { '-' }, name);
+        }
+    }
+}
+
+fn print_help() {
+    println!("Memory Monitor - Hacker Edition");
+    println!();
+    println!("USAGE: hacknet-memory-monitor [OPTIONS]");
+    println!();
+    println!("OPTIONS:");
+    println!("    --daemon           Run a headless sampler that UI instances attach to");
+    println!("    --agent <ADDR>     Serve samples over TCP for remote UIs (e.g. 0.0.0.0:47475)");
+    println!("    --connect <HOST>   Show a remote agent's memory instead of this machine's;");
+    println!("                       repeat it for a dashboard of several hosts");
+    println!("    --replay <FILE>    Play back a recording or export (CSV) as if it were live");
+    println!("    --pid <PID>        Focus on one process: plot its RSS and warn when it grows");
+    println!("    --budget <PID:SOFT[:HARD]>  Signal a process when its RSS passes these MiB budgets");
+    println!("    --standalone       Sample locally even if a daemon is running");
+    println!("    --compact          Start as a small frameless always-on-top widget");
+    println!("    --profile <NAME>   Save and restore the window layout under this name");
+    println!("    --statusbar        Print one status line per sample for i3/polybar, no GUI");
+    println!("    --format <FORMAT>  Status line format: plain or waybar (implies --statusbar)");
+    println!("    --output jsonl     Also write one JSON object per sample to stdout");
+    println!("    --output-file <FILE>  Append the --output lines to FILE instead of stdout");
+    println!("    --headless         With --output, stream without opening a window");
+    println!("    --web <ADDR>       Serve live graphs to browsers, e.g. 0.0.0.0:47481 for the LAN");
+    println!("    --api-port <PORT>  Serve /current, /history, /processes and /alerts as JSON on localhost");
+    println!("    --soak             Cap our own memory and disk use and log our footprint, for long runs");
+    println!("    --log-level <LEVEL>  error, warn, info, debug or trace (overrides RUST_LOG and the config)");
+    println!("    --safe-mode        Start with defaults: no config, user themes, plugins or network");
+    println!("    --selftest         Check that memory stats, config and data dirs work, then exit");
+    println!("    --export-alerts <FILE>  Write this machine's alert rules to a shareable preset file");
+    println!("    --import-alerts <FILE>  Replace this machine's alert rules with a preset's");
+    println!("    --bookmark <TEXT>  Mark this moment on the timeline; shows in the GUI and exports");
+    println!("    --screenshot <FILE>  Save the plot and statistics as PNG or SVG (by extension), no GUI");
+    println!("    --generate <COUNT> Developer: time the history layer with COUNT synthetic samples");
+    println!("    -V, --version      Print the version; add --features to list compiled-in features");
+    println!("    -h, --help         Print this help");
+}
@@ -0,0 +1,29 @@
+use std::sync::OnceLock;
+use sysinfo::{Pid, ProcessExt, System, SystemExt};
+
+// Windows 10+ keeps its compression store in the working set of the "Memory
+// Compression" pseudo-process; its size is what Task Manager reports as
+// "Compressed". The pid is looked up once since the process never restarts.
+const STORE_PROCESS: &str = "Memory Compression";
+
+static STORE_PID: OnceLock<Option<Pid>> = OnceLock::new();
+
+pub fn store_bytes(sys: &mut System) -> u64 {
+    if !cfg!(windows) {
+        return 0;
+    }
+    let pid = *STORE_PID.get_or_init(|| {
+        sys.refresh_processes();
+        sys.processes()
+            .iter()
+            .find(|(_, process)| process.name() == STORE_PROCESS)
+            .map(|(pid, _)| *pid)
+    });
+    let Some(pid) = pid else {
+        return 0;
+    };
+    if !sys.refresh_process(pid) {
+        return 0;
+    }
+    sys.process(pid).map_or(0, |process| process.memory())
+}
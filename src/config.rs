@@ -0,0 +1,587 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+#[cfg(feature = "gui")]
+use std::time::Duration;
+
+use rusty_mem_monitor::alert::{AlertRule, EscalationStage, Thresholds};
+
+use crate::diag;
+
+const APP_DIR: &str = "rusty_mem_monitor";
+const CONFIG_FILE: &str = "config.toml";
+pub const DEFAULT_THEME: &str = "Classic Green";
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct Config {
+    pub registry: RegistryConfig,
+    pub recording: RecordingConfig,
+    pub appearance: AppearanceConfig,
+    pub glitch: GlitchConfig,
+    pub alerts: AlertsConfig,
+    pub focus: FocusConfig,
+    pub performance: PerformanceConfig,
+    pub energy: EnergyConfig,
+    pub actions: ActionsConfig,
+    pub plot: PlotConfig,
+    pub sampling: SamplingConfig,
+    pub processes: ProcessesConfig,
+    pub soak: SoakConfig,
+    pub logging: LoggingConfig,
+    pub agent: AgentConfig,
+    pub influx: InfluxConfig,
+    pub mqtt: MqttConfig,
+    // [[budgets]] entries; see budget.rs
+    pub budgets: Vec<BudgetConfig>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct RegistryConfig {
+    // Off by default: nothing is fetched from the network unless the user opts in
+    pub enabled: bool,
+    pub url: String,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct RecordingConfig {
+    // Hold a sleep/idle inhibitor for as long as a recording is running
+    pub inhibit_sleep: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AppearanceConfig {
+    pub theme: String,
+    // Animated background whose pace follows memory pressure; off by default as
+    // it keeps the window repainting
+    pub rain: bool,
+    // Scanlines, phosphor glow and flicker drawn over the window
+    pub crt: bool,
+    // Zoom for text and widgets alike, 1.0 being the system's own scaling
+    pub ui_scale: f32,
+    // No glitches, flicker, rain or UI animations; alarm colors are kept
+    pub reduced_motion: bool,
+    // A catalog's language code, e.g. "de"; empty follows the system locale
+    pub language: String,
+}
+
+impl Default for AppearanceConfig {
+    fn default() -> Self {
+        Self {
+            theme: DEFAULT_THEME.to_string(),
+            rain: false,
+            crt: false,
+            ui_scale: 1.0,
+            reduced_motion: false,
+            language: String::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct GlitchConfig {
+    pub enabled: bool,
+    // Chance that a given frame is glitched at all
+    pub probability: f64,
+    // Chance that each character of a glitched string is replaced
+    pub char_probability: f64,
+    pub charset: String,
+    // Off by default so the usage bar can't be misread during a real alarm
+    pub affect_numbers: bool,
+}
+
+impl Default for GlitchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            probability: 0.05,
+            char_probability: 0.3,
+            charset: "!@#$%^&*()_+-=[]{}|;:,.<>?/~".to_string(),
+            affect_numbers: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AlertsConfig {
+    pub thresholds: Thresholds,
+    pub escalation: Vec<EscalationStage>,
+    // [[alerts.rules]]: named conditions such as swap over 50% for five minutes,
+    // logged as alarms and posted to their webhook when raised
+    pub rules: Vec<AlertRule>,
+    // Shell commands run when RAM passes the warning threshold, when the critical
+    // alarm is raised and when it clears
+    pub on_warning: Option<AlertCommand>,
+    pub on_critical: Option<AlertCommand>,
+    pub on_recovery: Option<AlertCommand>,
+    // Keep the warning and recovery commands back while a video or presentation
+    // holds the screen, and run them once it's over
+    pub hold_while_presenting: bool,
+    pub sound: SoundConfig,
+}
+
+impl Default for AlertsConfig {
+    fn default() -> Self {
+        Self {
+            thresholds: Thresholds::default(),
+            escalation: Vec::new(),
+            rules: Vec::new(),
+            on_warning: None,
+            on_critical: None,
+            on_recovery: None,
+            hold_while_presenting: true,
+            sound: SoundConfig::default(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SoundConfig {
+    // Beep while the critical alarm is up and nobody has acknowledged it
+    pub enabled: bool,
+    // 0-1; an escalation stage's sound_volume raises it until the alarm clears
+    pub volume: f32,
+    pub repeat_secs: f64,
+}
+
+impl Default for SoundConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            volume: 0.4,
+            repeat_secs: 30.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct AlertCommand {
+    pub command: String,
+    // Minimum time between two runs of this command, however often the alarm flaps
+    pub cooldown_minutes: f64,
+}
+
+impl Default for AlertCommand {
+    fn default() -> Self {
+        Self {
+            command: String::new(),
+            cooldown_minutes: 10.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct FocusConfig {
+    // Warn once the focused process's RSS has grown this much since focusing
+    pub growth_alert_percent: f64,
+}
+
+impl Default for FocusConfig {
+    fn default() -> Self {
+        Self {
+            growth_alert_percent: 50.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct ProcessesConfig {
+    // Applied to the process table in order; see highlight.rs
+    pub highlight: Vec<HighlightRule>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct HighlightRule {
+    // Regex matched against the process name; empty matches every process
+    pub pattern: String,
+    // "#rrggbb" or a color name; empty keeps the theme's color
+    pub color: String,
+    pub bold: bool,
+    // Only rows with at least this much RSS; 0 for any size
+    pub min_memory_mb: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct PerformanceConfig {
+    // Drop effects, caches and frame rate while the critical alarm is up
+    pub adapt_under_pressure: bool,
+    // The monitor's own RSS, frame time and allocations in a corner overlay
+    pub show_self_stats: bool,
+}
+
+impl Default for PerformanceConfig {
+    fn default() -> Self {
+        Self {
+            adapt_under_pressure: true,
+            show_self_stats: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct LoggingConfig {
+    // error, warn, info, debug or trace; --log-level and RUST_LOG take precedence
+    pub level: String,
+    // Daily files under logs/ in the data directory, the oldest deleted past keep_days
+    pub file: bool,
+    pub keep_days: usize,
+    // A line per sample; that's a lot of lines at short intervals
+    pub samples: bool,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            file: true,
+            keep_days: 7,
+            samples: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct InfluxConfig {
+    pub enabled: bool,
+    // http(s)://host:8086 for the v2 write API, or udp://host:8089 for line protocol
+    pub url: String,
+    // v2 only
+    pub org: String,
+    pub bucket: String,
+    pub token: String,
+    pub measurement: String,
+    // A batch goes out once it has this many samples or is this old
+    pub batch_size: usize,
+    pub flush_secs: u64,
+    // Samples queued on disk while InfluxDB is unreachable, in whole batches; the
+    // oldest go first past it
+    pub max_buffer: usize,
+}
+
+impl Default for InfluxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: String::new(),
+            org: String::new(),
+            bucket: String::new(),
+            token: String::new(),
+            measurement: "memory".to_string(),
+            batch_size: 20,
+            flush_secs: 10,
+            max_buffer: 10_000,
+        }
+    }
+}
+
+// Read in every build so a config file carries over, but only published with the
+// mqtt feature
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct MqttConfig {
+    pub enabled: bool,
+    // host:port of the broker
+    pub broker: String,
+    // memory-monitor-<host> when empty
+    pub client_id: String,
+    pub username: String,
+    pub password: String,
+    pub topic_prefix: String,
+    // Home Assistant discovery configs, under discovery_prefix
+    pub home_assistant: bool,
+    pub discovery_prefix: String,
+    // Samples kept on disk while the broker is unreachable; 0 drops them
+    pub queue: usize,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            broker: "localhost:1883".to_string(),
+            client_id: String::new(),
+            username: String::new(),
+            password: String::new(),
+            topic_prefix: "memory_monitor".to_string(),
+            home_assistant: false,
+            discovery_prefix: "homeassistant".to_string(),
+            queue: 3600,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct AgentConfig {
+    // Shared secret for the operator role. On an agent, commands from UIs that
+    // send it are run and the rest are refused; empty takes no commands at all.
+    // A UI sends its own to the agents it connects to.
+    pub operator_token: String,
+    // PEM certificate chain and private key the agent serves TLS with. Without
+    // them it takes commands only on a loopback address, where the token can't
+    // be read off the wire.
+    pub tls_cert: String,
+    pub tls_key: String,
+    // A UI reaches its agents over TLS when set, trusting the certificates in this
+    // PEM file; an agent's self-signed certificate works as its own CA
+    pub tls_ca: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct BudgetConfig {
+    // The process to watch, by name; or by pid, which only lasts as long as it runs
+    pub name: String,
+    pub pid: u32,
+    // In MiB; a hard budget of 0 means none
+    pub soft_mb: u64,
+    pub hard_mb: u64,
+    // Sent when a budget is crossed; empty sends nothing
+    pub soft_signal: String,
+    pub hard_signal: String,
+    // A named pipe that gets a JSON line on every change, back under included
+    pub pipe: String,
+}
+
+impl Default for BudgetConfig {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            pid: 0,
+            soft_mb: 0,
+            hard_mb: 0,
+            soft_signal: "SIGUSR1".to_string(),
+            hard_signal: "SIGUSR2".to_string(),
+            pipe: String::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SoakConfig {
+    // Also switched on for one run by --soak
+    pub enabled: bool,
+    // Our own RSS; past it the GUI sheds caches and history, a daemon exits to be restarted
+    pub max_memory_mb: u64,
+    // Everything under the data directory; old recordings and exports go first
+    pub max_disk_mb: u64,
+    pub footprint_minutes: u64,
+}
+
+impl Default for SoakConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_memory_mb: 256,
+            max_disk_mb: 1024,
+            footprint_minutes: 10,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SamplingConfig {
+    // Local sampling only; a daemon or remote agent samples at its own rate
+    pub interval_ms: u64,
+    // The process table, which walks every process, and the container, hugepage and
+    // zram readouts, which read a handful of files each; all only while on screen
+    pub process_interval_ms: u64,
+    pub system_interval_ms: u64,
+    // Time span the plot covers, whatever the interval
+    pub history_secs: u64,
+}
+
+#[cfg(feature = "gui")]
+impl SamplingConfig {
+    pub const MIN_INTERVAL_MS: u64 = 100;
+    pub const MAX_INTERVAL_MS: u64 = 60_000;
+    pub const MIN_HISTORY_SECS: u64 = 10;
+    pub const MAX_HISTORY_SECS: u64 = 3600;
+
+    pub fn interval(&self) -> Duration {
+        Self::clamped(self.interval_ms)
+    }
+
+    pub fn process_interval(&self) -> Duration {
+        Self::clamped(self.process_interval_ms)
+    }
+
+    pub fn system_interval(&self) -> Duration {
+        Self::clamped(self.system_interval_ms)
+    }
+
+    fn clamped(interval_ms: u64) -> Duration {
+        Duration::from_millis(interval_ms.clamp(Self::MIN_INTERVAL_MS, Self::MAX_INTERVAL_MS))
+    }
+
+    // Never fewer than two points, or there's no line to draw. A hand-edited span is
+    // held to the slider's range, like the intervals.
+    pub fn history_len(&self) -> usize {
+        let history_secs = self.history_secs.clamp(Self::MIN_HISTORY_SECS, Self::MAX_HISTORY_SECS);
+        (history_secs.saturating_mul(1000) as u128 / self.interval().as_millis()).max(2) as usize
+    }
+}
+
+impl Default for SamplingConfig {
+    fn default() -> Self {
+        Self {
+            interval_ms: 500,
+            process_interval_ms: 2000,
+            system_interval_ms: 5000,
+            history_secs: 50,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Smoothing {
+    Off,
+    MovingAverage,
+    Ema,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct PlotConfig {
+    pub smoothing: Smoothing,
+    // Samples in the moving average
+    pub window: usize,
+    pub ema_alpha: f64,
+    // Draw only the smoothed lines
+    pub hide_raw: bool,
+    // RAM and swap lines in bytes instead of percent
+    pub absolute: bool,
+    // Hold each reading until the next instead of drawing a slope between them,
+    // which would suggest readings nobody took. The RAM setting covers its range.
+    pub step_memory: bool,
+    pub step_swap: bool,
+    // Lines unticked in the legend: "events", "range", "ram", "swap"
+    pub hidden: Vec<String>,
+    // Processes with a band of their own in the top-processes chart
+    pub top_processes: usize,
+}
+
+impl Default for PlotConfig {
+    fn default() -> Self {
+        Self {
+            smoothing: Smoothing::Off,
+            window: 10,
+            ema_alpha: 0.2,
+            hide_raw: false,
+            absolute: false,
+            step_memory: false,
+            step_swap: false,
+            hidden: Vec::new(),
+            top_processes: 5,
+        }
+    }
+}
+
+// Sessions, shells and servers whose loss would hurt more than the memory they hold
+const KILL_EXCLUDE: [&str; 16] = [
+    "systemd", "sshd", "Xorg", "Xwayland", "gnome-shell", "kwin_wayland", "kwin_x11", "plasmashell",
+    "gnome-session-binary", "pipewire", "pulseaudio", "dbus-daemon", "tmux: server", "WindowServer", "Finder",
+    "explorer.exe",
+];
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ActionsConfig {
+    // Log what kills, budget signals, system changes and alert commands would do instead of doing it
+    pub dry_run: bool,
+    // Ask before an escalation stage kills the top offender
+    pub confirm_kill: bool,
+    // Process names an escalation never kills, on top of other users' processes
+    pub kill_exclude: Vec<String>,
+    // Ask before launching an elevated pagefile or hibernation change
+    pub confirm_system_changes: bool,
+    // Webhook deliveries kept on disk while their endpoint is unreachable; 0 drops them
+    pub webhook_queue: usize,
+}
+
+impl Default for ActionsConfig {
+    fn default() -> Self {
+        Self {
+            dry_run: false,
+            confirm_kill: true,
+            kill_exclude: KILL_EXCLUDE.iter().map(|name| name.to_string()).collect(),
+            confirm_system_changes: true,
+            webhook_queue: 500,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct EnergyConfig {
+    // Grams of CO2 per kWh from the local grid; no CO2 figure is shown without it
+    pub grid_g_co2_per_kwh: Option<f64>,
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join(CONFIG_FILE))
+    }
+
+    // A missing file is normal on first launch; a broken one is reported and ignored
+    // so a typo never keeps the monitor from starting.
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text).unwrap_or_else(|e| {
+                diag::log(format!("Ignoring invalid config {}: {}", path.display(), e));
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let path = Self::path().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "no config directory")
+        })?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let text = toml::to_string_pretty(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(path, text)
+    }
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    if cfg!(windows) {
+        return std::env::var_os("APPDATA").map(|dir| PathBuf::from(dir).join(APP_DIR));
+    }
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join(APP_DIR))
+}
+
+pub fn data_dir() -> Option<PathBuf> {
+    if cfg!(windows) {
+        return std::env::var_os("LOCALAPPDATA").map(|dir| PathBuf::from(dir).join(APP_DIR));
+    }
+    std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .map(|dir| dir.join(APP_DIR))
+}
@@ -0,0 +1,202 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// Containers are found through the cgroup filesystem, which works for Docker and
+// Podman alike and needs no privileges. Names come from the engine's API socket
+// when we're allowed to talk to it; otherwise the short id is shown.
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+const MAX_DEPTH: usize = 8;
+const SOCKET_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Clone)]
+pub struct Container {
+    pub id: String,
+    pub name: String,
+    pub runtime: &'static str,
+    pub usage: u64,
+    // None when the container runs without a memory limit
+    pub limit: Option<u64>,
+    // Times the kernel OOM killer fired inside this container
+    pub oom_kills: u64,
+}
+
+impl Container {
+    pub fn limit_percentage(&self) -> Option<f32> {
+        self.limit
+            .filter(|&limit| limit > 0)
+            .map(|limit| (self.usage as f64 / limit as f64 * 100.0) as f32)
+    }
+}
+
+#[derive(Deserialize)]
+struct ApiContainer {
+    #[serde(rename = "Id")]
+    id: String,
+    #[serde(rename = "Names", default)]
+    names: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct ContainerWatcher {
+    names: HashMap<String, String>,
+}
+
+impl ContainerWatcher {
+    pub fn collect(&mut self) -> Vec<Container> {
+        let mut found = Vec::new();
+        scan(Path::new(CGROUP_ROOT), 0, &mut found);
+
+        // Only bother the engine when a container we haven't named yet shows up
+        if found.iter().any(|(_, id, _)| !self.names.contains_key(id)) {
+            self.names.extend(api_names());
+        }
+
+        let mut containers: Vec<Container> = found
+            .into_iter()
+            .map(|(dir, id, runtime)| {
+                let (usage, limit, oom_kills) = read_memory(&dir);
+                Container {
+                    name: self.names.get(&id).cloned().unwrap_or_else(|| id[..12].to_string()),
+                    id,
+                    runtime,
+                    usage,
+                    limit,
+                    oom_kills,
+                }
+            })
+            .collect();
+        containers.sort_by_key(|container| std::cmp::Reverse(container.usage));
+        containers
+    }
+}
+
+// cgroup v2 uses docker-<id>.scope / libpod-<id>.scope under the systemd slices;
+// v1 keeps a plain <id> directory under memory/docker or memory/libpod_parent.
+fn container_id(dir: &Path) -> Option<(String, &'static str)> {
+    let name = dir.file_name()?.to_str()?;
+    let (id, runtime) = if let Some(rest) = name.strip_prefix("docker-") {
+        (rest.strip_suffix(".scope")?, "docker")
+    } else if let Some(rest) = name.strip_prefix("libpod-") {
+        (rest.strip_suffix(".scope")?, "podman")
+    } else {
+        let parent = dir.parent()?.file_name()?.to_str()?;
+        match parent {
+            "docker" => (name, "docker"),
+            "libpod_parent" => (name, "podman"),
+            _ => return None,
+        }
+    };
+    let is_id = id.len() == 64 && id.bytes().all(|b| b.is_ascii_hexdigit());
+    is_id.then(|| (id.to_string(), runtime))
+}
+
+fn scan(dir: &Path, depth: usize, found: &mut Vec<(PathBuf, String, &'static str)>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if !entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+            continue;
+        }
+        let path = entry.path();
+        if let Some((id, runtime)) = container_id(&path) {
+            found.push((path, id, runtime));
+        } else if depth < MAX_DEPTH {
+            scan(&path, depth + 1, found);
+        }
+    }
+}
+
+fn read_u64(path: PathBuf) -> Option<u64> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+fn read_memory(dir: &Path) -> (u64, Option<u64>, u64) {
+    // cgroup v2
+    if let Some(usage) = read_u64(dir.join("memory.current")) {
+        let limit = read_u64(dir.join("memory.max"));
+        return (usage, limit, read_oom_kills(&dir.join("memory.events")));
+    }
+    // cgroup v1 reports "no limit" as a huge page-aligned number
+    let usage = read_u64(dir.join("memory.usage_in_bytes")).unwrap_or(0);
+    let limit = read_u64(dir.join("memory.limit_in_bytes")).filter(|&limit| limit < i64::MAX as u64 / 2);
+    (usage, limit, read_oom_kills(&dir.join("memory.oom_control")))
+}
+
+fn read_oom_kills(path: &Path) -> u64 {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|text| {
+            text.lines()
+                .find_map(|line| line.strip_prefix("oom_kill "))
+                .and_then(|count| count.trim().parse().ok())
+        })
+        .unwrap_or(0)
+}
+
+fn socket_paths() -> Vec<PathBuf> {
+    let mut paths = vec![
+        PathBuf::from("/var/run/docker.sock"),
+        PathBuf::from("/run/podman/podman.sock"),
+    ];
+    if let Some(runtime_dir) = std::env::var_os("XDG_RUNTIME_DIR") {
+        paths.push(PathBuf::from(runtime_dir).join("podman/podman.sock"));
+    }
+    paths
+}
+
+fn api_names() -> HashMap<String, String> {
+    let mut names = HashMap::new();
+    for path in socket_paths() {
+        let Some(containers) = list_containers(&path) else { continue };
+        for container in containers {
+            if let Some(name) = container.names.first() {
+                names.insert(container.id, name.trim_start_matches('/').to_string());
+            }
+        }
+    }
+    names
+}
+
+// Both engines speak the Docker API; HTTP/1.0 keeps the response unchunked
+#[cfg(unix)]
+fn list_containers(path: &Path) -> Option<Vec<ApiContainer>> {
+    let mut stream = std::os::unix::net::UnixStream::connect(path).ok()?;
+    stream.set_read_timeout(Some(SOCKET_TIMEOUT)).ok()?;
+    stream.set_write_timeout(Some(SOCKET_TIMEOUT)).ok()?;
+    stream.write_all(b"GET /containers/json HTTP/1.0\r\nHost: localhost\r\n\r\n").ok()?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).ok()?;
+    let (head, body) = response.split_once("\r\n\r\n")?;
+    if !head.starts_with("HTTP/1.0 200") && !head.starts_with("HTTP/1.1 200") {
+        return None;
+    }
+    serde_json::from_str(body).ok()
+}
+
+#[cfg(not(unix))]
+fn list_containers(_path: &Path) -> Option<Vec<ApiContainer>> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "3f4e8a9b0c1d2e3f4a5b6c7d8e9f0a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6e7f";
+
+    #[test]
+    fn recognizes_container_cgroups() {
+        let v2 = PathBuf::from(format!("/sys/fs/cgroup/system.slice/docker-{}.scope", ID));
+        assert_eq!(container_id(&v2), Some((ID.to_string(), "docker")));
+        let rootless = PathBuf::from(format!("/sys/fs/cgroup/user.slice/user-1000.slice/libpod-{}.scope", ID));
+        assert_eq!(container_id(&rootless), Some((ID.to_string(), "podman")));
+        let v1 = PathBuf::from(format!("/sys/fs/cgroup/memory/docker/{}", ID));
+        assert_eq!(container_id(&v1), Some((ID.to_string(), "docker")));
+        assert_eq!(container_id(Path::new("/sys/fs/cgroup/system.slice/docker.service")), None);
+        assert_eq!(container_id(Path::new("/sys/fs/cgroup/system.slice/docker-short.scope")), None);
+    }
+}
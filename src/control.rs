@@ -0,0 +1,201 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{PidExt, ProcessExt, System, SystemExt};
+
+use rusty_mem_monitor::sample::{self, now_ms, Sample};
+
+use crate::{config, diag, selftest};
+
+const SNAPSHOT_PROCESSES: usize = 10;
+
+// What a UI can ask an agent to do on its host
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Command {
+    Snapshot,
+    StartRecording,
+    StopRecording,
+    Selftest,
+}
+
+// One line from the UI to the agent. The token decides the role: the agent's
+// operator token allows commands, anything else only receives samples.
+#[derive(Serialize, Deserialize)]
+pub struct Request {
+    pub id: u64,
+    pub command: Command,
+    #[serde(default)]
+    pub token: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Reply {
+    pub id: u64,
+    pub ok: bool,
+    pub message: String,
+}
+
+// Replies share the stream with samples, so they travel wrapped in an object
+// that can't be mistaken for one
+#[derive(Serialize, Deserialize)]
+pub struct ReplyLine {
+    pub reply: Reply,
+}
+
+pub struct Recording {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    samples: u64,
+}
+
+impl Recording {
+    fn record(&mut self, sample: &Sample) -> std::io::Result<()> {
+        writeln!(self.writer, "{}", sample.csv_row())?;
+        self.samples += 1;
+        // Flushed every sample, so a copy taken off the box mid-recording is complete
+        self.writer.flush()
+    }
+}
+
+// The agent's side of remote control: checks the role, runs the command on this
+// host, and keeps what commands leave running. Shared between the sample loop and
+// the request threads; each part has its own lock, so a slow snapshot or
+// self-test never holds up recording.
+pub struct Control {
+    token: String,
+    // Listening in the clear beyond loopback, where the token could be read off
+    // the wire: every command is refused
+    exposed: bool,
+    recording: Mutex<Option<Recording>>,
+    sys: Mutex<System>,
+}
+
+impl Control {
+    pub fn new(token: String, exposed: bool) -> Self {
+        Self { token, exposed, recording: Mutex::new(None), sys: Mutex::new(System::new()) }
+    }
+
+    // Adds the sample to the open recording, if any, and returns where it's
+    // going. A recording that fails to write is stopped.
+    pub fn record(&self, sample: &Sample) -> Option<PathBuf> {
+        let mut recording = self.recording.lock().unwrap();
+        if let Some(Err(e)) = recording.as_mut().map(|recording| recording.record(sample)) {
+            diag::log(format!("Remote recording stopped: {}", e));
+            *recording = None;
+        }
+        recording.as_ref().map(|recording| recording.path.clone())
+    }
+
+    pub fn handle(&self, request: &Request, peer: &str, latest: Option<&Sample>) -> Reply {
+        let result = if self.token.is_empty() {
+            Err("remote control is off on this agent; set [agent] operator_token to allow it".to_string())
+        } else if self.exposed {
+            Err("remote control needs TLS on this agent, or a loopback address; set [agent] tls_cert and tls_key".to_string())
+        } else if request.token != self.token {
+            Err("not an operator: the token doesn't match this agent's".to_string())
+        } else {
+            self.run(request.command, latest)
+        };
+        match &result {
+            Ok(message) => diag::info(format!("{:?} from {}: {}", request.command, peer, message)),
+            Err(message) => diag::log(format!("Refused {:?} from {}: {}", request.command, peer, message)),
+        }
+        let (ok, message) = match result {
+            Ok(message) => (true, message),
+            Err(message) => (false, message),
+        };
+        Reply { id: request.id, ok, message }
+    }
+
+    fn run(&self, command: Command, latest: Option<&Sample>) -> Result<String, String> {
+        match command {
+            Command::Snapshot => {
+                let path = self.snapshot(latest).map_err(|e| format!("snapshot failed: {}", e))?;
+                Ok(format!("snapshot saved on the agent as {}", path.display()))
+            }
+            Command::StartRecording => {
+                let mut recording = self.recording.lock().unwrap();
+                if let Some(recording) = recording.as_ref() {
+                    return Err(format!("already recording to {}", recording.path.display()));
+                }
+                let path = output_path("recordings", "recording", "csv").map_err(|e| e.to_string())?;
+                let mut writer = BufWriter::new(File::create(&path).map_err(|e| e.to_string())?);
+                writeln!(writer, "{}", sample::CSV_HEADER).map_err(|e| e.to_string())?;
+                let message = format!("recording on the agent to {}", path.display());
+                *recording = Some(Recording { path, writer, samples: 0 });
+                Ok(message)
+            }
+            Command::StopRecording => {
+                let recording = self.recording.lock().unwrap().take().ok_or("the agent isn't recording")?;
+                Ok(format!("saved {} samples on the agent to {}", recording.samples, recording.path.display()))
+            }
+            Command::Selftest => {
+                let checks = selftest::run();
+                let report = selftest::format(&checks);
+                if checks.iter().all(|check| check.ok) { Ok(report) } else { Err(report) }
+            }
+        }
+    }
+
+    // The latest sample and the biggest processes, as JSON
+    fn snapshot(&self, latest: Option<&Sample>) -> std::io::Result<PathBuf> {
+        let mut sys = self.sys.lock().unwrap();
+        sys.refresh_processes();
+        let mut processes: Vec<_> = sys.processes().values().collect();
+        processes.sort_by_key(|process| std::cmp::Reverse(process.memory()));
+        let top: Vec<_> = processes
+            .iter()
+            .take(SNAPSHOT_PROCESSES)
+            .map(|process| serde_json::json!({
+                "pid": process.pid().as_u32(),
+                "name": process.name(),
+                "memory": process.memory(),
+            }))
+            .collect();
+        let snapshot = serde_json::json!({
+            "taken_at_ms": now_ms(),
+            "sample": latest,
+            "top_processes": top,
+        });
+        let path = output_path("snapshots", "agent-snapshot", "json")?;
+        std::fs::write(&path, serde_json::to_string_pretty(&snapshot)?)?;
+        Ok(path)
+    }
+}
+
+fn output_path(subdir: &str, prefix: &str, extension: &str) -> std::io::Result<PathBuf> {
+    let dir = config::data_dir()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no data directory"))?
+        .join(subdir);
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir.join(format!("{}-{}.{}", prefix, now_ms(), extension)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_operators_may_run_commands() {
+        let request = |token: &str| Request { id: 7, command: Command::StopRecording, token: token.to_string() };
+        let off = Control::new(String::new(), false);
+        assert!(!off.handle(&request("secret"), "test", None).ok);
+        let exposed = Control::new("secret".to_string(), true);
+        let refused = exposed.handle(&request("secret"), "test", None);
+        assert!(!refused.ok && refused.message.contains("needs TLS"));
+
+        let control = Control::new("secret".to_string(), false);
+        let refused = control.handle(&request("guess"), "test", None);
+        assert!(!refused.ok && refused.message.contains("not an operator"));
+        let allowed = control.handle(&request("secret"), "test", None);
+        assert_eq!(allowed.id, 7);
+        assert_eq!(allowed.message, "the agent isn't recording");
+
+        let line = r#"{"id":1,"command":"start_recording","token":"x"}"#;
+        assert_eq!(serde_json::from_str::<Request>(line).unwrap().command, Command::StartRecording);
+    }
+}
@@ -0,0 +1,79 @@
+use eframe::egui::{self, Color32, Id, LayerId, Mesh, Order, Rect, Shape, Stroke};
+use rand::Rng;
+
+// Pixels between the dark lines
+const SCANLINE_SPACING: f32 = 3.0;
+const SCANLINE_ALPHA: f32 = 0.18;
+const GLOW_ALPHA: f32 = 0.07;
+const VIGNETTE_ALPHA: f32 = 0.35;
+
+// A retro monitor drawn over everything else: scanlines, a phosphor haze in the
+// theme's primary color that's brightest mid-screen, darker corners, and on
+// glitched frames a flicker. Purely cosmetic; it only adds shapes on top.
+pub fn paint(ctx: &egui::Context, primary: Color32, flicker: bool) {
+    let rect = ctx.screen_rect();
+    let painter = ctx.layer_painter(LayerId::new(Order::Foreground, Id::new("crt")));
+
+    painter.add(radial(rect, primary.gamma_multiply(GLOW_ALPHA), Color32::TRANSPARENT));
+    painter.add(radial(rect, Color32::TRANSPARENT, Color32::BLACK.gamma_multiply(VIGNETTE_ALPHA)));
+    let stroke = Stroke::new(1.0, Color32::BLACK.gamma_multiply(SCANLINE_ALPHA));
+    for y in scanlines(rect) {
+        painter.hline(rect.x_range(), y, stroke);
+    }
+
+    if flicker {
+        let mut rng = rand::thread_rng();
+        painter.rect_filled(rect, 0.0, Color32::BLACK.gamma_multiply(rng.gen_range(0.05..0.2)));
+        // A bright band rolling through, like a badly synced tube
+        let top = rng.gen_range(rect.top()..rect.bottom());
+        let band = Rect::from_x_y_ranges(rect.x_range(), top..=top + rng.gen_range(4.0..16.0));
+        painter.rect_filled(band, 0.0, primary.gamma_multiply(0.08));
+    }
+}
+
+fn scanlines(rect: Rect) -> impl Iterator<Item = f32> {
+    let count = (rect.height() / SCANLINE_SPACING).ceil() as usize;
+    (0..count).map(move |i| rect.top() + i as f32 * SCANLINE_SPACING + 0.5)
+}
+
+// A fan from the middle of `rect` out to its corners and edges, blending `center` into `edge`
+fn radial(rect: Rect, center: Color32, edge: Color32) -> Shape {
+    let mut mesh = Mesh::default();
+    mesh.colored_vertex(rect.center(), center);
+    let ring = [
+        rect.left_top(),
+        rect.center_top(),
+        rect.right_top(),
+        rect.right_center(),
+        rect.right_bottom(),
+        rect.center_bottom(),
+        rect.left_bottom(),
+        rect.left_center(),
+    ];
+    for point in ring {
+        mesh.colored_vertex(point, edge);
+    }
+    for i in 0..ring.len() as u32 {
+        mesh.add_triangle(0, 1 + i, 1 + (i + 1) % ring.len() as u32);
+    }
+    Shape::mesh(mesh)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eframe::egui::Pos2;
+
+    #[test]
+    fn covers_the_screen() {
+        let rect = Rect::from_min_size(Pos2::new(0.0, 10.0), egui::vec2(100.0, 30.0));
+        let lines: Vec<f32> = scanlines(rect).collect();
+        assert_eq!(lines, [10.5, 13.5, 16.5, 19.5, 22.5, 25.5, 28.5, 31.5, 34.5, 37.5]);
+
+        let Shape::Mesh(mesh) = radial(rect, Color32::WHITE, Color32::TRANSPARENT) else {
+            panic!("expected a mesh");
+        };
+        assert_eq!((mesh.vertices.len(), mesh.indices.len()), (9, 24));
+        assert!(mesh.is_valid());
+    }
+}
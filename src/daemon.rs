@@ -0,0 +1,614 @@
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+#[cfg(feature = "gui")]
+use std::net::TcpStream;
+#[cfg(feature = "gui")]
+use std::path::Path;
+#[cfg(feature = "gui")]
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+#[cfg(any(feature = "gui", feature = "mqtt"))]
+use std::time::Instant;
+
+use rusty_mem_monitor::sample::now_ms;
+#[cfg(feature = "mqtt")]
+use rusty_mem_monitor::AlertState;
+use rusty_mem_monitor::{Sample, Sampler};
+
+use crate::config::{AgentConfig, Config};
+#[cfg(feature = "gui")]
+use crate::control::Command;
+use crate::control::{Control, Reply, ReplyLine, Request};
+use crate::diag;
+use crate::influx::InfluxSink;
+#[cfg(feature = "mqtt")]
+use crate::jsonl::Alarm;
+use crate::logging;
+#[cfg(feature = "mqtt")]
+use crate::mqtt::MqttSink;
+use crate::soak::Soak;
+#[cfg(feature = "gui")]
+use crate::tls::Connector;
+use crate::tls::{self, Acceptor};
+#[cfg(feature = "web")]
+use crate::web::WebServer;
+
+pub const SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+// Readings folded into each sample, so spikes shorter than SAMPLE_INTERVAL still
+// show up in its min/max
+pub const SUB_SAMPLES: u32 = 5;
+pub const AGENT_PORT: u16 = 47475;
+pub const BACKLOG: usize = 100;
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+#[cfg(feature = "gui")]
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+// Clocks further apart than this get flagged; below it, NTP jitter and the
+// handshake's own error make the number meaningless
+#[cfg(feature = "gui")]
+pub const SKEW_WARNING_MS: i64 = 1000;
+
+#[cfg(unix)]
+type Stream = std::os::unix::net::UnixStream;
+#[cfg(unix)]
+type Listener = std::os::unix::net::UnixListener;
+#[cfg(not(unix))]
+type Stream = std::net::TcpStream;
+#[cfg(not(unix))]
+type Listener = std::net::TcpListener;
+
+#[cfg(not(unix))]
+const DAEMON_ADDR: &str = "127.0.0.1:47474";
+
+#[cfg(unix)]
+fn socket_path() -> std::path::PathBuf {
+    let user = std::env::var("USER").unwrap_or_else(|_| "user".to_string());
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir)
+        .join(format!("rusty_mem_monitor-{}.sock", user))
+}
+
+#[cfg(unix)]
+fn connect() -> std::io::Result<Stream> {
+    Stream::connect(socket_path())
+}
+
+#[cfg(not(unix))]
+fn connect() -> std::io::Result<Stream> {
+    Stream::connect(DAEMON_ADDR)
+}
+
+#[cfg(unix)]
+fn listen() -> std::io::Result<Listener> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = socket_path();
+    if path.exists() {
+        if connect().is_ok() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AddrInUse,
+                format!("a daemon is already listening on {}", path.display()),
+            ));
+        }
+        // Left behind by a daemon that didn't shut down cleanly
+        std::fs::remove_file(&path)?;
+    }
+    let listener = Listener::bind(&path)?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+#[cfg(not(unix))]
+fn listen() -> std::io::Result<Listener> {
+    Listener::bind(DAEMON_ADDR)
+}
+
+// The first line a client receives, ahead of the backlog, so it can tell how far
+// our clock is from its own. Older clients skip it as a line that isn't a sample.
+#[derive(Serialize, Deserialize)]
+struct Hello {
+    clock_ms: u64,
+}
+
+struct Client {
+    id: u64,
+    stream: Box<dyn Write + Send>,
+}
+
+#[derive(Default)]
+struct Shared {
+    history: VecDeque<Sample>,
+    clients: Vec<Client>,
+    next_client: u64,
+    // Agents only; the local daemon's socket carries samples alone
+    control: Option<Arc<Control>>,
+}
+
+// Samples memory on a fixed cadence and streams each sample as a JSON line to every
+// attached UI. New clients first receive the backlog so their plot starts full.
+pub fn run(soak: Option<Soak>, sinks: Sinks) -> std::io::Result<()> {
+    let listener = listen()?;
+    let shared = Arc::new(Mutex::new(Shared::default()));
+
+    let accept_shared = Arc::clone(&shared);
+    std::thread::spawn(move || {
+        let incoming = listener.incoming().map(|stream| {
+            let stream = stream?;
+            stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+            Ok(stream)
+        });
+        accept(incoming, &accept_shared);
+    });
+
+    println!("Memory monitor daemon running, {} ms interval", SAMPLE_INTERVAL.as_millis());
+    sample_loop(&shared, soak, sinks)
+}
+
+// Same stream as the local daemon, but on a TCP port so a UI on another machine
+// can `--connect` to it. Anyone who can connect sees the samples, so bind it to
+// a trusted network or set up TLS; commands also need the operator token, and
+// without one configured the agent takes none.
+pub fn run_agent(addr: &str, soak: Option<Soak>, sinks: Sinks, agent: &AgentConfig) -> std::io::Result<()> {
+    let addr = with_default_port(addr);
+    let acceptor = Acceptor::load(agent).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    let listener = TcpListener::bind(&addr)?;
+    // In the clear beyond loopback, the token could be sniffed and replayed
+    let exposed = acceptor.is_none() && !listener.local_addr()?.ip().is_loopback();
+    if exposed && !agent.operator_token.is_empty() {
+        diag::log("Not taking commands: the agent listens beyond loopback without TLS");
+    }
+    let scheme = if acceptor.is_some() { " over TLS" } else { "" };
+    let control = Some(Arc::new(Control::new(agent.operator_token.clone(), exposed)));
+    let shared = Arc::new(Mutex::new(Shared { control, ..Shared::default() }));
+
+    let acceptor = acceptor.map(Arc::new);
+    let accept_shared = Arc::clone(&shared);
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else { continue };
+            let shared = Arc::clone(&accept_shared);
+            let acceptor = acceptor.clone();
+            // A TLS handshake goes at the peer's pace, so each connection gets a
+            // thread of its own and a slow one can't hold up the others
+            std::thread::spawn(move || {
+                let stream = stream.set_write_timeout(Some(WRITE_TIMEOUT)).and_then(|()| {
+                    stream.set_nodelay(true)?;
+                    match &acceptor {
+                        Some(acceptor) => acceptor.accept(stream),
+                        None => Ok(tls::Stream::Plain(stream)),
+                    }
+                });
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(e) => return diag::log(format!("Dropped an agent connection: {}", e)),
+                };
+                let request_shared = Arc::clone(&shared);
+                register(stream, &shared, move |stream: &tls::Stream, id| {
+                    let Ok(reader) = stream.try_clone() else { return };
+                    let shared = Arc::clone(&request_shared);
+                    std::thread::spawn(move || serve_requests(reader, id, &shared));
+                });
+            });
+        }
+    });
+
+    println!("Memory monitor agent listening on {}{}, {} ms interval", addr, scheme, SAMPLE_INTERVAL.as_millis());
+    sample_loop(&shared, soak, sinks)
+}
+
+fn accept<S: Write + Send + 'static>(incoming: impl Iterator<Item = std::io::Result<S>>, shared: &Mutex<Shared>) {
+    for stream in incoming.flatten() {
+        register(stream, shared, |_, _| {});
+    }
+}
+
+// Sends a new client the hello and backlog, then adds it to those getting samples.
+// `opened` gets it just before, with the id its replies are addressed by.
+fn register<S: Write + Send + 'static>(mut stream: S, shared: &Mutex<Shared>, opened: impl FnOnce(&S, u64)) {
+    let mut shared = shared.lock().unwrap();
+    let hello = serde_json::to_string(&Hello { clock_ms: now_ms() }).map_err(std::io::Error::from);
+    let backlog_sent = hello.and_then(|hello| writeln!(stream, "{}", hello)).is_ok()
+        && shared.history.iter().all(|sample| write_sample(&mut stream, sample).is_ok());
+    if backlog_sent {
+        let id = shared.next_client;
+        shared.next_client += 1;
+        opened(&stream, id);
+        shared.clients.push(Client { id, stream: Box::new(stream) });
+    }
+}
+
+// Reads a client's command lines until it hangs up, answering each on its own
+// stream between samples. Commands run without the shared lock, so a snapshot
+// or self-test doesn't stall sampling or the other clients.
+fn serve_requests(stream: tls::Stream, id: u64, shared: &Mutex<Shared>) {
+    let peer = stream.peer_addr().map_or_else(|_| "unknown peer".to_string(), |addr| addr.to_string());
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else { break };
+        let Ok(request) = serde_json::from_str::<Request>(&line) else {
+            diag::log(format!("Ignoring a line from {} that isn't a command", peer));
+            continue;
+        };
+        let (control, latest) = {
+            let shared = shared.lock().unwrap();
+            (shared.control.clone(), shared.history.back().copied())
+        };
+        let Some(control) = control else { break };
+        let reply = control.handle(&request, &peer, latest.as_ref());
+        let mut shared = shared.lock().unwrap();
+        let Some(client) = shared.clients.iter_mut().find(|client| client.id == id) else { break };
+        let sent = serde_json::to_string(&ReplyLine { reply }).map_err(std::io::Error::from);
+        if sent.and_then(|line| writeln!(client.stream, "{}", line)).is_err() {
+            break;
+        }
+    }
+}
+
+fn sample_loop(shared: &Mutex<Shared>, mut soak: Option<Soak>, mut sinks: Sinks) -> std::io::Result<()> {
+    let mut sampler = Sampler::new();
+    loop {
+        let sample = sampler.sample_window(SUB_SAMPLES as usize, SAMPLE_INTERVAL / SUB_SAMPLES);
+        logging::sample(&sample);
+        sinks.push(&sample);
+        let (backlog, recording) = {
+            let mut shared = shared.lock().unwrap();
+            shared.history.push_back(sample);
+            if shared.history.len() > BACKLOG {
+                shared.history.pop_front();
+            }
+            shared.clients.retain_mut(|client| write_sample(&mut client.stream, &sample).is_ok());
+            let recording = shared.control.as_ref().and_then(|control| control.record(&sample));
+            (shared.history.len(), recording)
+        };
+        // The backlog is all we hold, so there's nothing to trim: a fresh process is
+        // the only way back under the cap, and the service manager provides one.
+        // A recording still being written is kept from the disk cap.
+        let keep: Vec<&std::path::Path> = recording.as_deref().into_iter().collect();
+        let footprint = soak.as_mut().and_then(|soak| soak.check(sampler.system_mut(), backlog, &keep));
+        if footprint.is_some_and(|footprint| footprint.over_memory) {
+            diag::log("Soak mode: over the memory cap, exiting to be restarted");
+            std::process::exit(1);
+        }
+    }
+}
+
+// Where samples go besides the clients
+#[derive(Default)]
+pub struct Sinks {
+    influx: Option<InfluxSink>,
+    #[cfg(feature = "mqtt")]
+    mqtt: Option<MqttSink>,
+    // Judged here for MQTT's alarm topic, against the configured thresholds as
+    // headless --output jsonl does
+    #[cfg(feature = "mqtt")]
+    alerts: AlertState,
+    #[cfg(feature = "web")]
+    web: Option<WebServer>,
+}
+
+impl Sinks {
+    pub fn start(config: &Config, host: String, web: Option<&str>) -> Self {
+        #[cfg(feature = "web")]
+        let web = web.and_then(|addr| match WebServer::start(addr, &host) {
+            Ok(server) => Some(server),
+            Err(e) => {
+                diag::log(format!("Could not serve the web view on {}: {}", addr, e));
+                None
+            }
+        });
+        #[cfg(not(feature = "web"))]
+        let _ = web;
+        #[cfg(feature = "mqtt")]
+        let alerts = {
+            let mut alerts = AlertState::default();
+            alerts.thresholds = config.alerts.thresholds;
+            alerts
+        };
+        Self {
+            #[cfg(feature = "mqtt")]
+            mqtt: MqttSink::start(&config.mqtt, &host),
+            #[cfg(feature = "mqtt")]
+            alerts,
+            #[cfg(feature = "web")]
+            web,
+            influx: InfluxSink::start(&config.influx, host),
+        }
+    }
+
+    fn push(&mut self, sample: &Sample) {
+        if let Some(influx) = &self.influx {
+            influx.push(sample);
+        }
+        #[cfg(feature = "mqtt")]
+        if let Some(mqtt) = &self.mqtt {
+            if sample.has_memory() {
+                self.alerts.update(sample.memory_percentage(), Instant::now(), &[]);
+            }
+            mqtt.push(sample, Alarm::of(&self.alerts));
+        }
+        #[cfg(feature = "web")]
+        if let Some(web) = &self.web {
+            web.push(sample);
+        }
+    }
+}
+
+fn write_sample(stream: &mut impl Write, sample: &Sample) -> std::io::Result<()> {
+    let line = serde_json::to_string(sample)?;
+    writeln!(stream, "{}", line)
+}
+
+// "homelab" and "homelab:9000" both work; IPv6 literals need brackets to carry a port
+pub fn with_default_port(addr: &str) -> String {
+    let has_port = addr
+        .rsplit_once(':')
+        .is_some_and(|(host, port)| (!host.contains(':') || host.ends_with(']')) && port.parse::<u16>().is_ok());
+    if has_port {
+        addr.to_string()
+    } else {
+        format!("{}:{}", addr, AGENT_PORT)
+    }
+}
+
+pub struct DaemonClient {
+    rx: Receiver<Sample>,
+    connected: Arc<AtomicBool>,
+    // Their clock minus ours, from the last connection's hello; None before one arrives
+    #[cfg(feature = "gui")]
+    skew_ms: Arc<Mutex<Option<i64>>>,
+    // Remote clients only: the agent's stream while connected, for commands, and
+    // what it answered
+    #[cfg(feature = "gui")]
+    writer: Arc<Mutex<Option<tls::Stream>>>,
+    #[cfg(feature = "gui")]
+    replies: Receiver<Reply>,
+    #[cfg(feature = "gui")]
+    next_request: AtomicU64,
+}
+
+// What the reader thread hands over
+struct Channels {
+    samples: Sender<Sample>,
+    replies: Sender<Reply>,
+}
+
+impl DaemonClient {
+    pub fn connect() -> Option<Self> {
+        let stream = connect().ok()?;
+        let (tx, rx) = channel();
+        let connected = Arc::new(AtomicBool::new(true));
+        let reader_connected = Arc::clone(&connected);
+        let skew_ms = Arc::new(Mutex::new(None));
+        let reader_skew = Arc::clone(&skew_ms);
+        let (replies_tx, replies) = channel();
+        std::thread::spawn(move || {
+            let channels = Channels { samples: tx, replies: replies_tx };
+            read_samples(stream, &channels, &reader_skew, Duration::ZERO);
+            reader_connected.store(false, Ordering::Relaxed);
+        });
+        Some(Self::new(rx, connected, skew_ms, replies))
+    }
+
+    fn new(
+        rx: Receiver<Sample>,
+        connected: Arc<AtomicBool>,
+        skew_ms: Arc<Mutex<Option<i64>>>,
+        replies: Receiver<Reply>,
+    ) -> Self {
+        #[cfg(not(feature = "gui"))]
+        let _ = (skew_ms, replies);
+        Self {
+            rx,
+            connected,
+            #[cfg(feature = "gui")]
+            skew_ms,
+            #[cfg(feature = "gui")]
+            writer: Arc::new(Mutex::new(None)),
+            #[cfg(feature = "gui")]
+            replies,
+            #[cfg(feature = "gui")]
+            next_request: AtomicU64::new(1),
+        }
+    }
+
+    // The daemon's sampling loop on a thread of our own, for when there's no daemon.
+    // The interval is re-read before every sample so it can change at runtime.
+    #[cfg(feature = "gui")]
+    pub fn in_process(interval_ms: Arc<AtomicU64>) -> Self {
+        let (tx, rx) = channel();
+        std::thread::spawn(move || {
+            let mut sampler = Sampler::new();
+            loop {
+                let interval = Duration::from_millis(interval_ms.load(Ordering::Relaxed));
+                let sample = sampler.sample_window(SUB_SAMPLES as usize, interval / SUB_SAMPLES);
+                if tx.send(sample).is_err() {
+                    break;
+                }
+            }
+        });
+        Self::new(rx, Arc::new(AtomicBool::new(true)), Arc::new(Mutex::new(Some(0))), channel().1)
+    }
+
+    // Unlike the local daemon there's nothing to fall back to, so a remote client
+    // keeps reconnecting in the background for as long as it lives. TLS settings
+    // that don't load leave it never connecting, rather than falling back to the
+    // clear.
+    #[cfg(feature = "gui")]
+    pub fn remote(addr: &str, agent: &AgentConfig) -> Self {
+        let addr = with_default_port(addr);
+        let connector = Connector::load(agent);
+        let (tx, rx) = channel();
+        let connected = Arc::new(AtomicBool::new(false));
+        let reader_connected = Arc::clone(&connected);
+        let skew_ms = Arc::new(Mutex::new(None));
+        let reader_skew = Arc::clone(&skew_ms);
+        let (replies_tx, replies) = channel();
+        let channels = Channels { samples: tx, replies: replies_tx };
+        let client = Self::new(rx, connected, skew_ms, replies);
+        let writer = Arc::clone(&client.writer);
+        std::thread::spawn(move || loop {
+            let started = Instant::now();
+            let mut round_trip = Duration::ZERO;
+            let connected = match &connector {
+                Ok(connector) => TcpStream::connect(&addr).and_then(|stream| {
+                    // Connecting takes one round trip, which is close enough to the
+                    // hello's; a TLS handshake's would overstate it
+                    round_trip = started.elapsed();
+                    match connector {
+                        Some(connector) => connector.connect(stream, &addr),
+                        None => Ok(tls::Stream::Plain(stream)),
+                    }
+                }),
+                Err(e) => Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, e.clone())),
+            };
+            match connected {
+                Ok(stream) => {
+                    *writer.lock().unwrap() = stream.try_clone().ok();
+                    reader_connected.store(true, Ordering::Relaxed);
+                    let open = read_samples(stream, &channels, &reader_skew, round_trip);
+                    reader_connected.store(false, Ordering::Relaxed);
+                    *writer.lock().unwrap() = None;
+                    if !open {
+                        break;
+                    }
+                    diag::log(format!("Lost connection to agent {}", addr));
+                }
+                Err(e) => diag::log(format!("Could not connect to agent {}: {}", addr, e)),
+            }
+            std::thread::sleep(RECONNECT_INTERVAL);
+        });
+        client
+    }
+
+    // A recording or export played at the pace it was taken, moved onto our clock
+    // so it runs like a live feed; disconnected once it's over
+    #[cfg(feature = "gui")]
+    pub fn replay(path: &Path) -> std::io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        let samples: Vec<Sample> = text.lines().filter_map(Sample::from_csv_row).collect();
+        if samples.is_empty() {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "no samples in the file"));
+        }
+        Ok(Self::play(samples))
+    }
+
+    #[cfg(any(feature = "gui", test))]
+    fn play(samples: Vec<Sample>) -> Self {
+        let (tx, rx) = channel();
+        let connected = Arc::new(AtomicBool::new(true));
+        let playing = Arc::clone(&connected);
+        std::thread::spawn(move || {
+            let started = now_ms();
+            let first_ms = samples.first().map_or(0, |sample| sample.timestamp_ms);
+            for mut sample in samples {
+                let due_ms = started + sample.timestamp_ms.saturating_sub(first_ms);
+                std::thread::sleep(Duration::from_millis(due_ms.saturating_sub(now_ms())));
+                sample.timestamp_ms = due_ms;
+                if tx.send(sample).is_err() {
+                    return;
+                }
+            }
+            playing.store(false, Ordering::Relaxed);
+        });
+        Self::new(rx, connected, Arc::new(Mutex::new(None)), channel().1)
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    pub fn poll(&self) -> Vec<Sample> {
+        self.rx.try_iter().collect()
+    }
+
+    // Samples from poll() are already shifted onto our clock by this much
+    #[cfg(feature = "gui")]
+    pub fn clock_skew_ms(&self) -> Option<i64> {
+        *self.skew_ms.lock().unwrap()
+    }
+
+    // Asks a connected agent to run `command`; its reply turns up in replies()
+    // under the returned id
+    #[cfg(feature = "gui")]
+    pub fn send(&self, command: Command, token: &str) -> Result<u64, String> {
+        let mut writer = self.writer.lock().unwrap();
+        let stream = writer.as_mut().ok_or("not connected to an agent")?;
+        let id = self.next_request.fetch_add(1, Ordering::Relaxed);
+        let request = Request { id, command, token: token.to_string() };
+        let line = serde_json::to_string(&request).map_err(|e| e.to_string())?;
+        writeln!(stream, "{}", line).map_err(|e| e.to_string())?;
+        Ok(id)
+    }
+
+    #[cfg(feature = "gui")]
+    pub fn replies(&self) -> Vec<Reply> {
+        self.replies.try_iter().collect()
+    }
+}
+
+// Returns false once the receiving side has gone away. Timestamps are moved onto
+// our clock once the hello says how far off theirs is, so hosts line up in time.
+fn read_samples(stream: impl Read, channels: &Channels, skew_ms: &Mutex<Option<i64>>, round_trip: Duration) -> bool {
+    *skew_ms.lock().unwrap() = None;
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else { break };
+        let Ok(mut sample) = serde_json::from_str::<Sample>(&line) else {
+            if let Ok(hello) = serde_json::from_str::<Hello>(&line) {
+                // It left them about half a round trip ago
+                let sent_ms = now_ms() as i64 - round_trip.as_millis() as i64 / 2;
+                *skew_ms.lock().unwrap() = Some(hello.clock_ms as i64 - sent_ms);
+            } else if let Ok(ReplyLine { reply }) = serde_json::from_str(&line) {
+                let _ = channels.replies.send(reply);
+            }
+            continue;
+        };
+        if let Some(skew) = *skew_ms.lock().unwrap() {
+            sample.timestamp_ms = sample.timestamp_ms.saturating_add_signed(-skew);
+        }
+        if channels.samples.send(sample).is_err() {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hello_shifts_samples_onto_our_clock() {
+        let ahead_ms = now_ms() + 60_000;
+        let lines = format!(
+            "{}\n{}\n",
+            serde_json::to_string(&Hello { clock_ms: ahead_ms }).unwrap(),
+            serde_json::to_string(&Sample { timestamp_ms: ahead_ms, ..Sample::default() }).unwrap(),
+        );
+        let (tx, rx) = channel();
+        let (replies_tx, _replies) = channel();
+        let skew_ms = Mutex::new(None);
+        let channels = Channels { samples: tx, replies: replies_tx };
+        assert!(read_samples(lines.as_bytes(), &channels, &skew_ms, Duration::ZERO));
+        let skew = skew_ms.lock().unwrap().unwrap();
+        assert!((59_000..=60_000).contains(&skew));
+        let sample = rx.try_recv().unwrap();
+        assert!(sample.timestamp_ms.abs_diff(now_ms()) < 1000);
+    }
+
+    #[test]
+    fn replay_keeps_the_recorded_pace() {
+        let recorded = |timestamp_ms| Sample { timestamp_ms, used_memory: timestamp_ms, ..Sample::default() };
+        let client = DaemonClient::play(vec![recorded(5_000), recorded(5_050)]);
+        std::thread::sleep(Duration::from_millis(300));
+        let samples = client.poll();
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[1].timestamp_ms - samples[0].timestamp_ms, 50);
+        assert_eq!(samples[1].used_memory, 5_050);
+        assert!(samples[0].timestamp_ms.abs_diff(now_ms()) < 1000);
+        assert!(!client.is_connected());
+    }
+}
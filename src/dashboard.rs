@@ -0,0 +1,352 @@
+use eframe::egui::{self, Color32};
+use egui_plot::{Legend, Line, Plot};
+use std::time::Instant;
+
+use rusty_mem_monitor::units::{format_bytes, format_bytes_delta};
+use rusty_mem_monitor::{AlertState, History, Sample};
+
+use crate::config::{ActionsConfig, AgentConfig, AlertsConfig};
+use crate::daemon::{DaemonClient, SKEW_WARNING_MS};
+use crate::i18n::{tr, tr_args};
+use crate::notify;
+#[cfg(feature = "audio")]
+use crate::sound::AlarmSound;
+use crate::theme::Theme;
+
+const HOST_HISTORY_LEN: usize = 100;
+const MAX_COLUMNS: usize = 3;
+const PLOT_HEIGHT: f32 = 110.0;
+const COMPARE_PLOT_HEIGHT: f32 = 200.0;
+
+// Line colors that stay apart from each other and from the alarm colors, on
+// both dark and light themes
+const HOST_COLORS: [Color32; 6] = [
+    Color32::from_rgb(0x4f, 0xc3, 0xf7),
+    Color32::from_rgb(0xba, 0x68, 0xc8),
+    Color32::from_rgb(0x81, 0xc7, 0x84),
+    Color32::from_rgb(0xff, 0xb7, 0x4d),
+    Color32::from_rgb(0xf0, 0x62, 0x92),
+    Color32::from_rgb(0x4d, 0xb6, 0xac),
+];
+
+struct Host {
+    addr: String,
+    client: DaemonClient,
+    history: History,
+    latest: Option<Sample>,
+    alerts: AlertState,
+    color: Color32,
+}
+
+// One card per remote agent, each with its own history and alarm, for watching
+// a handful of machines from a single window.
+pub struct Dashboard {
+    hosts: Vec<Host>,
+    // Indices of the two hosts overlaid in the comparison view, when it's on
+    compare: Option<[usize; 2]>,
+    // One alarm sound for the lot, going while any host's alarm is unacknowledged
+    #[cfg(feature = "audio")]
+    sound: AlarmSound,
+}
+
+impl Dashboard {
+    pub fn new(addrs: &[String], agent: &AgentConfig) -> Self {
+        let hosts = addrs
+            .iter()
+            .enumerate()
+            .map(|(i, addr)| Host {
+                addr: addr.clone(),
+                client: DaemonClient::remote(addr, agent),
+                history: History::new(HOST_HISTORY_LEN),
+                latest: None,
+                alerts: AlertState::default(),
+                color: HOST_COLORS[i % HOST_COLORS.len()],
+            })
+            .collect();
+        Self {
+            hosts,
+            compare: None,
+            #[cfg(feature = "audio")]
+            sound: AlarmSound::default(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.hosts.len()
+    }
+
+    pub fn poll(&mut self) {
+        for host in &mut self.hosts {
+            for sample in host.client.poll() {
+                host.history.push(&sample);
+                host.latest = Some(sample);
+            }
+        }
+    }
+
+    pub fn update_alerts(&mut self, alerts: &AlertsConfig, actions: &ActionsConfig) {
+        let now = Instant::now();
+        let stages = &alerts.escalation;
+        for host in &mut self.hosts {
+            host.alerts.thresholds = alerts.thresholds;
+            // Without totals there's nothing to judge, so alarms stay as they were
+            let Some(latest) = host.latest.filter(|sample| sample.has_memory()) else { continue };
+            let memory_percentage = latest.memory_percentage();
+            for index in host.alerts.update(memory_percentage, now, stages) {
+                #[cfg(feature = "audio")]
+                if let Some(volume) = stages[index].sound_volume {
+                    self.sound.escalate(volume);
+                }
+                let context = notify::AlertContext {
+                    hostname: host.addr.clone(),
+                    memory_percentage,
+                    level: index + 1,
+                };
+                notify::fire_stage(&stages[index], context, None, actions);
+            }
+        }
+        #[cfg(feature = "audio")]
+        {
+            let sounding = self.hosts.iter().any(|host| host.alerts.is_sounding(now));
+            self.sound.update(sounding, &alerts.sound);
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, theme: &Theme) {
+        self.compare_ui(ui, theme);
+        let columns = self.hosts.len().clamp(1, MAX_COLUMNS);
+        for (row, hosts) in self.hosts.chunks_mut(columns).enumerate() {
+            ui.columns(columns, |cols| {
+                for (i, host) in hosts.iter_mut().enumerate() {
+                    host_ui(&mut cols[i], host, theme, row * columns + i);
+                }
+            });
+            ui.add_space(10.0);
+        }
+    }
+}
+
+impl Dashboard {
+    // Two hosts on the same axes, lined up on wall-clock time, for checking whether
+    // supposedly identical servers behave the same
+    fn compare_ui(&mut self, ui: &mut egui::Ui, theme: &Theme) {
+        if self.hosts.len() < 2 {
+            return;
+        }
+        let mut comparing = self.compare.is_some();
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut comparing, tr("dashboard-compare"));
+            let Some(pair) = self.compare.as_mut() else { return };
+            for (side, index) in pair.iter_mut().enumerate() {
+                egui::ComboBox::from_id_source(("compare_host", side))
+                    .selected_text(&self.hosts[*index].addr)
+                    .show_ui(ui, |ui| {
+                        for (i, host) in self.hosts.iter().enumerate() {
+                            ui.selectable_value(index, i, &host.addr);
+                        }
+                    });
+                if side == 0 {
+                    ui.label(tr("dashboard-versus"));
+                }
+            }
+        });
+        if comparing != self.compare.is_some() {
+            self.compare = comparing.then_some([0, 1]);
+        }
+        let Some([a, b]) = self.compare else {
+            return;
+        };
+        let (a, b) = (&self.hosts[a], &self.hosts[b]);
+
+        let has_memory = |host: &Host| host.latest.filter(|sample| sample.has_memory());
+        if let (Some(latest_a), Some(latest_b)) = (has_memory(a), has_memory(b)) {
+            let delta = latest_a.memory_percentage() - latest_b.memory_percentage();
+            let bytes = latest_a.used_memory as i64 - latest_b.used_memory as i64;
+            let delta = format!("{:+.1}", delta);
+            let mut readout = tr_args("dashboard-compare-now", &[("delta", &delta), ("bytes", &format_bytes_delta(bytes))]);
+            if let Some(mean) = mean_delta(&a.history, &b.history) {
+                readout.push_str("  ");
+                readout.push_str(&tr_args("dashboard-compare-mean", &[("delta", &format!("{:+.1}", mean))]));
+            }
+            ui.label(egui::RichText::new(readout).color(theme.info).monospace());
+        } else {
+            ui.label(egui::RichText::new(tr("dashboard-compare-waiting")).color(theme.dim).monospace());
+        }
+
+        // One time origin for both, so the same x is the same moment on each host
+        let latest_ms = [&a.history, &b.history]
+            .iter()
+            .filter_map(|history| history.last().map(|sample| sample.timestamp_ms))
+            .max()
+            .unwrap_or(0);
+        Plot::new("compare_plot")
+            .height(COMPARE_PLOT_HEIGHT)
+            .show_background(false)
+            .legend(Legend::default())
+            .include_y(0.0)
+            .include_y(100.0)
+            .x_axis_formatter(|x, _, _| format!("{:.0}s", x))
+            .y_axis_formatter(|y, _, _| format!("{:.0}%", y))
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new(points(&a.history, latest_ms)).color(a.color).name(&a.addr));
+                plot_ui.line(Line::new(points(&b.history, latest_ms)).color(b.color).name(&b.addr));
+            });
+        ui.add_space(10.0);
+    }
+}
+
+// Difference of mean RAM % over the stretch of time both histories cover
+fn mean_delta(a: &History, b: &History) -> Option<f32> {
+    let start = a.iter().next()?.timestamp_ms.max(b.iter().next()?.timestamp_ms);
+    let end = a.last()?.timestamp_ms.min(b.last()?.timestamp_ms);
+    let mean = |history: &History| {
+        let values: Vec<f32> = history
+            .iter()
+            .filter(|sample| sample.has_memory() && (start..=end).contains(&sample.timestamp_ms))
+            .map(|sample| sample.memory_percentage())
+            .collect();
+        (!values.is_empty()).then(|| values.iter().sum::<f32>() / values.len() as f32)
+    };
+    Some(mean(a)? - mean(b)?)
+}
+
+// RAM % against seconds before latest_ms, with a gap rather than a drop to zero
+// where a host sent no totals
+fn points(history: &History, latest_ms: u64) -> Vec<[f64; 2]> {
+    history
+        .iter()
+        .filter(|sample| sample.has_memory())
+        .map(|sample| {
+            [
+                (sample.timestamp_ms as f64 - latest_ms as f64) / 1000.0,
+                sample.memory_percentage() as f64,
+            ]
+        })
+        .collect()
+}
+
+// "MEM 42.0%  SWP 3.1%", with N/A for whichever the host has no totals for
+fn readout(sample: &Sample) -> String {
+    let percent = |known: bool, percentage: f32| {
+        if known {
+            format!("{:.1}%", percentage)
+        } else {
+            tr("not-available")
+        }
+    };
+    let memory = percent(sample.has_memory(), sample.memory_percentage());
+    let swap = percent(sample.has_swap(), sample.swap_percentage());
+    tr_args("dashboard-readout", &[("memory", &memory), ("swap", &swap)])
+}
+
+// Their clock against ours, and whether it's off enough to flag. Samples are already
+// corrected for it, but a host far off usually has broken NTP, which is worth knowing.
+pub fn skew_text(skew_ms: i64) -> (String, bool) {
+    let skewed = skew_ms.abs() >= SKEW_WARNING_MS;
+    let offset = format!("{:+.1}s", skew_ms as f64 / 1000.0);
+    let text = tr_args(if skewed { "dashboard-clock-skewed" } else { "dashboard-clock" }, &[("offset", &offset)]);
+    (text, skewed)
+}
+
+fn host_ui(ui: &mut egui::Ui, host: &mut Host, theme: &Theme, index: usize) {
+    let percentage = host.latest.map_or(0.0, |sample| sample.memory_percentage());
+    ui.group(|ui| {
+        ui.label(egui::RichText::new(&host.addr).color(host.color).monospace().strong());
+
+        match (&host.latest, host.client.is_connected()) {
+            (_, false) => {
+                ui.label(egui::RichText::new(tr("badge-offline")).color(theme.dim).monospace());
+            }
+            (None, true) => {
+                ui.label(egui::RichText::new(tr("dashboard-waiting")).color(theme.dim).monospace());
+            }
+            (Some(sample), true) => {
+                let color = if sample.has_memory() {
+                    theme.level(percentage, &host.alerts.thresholds)
+                } else {
+                    theme.dim
+                };
+                ui.label(egui::RichText::new(readout(sample)).color(color).monospace());
+                if sample.has_memory() {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{} / {}",
+                            format_bytes(sample.used_memory),
+                            format_bytes(sample.total_memory)
+                        ))
+                        .color(theme.info)
+                        .monospace()
+                    );
+                }
+            }
+        }
+
+        if let Some(skew_ms) = host.client.clock_skew_ms().filter(|_| host.client.is_connected()) {
+            let (text, skewed) = skew_text(skew_ms);
+            ui.label(egui::RichText::new(text).color(if skewed { theme.warning } else { theme.dim }).monospace());
+        }
+
+        if host.alerts.critical {
+            ui.horizontal(|ui| {
+                let label = tr(if host.alerts.acknowledged { "dashboard-critical-ack" } else { "dashboard-critical" });
+                ui.label(egui::RichText::new(label).color(theme.critical).monospace());
+                if !host.alerts.acknowledged && ui.small_button(tr("dashboard-ack")).clicked() {
+                    host.alerts.acknowledge();
+                }
+            });
+        }
+
+        let latest_ms = host.history.last().map_or(0, |sample| sample.timestamp_ms);
+        let points = points(&host.history, latest_ms);
+        Plot::new(("host_plot", index))
+            .height(PLOT_HEIGHT)
+            .show_background(false)
+            .allow_zoom(false)
+            .allow_drag(false)
+            .allow_scroll(false)
+            .include_y(0.0)
+            .include_y(100.0)
+            .x_axis_formatter(|x, _, _| format!("{:.0}s", x))
+            .y_axis_formatter(|y, _, _| format!("{:.0}%", y))
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new(points).color(host.color).name("RAM %"));
+            });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(samples: &[(u64, u64)]) -> History {
+        let mut history = History::new(HOST_HISTORY_LEN);
+        for (timestamp_ms, used) in samples {
+            history.push(&Sample {
+                timestamp_ms: *timestamp_ms,
+                total_memory: 100,
+                used_memory: *used,
+                ..Sample::default()
+            });
+        }
+        history
+    }
+
+    #[test]
+    fn mean_delta_only_counts_the_overlap() {
+        let a = history(&[(0, 90), (1000, 50), (2000, 50)]);
+        let b = history(&[(1000, 40), (2000, 40), (3000, 0)]);
+        assert_eq!(mean_delta(&a, &b), Some(10.0));
+        assert_eq!(mean_delta(&a, &history(&[])), None);
+    }
+
+    #[test]
+    fn hosts_without_totals_read_na_and_leave_gaps() {
+        let mut history = history(&[(0, 50), (2000, 60)]);
+        history.push(&Sample { timestamp_ms: 1000, ..Sample::default() });
+        assert_eq!(points(&history, 2000), vec![[-2.0, 50.0], [0.0, 60.0]]);
+        let na = tr("not-available");
+        assert_eq!(readout(&Sample::default()), tr_args("dashboard-readout", &[("memory", &na), ("swap", &na)]));
+        let sample = Sample { total_memory: 200, used_memory: 50, ..Sample::default() };
+        assert_eq!(readout(&sample), tr_args("dashboard-readout", &[("memory", &"25.0%"), ("swap", &na)]));
+    }
+}
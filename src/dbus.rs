@@ -0,0 +1,113 @@
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+use zbus::blocking::{Connection, ConnectionBuilder};
+use zbus::{dbus_interface, fdo, SignalContext};
+
+use rusty_mem_monitor::Sample;
+
+use crate::diag;
+use crate::notify::Transition;
+
+const NAME: &str = "org.rusty.MemMonitor";
+const PATH: &str = "/org/rusty/MemMonitor";
+
+// timestamp_ms, total, used, total_swap, used_swap, memory %, swap %, critical
+type Stats = (u64, u64, u64, u64, u64, f64, f64, bool);
+
+// What callers ask the window for; it takes them up on its next frame
+pub enum Request {
+    Snapshot(String),
+}
+
+#[derive(Default, Clone, Copy)]
+struct State {
+    latest: Sample,
+    critical: bool,
+}
+
+struct Service {
+    state: Arc<Mutex<State>>,
+    requests: Sender<Request>,
+}
+
+#[dbus_interface(name = "org.rusty.MemMonitor")]
+impl Service {
+    fn stats(&self) -> Stats {
+        stats(&self.state.lock().unwrap())
+    }
+
+    // An empty name gets the window's usual snapshot-N
+    fn take_snapshot(&self, name: String) -> fdo::Result<()> {
+        let request = Request::Snapshot(name);
+        self.requests.send(request).map_err(|_| fdo::Error::Failed("the window has closed".to_string()))
+    }
+
+    // "warning", "critical" or "recovery", with RAM use in percent at the time
+    #[dbus_interface(signal)]
+    async fn alarm_changed(context: &SignalContext<'_>, transition: &str, percent: f64) -> zbus::Result<()>;
+}
+
+fn stats(state: &State) -> Stats {
+    let sample = &state.latest;
+    let memory = if sample.has_memory() { sample.memory_percentage() as f64 } else { 0.0 };
+    let swap = if sample.has_swap() { sample.swap_percentage() as f64 } else { 0.0 };
+    (
+        sample.timestamp_ms,
+        sample.total_memory,
+        sample.used_memory,
+        sample.total_swap,
+        sample.used_swap,
+        memory,
+        swap,
+        state.critical,
+    )
+}
+
+// org.rusty.MemMonitor on the session bus, for desktop automation that would
+// rather be told than poll: the AlarmChanged signal, Stats, TakeSnapshot, and
+// the standard introspection zbus provides
+pub struct DbusService {
+    connection: Connection,
+    state: Arc<Mutex<State>>,
+    requests: Receiver<Request>,
+}
+
+impl DbusService {
+    pub fn start() -> zbus::Result<Self> {
+        let state = Arc::new(Mutex::new(State::default()));
+        let (tx, requests) = channel();
+        let service = Service { state: Arc::clone(&state), requests: tx };
+        let connection = ConnectionBuilder::session()?.name(NAME)?.serve_at(PATH, service)?.build()?;
+        Ok(Self { connection, state, requests })
+    }
+
+    pub fn update(&self, latest: &Sample, critical: bool) {
+        *self.state.lock().unwrap() = State { latest: *latest, critical };
+    }
+
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.try_iter().collect()
+    }
+
+    pub fn alarm_changed(&self, transition: Transition, percent: f32) {
+        let sent = SignalContext::new(self.connection.inner(), PATH).and_then(|context| {
+            zbus::block_on(Service::alarm_changed(&context, transition.name(), percent as f64))
+        });
+        if let Err(e) = sent {
+            diag::log(format!("Could not send the D-Bus alarm signal: {}", e));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_leave_missing_totals_at_zero() {
+        let latest = Sample { timestamp_ms: 7, total_memory: 200, used_memory: 50, ..Sample::default() };
+        let state = State { latest, critical: true };
+        assert_eq!(stats(&state), (7, 200, 50, 0, 0, 25.0, 0.0, true));
+    }
+}
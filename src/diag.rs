@@ -0,0 +1,43 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use rusty_mem_monitor::sample;
+
+const MAX_ENTRIES: usize = 200;
+
+static ENTRIES: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+// Problems, logged as warnings. They're also kept in memory so whatever only
+// ever reached the log can still be attached to a bug report.
+pub fn log(message: impl AsRef<str>) {
+    let message = message.as_ref();
+    if tracing::dispatcher::has_been_set() {
+        tracing::warn!("{}", message);
+    } else {
+        // Before logging is set up, e.g. a broken config file
+        eprintln!("{}", message);
+    }
+    remember(message);
+}
+
+// Things that went as intended but are worth having on record: exports, alert
+// commands, mode changes
+pub fn info(message: impl AsRef<str>) {
+    let message = message.as_ref();
+    tracing::info!("{}", message);
+    remember(message);
+}
+
+fn remember(message: &str) {
+    let mut entries = ENTRIES.lock().unwrap_or_else(|e| e.into_inner());
+    entries.push_back(format!("[{}] {}", sample::now_ms(), message));
+    if entries.len() > MAX_ENTRIES {
+        entries.pop_front();
+    }
+}
+
+#[cfg(feature = "gui")]
+pub fn recent(count: usize) -> Vec<String> {
+    let entries = ENTRIES.lock().unwrap_or_else(|e| e.into_inner());
+    entries.iter().rev().take(count).rev().cloned().collect()
+}
@@ -0,0 +1,124 @@
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+const POWERCAP_ROOT: &str = "/sys/class/powercap";
+
+struct Reading {
+    energy_uj: Vec<u64>,
+    at: Instant,
+}
+
+struct Zone {
+    energy: PathBuf,
+    max_range_uj: u64,
+}
+
+// Package energy from Intel/AMD RAPL counters, integrated over the session and
+// split out for the time memory sat above the warning threshold. Kernels since
+// 5.10 make energy_uj root-only, so it often needs a chmod to work.
+pub struct EnergyMeter {
+    zones: Result<Vec<Zone>, String>,
+    last: Option<Reading>,
+    pub watts: Option<f64>,
+    pub total_joules: f64,
+    pub high_memory_joules: f64,
+    pub high_memory_secs: f64,
+}
+
+impl EnergyMeter {
+    pub fn new() -> Self {
+        Self {
+            zones: find_zones(),
+            last: None,
+            watts: None,
+            total_joules: 0.0,
+            high_memory_joules: 0.0,
+            high_memory_secs: 0.0,
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.zones.as_ref().err().map(String::as_str)
+    }
+
+    pub fn update(&mut self, high_memory: bool) {
+        let Ok(zones) = &self.zones else { return };
+        let energy_uj: Option<Vec<u64>> = zones.iter().map(|zone| read_u64(&zone.energy)).collect();
+        let Some(energy_uj) = energy_uj else {
+            self.zones = Err("RAPL counters stopped being readable".to_string());
+            return;
+        };
+        let now = Reading { energy_uj, at: Instant::now() };
+        if let Some(last) = &self.last {
+            let secs = now.at.duration_since(last.at).as_secs_f64();
+            let joules: f64 = zones
+                .iter()
+                .zip(last.energy_uj.iter().zip(&now.energy_uj))
+                .map(|(zone, (&before, &after))| delta_uj(before, after, zone.max_range_uj) as f64 / 1e6)
+                .sum();
+            if secs > 0.0 {
+                self.watts = Some(joules / secs);
+            }
+            self.total_joules += joules;
+            if high_memory {
+                self.high_memory_joules += joules;
+                self.high_memory_secs += secs;
+            }
+        }
+        self.last = Some(now);
+    }
+}
+
+// The counter wraps at max_energy_range_uj
+fn delta_uj(before: u64, after: u64, max_range_uj: u64) -> u64 {
+    if after >= before {
+        after - before
+    } else {
+        max_range_uj.saturating_sub(before) + after
+    }
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+// Top-level packages only (intel-rapl:0, not intel-rapl:0:0), since the
+// subzones are already counted in their package
+fn find_zones() -> Result<Vec<Zone>, String> {
+    if !cfg!(target_os = "linux") {
+        return Err("energy readings need Linux RAPL counters".to_string());
+    }
+    let entries = std::fs::read_dir(POWERCAP_ROOT).map_err(|_| "no RAPL counters on this machine".to_string())?;
+    let mut zones = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(index) = name.strip_prefix("intel-rapl:") else { continue };
+        if index.contains(':') {
+            continue;
+        }
+        let dir = entry.path();
+        let energy = dir.join("energy_uj");
+        if read_u64(&energy).is_none() {
+            return Err(format!("{} isn't readable; try sudo chmod o+r on it", energy.display()));
+        }
+        zones.push(Zone {
+            energy,
+            max_range_uj: read_u64(&dir.join("max_energy_range_uj")).unwrap_or(u64::MAX),
+        });
+    }
+    if zones.is_empty() {
+        return Err("no RAPL counters on this machine".to_string());
+    }
+    Ok(zones)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counter_wraps_at_max_range() {
+        assert_eq!(delta_uj(1_000, 5_000, 10_000), 4_000);
+        assert_eq!(delta_uj(9_000, 500, 10_000), 1_500);
+    }
+}
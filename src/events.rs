@@ -0,0 +1,152 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use rusty_mem_monitor::alert::Thresholds;
+use rusty_mem_monitor::Sample;
+
+const MAX_EVENTS: usize = 500;
+const OOM_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EventKind {
+    Threshold,
+    AlarmStart,
+    AlarmEnd,
+    Snapshot,
+    Oom,
+    Bookmark,
+}
+
+pub struct Event {
+    pub timestamp_ms: u64,
+    pub kind: EventKind,
+    pub message: String,
+}
+
+// Things worth finding again when reviewing a session: they're listed in the
+// EVENTS panel and marked on the plot at the time they happened.
+#[derive(Default)]
+pub struct EventLog {
+    events: VecDeque<Event>,
+    // 0 below the warning threshold, 1 above it, 2 above critical
+    last_level: Option<u8>,
+    oom_kills: Option<u64>,
+    oom_checked: Option<Instant>,
+}
+
+impl EventLog {
+    pub fn push(&mut self, timestamp_ms: u64, kind: EventKind, message: impl Into<String>) {
+        let message = message.into();
+        match kind {
+            EventKind::AlarmStart | EventKind::Oom => tracing::warn!(?kind, timestamp_ms, "{}", message),
+            _ => tracing::info!(?kind, timestamp_ms, "{}", message),
+        }
+        self.events.push_back(Event {
+            timestamp_ms,
+            kind,
+            message,
+        });
+        if self.events.len() > MAX_EVENTS {
+            self.events.pop_front();
+        }
+    }
+
+    // Raw threshold crossings; the critical alarm itself has hysteresis and is
+    // logged separately when it's raised and cleared. Returns true when RAM has
+    // just risen past the warning threshold from below it.
+    pub fn observe(&mut self, sample: &Sample, thresholds: &Thresholds) -> bool {
+        let percentage = sample.memory_percentage();
+        let level = if percentage > thresholds.critical {
+            2
+        } else if percentage > thresholds.warning {
+            1
+        } else {
+            0
+        };
+        let Some(last) = self.last_level.replace(level) else {
+            return false;
+        };
+        let message = if level > last {
+            let threshold = if level == 2 { thresholds.critical } else { thresholds.warning };
+            format!("RAM above {:.0}% ({:.1}%)", threshold, percentage)
+        } else if level < last {
+            let threshold = if level == 1 { thresholds.critical } else { thresholds.warning };
+            format!("RAM back below {:.0}% ({:.1}%)", threshold, percentage)
+        } else {
+            return false;
+        };
+        self.push(sample.timestamp_ms, EventKind::Threshold, message);
+        last == 0 && level > 0
+    }
+
+    // The kernel counts every OOM kill in /proc/vmstat; the first read is the baseline
+    pub fn check_oom(&mut self, timestamp_ms: u64) {
+        if self.oom_checked.is_some_and(|at| at.elapsed() < OOM_CHECK_INTERVAL) {
+            return;
+        }
+        self.oom_checked = Some(Instant::now());
+        let Some(kills) = read_oom_kills() else { return };
+        if let Some(previous) = self.oom_kills.replace(kills) {
+            if kills > previous {
+                self.push(timestamp_ms, EventKind::Oom, format!("OOM killer fired ({} kills)", kills - previous));
+            }
+        }
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Event> {
+        self.events.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+fn read_oom_kills() -> Option<u64> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    let text = std::fs::read_to_string("/proc/vmstat").ok()?;
+    text.lines()
+        .find_map(|line| line.strip_prefix("oom_kill "))
+        .and_then(|count| count.trim().parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(timestamp_ms: u64, used_memory: u64) -> Sample {
+        Sample {
+            timestamp_ms,
+            total_memory: 100,
+            used_memory,
+            ..Sample::default()
+        }
+    }
+
+    #[test]
+    fn logs_threshold_crossings_once() {
+        let mut log = EventLog::default();
+        let mut warned = Vec::new();
+        for (timestamp_ms, used) in [(0, 50), (1, 75), (2, 76), (3, 95), (4, 60)] {
+            if log.observe(&sample(timestamp_ms, used), &Thresholds::default()) {
+                warned.push(timestamp_ms);
+            }
+        }
+        assert_eq!(warned, [1]);
+        let messages: Vec<(u64, &str)> = log.iter().map(|event| (event.timestamp_ms, event.message.as_str())).collect();
+        assert_eq!(
+            messages,
+            [
+                (1, "RAM above 70% (75.0%)"),
+                (3, "RAM above 90% (95.0%)"),
+                (4, "RAM back below 70% (60.0%)"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,137 @@
+//! C ABI over [`Sampler`] and [`History`], built into the cdylib. The matching
+//! declarations are in `include/rusty_mem_monitor.h`.
+
+use crate::{History, Sample, Sampler};
+
+/// [`Sample`] with a fixed C layout.
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct RmmSample {
+    pub timestamp_ms: u64,
+    pub total_memory: u64,
+    pub used_memory: u64,
+    pub used_memory_min: u64,
+    pub used_memory_max: u64,
+    pub total_swap: u64,
+    pub used_swap: u64,
+    pub free_memory: u64,
+    pub buffers_memory: u64,
+    pub compressed_memory: u64,
+    pub cgroup_limited: bool,
+}
+
+impl From<&Sample> for RmmSample {
+    fn from(sample: &Sample) -> Self {
+        Self {
+            timestamp_ms: sample.timestamp_ms,
+            total_memory: sample.total_memory,
+            used_memory: sample.used_memory,
+            used_memory_min: sample.used_memory_min,
+            used_memory_max: sample.used_memory_max,
+            total_swap: sample.total_swap,
+            used_swap: sample.used_swap,
+            free_memory: sample.free_memory,
+            buffers_memory: sample.buffers_memory,
+            compressed_memory: sample.compressed_memory,
+            cgroup_limited: sample.cgroup_limited,
+        }
+    }
+}
+
+/// Opaque to C: a sampler and the history of everything it sampled.
+pub struct RmmMonitor {
+    sampler: Sampler,
+    history: History,
+}
+
+/// Creates a monitor keeping the last `capacity` samples. Free it with [`rmm_free`].
+#[no_mangle]
+pub extern "C" fn rmm_new(capacity: usize) -> *mut RmmMonitor {
+    Box::into_raw(Box::new(RmmMonitor {
+        sampler: Sampler::new(),
+        history: History::new(capacity),
+    }))
+}
+
+/// # Safety
+/// `monitor` must come from [`rmm_new`] and not be used afterwards. Null is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn rmm_free(monitor: *mut RmmMonitor) {
+    if !monitor.is_null() {
+        drop(Box::from_raw(monitor));
+    }
+}
+
+/// Takes a sample, appends it to the history and writes it to `out` unless that's null.
+/// Returns false if `monitor` is null.
+///
+/// # Safety
+/// `monitor` must be null or come from [`rmm_new`]; `out` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn rmm_sample(monitor: *mut RmmMonitor, out: *mut RmmSample) -> bool {
+    let Some(monitor) = monitor.as_mut() else {
+        return false;
+    };
+    let sample = monitor.sampler.sample();
+    monitor.history.push(&sample);
+    if let Some(out) = out.as_mut() {
+        *out = RmmSample::from(&sample);
+    }
+    true
+}
+
+/// # Safety
+/// `monitor` must be null or come from [`rmm_new`].
+#[no_mangle]
+pub unsafe extern "C" fn rmm_history_len(monitor: *const RmmMonitor) -> usize {
+    monitor.as_ref().map_or(0, |monitor| monitor.history.len())
+}
+
+/// Copies up to `max` samples, oldest first, starting `start` samples into the
+/// history. Returns how many were written.
+///
+/// # Safety
+/// `monitor` must be null or come from [`rmm_new`]; `out` must have room for `max` samples.
+#[no_mangle]
+pub unsafe extern "C" fn rmm_history_range(
+    monitor: *const RmmMonitor,
+    start: usize,
+    out: *mut RmmSample,
+    max: usize,
+) -> usize {
+    let Some(monitor) = monitor.as_ref() else {
+        return 0;
+    };
+    if out.is_null() {
+        return 0;
+    }
+    let out = std::slice::from_raw_parts_mut(out, max);
+    let mut written = 0;
+    for (slot, sample) in out.iter_mut().zip(monitor.history.iter().skip(start)) {
+        *slot = RmmSample::from(sample);
+        written += 1;
+    }
+    written
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_range_is_clipped() {
+        let monitor = rmm_new(3);
+        let mut out = [RmmSample::default(); 4];
+        unsafe {
+            for _ in 0..5 {
+                assert!(rmm_sample(monitor, std::ptr::null_mut()));
+            }
+            assert_eq!(rmm_history_len(monitor), 3);
+            assert_eq!(rmm_history_range(monitor, 1, out.as_mut_ptr(), out.len()), 2);
+            assert_eq!(rmm_history_range(monitor, 0, out.as_mut_ptr(), 1), 1);
+            assert_eq!(rmm_history_range(std::ptr::null(), 0, out.as_mut_ptr(), 4), 0);
+            rmm_free(monitor);
+        }
+        assert!(out[0].total_memory > 0);
+    }
+}
@@ -0,0 +1,241 @@
+use eframe::egui;
+use egui_plot::{Bar, BarChart, Legend, Line, Plot};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, PidExt, ProcessExt, System, SystemExt};
+
+use rusty_mem_monitor::sample::now_ms;
+use rusty_mem_monitor::units::{format_bytes, format_bytes_delta};
+
+use crate::diag;
+use crate::i18n::{tr, tr_args};
+use crate::smaps::{self, MemoryMap};
+use crate::theme::Theme;
+
+const FOCUS_HISTORY_LEN: usize = 240;
+const PLOT_HEIGHT: f32 = 140.0;
+const MAP_REFRESH: Duration = Duration::from_secs(5);
+const MAP_CHART_HEIGHT: f32 = 120.0;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum FocusMetric {
+    Rss,
+    Virtual,
+    Share,
+}
+
+#[derive(Clone, Copy)]
+struct FocusPoint {
+    timestamp_ms: u64,
+    rss: u64,
+    virtual_memory: u64,
+    // Percent of total system memory
+    share: f64,
+}
+
+// Follows one process, chosen with --pid or from the process table, so an app
+// developer can watch their own program instead of the whole machine.
+pub struct ProcessFocus {
+    pub pid: u32,
+    name: String,
+    points: VecDeque<FocusPoint>,
+    baseline_rss: Option<u64>,
+    growth_alert: bool,
+    exited: bool,
+    metric: FocusMetric,
+    map: Option<Result<MemoryMap, String>>,
+    map_read: Option<Instant>,
+}
+
+impl ProcessFocus {
+    pub fn new(pid: u32) -> Self {
+        Self {
+            pid,
+            name: String::new(),
+            points: VecDeque::with_capacity(FOCUS_HISTORY_LEN),
+            baseline_rss: None,
+            growth_alert: false,
+            exited: false,
+            metric: FocusMetric::Rss,
+            map: None,
+            map_read: None,
+        }
+    }
+
+    // Only this one process is refreshed, which is cheap enough to do every frame
+    pub fn update(&mut self, sys: &mut System, total_memory: u64, growth_alert_percent: f64) {
+        if self.exited {
+            return;
+        }
+        let pid = Pid::from_u32(self.pid);
+        if !sys.refresh_process(pid) {
+            self.exited = true;
+            diag::info(format!("Focused process {} exited", self.pid));
+            return;
+        }
+        let Some(process) = sys.process(pid) else { return };
+        if self.name.is_empty() {
+            self.name = process.name().to_string();
+        }
+
+        let rss = process.memory();
+        self.points.push_back(FocusPoint {
+            timestamp_ms: now_ms(),
+            rss,
+            virtual_memory: process.virtual_memory(),
+            share: rss as f64 / total_memory.max(1) as f64 * 100.0,
+        });
+        if self.points.len() > FOCUS_HISTORY_LEN {
+            self.points.pop_front();
+        }
+
+        let baseline = *self.baseline_rss.get_or_insert(rss);
+        let growing = baseline > 0 && self.growth_percent(rss, baseline) > growth_alert_percent;
+        if growing && !self.growth_alert {
+            diag::log(format!(
+                "{} (pid {}) grew {:.0}% since focus started, now {}",
+                self.name,
+                self.pid,
+                self.growth_percent(rss, baseline),
+                format_bytes(rss),
+            ));
+        }
+        self.growth_alert = growing;
+    }
+
+    fn growth_percent(&self, rss: u64, baseline: u64) -> f64 {
+        (rss as f64 - baseline as f64) / baseline as f64 * 100.0
+    }
+
+    // Returns false once the user asked to stop focusing
+    pub fn ui(&mut self, ui: &mut egui::Ui, theme: &Theme) -> bool {
+        let mut keep = true;
+        ui.horizontal(|ui| {
+            let title = if self.name.is_empty() {
+                tr_args("focus-pid", &[("pid", &self.pid)])
+            } else {
+                tr_args("focus-title", &[("name", &self.name), ("pid", &self.pid)])
+            };
+            ui.label(egui::RichText::new(title).color(theme.info).monospace().strong());
+            if ui.small_button(tr("button-unfocus")).clicked() {
+                keep = false;
+            }
+        });
+
+        if self.exited {
+            ui.label(egui::RichText::new(tr("focus-exited")).color(theme.dim).monospace());
+        }
+        let (Some(first), Some(latest)) = (self.points.front(), self.points.back()) else {
+            return keep;
+        };
+
+        let growth_color = if self.growth_alert { theme.critical } else { theme.primary };
+        ui.label(
+            egui::RichText::new(tr_args("focus-readout", &[
+                ("rss", &format_bytes(latest.rss)),
+                ("virtual", &format_bytes(latest.virtual_memory)),
+                ("share", &format!("{:.2}%", latest.share)),
+            ]))
+            .color(theme.primary)
+            .monospace()
+        );
+        ui.label(
+            egui::RichText::new(tr_args("focus-growth", &[
+                ("bytes", &format_bytes_delta(latest.rss as i64 - first.rss as i64)),
+            ]))
+            .color(growth_color)
+            .monospace()
+        );
+
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.metric, FocusMetric::Rss, tr("focus-rss"));
+            ui.selectable_value(&mut self.metric, FocusMetric::Virtual, tr("focus-virtual"));
+            ui.selectable_value(&mut self.metric, FocusMetric::Share, tr("focus-share"));
+        });
+
+        let latest_ms = latest.timestamp_ms;
+        let metric = self.metric;
+        let points: Vec<[f64; 2]> = self.points
+            .iter()
+            .map(|point| {
+                let y = match metric {
+                    FocusMetric::Rss => point.rss as f64,
+                    FocusMetric::Virtual => point.virtual_memory as f64,
+                    FocusMetric::Share => point.share,
+                };
+                [(point.timestamp_ms as f64 - latest_ms as f64) / 1000.0, y]
+            })
+            .collect();
+        Plot::new("focus_plot")
+            .height(PLOT_HEIGHT)
+            .show_background(false)
+            .include_y(0.0)
+            .x_axis_formatter(|x, _, _| format!("{:.0}s", x))
+            .y_axis_formatter(move |y, _, _| match metric {
+                FocusMetric::Share => format!("{:.1}%", y),
+                _ => format_bytes(y.max(0.0) as u64),
+            })
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new(points).color(growth_color).width(2.0));
+            });
+
+        if cfg!(target_os = "linux") {
+            egui::CollapsingHeader::new(egui::RichText::new(tr("focus-memory-map")).color(theme.info).monospace())
+                .default_open(false)
+                .show(ui, |ui| self.map_ui(ui, theme));
+        }
+        keep
+    }
+
+    // smaps is read only while this section is open; it walks every mapping
+    fn map_ui(&mut self, ui: &mut egui::Ui, theme: &Theme) {
+        let stale = self.map_read.is_none_or(|at| at.elapsed() >= MAP_REFRESH);
+        if stale && !self.exited {
+            self.map = Some(smaps::read(self.pid));
+            self.map_read = Some(Instant::now());
+        }
+        let map = match &self.map {
+            Some(Ok(map)) => *map,
+            Some(Err(e)) => {
+                let text = tr_args("focus-map-unreadable", &[("error", e)]);
+                ui.label(egui::RichText::new(text).color(theme.dim).monospace());
+                return;
+            }
+            None => return,
+        };
+
+        let colors = [theme.primary, theme.info, theme.warning, theme.buffers, theme.free, theme.swap];
+        let charts: Vec<BarChart> = map.bands()
+            .iter()
+            .zip(colors)
+            .enumerate()
+            .map(|(i, ((name, bytes), color))| {
+                BarChart::new(vec![Bar::new(-(i as f64), *bytes as f64).width(0.7)])
+                    .horizontal()
+                    .color(color)
+                    .name(format!("{} {}", name, format_bytes(*bytes)))
+            })
+            .collect();
+        Plot::new("focus_map")
+            .height(MAP_CHART_HEIGHT)
+            .show_background(false)
+            .show_y(false)
+            .show_axes([true, false])
+            .allow_zoom(false)
+            .allow_drag(false)
+            .allow_scroll(false)
+            .include_x(0.0)
+            .legend(Legend::default())
+            .x_axis_formatter(|x, _, _| format_bytes(x.max(0.0) as u64))
+            .show(ui, |plot_ui| {
+                for chart in charts {
+                    plot_ui.bar_chart(chart);
+                }
+            });
+        ui.label(
+            egui::RichText::new(tr_args("focus-map-rss", &[("bytes", &format_bytes(map.rss))]))
+                .color(theme.dim)
+                .monospace()
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use crate::history::History;
+use crate::stats;
+
+const MB: f64 = 1024.0 * 1024.0;
+
+/// One ingredient of the [`Health`] score: how many of its points it lost and
+/// what the reading behind that was.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Factor {
+    pub name: &'static str,
+    pub weight: f64,
+    pub penalty: f64,
+    pub detail: String,
+}
+
+/// A 0–100 memory health figure, 100 being a machine with room to spare.
+/// Utilization, pressure, swap activity and trend each take up to their weight
+/// in points off; `factors` says which did.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Health {
+    pub score: u8,
+    pub factors: Vec<Factor>,
+}
+
+impl Health {
+    /// `pressure` is the share of time, in percent, that tasks stalled waiting
+    /// for memory (Linux PSI "some avg10"), or None where there's no such figure.
+    pub fn assess(history: &History, pressure: Option<f64>) -> Option<Self> {
+        let latest = history.last().filter(|sample| sample.has_memory())?;
+        let used = latest.memory_percentage() as f64;
+
+        // Nothing lost up to 60%; all of it by 100%
+        let utilization = Factor {
+            name: "Utilization",
+            weight: 40.0,
+            penalty: 40.0 * ((used - 60.0) / 40.0).clamp(0.0, 1.0),
+            detail: format!("{:.1}% of RAM in use", used),
+        };
+
+        // A quarter of the time stalled is as bad as it gets
+        let pressure = Factor {
+            name: "Pressure",
+            weight: 25.0,
+            penalty: pressure.map_or(0.0, |stalled| 25.0 * (stalled / 25.0).clamp(0.0, 1.0)),
+            detail: match pressure {
+                Some(stalled) => format!("stalled {:.1}%", stalled),
+                None => "not available".to_string(),
+            },
+        };
+
+        // Pages moving to or from swap, from how much used swap changed between
+        // samples; 100 MB a minute loses everything
+        let minutes = history
+            .first()
+            .map_or(0.0, |first| latest.timestamp_ms.saturating_sub(first.timestamp_ms) as f64 / 60_000.0);
+        let samples: Vec<_> = history.iter().collect();
+        let moved: u64 = samples.windows(2).map(|pair| pair[0].used_swap.abs_diff(pair[1].used_swap)).sum();
+        let swapped_per_minute = if minutes > 0.0 { moved as f64 / MB / minutes } else { 0.0 };
+        let swap = Factor {
+            name: "Swap activity",
+            weight: 20.0,
+            penalty: 20.0 * (swapped_per_minute / 100.0).clamp(0.0, 1.0),
+            detail: format!("{:.1} MB/min", swapped_per_minute),
+        };
+
+        // How long until the free RAM is gone at the current growth; under an
+        // hour starts losing points
+        let available = latest.total_memory.saturating_sub(latest.used_memory) as f64;
+        let growth = stats::growth_per_minute(history).filter(|growth| *growth > 0.0);
+        let trend = Factor {
+            name: "Trend",
+            weight: 15.0,
+            penalty: growth.map_or(0.0, |growth| 15.0 * (1.0 - available / growth / 60.0).clamp(0.0, 1.0)),
+            detail: match growth {
+                Some(growth) => format!("+{:.1} MB/min, full in {:.0} min", growth / MB, available / growth),
+                None => "not growing".to_string(),
+            },
+        };
+
+        let factors = vec![utilization, pressure, swap, trend];
+        let lost: f64 = factors.iter().map(|factor| factor.penalty).sum();
+        Some(Self { score: (100.0 - lost).round().clamp(0.0, 100.0) as u8, factors })
+    }
+}
+
+/// The memory "some avg10" figure from /proc/pressure/memory; None off Linux
+/// and on kernels built without PSI.
+pub fn read_pressure() -> Option<f64> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    parse_pressure(&std::fs::read_to_string("/proc/pressure/memory").ok()?)
+}
+
+pub fn parse_pressure(text: &str) -> Option<f64> {
+    let some = text.lines().find(|line| line.starts_with("some "))?;
+    let avg10 = some.split_whitespace().find_map(|field| field.strip_prefix("avg10="))?;
+    avg10.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample::Sample;
+
+    #[test]
+    fn score_loses_points_per_factor() {
+        let gb = 1024 * 1024 * 1024;
+        let sample = |secs: u64, used: u64, swap: u64| Sample {
+            timestamp_ms: secs * 1000,
+            total_memory: 10 * gb,
+            used_memory: used,
+            total_swap: gb,
+            used_swap: swap,
+            ..Sample::default()
+        };
+        let mut history = History::new(10);
+        history.push(&sample(0, 5 * gb, 0));
+        history.push(&sample(60, 5 * gb, 0));
+        assert_eq!(Health::assess(&history, None).unwrap().score, 100);
+
+        let mut history = History::new(10);
+        history.push(&sample(0, 8 * gb, 0));
+        history.push(&sample(60, 8 * gb, 0));
+        let health = Health::assess(&history, Some(12.5)).unwrap();
+        assert_eq!(health.factors[0].penalty, 20.0);
+        assert_eq!(health.factors[1].penalty, 12.5);
+        assert_eq!(health.score, 68);
+
+        assert_eq!(parse_pressure("some avg10=1.50 avg60=0.20 avg300=0.00 total=1\nfull avg10=0.00"), Some(1.5));
+        assert!(Health::assess(&History::new(10), None).is_none());
+    }
+}
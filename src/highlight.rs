@@ -0,0 +1,133 @@
+use eframe::egui::Color32;
+use regex::Regex;
+
+use crate::config::HighlightRule;
+use crate::processes::ProcessRow;
+use crate::theme;
+
+const NAMED_COLORS: [(&str, [u8; 3]); 11] = [
+    ("red", [255, 60, 60]),
+    ("orange", [255, 150, 0]),
+    ("yellow", [255, 230, 0]),
+    ("green", [0, 220, 0]),
+    ("cyan", [0, 230, 230]),
+    ("blue", [70, 130, 255]),
+    ("purple", [170, 90, 255]),
+    ("magenta", [255, 0, 255]),
+    ("pink", [255, 120, 190]),
+    ("white", [255, 255, 255]),
+    ("gray", [150, 150, 150]),
+];
+
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct Highlight {
+    pub color: Option<Color32>,
+    pub bold: bool,
+}
+
+struct Rule {
+    pattern: Option<Regex>,
+    color: Option<Color32>,
+    bold: bool,
+    min_memory: u64,
+}
+
+// The [[processes.highlight]] rules, compiled once. A row takes its color from
+// the first matching rule that sets one, and is bold if any matching rule says so.
+#[derive(Default)]
+pub struct Highlighter {
+    rules: Vec<Rule>,
+    // Index of the rule and what's wrong with it; those rules are skipped
+    pub errors: Vec<(usize, String)>,
+}
+
+impl Highlighter {
+    pub fn new(rules: &[HighlightRule]) -> Self {
+        let mut highlighter = Self::default();
+        for (index, rule) in rules.iter().enumerate() {
+            let pattern = match rule.pattern.trim() {
+                "" => None,
+                pattern => match Regex::new(pattern) {
+                    Ok(regex) => Some(regex),
+                    Err(e) => {
+                        highlighter.errors.push((index, e.to_string()));
+                        continue;
+                    }
+                },
+            };
+            let color = match rule.color.trim() {
+                "" => None,
+                color => match parse_color(color) {
+                    Some(color) => Some(color),
+                    None => {
+                        highlighter.errors.push((index, format!("unknown color {:?}", color)));
+                        continue;
+                    }
+                },
+            };
+            highlighter.rules.push(Rule {
+                pattern,
+                color,
+                bold: rule.bold,
+                min_memory: rule.min_memory_mb * 1024 * 1024,
+            });
+        }
+        highlighter
+    }
+
+    pub fn style(&self, row: &ProcessRow) -> Highlight {
+        let mut highlight = Highlight::default();
+        for rule in &self.rules {
+            let name_matches = rule.pattern.as_ref().is_none_or(|pattern| pattern.is_match(&row.name));
+            if name_matches && row.memory >= rule.min_memory {
+                highlight.color = highlight.color.or(rule.color);
+                highlight.bold |= rule.bold;
+            }
+        }
+        highlight
+    }
+}
+
+fn parse_color(text: &str) -> Option<Color32> {
+    let named = NAMED_COLORS.iter().find(|(name, _)| name.eq_ignore_ascii_case(text));
+    named.map(|(_, [r, g, b])| Color32::from_rgb(*r, *g, *b)).or_else(|| theme::parse_hex(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, color: &str, bold: bool, min_memory_mb: u64) -> HighlightRule {
+        HighlightRule {
+            pattern: pattern.to_string(),
+            color: color.to_string(),
+            bold,
+            min_memory_mb,
+        }
+    }
+
+    fn row(name: &str, memory_mb: u64) -> ProcessRow {
+        ProcessRow {
+            name: name.to_string(),
+            memory: memory_mb * 1024 * 1024,
+            ..ProcessRow::default()
+        }
+    }
+
+    #[test]
+    fn first_color_wins_and_bold_adds_up() {
+        let highlighter = Highlighter::new(&[
+            rule("^java", "orange", false, 0),
+            rule("", "#ff0000", true, 2048),
+            rule("(", "red", false, 0),
+            rule("x", "chartreuse-ish", false, 0),
+        ]);
+        let orange = Some(Color32::from_rgb(255, 150, 0));
+        assert_eq!(highlighter.errors.iter().map(|(index, _)| *index).collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(highlighter.style(&row("java", 100)), Highlight { color: orange, bold: false });
+        assert_eq!(highlighter.style(&row("java", 4096)), Highlight { color: orange, bold: true });
+        let red = Some(Color32::from_rgb(255, 0, 0));
+        assert_eq!(highlighter.style(&row("firefox", 4096)), Highlight { color: red, bold: true });
+        assert_eq!(highlighter.style(&row("bash", 10)), Highlight::default());
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::VecDeque;
+
+use crate::sample::Sample;
+
+/// Fixed-capacity ring of the most recent samples; the oldest is dropped on overflow.
+/// Samples are kept as raw byte counts, so percentages are only derived for display.
+pub struct History {
+    samples: VecDeque<Sample>,
+    capacity: usize,
+}
+
+impl History {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, sample: &Sample) {
+        self.samples.push_back(*sample);
+        while self.samples.len() > self.capacity {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Sample> + ExactSizeIterator {
+        self.samples.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Changes how many samples are kept, dropping the oldest if there are now too many.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.samples.len() > capacity {
+            self.samples.pop_front();
+        }
+        self.samples.shrink_to(capacity);
+    }
+
+    pub fn first(&self) -> Option<&Sample> {
+        self.samples.front()
+    }
+
+    pub fn last(&self) -> Option<&Sample> {
+        self.samples.back()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(timestamp_ms: u64, used_memory: u64) -> Sample {
+        Sample {
+            timestamp_ms,
+            total_memory: 100,
+            used_memory,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn drops_oldest_beyond_capacity() {
+        let mut history = History::new(3);
+        for t in 0..5 {
+            history.push(&sample(t, t * 10));
+        }
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.first().unwrap().timestamp_ms, 2);
+        assert_eq!(history.last().unwrap().memory_percentage(), 40.0);
+        let timestamps: Vec<u64> = history.iter().map(|sample| sample.timestamp_ms).collect();
+        assert_eq!(timestamps, [2, 3, 4]);
+
+        history.set_capacity(2);
+        assert_eq!(history.first().unwrap().timestamp_ms, 3);
+        history.push(&sample(5, 50));
+        assert_eq!(history.len(), 2);
+    }
+
+    #[test]
+    fn keeps_terabyte_counts_exact() {
+        let total = 24 * 1024u64.pow(4);
+        let mut history = History::new(2);
+        history.push(&Sample {
+            total_memory: total,
+            used_memory: total - 1,
+            ..Default::default()
+        });
+        let kept = history.last().unwrap();
+        assert_eq!(kept.total_memory - kept.used_memory, 1);
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use crate::meminfo;
+
+const THP_ROOT: &str = "/sys/kernel/mm/transparent_hugepage";
+
+/// The static hugepage pool and transparent hugepage usage. Pool pages are taken
+/// out of "available" as soon as they're reserved, so a host with a big pool
+/// looks far more used than anything running on it explains.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HugePages {
+    /// Size of one page in the default pool, in bytes.
+    pub page_size: u64,
+    /// Page counts in the default pool. `reserved` pages are promised to a
+    /// mapping but not faulted in yet; `surplus` pages were allocated beyond the
+    /// pool's size through overcommit.
+    pub total: u64,
+    pub free: u64,
+    pub reserved: u64,
+    pub surplus: u64,
+    /// Bytes in every hugetlb pool, all page sizes together (`Hugetlb`, Linux 4.16+).
+    pub hugetlb: u64,
+    /// Bytes backed by transparent hugepages.
+    pub anon_thp: u64,
+    pub shmem_thp: u64,
+    pub file_thp: u64,
+    /// The selected `enabled` and `defrag` THP modes, e.g. `madvise`.
+    pub thp_enabled: Option<String>,
+    pub thp_defrag: Option<String>,
+}
+
+impl HugePages {
+    /// Linux only.
+    pub fn read() -> Option<Self> {
+        let mut pages = Self::from_meminfo(&meminfo::read()?);
+        pages.thp_enabled = read_mode("enabled");
+        pages.thp_defrag = read_mode("defrag");
+        Some(pages)
+    }
+
+    pub fn from_meminfo(info: &HashMap<String, u64>) -> Self {
+        let field = |name: &str| info.get(name).copied().unwrap_or(0);
+        let page_size = field("Hugepagesize");
+        let total = field("HugePages_Total");
+        Self {
+            page_size,
+            total,
+            free: field("HugePages_Free"),
+            reserved: field("HugePages_Rsvd"),
+            surplus: field("HugePages_Surp"),
+            hugetlb: info.get("Hugetlb").copied().unwrap_or(total * page_size),
+            anon_thp: field("AnonHugePages"),
+            shmem_thp: field("ShmemHugePages"),
+            file_thp: field("FileHugePages"),
+            thp_enabled: None,
+            thp_defrag: None,
+        }
+    }
+
+    /// Bytes of the default pool that are mapped by something.
+    pub fn pool_in_use(&self) -> u64 {
+        self.total.saturating_sub(self.free) * self.page_size
+    }
+
+    pub fn thp_bytes(&self) -> u64 {
+        self.anon_thp + self.shmem_thp + self.file_thp
+    }
+}
+
+fn read_mode(name: &str) -> Option<String> {
+    selected_mode(&std::fs::read_to_string(format!("{}/{}", THP_ROOT, name)).ok()?)
+}
+
+/// The bracketed choice in a sysfs mode list such as `always [madvise] never`.
+pub fn selected_mode(text: &str) -> Option<String> {
+    let start = text.find('[')? + 1;
+    let end = start + text[start..].find(']')?;
+    Some(text[start..end].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_pool_and_thp() {
+        let info = meminfo::parse(
+            "AnonHugePages:    204800 kB\n\
+             ShmemHugePages:        0 kB\n\
+             HugePages_Total:     512\n\
+             HugePages_Free:      384\n\
+             HugePages_Rsvd:       16\n\
+             HugePages_Surp:        0\n\
+             Hugepagesize:       2048 kB\n\
+             Hugetlb:         1048576 kB\n",
+        );
+        let pages = HugePages::from_meminfo(&info);
+        assert_eq!(pages.total, 512);
+        assert_eq!(pages.reserved, 16);
+        assert_eq!(pages.page_size, 2 * 1024 * 1024);
+        assert_eq!(pages.pool_in_use(), 128 * 2 * 1024 * 1024);
+        assert_eq!(pages.hugetlb, 1024 * 1024 * 1024);
+        assert_eq!(pages.thp_bytes(), 200 * 1024 * 1024);
+        assert_eq!(selected_mode("always [madvise] never\n").as_deref(), Some("madvise"));
+        assert_eq!(selected_mode("always madvise never"), None);
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::RwLock;
+
+use crate::{config, diag};
+
+// Catalogs compiled in. More languages are added by dropping `<code>.ftl` into
+// the config dir's locales/ folder; a file there also overrides a built-in one
+// message by message, so a translation can be fixed without a rebuild.
+const BUILT_IN: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.ftl")),
+    ("de", include_str!("../locales/de.ftl")),
+];
+const FALLBACK: &str = "en";
+
+static CATALOG: RwLock<Option<Catalog>> = RwLock::new(None);
+
+struct Catalog {
+    messages: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+// Picks the language for every later `tr`: `language` if set, else the system
+// locale, else English. User catalogs are skipped in safe mode.
+pub fn init(language: &str, user_catalogs: bool) {
+    let wanted = if language.is_empty() { system_language() } else { Some(language.to_string()) };
+    let available = languages(user_catalogs);
+    let language = wanted.filter(|wanted| available.contains(wanted)).unwrap_or_else(|| FALLBACK.to_string());
+    let catalog = Catalog {
+        messages: load(&language, user_catalogs),
+        fallback: load(FALLBACK, user_catalogs),
+    };
+    *CATALOG.write().unwrap_or_else(|e| e.into_inner()) = Some(catalog);
+}
+
+// Language codes with a catalog, built-in ones first
+pub fn languages(user_catalogs: bool) -> Vec<String> {
+    let mut languages: Vec<String> = BUILT_IN.iter().map(|(code, _)| code.to_string()).collect();
+    if !user_catalogs {
+        return languages;
+    }
+    let Some(entries) = config::config_dir().and_then(|dir| std::fs::read_dir(dir.join("locales")).ok()) else {
+        return languages;
+    };
+    let mut extra: Vec<String> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ftl"))
+        .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+        .filter(|code| !languages.contains(code))
+        .collect();
+    extra.sort();
+    languages.extend(extra);
+    languages
+}
+
+pub fn tr(key: &str) -> String {
+    tr_args(key, &[])
+}
+
+// Fills `{ $name }` placeholders. A message missing from the catalog comes from
+// English, and one missing there too shows its key, so gaps are easy to spot.
+pub fn tr_args(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    let catalog = CATALOG.read().unwrap_or_else(|e| e.into_inner());
+    let message = catalog.as_ref().and_then(|catalog| {
+        catalog.messages.get(key).or_else(|| catalog.fallback.get(key))
+    });
+    let Some(message) = message else {
+        return key.to_string();
+    };
+    let mut text = message.clone();
+    for (name, value) in args {
+        text = text.replace(&format!("{{ ${} }}", name), &value.to_string());
+    }
+    text
+}
+
+fn load(language: &str, user_catalogs: bool) -> HashMap<String, String> {
+    let mut messages = BUILT_IN
+        .iter()
+        .find(|(code, _)| *code == language)
+        .map_or_else(HashMap::new, |(_, text)| parse(text));
+    let path = config::config_dir().map(|dir| dir.join("locales").join(format!("{}.ftl", language)));
+    if let Some(path) = path.filter(|path| user_catalogs && path.exists()) {
+        match std::fs::read_to_string(&path) {
+            Ok(text) => messages.extend(parse(&text)),
+            Err(e) => diag::log(format!("Could not read {}: {}", path.display(), e)),
+        }
+    }
+    messages
+}
+
+// The subset of Fluent the catalogs use: `key = value`, `#` comments, and
+// indented lines continuing the previous value on a new line
+fn parse(text: &str) -> HashMap<String, String> {
+    let mut messages: HashMap<String, String> = HashMap::new();
+    let mut last: Option<String> = None;
+    for line in text.lines() {
+        if line.trim_start().starts_with('#') || line.trim().is_empty() {
+            last = None;
+            continue;
+        }
+        if line.starts_with([' ', '\t']) {
+            if let Some(value) = last.as_ref().and_then(|key| messages.get_mut(key)) {
+                if !value.is_empty() {
+                    value.push('\n');
+                }
+                value.push_str(line.trim());
+            }
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            let key = key.trim().to_string();
+            messages.insert(key.clone(), value.trim().to_string());
+            last = Some(key);
+        }
+    }
+    messages
+}
+
+// POSIX locale variables, in their order of precedence: "de_AT.UTF-8" gives "de"
+pub fn system_language() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG", "LANGUAGE"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| {
+            let code: String = value.chars().take_while(|c| c.is_ascii_alphabetic()).collect();
+            (!code.is_empty() && code != "C" && code != "POSIX").then(|| code.to_lowercase())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalogs_parse_and_cover_english() {
+        let messages = parse("# comment\ntitle = MEMORY MONITOR\nhelp =\n    first line\n    second { $n }\n");
+        assert_eq!(messages["title"], "MEMORY MONITOR");
+        assert_eq!(messages["help"], "first line\nsecond { $n }");
+
+        let english = parse(BUILT_IN[0].1);
+        for (code, text) in BUILT_IN {
+            let messages = parse(text);
+            let missing: Vec<_> = english.keys().filter(|key| !messages.contains_key(*key)).collect();
+            assert!(missing.is_empty(), "{} lacks {:?}", code, missing);
+        }
+
+        init("de", false);
+        assert_eq!(tr_args("memory-usage", &[("percent", &"42.0%")]), "Speicherauslastung: 42.0%");
+        assert_eq!(tr("no-such-message"), "no-such-message");
+        init("xx", false);
+        assert_eq!(tr("title"), "MEMORY MONITOR");
+    }
+}
@@ -0,0 +1,259 @@
+use std::net::UdpSocket;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::time::{Duration, Instant};
+
+use rusty_mem_monitor::Sample;
+
+use crate::config::InfluxConfig;
+use crate::diag;
+use crate::outbox::{Delivery, Outbox};
+
+// Influx's UDP listener takes a datagram at a time; staying under a typical MTU
+// keeps batches from being fragmented or dropped along the way
+const DATAGRAM_BYTES: usize = 1400;
+const MAX_RETRY: Duration = Duration::from_secs(300);
+const OUTBOX: &str = "influx";
+
+// Pushes samples to InfluxDB so the monitor doubles as a collector: the v2 HTTP
+// write API for an http(s):// url, or line protocol datagrams for udp://. Lines
+// are batched on a thread of their own. A batch that fails goes to the outbox,
+// so it outlives a restart, and is retried with backoff ahead of newer ones, up
+// to about `max_buffer` lines with the oldest dropped first.
+pub struct InfluxSink {
+    tx: Sender<Sample>,
+}
+
+impl InfluxSink {
+    pub fn start(config: &InfluxConfig, host: String) -> Option<Self> {
+        if !config.enabled || config.url.is_empty() {
+            return None;
+        }
+        let (tx, rx) = channel();
+        let config = config.clone();
+        let batches = (config.max_buffer / config.batch_size.max(1)).max(1);
+        let outbox = Outbox::open(OUTBOX, batches);
+        let spawned = std::thread::Builder::new()
+            .name("influx".to_string())
+            .spawn(move || run(rx, &config, &host, outbox.as_ref()));
+        if let Err(e) = spawned {
+            diag::log(format!("Could not start the InfluxDB sink: {}", e));
+            return None;
+        }
+        Some(Self { tx })
+    }
+
+    pub fn push(&self, sample: &Sample) {
+        let _ = self.tx.send(*sample);
+    }
+}
+
+fn run(rx: Receiver<Sample>, config: &InfluxConfig, host: &str, outbox: Option<&Outbox>) {
+    let flush_interval = Duration::from_secs(config.flush_secs.max(1));
+    let mut pending: Vec<String> = Vec::new();
+    let mut flushed = Instant::now();
+    let mut failures = 0u32;
+    let mut retry_at: Option<Instant> = None;
+    // A previous run may have left batches behind
+    let mut queued = outbox.is_some();
+    loop {
+        let closing = match rx.recv_timeout(flush_interval) {
+            Ok(sample) => {
+                pending.extend(line(&config.measurement, host, &sample));
+                false
+            }
+            Err(RecvTimeoutError::Timeout) => false,
+            // The sampler's gone; one last try, and what's left stays queued
+            Err(RecvTimeoutError::Disconnected) => true,
+        };
+        let due = closing || pending.len() >= config.batch_size.max(1) || flushed.elapsed() >= flush_interval;
+        if !due || (pending.is_empty() && !queued) {
+            if closing {
+                return;
+            }
+            continue;
+        }
+        flushed = Instant::now();
+        let batch = std::mem::take(&mut pending).join("\n");
+        if retry_at.is_some_and(|at| Instant::now() < at) {
+            queued |= keep(outbox, config, batch);
+            if closing {
+                return;
+            }
+            continue;
+        }
+        // The queue goes first, so InfluxDB gets the samples in order
+        let sent = send_queued(outbox, config).and_then(|()| write(config, &batch));
+        match sent {
+            Ok(()) => {
+                if failures > 0 {
+                    diag::info("InfluxDB writes recovered and the queued samples are sent");
+                }
+                queued = false;
+                failures = 0;
+                retry_at = None;
+            }
+            Err(e) => {
+                queued |= keep(outbox, config, batch);
+                let delay = flush_interval.saturating_mul(1 << failures.min(16)).min(MAX_RETRY);
+                diag::log(format!("InfluxDB write failed, retrying in {}s: {}", delay.as_secs(), e));
+                failures = failures.saturating_add(1);
+                retry_at = Some(Instant::now() + delay);
+            }
+        }
+        if closing {
+            return;
+        }
+    }
+}
+
+// Stops at the first batch that fails; it and the rest wait for the next try
+fn send_queued(outbox: Option<&Outbox>, config: &InfluxConfig) -> Result<(), String> {
+    let Some(outbox) = outbox else { return Ok(()) };
+    let mut failed = None;
+    outbox
+        .flush(|delivery| write(config, &delivery.body).inspect_err(|e| failed = Some(e.clone())))
+        .map_err(|e| format!("could not rewrite the queue: {}", e))?;
+    failed.map_or(Ok(()), Err)
+}
+
+// Whether the batch is now waiting in the queue
+fn keep(outbox: Option<&Outbox>, config: &InfluxConfig, batch: String) -> bool {
+    if batch.is_empty() {
+        return false;
+    }
+    let samples = batch.lines().count();
+    let Some(outbox) = outbox else {
+        diag::log(format!("No data directory to queue InfluxDB samples in: dropped {}", samples));
+        return false;
+    };
+    match outbox.push(Delivery::new(&config.url, batch)) {
+        Ok(0) => true,
+        Ok(dropped) => {
+            diag::log(format!("InfluxDB queue full: dropped the {} oldest batches", dropped));
+            true
+        }
+        Err(e) => {
+            diag::log(format!("Could not queue {} InfluxDB samples: {}", samples, e));
+            false
+        }
+    }
+}
+
+fn write(config: &InfluxConfig, body: &str) -> Result<(), String> {
+    if body.is_empty() {
+        return Ok(());
+    }
+    match config.url.strip_prefix("udp://") {
+        Some(addr) => send_udp(addr, body),
+        None => send_http(config, body),
+    }
+}
+
+fn send_udp(addr: &str, body: &str) -> Result<(), String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").map_err(|e| e.to_string())?;
+    socket.connect(addr).map_err(|e| e.to_string())?;
+    let mut datagram = String::new();
+    for line in body.lines() {
+        if !datagram.is_empty() && datagram.len() + line.len() + 1 > DATAGRAM_BYTES {
+            socket.send(datagram.as_bytes()).map_err(|e| e.to_string())?;
+            datagram.clear();
+        }
+        if !datagram.is_empty() {
+            datagram.push('\n');
+        }
+        datagram.push_str(line);
+    }
+    socket.send(datagram.as_bytes()).map(|_| ()).map_err(|e| e.to_string())
+}
+
+#[cfg(any(feature = "gui", feature = "influx"))]
+fn send_http(config: &InfluxConfig, body: &str) -> Result<(), String> {
+    let url = format!("{}/api/v2/write", config.url.trim_end_matches('/'));
+    let mut request = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .post(&url)
+        .query("org", &config.org)
+        .query("bucket", &config.bucket)
+        .query("precision", "ns")
+        .set("Content-Type", "text/plain; charset=utf-8");
+    if !config.token.is_empty() {
+        request = request.set("Authorization", &format!("Token {}", config.token));
+    }
+    request.send_string(body).map(|_| ()).map_err(|e| e.to_string())
+}
+
+#[cfg(not(any(feature = "gui", feature = "influx")))]
+fn send_http(_config: &InfluxConfig, _body: &str) -> Result<(), String> {
+    Err("built without HTTP support; use a udp:// url or the influx feature".to_string())
+}
+
+// One sample in line protocol, with nanosecond timestamps as the UDP listener
+// expects by default. Nothing when there are no totals, rather than a misleading
+// row of zeros.
+fn line(measurement: &str, host: &str, sample: &Sample) -> Option<String> {
+    if !sample.has_memory() {
+        return None;
+    }
+    let mut fields = vec![
+        format!("total={}i", sample.total_memory),
+        format!("used={}i", sample.used_memory),
+        format!("free={}i", sample.free_memory),
+        format!("percent={}", sample.memory_percentage()),
+    ];
+    if sample.has_swap() {
+        fields.push(format!("swap_total={}i", sample.total_swap));
+        fields.push(format!("swap_used={}i", sample.used_swap));
+        fields.push(format!("swap_percent={}", sample.swap_percentage()));
+    }
+    let nanos = sample.timestamp_ms * 1_000_000;
+    Some(format!("{},host={} {} {}", escape(measurement), escape(host), fields.join(","), nanos))
+}
+
+// Commas, spaces and equals signs would end a measurement or tag early
+fn escape(text: &str) -> String {
+    text.replace(',', "\\,").replace(' ', "\\ ").replace('=', "\\=")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_become_line_protocol() {
+        let sample = Sample { timestamp_ms: 1000, total_memory: 200, used_memory: 50, ..Sample::default() };
+        assert_eq!(
+            line("memory", "my host", &sample).unwrap(),
+            "memory,host=my\\ host total=200i,used=50i,free=0i,percent=25 1000000000"
+        );
+        let with_swap = Sample { total_swap: 100, used_swap: 10, ..sample };
+        assert!(line("memory", "h", &with_swap).unwrap().contains(",swap_total=100i,swap_used=10i,swap_percent=10 "));
+        assert_eq!(line("memory", "h", &Sample::default()), None);
+    }
+
+    #[test]
+    fn failed_batches_outlive_the_sink() {
+        let path = std::env::temp_dir().join(format!("rmm-influx-outbox-{}.jsonl", std::process::id()));
+        let outbox = Outbox::new(path.clone(), 10);
+        let unreachable = InfluxConfig { url: "http://127.0.0.1:1".to_string(), ..InfluxConfig::default() };
+        let (tx, rx) = channel();
+        for timestamp_ms in [1000, 2000, 3000] {
+            tx.send(Sample { timestamp_ms, total_memory: 200, used_memory: 50, ..Sample::default() }).unwrap();
+        }
+        drop(tx);
+        run(rx, &unreachable, "h", Some(&outbox));
+        assert!(path.exists());
+
+        // The next run, with InfluxDB back, sends them before anything else
+        let listener = UdpSocket::bind("127.0.0.1:0").unwrap();
+        listener.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let url = format!("udp://{}", listener.local_addr().unwrap());
+        let (tx, rx) = channel::<Sample>();
+        drop(tx);
+        run(rx, &InfluxConfig { url, ..InfluxConfig::default() }, "h", Some(&outbox));
+        let mut datagram = [0u8; DATAGRAM_BYTES];
+        let read = listener.recv(&mut datagram).unwrap();
+        assert_eq!(String::from_utf8_lossy(&datagram[..read]).lines().count(), 3);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,87 @@
+// Keeps the machine from suspending while held. Linux and macOS delegate to the
+// platform tools (logind via systemd-inhibit, IOKit via caffeinate) so no extra
+// bindings are needed; Windows uses SetThreadExecutionState directly.
+pub struct SleepInhibitor {
+    #[cfg(not(windows))]
+    child: std::process::Child,
+}
+
+#[cfg(target_os = "linux")]
+fn spawn_inhibitor(reason: &str) -> std::io::Result<std::process::Child> {
+    std::process::Command::new("systemd-inhibit")
+        .arg("--what=sleep:idle")
+        .arg("--who=rusty_mem_monitor")
+        .arg(format!("--why={}", reason))
+        .arg("--mode=block")
+        .args(["sleep", "infinity"])
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .spawn()
+}
+
+#[cfg(target_os = "macos")]
+fn spawn_inhibitor(_reason: &str) -> std::io::Result<std::process::Child> {
+    std::process::Command::new("caffeinate")
+        .args(["-i", "-w"])
+        .arg(std::process::id().to_string())
+        .spawn()
+}
+
+#[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+fn spawn_inhibitor(_reason: &str) -> std::io::Result<std::process::Child> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "sleep inhibition is not supported on this platform",
+    ))
+}
+
+#[cfg(windows)]
+mod win {
+    pub const ES_CONTINUOUS: u32 = 0x8000_0000;
+    pub const ES_SYSTEM_REQUIRED: u32 = 0x0000_0001;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        pub fn SetThreadExecutionState(flags: u32) -> u32;
+    }
+}
+
+impl SleepInhibitor {
+    #[cfg(not(windows))]
+    pub fn acquire(reason: &str) -> std::io::Result<Self> {
+        let mut child = spawn_inhibitor(reason)?;
+        // The helper exits straight away if it couldn't take the lock (no logind, etc.)
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        if let Some(status) = child.try_wait()? {
+            return Err(std::io::Error::other(format!("inhibitor exited with {}", status)));
+        }
+        Ok(Self { child })
+    }
+
+    // Must be called from a long-lived thread: the state is tied to the calling thread
+    #[cfg(windows)]
+    pub fn acquire(_reason: &str) -> std::io::Result<Self> {
+        let previous = unsafe {
+            win::SetThreadExecutionState(win::ES_CONTINUOUS | win::ES_SYSTEM_REQUIRED)
+        };
+        if previous == 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(Self {})
+    }
+}
+
+impl Drop for SleepInhibitor {
+    #[cfg(not(windows))]
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+
+    #[cfg(windows)]
+    fn drop(&mut self) {
+        unsafe {
+            win::SetThreadExecutionState(win::ES_CONTINUOUS);
+        }
+    }
+}
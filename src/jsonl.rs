@@ -0,0 +1,113 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::time::Instant;
+
+use serde::Serialize;
+
+use rusty_mem_monitor::{AlertState, Sample, Sampler};
+
+use crate::config::Config;
+use crate::daemon::{self, DaemonClient};
+
+#[derive(Serialize, Clone, Copy, Default, PartialEq, Debug)]
+pub struct Alarm {
+    pub critical: bool,
+    pub acknowledged: bool,
+    pub snoozed: bool,
+}
+
+impl Alarm {
+    pub fn of(alerts: &AlertState) -> Self {
+        Self {
+            critical: alerts.critical,
+            acknowledged: alerts.acknowledged,
+            snoozed: alerts.is_snoozed(Instant::now()),
+        }
+    }
+}
+
+// The sample's own fields plus what a script would otherwise have to work out;
+// percentages are null when there's no total to take them of
+#[derive(Serialize)]
+struct Line<'a> {
+    #[serde(flatten)]
+    sample: &'a Sample,
+    memory_percentage: Option<f32>,
+    swap_percentage: Option<f32>,
+    alarm: Alarm,
+}
+
+// `--output jsonl`: one JSON object per sample, for jq, Vector and the like.
+// Every line is flushed, so a reader at the other end of a pipe sees it at once.
+pub struct JsonlOutput {
+    out: Box<dyn Write + Send>,
+}
+
+impl JsonlOutput {
+    // Appends to `path`, or writes to stdout without one
+    pub fn open(path: Option<&str>) -> std::io::Result<Self> {
+        let out: Box<dyn Write + Send> = match path {
+            Some(path) => Box::new(OpenOptions::new().create(true).append(true).open(path)?),
+            None => Box::new(std::io::stdout()),
+        };
+        Ok(Self { out })
+    }
+
+    pub fn write(&mut self, sample: &Sample, alarm: Alarm) -> std::io::Result<()> {
+        writeln!(self.out, "{}", line(sample, alarm))?;
+        self.out.flush()
+    }
+}
+
+pub fn line(sample: &Sample, alarm: Alarm) -> String {
+    let line = Line {
+        sample,
+        memory_percentage: sample.has_memory().then(|| sample.memory_percentage()),
+        swap_percentage: sample.has_swap().then(|| sample.swap_percentage()),
+        alarm,
+    };
+    serde_json::to_string(&line).unwrap_or_default()
+}
+
+// Without a window: samples from the daemon when one's running, like the status
+// bar, and the critical alarm judged here against the configured thresholds
+pub fn run(path: Option<&str>, standalone: bool) -> std::io::Result<()> {
+    let mut output = JsonlOutput::open(path)?;
+    let daemon = if standalone { None } else { DaemonClient::connect() };
+    let mut sampler = Sampler::new();
+    let config = Config::load();
+    let mut alerts = AlertState::default();
+    alerts.thresholds = config.alerts.thresholds;
+    loop {
+        let samples = match &daemon {
+            Some(daemon) if daemon.is_connected() => daemon.poll(),
+            _ => vec![sampler.sample()],
+        };
+        for sample in samples {
+            if sample.has_memory() {
+                alerts.update(sample.memory_percentage(), Instant::now(), &[]);
+            }
+            output.write(&sample, Alarm::of(&alerts))?;
+        }
+        std::thread::sleep(daemon::SAMPLE_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_carry_sample_and_alarm() {
+        let sample = Sample { timestamp_ms: 5, total_memory: 200, used_memory: 50, ..Sample::default() };
+        let alarm = Alarm { critical: true, ..Alarm::default() };
+        let value: serde_json::Value = serde_json::from_str(&line(&sample, alarm)).unwrap();
+        assert_eq!(value["timestamp_ms"], 5);
+        assert_eq!(value["used_memory"], 50);
+        assert_eq!(value["memory_percentage"], 25.0);
+        assert!(value["swap_percentage"].is_null());
+        assert_eq!(value["alarm"]["critical"], true);
+        // Still a sample to anything that reads those
+        assert_eq!(serde_json::from_value::<Sample>(value).unwrap().used_memory, 50);
+    }
+}
@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::app::PlotMode;
+use crate::processes::{self, ColumnLayout, ProcessGrouping, ProcessSort};
+use crate::{config, diag};
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+// How the window was arranged when it was last closed, kept per --profile in
+// <config dir>/layouts/<profile>.toml. Unlike config.toml this is written on
+// every exit, so nothing in here should be something a user edits by hand.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Layout {
+    // Outer position and inner size of the full window, in points
+    pub window_position: Option<[f32; 2]>,
+    pub window_size: Option<[f32; 2]>,
+    // Position and size above are from before it was maximized
+    pub maximized: bool,
+    // Where the compact widget was last dragged to
+    pub compact_position: Option<[f32; 2]>,
+    pub compact: bool,
+    pub panels: BTreeMap<String, bool>,
+    pub plot_mode: PlotMode,
+    // [min x, min y, max x, max y] once the plot was zoomed or dragged; x is
+    // seconds relative to the newest sample
+    pub plot_bounds: Option<[f64; 4]>,
+    pub process_sort: ProcessSort,
+    pub process_grouping: ProcessGrouping,
+    // Order, visibility and width of the process table's columns
+    pub process_columns: Vec<ColumnLayout>,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            window_position: None,
+            window_size: None,
+            maximized: false,
+            compact_position: None,
+            compact: false,
+            panels: BTreeMap::new(),
+            plot_mode: PlotMode::Lines,
+            plot_bounds: None,
+            process_sort: ProcessSort::Memory,
+            process_grouping: ProcessGrouping::None,
+            process_columns: processes::default_columns(),
+        }
+    }
+}
+
+impl Layout {
+    pub fn load(profile: &str) -> Self {
+        let Some(path) = path(profile) else {
+            return Self::default();
+        };
+        let mut layout: Self = match std::fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text).unwrap_or_else(|e| {
+                diag::log(format!("Ignoring invalid layout {}: {}", path.display(), e));
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        };
+        processes::complete_columns(&mut layout.process_columns);
+        layout
+    }
+
+    pub fn save(&self, profile: &str) -> std::io::Result<()> {
+        let path = path(profile).ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "no config directory")
+        })?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let text = toml::to_string_pretty(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(path, text)
+    }
+
+    pub fn is_open(&self, panel: &str, default: bool) -> bool {
+        self.panels.get(panel).copied().unwrap_or(default)
+    }
+
+    pub fn set_open(&mut self, panel: &str, open: bool) {
+        if self.panels.get(panel) != Some(&open) {
+            self.panels.insert(panel.to_string(), open);
+        }
+    }
+}
+
+// Every profile with a saved layout, by file name; the current one may not have
+// been saved yet
+pub fn profiles() -> Vec<String> {
+    let dir = config::config_dir().map(|dir| dir.join("layouts"));
+    let Some(entries) = dir.and_then(|dir| std::fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    let mut profiles: Vec<String> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+        .collect();
+    profiles.sort();
+    profiles
+}
+
+// Profile names end up in a file name, so anything but [A-Za-z0-9_-] is replaced
+fn file_name(profile: &str) -> String {
+    let name: String = profile
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    if name.is_empty() { DEFAULT_PROFILE.to_string() } else { name }
+}
+
+fn path(profile: &str) -> Option<PathBuf> {
+    config::config_dir().map(|dir| dir.join("layouts").join(format!("{}.toml", file_name(profile))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_toml() {
+        let mut layout = Layout {
+            window_position: Some([40.0, 80.0]),
+            plot_mode: PlotMode::Stacked,
+            plot_bounds: Some([-30.0, 0.0, 0.0, 100.0]),
+            process_grouping: ProcessGrouping::User,
+            ..Layout::default()
+        };
+        layout.process_columns.swap(0, 2);
+        layout.process_columns[0].width = 120.0;
+        layout.set_open("processes", true);
+        let loaded: Layout = toml::from_str(&toml::to_string_pretty(&layout).unwrap()).unwrap();
+        assert_eq!(loaded.window_position, Some([40.0, 80.0]));
+        assert!(loaded.plot_mode == PlotMode::Stacked);
+        assert_eq!(loaded.plot_bounds, Some([-30.0, 0.0, 0.0, 100.0]));
+        assert!(loaded.process_grouping == ProcessGrouping::User);
+        assert_eq!(loaded.process_columns, layout.process_columns);
+        assert!(loaded.is_open("processes", false));
+        assert!(loaded.is_open("snapshots", true));
+    }
+
+    #[test]
+    fn profile_names_stay_in_the_layouts_dir() {
+        assert_eq!(file_name("work laptop"), "work_laptop");
+        assert_eq!(file_name("../../etc/passwd"), "______etc_passwd");
+        assert_eq!(file_name(""), DEFAULT_PROFILE);
+    }
+}
@@ -0,0 +1,44 @@
+//! Sampling, history and alerting core of the hacknet memory monitor.
+//!
+//! Build with `default-features = false` to use it without pulling in eframe:
+//!
+//! ```no_run
+//! use rusty_mem_monitor::{AlertState, History, Sampler};
+//!
+//! let mut sampler = Sampler::new();
+//! let mut history = History::new(100);
+//! let mut alerts = AlertState::default();
+//! let sample = sampler.sample();
+//! history.push(&sample);
+//! alerts.update(sample.memory_percentage(), std::time::Instant::now(), &[]);
+//! ```
+//!
+//! With the `gui` feature, [`widget::MemoryMonitorWidget`] draws the usage bar and
+//! plot inside another egui application. With `wasm`, built for
+//! `wasm32-unknown-unknown`, the library is also a web page showing a remote
+//! agent's memory (see `web_app`).
+
+pub mod alert;
+pub mod cgroup;
+mod compressed;
+pub mod ffi;
+pub mod health;
+pub mod history;
+pub mod hugepages;
+pub mod meminfo;
+#[cfg(feature = "python")]
+mod python;
+pub mod sample;
+pub mod sampler;
+pub mod stats;
+pub mod units;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub mod web_app;
+#[cfg(any(feature = "gui", feature = "wasm"))]
+pub mod widget;
+pub mod zram;
+
+pub use alert::AlertState;
+pub use history::History;
+pub use sample::Sample;
+pub use sampler::{Sampler, SystemProvider};
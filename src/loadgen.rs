@@ -0,0 +1,221 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use sysinfo::{Pid, PidExt, ProcessExt, ProcessRefreshKind, System, SystemExt};
+
+use rusty_mem_monitor::sample::{self, now_ms};
+use rusty_mem_monitor::units::format_bytes;
+use rusty_mem_monitor::stats::{self, Summary};
+use rusty_mem_monitor::{History, Sample};
+
+use crate::config;
+use crate::daemon;
+
+const TOTAL_MEMORY: u64 = 16 * 1024 * 1024 * 1024;
+const TOTAL_SWAP: u64 = 4 * 1024 * 1024 * 1024;
+// A process that leaks until something restarts it, roughly every six hours of samples
+const LEAK_PERIOD: u64 = 43_200;
+const SMOOTHING_WINDOW: usize = 20;
+
+pub struct Timing {
+    pub name: &'static str,
+    pub took: Duration,
+    pub detail: String,
+}
+
+// Deterministic, so runs are comparable; xorshift is plenty for plausible noise
+struct Noise(u64);
+
+impl Noise {
+    fn next(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+// `count` samples at the daemon's interval up to `end_ms`: a daily rhythm, a
+// sawtooth leak, noise and the odd spike, so nothing downstream can take shortcuts
+pub fn generate(count: usize, end_ms: u64) -> impl Iterator<Item = Sample> {
+    let interval_ms = daemon::SAMPLE_INTERVAL.as_millis() as u64;
+    let start_ms = end_ms.saturating_sub(count as u64 * interval_ms);
+    let mut noise = Noise(0x9e37_79b9_7f4a_7c15);
+    (0..count as u64).map(move |i| {
+        let timestamp_ms = start_ms + i * interval_ms;
+        let day = (timestamp_ms % 86_400_000) as f64 / 86_400_000.0;
+        let daily = (day * std::f64::consts::TAU).sin() * 0.15;
+        let leak = (i % LEAK_PERIOD) as f64 / LEAK_PERIOD as f64 * 0.2;
+        let spike = if noise.next() < 0.001 { 0.25 } else { 0.0 };
+        let fraction = (0.45 + daily + leak + spike + (noise.next() - 0.5) * 0.04).clamp(0.02, 0.99);
+        let used_memory = (TOTAL_MEMORY as f64 * fraction) as u64;
+        let jitter = (TOTAL_MEMORY as f64 * 0.01 * noise.next()) as u64;
+        let used_swap = (TOTAL_SWAP as f64 * (fraction - 0.8).max(0.0) * 4.0) as u64;
+        Sample {
+            timestamp_ms,
+            total_memory: TOTAL_MEMORY,
+            used_memory,
+            total_swap: TOTAL_SWAP,
+            used_swap: used_swap.min(TOTAL_SWAP),
+            free_memory: (TOTAL_MEMORY - used_memory) / 3,
+            buffers_memory: TOTAL_MEMORY / 50,
+            compressed_memory: 0,
+            used_memory_min: used_memory.saturating_sub(jitter),
+            used_memory_max: (used_memory + jitter).min(TOTAL_MEMORY),
+            cgroup_limited: false,
+        }
+    })
+}
+
+// `--generate`: fills a history with `count` synthetic samples, writes them to a
+// recording and reads it back, timing each step and the queries the UI runs on
+// the history, to see how far the history layer scales
+pub fn run(count: usize) -> std::io::Result<Vec<Timing>> {
+    let mut timings = Vec::new();
+    let mut sys = System::new();
+    let rss_before = rss(&mut sys);
+
+    let started = Instant::now();
+    let mut history = History::new(count);
+    generate(count, now_ms()).for_each(|sample| history.push(&sample));
+    timings.push(Timing {
+        name: "generate + push",
+        took: started.elapsed(),
+        detail: format!("{} samples", history.len()),
+    });
+    let footprint = count * std::mem::size_of::<Sample>();
+    let rss_after = rss(&mut sys);
+    timings.push(Timing {
+        name: "history footprint",
+        took: Duration::ZERO,
+        detail: format!(
+            "{} of samples ({} B each), RSS grew {}",
+            format_bytes(footprint as u64),
+            std::mem::size_of::<Sample>(),
+            format_bytes(rss_after.saturating_sub(rss_before))
+        ),
+    });
+
+    let started = Instant::now();
+    let summary = stats::memory_summary(&history);
+    timings.push(Timing {
+        name: "summary",
+        took: started.elapsed(),
+        detail: summary.map_or(String::new(), |Summary { min, max, mean, p95 }| {
+            format!("min {:.1}% avg {:.1}% p95 {:.1}% max {:.1}%", min, mean, p95, max)
+        }),
+    });
+    let started = Instant::now();
+    let growth = stats::growth_per_minute(&history).unwrap_or_default();
+    timings.push(Timing {
+        name: "growth",
+        took: started.elapsed(),
+        detail: format!("{}{}/min", if growth < 0.0 { "-" } else { "+" }, format_bytes(growth.abs() as u64)),
+    });
+    let started = Instant::now();
+    let percentages: Vec<f64> = history.iter().map(|sample| sample.memory_percentage() as f64).collect();
+    let smoothed = stats::moving_average(&percentages, SMOOTHING_WINDOW);
+    timings.push(Timing {
+        name: "moving average",
+        took: started.elapsed(),
+        detail: format!("{} points, window {}", smoothed.len(), SMOOTHING_WINDOW),
+    });
+    let started = Instant::now();
+    let since_ms = history.last().map_or(0, |last| last.timestamp_ms.saturating_sub(3_600_000));
+    let last_hour = history.iter().rev().take_while(|sample| sample.timestamp_ms >= since_ms).count();
+    timings.push(Timing {
+        name: "last hour",
+        took: started.elapsed(),
+        detail: format!("{} samples", last_hour),
+    });
+    #[cfg(feature = "gui")]
+    {
+        let started = Instant::now();
+        let mut series = crate::series::PlotSeries::default();
+        let latest_ms = history.last().map_or(0, |last| last.timestamp_ms);
+        series.update(&history, latest_ms, false, &crate::config::PlotConfig::default());
+        timings.push(Timing {
+            name: "plot series",
+            took: started.elapsed(),
+            detail: format!("{} points per line", series.memory.len()),
+        });
+    }
+
+    let path = recording_path(count)?;
+    let started = Instant::now();
+    let mut writer = BufWriter::new(File::create(&path)?);
+    writeln!(writer, "{}", sample::CSV_HEADER)?;
+    for sample in history.iter() {
+        writeln!(writer, "{}", sample.csv_row())?;
+    }
+    writer.flush()?;
+    drop(writer);
+    timings.push(Timing {
+        name: "write recording",
+        took: started.elapsed(),
+        detail: format!("{} to {}", format_bytes(std::fs::metadata(&path)?.len()), path.display()),
+    });
+    let started = Instant::now();
+    let mut read = 0;
+    for line in BufReader::new(File::open(&path)?).lines().skip(1) {
+        read += usize::from(Sample::from_csv_row(&line?).is_some());
+    }
+    timings.push(Timing {
+        name: "read recording",
+        took: started.elapsed(),
+        detail: format!("{} samples", read),
+    });
+    Ok(timings)
+}
+
+fn recording_path(count: usize) -> std::io::Result<PathBuf> {
+    let dir = config::data_dir()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no data directory"))?
+        .join("recordings");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir.join(format!("synthetic-{}.csv", count)))
+}
+
+fn rss(sys: &mut System) -> u64 {
+    let pid = Pid::from_u32(std::process::id());
+    sys.refresh_process_specifics(pid, ProcessRefreshKind::new());
+    sys.process(pid).map_or(0, |process| process.memory())
+}
+
+pub fn format(timings: &[Timing]) -> String {
+    timings
+        .iter()
+        .map(|timing| {
+            let took = if timing.took.is_zero() {
+                String::new()
+            } else {
+                format!("{:.1?}", timing.took)
+            };
+            format!("{:<18} {:>10}  {}\n", timing.name, took, timing.detail)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_evenly_spaced_plausible_samples() {
+        let samples: Vec<Sample> = generate(1000, 1_700_000_000_000).collect();
+        assert_eq!(samples.len(), 1000);
+        let interval_ms = daemon::SAMPLE_INTERVAL.as_millis() as u64;
+        assert!(samples.windows(2).all(|pair| pair[1].timestamp_ms - pair[0].timestamp_ms == interval_ms));
+        assert!(samples.iter().all(|sample| {
+            sample.used_memory_min <= sample.used_memory
+                && sample.used_memory <= sample.used_memory_max
+                && sample.used_memory_max <= sample.total_memory
+                && sample.used_swap <= sample.total_swap
+        }));
+        let first: Vec<u64> = generate(10, 1_700_000_000_000).map(|sample| sample.used_memory).collect();
+        let again: Vec<u64> = generate(10, 1_700_000_000_000).map(|sample| sample.used_memory).collect();
+        assert_eq!(first, again);
+    }
+}
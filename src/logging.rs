@@ -0,0 +1,107 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter, Layer};
+
+use rusty_mem_monitor::Sample;
+
+use crate::config::{self, LoggingConfig};
+use crate::diag;
+
+const LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+// Full `tracing` filter directives, for anyone who needs more than a level
+const ENV: &str = "RUST_LOG";
+const DIR_NAME: &str = "logs";
+const FILE_PREFIX: &str = "monitor.log";
+// Target of the per-sample lines, so a filter can pick them out: RUST_LOG=samples=info
+const SAMPLES_TARGET: &str = "samples";
+
+static SAMPLES: AtomicBool = AtomicBool::new(false);
+// Set while the sampler keeps reporting no memory at all, so that's logged once
+static ZEROS: AtomicBool = AtomicBool::new(false);
+
+// Sets up logging for the whole process: stderr, plus daily files under logs/ in
+// the data directory unless they're switched off. The level comes from
+// --log-level, then RUST_LOG, then the config; our own messages follow it, while
+// the GUI toolkit's stay at warnings. The guard flushes the file when dropped.
+pub fn init(level: Option<&str>, config: &LoggingConfig) -> Result<Option<WorkerGuard>, String> {
+    let filter = match (level, std::env::var(ENV)) {
+        (Some(level), _) => filter_for(level)?,
+        (None, Ok(directives)) if !directives.is_empty() => {
+            EnvFilter::try_new(&directives).map_err(|e| format!("invalid {}: {}", ENV, e))?
+        }
+        _ => filter_for(&config.level)?,
+    };
+    SAMPLES.store(config.samples, Ordering::Relaxed);
+
+    let stderr = fmt::layer().with_writer(std::io::stderr).with_target(false);
+    let (file, guard) = match config::data_dir().filter(|_| config.file) {
+        Some(dir) => {
+            let dir = dir.join(DIR_NAME);
+            std::fs::create_dir_all(&dir).map_err(|e| format!("could not create {}: {}", dir.display(), e))?;
+            let appender = RollingFileAppender::builder()
+                .rotation(Rotation::DAILY)
+                .filename_prefix(FILE_PREFIX)
+                .max_log_files(config.keep_days.max(1))
+                .build(dir)
+                .map_err(|e| format!("could not open the log file: {}", e))?;
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            (Some(fmt::layer().with_writer(writer).with_ansi(false)), Some(guard))
+        }
+        None => (None, None),
+    };
+    tracing_subscriber::registry()
+        .with(stderr.and_then(file).with_filter(filter))
+        .try_init()
+        .map_err(|e| e.to_string())?;
+    Ok(guard)
+}
+
+fn filter_for(level: &str) -> Result<EnvFilter, String> {
+    if !LEVELS.contains(&level) {
+        return Err(format!("unknown log level: {} (one of {})", level, LEVELS.join(", ")));
+    }
+    let own = env!("CARGO_CRATE_NAME");
+    EnvFilter::try_new(format!("warn,{}={},{}={}", own, level, SAMPLES_TARGET, level)).map_err(|e| e.to_string())
+}
+
+// One structured line per sample when `[logging] samples` is on. Readings with no
+// memory at all mean the platform backend failed, which is always worth a warning
+// and goes into bug reports.
+pub fn sample(sample: &Sample) {
+    let failed = sample.total_memory == 0;
+    if ZEROS.swap(failed, Ordering::Relaxed) != failed {
+        if failed {
+            diag::log("Memory sampler returned zero total memory; showing N/A until it reports totals again");
+        } else {
+            diag::info("Memory sampler is reporting totals again");
+        }
+    }
+    if SAMPLES.load(Ordering::Relaxed) {
+        tracing::info!(
+            target: SAMPLES_TARGET,
+            timestamp_ms = sample.timestamp_ms,
+            used_memory = sample.used_memory,
+            total_memory = sample.total_memory,
+            used_swap = sample.used_swap,
+            total_swap = sample.total_swap,
+            memory_percent = format_args!("{:.1}", sample.memory_percentage()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_become_filters_for_our_own_targets() {
+        let filter = filter_for("debug").unwrap().to_string();
+        assert!(filter.contains("hacknet_memory_monitor=debug"), "{}", filter);
+        assert!(filter.contains("samples=debug") && filter.contains("warn"), "{}", filter);
+        assert!(filter_for("loud").is_err());
+    }
+}
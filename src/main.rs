@@ -1,190 +1,303 @@
+
+#[cfg(feature = "gui")]
 use eframe::egui;
-use egui_plot::{Line, Plot};  // Removed unused PlotPoints
-use sysinfo::{System, SystemExt};
-use std::time::Duration;
-use rand::Rng;
-
-struct MemoryMonitor {
-    sys: System,
-    memory_history: Vec<f32>,
-    swap_history: Vec<f32>,
-    max_history: usize,
-    glitch_effect: bool,
-    critical_alarm: bool,
-}
 
-impl MemoryMonitor {
-    fn new() -> Self {
-        Self {
-            sys: System::new_all(),
-            memory_history: Vec::new(),
-            swap_history: Vec::new(),
-            max_history: 100,
-            glitch_effect: false,
-            critical_alarm: false,
+#[cfg(feature = "gui")]
+mod accounting;
+#[cfg(feature = "gui")]
+mod api;
+#[cfg(feature = "gui")]
+mod app;
+mod bookmarks;
+#[cfg(feature = "gui")]
+mod budget;
+#[cfg(feature = "gui")]
+mod bugreport;
+mod cli;
+mod config;
+mod control;
+#[cfg(feature = "gui")]
+mod containers;
+mod daemon;
+#[cfg(feature = "gui")]
+mod crt;
+#[cfg(feature = "gui")]
+mod dashboard;
+#[cfg(all(feature = "dbus", target_os = "linux"))]
+mod dbus;
+mod diag;
+#[cfg(feature = "gui")]
+mod energy;
+#[cfg(feature = "gui")]
+mod events;
+#[cfg(feature = "gui")]
+mod focus;
+#[cfg(feature = "gui")]
+mod highlight;
+#[cfg(feature = "gui")]
+mod i18n;
+#[cfg(feature = "gui")]
+mod inhibit;
+mod influx;
+mod jsonl;
+#[cfg(feature = "gui")]
+mod layout;
+mod loadgen;
+mod logging;
+#[cfg(feature = "mqtt")]
+mod mqtt;
+#[cfg(feature = "gui")]
+mod notify;
+#[cfg(feature = "gui")]
+mod offenders;
+mod outbox;
+#[cfg(feature = "gui")]
+mod pagefile;
+#[cfg(feature = "gui")]
+mod plugins;
+mod presets;
+#[cfg(feature = "gui")]
+mod presentation;
+#[cfg(feature = "gui")]
+mod pressure;
+#[cfg(feature = "gui")]
+mod processes;
+#[cfg(feature = "gui")]
+mod profiles;
+#[cfg(feature = "gui")]
+mod rain;
+#[cfg(feature = "gui")]
+mod recorder;
+#[cfg(feature = "gui")]
+mod registry;
+#[cfg(feature = "gui")]
+mod report;
+mod screenshot;
+#[cfg(feature = "gui")]
+mod selfstats;
+mod selftest;
+#[cfg(feature = "gui")]
+mod sensors;
+#[cfg(feature = "gui")]
+mod series;
+#[cfg(feature = "gui")]
+mod smaps;
+#[cfg(feature = "gui")]
+mod snapshot;
+mod soak;
+#[cfg(all(feature = "gui", feature = "audio"))]
+mod sound;
+#[cfg(feature = "gui")]
+mod source;
+mod statusbar;
+#[cfg(feature = "gui")]
+mod theme;
+mod tls;
+#[cfg(feature = "gui")]
+mod topn;
+#[cfg(feature = "gui")]
+mod watchdog;
+#[cfg(feature = "web")]
+mod web;
+
+#[cfg(feature = "gui")]
+use app::{MemoryMonitor, COMPACT_SIZE, FULL_SIZE};
+#[cfg(feature = "gui")]
+use layout::Layout;
+
+#[cfg(feature = "gui")]
+#[global_allocator]
+static ALLOCATOR: selfstats::CountingAllocator = selfstats::CountingAllocator;
+
+fn main() {
+    let mut args = match cli::Args::parse() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{} (see --help)", e);
+            std::process::exit(2);
         }
+    };
+
+    if args.safe_mode {
+        // Default layout, and nothing that reaches out over the network or listens
+        // on it, even when asked for on the command line
+        args.compact = false;
+        args.connect.clear();
+        args.api_port = None;
+        args.web = None;
     }
 
-    fn generate_glitch_text(&self, text: &str) -> String {
-        let mut rng = rand::thread_rng();
-        text.chars()
-            .map(|c| if rng.gen_bool(0.3) { 
-                let glitch_chars = "!@#$%^&*()_+-=[]{}|;:,.<>?/~";
-                glitch_chars.chars().nth(rng.gen_range(0..glitch_chars.len())).unwrap_or(c)
-            } else { 
-                c 
-            })
-            .collect()
+    if args.version || args.features {
+        cli::print_version(args.features);
+        return;
+    }
+
+    let logging = if args.safe_mode { config::LoggingConfig::default() } else { config::Config::load().logging };
+    // Flushes the log file when main returns
+    let _log_guard = match logging::init(args.log_level.as_deref(), &logging) {
+        Ok(guard) => guard,
+        Err(e) => {
+            eprintln!("{} (see --help)", e);
+            std::process::exit(2);
+        }
+    };
+
+    if let Some(path) = &args.export_alerts {
+        match presets::export(std::path::Path::new(path)) {
+            Ok(()) => println!("Exported alert rules to {}", path),
+            Err(e) => {
+                eprintln!("Export failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if let Some(path) = &args.import_alerts {
+        match presets::import(std::path::Path::new(path)) {
+            Ok(name) => println!("Imported alert preset \"{}\"", name),
+            Err(e) => {
+                eprintln!("Import failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if let Some(text) = &args.bookmark {
+        match bookmarks::add(text) {
+            Ok(bookmark) => println!("Bookmarked \"{}\" at {} ms", bookmark.text, bookmark.timestamp_ms),
+            Err(e) => {
+                eprintln!("Bookmark failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if let Some(path) = &args.screenshot {
+        match screenshot::capture(std::path::Path::new(path), args.standalone) {
+            Ok(()) => println!("Saved the plot to {}", path),
+            Err(e) => {
+                eprintln!("Screenshot failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if let Some(count) = args.generate {
+        match loadgen::run(count) {
+            Ok(timings) => print!("{}", loadgen::format(&timings)),
+            Err(e) => {
+                eprintln!("Generate failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if args.selftest {
+        let checks = selftest::run();
+        print!("{}", selftest::format(&checks));
+        std::process::exit(if checks.iter().all(|check| check.ok) { 0 } else { 1 });
+    }
+
+    if let Some(format) = args.statusbar {
+        if let Err(e) = statusbar::run(format, args.standalone) {
+            // A closed pipe just means the bar went away
+            if e.kind() != std::io::ErrorKind::BrokenPipe {
+                eprintln!("Status bar output failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    // Built without the GUI there's no window to stream alongside
+    if args.output.is_some() && (args.headless || !cfg!(feature = "gui")) {
+        if let Err(e) = jsonl::run(args.output_file.as_deref(), args.standalone) {
+            if e.kind() != std::io::ErrorKind::BrokenPipe {
+                eprintln!("Output failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if let Some(addr) = &args.agent {
+        let agent = if args.safe_mode { config::AgentConfig::default() } else { config::Config::load().agent };
+        if let Err(e) = daemon::run_agent(addr, start_soak(&args), start_sinks(&args), &agent) {
+            eprintln!("Agent failed: {}", e);
+            std::process::exit(1);
+        }
+        return;
     }
-}
 
-impl eframe::App for MemoryMonitor {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        self.sys.refresh_memory();
-        
-        self.glitch_effect = rand::thread_rng().gen_bool(0.05);
-        
-        let total_memory = self.sys.total_memory() as f64;
-        let used_memory = self.sys.used_memory() as f64;
-        let memory_percentage = (used_memory / total_memory * 100.0) as f32;
-        
-        let swap_percentage = if self.sys.total_swap() > 0 {
-            (self.sys.used_swap() as f64 / self.sys.total_swap() as f64 * 100.0) as f32
-        } else {
-            0.0
-        };
-
-        // Update history
-        self.memory_history.push(memory_percentage);
-        self.swap_history.push(swap_percentage);
-        if self.memory_history.len() > self.max_history {
-            self.memory_history.remove(0);
-            self.swap_history.remove(0);
+    if args.daemon {
+        if let Err(e) = daemon::run(start_soak(&args), start_sinks(&args)) {
+            eprintln!("Daemon failed: {}", e);
+            std::process::exit(1);
         }
+        return;
+    }
 
-        self.critical_alarm = memory_percentage > 90.0;
-
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.visuals_mut().panel_fill = egui::Color32::from_rgb(0, 15, 0);
-            
-            ui.vertical_centered(|ui| {
-                let title = if self.glitch_effect {
-                    self.generate_glitch_text("MEMORY MONITOR")
-                } else {
-                    "MEMORY MONITOR".to_string()
-                };
-                
-                ui.heading(
-                    egui::RichText::new(title)
-                        .color(if self.critical_alarm {
-                            egui::Color32::from_rgb(255, 0, 0)
-                        } else {
-                            egui::Color32::from_rgb(0, 255, 0)
-                        })
-                        .monospace()
-                );
-
-                ui.add_space(20.0);
-                
-                ui.label(
-                    egui::RichText::new(format!("Memory Usage: {:.1}%", memory_percentage))
-                        .color(if memory_percentage > 90.0 {
-                            egui::Color32::from_rgb(255, 0, 0)
-                        } else if memory_percentage > 70.0 {
-                            egui::Color32::from_rgb(255, 255, 0)
-                        } else {
-                            egui::Color32::from_rgb(0, 255, 0)
-                        })
-                        .monospace()
-                );
-
-                let bar_text = if self.glitch_effect {
-                    self.generate_glitch_text(&format!("[{:^50}]", "#".repeat((memory_percentage/2.0) as usize)))
-                } else {
-                    format!("[{:^50}]", "#".repeat((memory_percentage/2.0) as usize))
-                };
-                
-                ui.label(
-                    egui::RichText::new(bar_text)
-                        .color(if memory_percentage > 90.0 {
-                            egui::Color32::from_rgb(255, 0, 0)
-                        } else {
-                            egui::Color32::from_rgb(0, 255, 0)
-                        })
-                        .monospace()
-                );
-
-                ui.add_space(20.0);
-                
-                let plot = Plot::new("memory_usage")
-                    .height(200.0)
-                    .show_axes([false, true])
-                    .show_background(false);
-                
-                let memory_points: Vec<[f64; 2]> = self.memory_history.iter()
-                    .enumerate()
-                    .map(|(i, &y)| [i as f64, y as f64])
-                    .collect();
-                
-                let swap_points: Vec<[f64; 2]> = self.swap_history.iter()
-                    .enumerate()
-                    .map(|(i, &y)| [i as f64, y as f64])
-                    .collect();
-
-                plot.show(ui, |plot_ui| {
-                    plot_ui.line(
-                        Line::new(memory_points)
-                            .color(egui::Color32::from_rgb(0, 255, 0))
-                            .name("RAM")
-                            .width(2.0)
-                    );
-                    plot_ui.line(
-                        Line::new(swap_points)
-                            .color(egui::Color32::from_rgb(255, 100, 0))
-                            .name("Swap")
-                            .width(2.0)
-                    );
-                });
-
-                if self.critical_alarm {
-                    ui.add_space(10.0);
-                    ui.label(
-                        egui::RichText::new("WARNING: CRITICAL MEMORY USAGE!")
-                            .color(egui::Color32::from_rgb(255, 0, 0))
-                            .strong()
-                            .heading()
-                    );
-                }
-
-                ui.add_space(20.0);
-                ui.label(
-                    egui::RichText::new(format!("Total Memory: {:.1} GB", total_memory / 1024.0 / 1024.0 / 1024.0))
-                        .color(egui::Color32::from_rgb(0, 255, 255))
-                        .monospace()
-                );
-                ui.label(
-                    egui::RichText::new(format!("Used Memory:  {:.1} GB", used_memory / 1024.0 / 1024.0 / 1024.0))
-                        .color(egui::Color32::from_rgb(0, 255, 255))
-                        .monospace()
-                );
-            });
-        });
-
-        ctx.request_repaint_after(Duration::from_millis(500));
+    if let Err(e) = run_gui(args) {
+        eprintln!("{}", e);
+        std::process::exit(1);
     }
 }
 
-fn main() -> eframe::Result<()> {
+fn start_soak(args: &cli::Args) -> Option<soak::Soak> {
+    // Safe mode skips the config, so only --soak switches it on there
+    let config = if args.safe_mode { config::SoakConfig::default() } else { config::Config::load().soak };
+    (args.soak || config.enabled).then(|| soak::Soak::start(&config))
+}
+
+fn start_sinks(args: &cli::Args) -> daemon::Sinks {
+    use sysinfo::SystemExt;
+    // Safe mode leaves out the configured sinks
+    let config = if args.safe_mode { config::Config::default() } else { config::Config::load() };
+    let host = sysinfo::System::new().host_name().unwrap_or_else(|| "unknown".to_string());
+    daemon::Sinks::start(&config, host, args.web.as_deref())
+}
+
+#[cfg(feature = "gui")]
+fn run_gui(args: cli::Args) -> Result<(), String> {
+    let profile = args.profile.as_deref().unwrap_or(layout::DEFAULT_PROFILE);
+    let layout = if args.safe_mode { Layout::default() } else { Layout::load(profile) };
+    // --compact wins over a layout that was saved in the full view, not the other way round
+    let compact = args.compact || layout.compact;
+    let (size, position) = if compact {
+        (COMPACT_SIZE, layout.compact_position)
+    } else {
+        (layout.window_size.unwrap_or(FULL_SIZE), layout.window_position)
+    };
+    let mut viewport = egui::ViewportBuilder::default()
+        .with_inner_size(size)
+        .with_title("Memory Monitor - Hacker Edition");
+    if let Some(position) = position {
+        viewport = viewport.with_position(position);
+    }
+    if compact {
+        viewport = viewport.with_decorations(false).with_always_on_top();
+    } else if layout.maximized {
+        viewport = viewport.with_maximized(true);
+    }
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size([500.0, 700.0])
-            .with_title("Memory Monitor - Hacker Edition"),
+        viewport,
         ..Default::default()
     };
     
     eframe::run_native(
         "Memory Monitor",
         options,
-        Box::new(|_cc| Box::new(MemoryMonitor::new())),
+        Box::new(move |_cc| Box::new(MemoryMonitor::new(&args, layout))),
     )
-}
\ No newline at end of file
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "gui"))]
+fn run_gui(_args: cli::Args) -> Result<(), String> {
+    Err("built without the gui feature; use --daemon, --statusbar, --output, --screenshot or --selftest".to_string())
+}
@@ -0,0 +1,26 @@
+use std::collections::HashMap;
+
+// Parses /proc/meminfo into byte counts keyed by field name. Only Linux has it;
+// everywhere else callers fall back to what sysinfo exposes.
+pub fn read() -> Option<HashMap<String, u64>> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    let text = std::fs::read_to_string("/proc/meminfo").ok()?;
+    Some(parse(&text))
+}
+
+pub fn parse(text: &str) -> HashMap<String, u64> {
+    text.lines()
+        .filter_map(|line| {
+            let (key, rest) = line.split_once(':')?;
+            let mut parts = rest.split_whitespace();
+            let value: u64 = parts.next()?.parse().ok()?;
+            let bytes = match parts.next() {
+                Some("kB") => value * 1024,
+                _ => value,
+            };
+            Some((key.trim().to_string(), bytes))
+        })
+        .collect()
+}
@@ -0,0 +1,371 @@
+use std::io::{Error, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::time::Duration;
+
+use serde_json::json;
+
+#[cfg(any(feature = "gui", test))]
+use rusty_mem_monitor::sample::now_ms;
+use rusty_mem_monitor::Sample;
+
+use crate::config::MqttConfig;
+use crate::diag;
+use crate::jsonl::{self, Alarm};
+use crate::outbox::{Delivery, Outbox};
+
+const CONNECT: u8 = 0x10;
+const CONNACK: u8 = 0x20;
+const PUBLISH: u8 = 0x30;
+const PINGREQ: u8 = 0xc0;
+const DISCONNECT: u8 = 0xe0;
+
+const KEEP_ALIVE: Duration = Duration::from_secs(60);
+const TIMEOUT: Duration = Duration::from_secs(10);
+const FIRST_RETRY: Duration = Duration::from_secs(5);
+const MAX_RETRY: Duration = Duration::from_secs(300);
+const OUTBOX: &str = "mqtt";
+
+// Publishes every sample to an MQTT broker: `<prefix>/<host>/memory` and `/swap`
+// in percent, `/alarm` as ON or OFF, `/sample` as a JSON line with its timestamp,
+// and `/availability`, which the broker turns to offline should we vanish. QoS 0
+// over plain TCP. Samples taken while the broker is unreachable wait in the
+// outbox and go out on `/sample` after reconnecting; the state topics only get
+// the latest.
+pub struct MqttSink {
+    tx: Sender<(Sample, Alarm)>,
+}
+
+impl MqttSink {
+    pub fn start(config: &MqttConfig, host: &str) -> Option<Self> {
+        if !config.enabled || config.broker.is_empty() {
+            return None;
+        }
+        let (tx, rx) = channel();
+        let topics = Topics::new(&config.topic_prefix, host);
+        let config = config.clone();
+        let spawned = std::thread::Builder::new().name("mqtt".to_string()).spawn(move || run(rx, &config, &topics));
+        if let Err(e) = spawned {
+            diag::log(format!("Could not start MQTT publishing: {}", e));
+            return None;
+        }
+        Some(Self { tx })
+    }
+
+    pub fn push(&self, sample: &Sample, alarm: Alarm) {
+        let _ = self.tx.send((*sample, alarm));
+    }
+}
+
+struct Topics {
+    base: String,
+    // The host name cut down to what topics and Home Assistant ids allow
+    node: String,
+    host: String,
+}
+
+impl Topics {
+    fn new(prefix: &str, host: &str) -> Self {
+        let node: String = host
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        Self { base: format!("{}/{}", prefix.trim_end_matches('/'), node), node, host: host.to_string() }
+    }
+
+    fn topic(&self, name: &str) -> String {
+        format!("{}/{}", self.base, name)
+    }
+}
+
+fn run(rx: Receiver<(Sample, Alarm)>, config: &MqttConfig, topics: &Topics) {
+    let outbox = (config.queue > 0).then(|| Outbox::open(OUTBOX, config.queue)).flatten();
+    let mut failures = 0u32;
+    loop {
+        let e = match session(&rx, config, topics, outbox.as_ref(), &mut failures) {
+            Ok(()) => return,
+            Err(e) => e,
+        };
+        let delay = FIRST_RETRY.saturating_mul(1 << failures.min(16)).min(MAX_RETRY);
+        diag::log(format!("MQTT broker {}: {}, reconnecting in {}s", config.broker, e, delay.as_secs()));
+        failures = failures.saturating_add(1);
+        std::thread::sleep(delay);
+        // What came in meanwhile waits on disk for the next connection; the
+        // sampler hanging up ends it all
+        let mut missed = Vec::new();
+        let gone = loop {
+            match rx.try_recv() {
+                Ok((sample, alarm)) => missed.push(sample_delivery(topics, &sample, alarm)),
+                Err(TryRecvError::Empty) => break false,
+                Err(TryRecvError::Disconnected) => break true,
+            }
+        };
+        queue(outbox.as_ref(), missed);
+        if gone {
+            return;
+        }
+    }
+}
+
+// One connection, until it fails or the sampler's gone
+fn session(
+    rx: &Receiver<(Sample, Alarm)>,
+    config: &MqttConfig,
+    topics: &Topics,
+    outbox: Option<&Outbox>,
+    failures: &mut u32,
+) -> std::io::Result<()> {
+    let will = topics.topic("availability");
+    let mut stream = connect(config, &client_id(config, topics), Some(&will))?;
+    if *failures > 0 {
+        diag::info(format!("Reconnected to MQTT broker {}", config.broker));
+    }
+    *failures = 0;
+    let availability = topics.topic("availability");
+    stream.write_all(&publish_packet(&availability, "online", true))?;
+    if config.home_assistant {
+        for (topic, payload) in discovery(&config.discovery_prefix, topics) {
+            stream.write_all(&publish_packet(&topic, &payload, true))?;
+        }
+    }
+    if let Some(outbox) = outbox {
+        let flushed = outbox.flush(|delivery| {
+            stream.write_all(&publish_packet(&delivery.url, &delivery.body, false)).map_err(|e| e.to_string())
+        });
+        match flushed {
+            Ok(0) => {}
+            Ok(sent) => diag::info(format!("Sent {} queued samples to MQTT broker {}", sent, config.broker)),
+            Err(e) => diag::log(format!("Could not rewrite the MQTT queue: {}", e)),
+        }
+    }
+    loop {
+        match rx.recv_timeout(KEEP_ALIVE / 2) {
+            Ok((sample, alarm)) => {
+                if let Err(e) = publish(&mut stream, topics, &sample, alarm) {
+                    queue(outbox, vec![sample_delivery(topics, &sample, alarm)]);
+                    return Err(e);
+                }
+            }
+            // Sampling slower than the keep-alive mustn't look like a dead client
+            Err(RecvTimeoutError::Timeout) => stream.write_all(&[PINGREQ, 0])?,
+            Err(RecvTimeoutError::Disconnected) => {
+                stream.write_all(&publish_packet(&availability, "offline", true))?;
+                return stream.write_all(&[DISCONNECT, 0]);
+            }
+        }
+    }
+}
+
+fn publish(stream: &mut TcpStream, topics: &Topics, sample: &Sample, alarm: Alarm) -> std::io::Result<()> {
+    if sample.has_memory() {
+        let percent = format!("{:.1}", sample.memory_percentage());
+        stream.write_all(&publish_packet(&topics.topic("memory"), &percent, false))?;
+    }
+    if sample.has_swap() {
+        let percent = format!("{:.1}", sample.swap_percentage());
+        stream.write_all(&publish_packet(&topics.topic("swap"), &percent, false))?;
+    }
+    let state = if alarm.critical { "ON" } else { "OFF" };
+    stream.write_all(&publish_packet(&topics.topic("alarm"), state, true))?;
+    let delivery = sample_delivery(topics, sample, alarm);
+    stream.write_all(&publish_packet(&delivery.url, &delivery.body, false))
+}
+
+// The /sample message, which is also what the outbox keeps
+fn sample_delivery(topics: &Topics, sample: &Sample, alarm: Alarm) -> Delivery {
+    Delivery::new(&topics.topic("sample"), jsonl::line(sample, alarm))
+}
+
+fn queue(outbox: Option<&Outbox>, deliveries: Vec<Delivery>) {
+    let Some(outbox) = outbox.filter(|_| !deliveries.is_empty()) else { return };
+    match outbox.extend(deliveries) {
+        Ok(0) => {}
+        Ok(dropped) => diag::log(format!("MQTT queue full: dropped the {} oldest samples", dropped)),
+        Err(e) => diag::log(format!("Could not queue MQTT samples: {}", e)),
+    }
+}
+
+// Connects on the side with a client id of its own, publishes a test message to
+// `<prefix>/<host>/test` and hangs up, leaving the running sink's session be
+#[cfg(any(feature = "gui", test))]
+pub fn test_publish(config: &MqttConfig, host: &str, summary: &str) -> Result<String, String> {
+    let topics = Topics::new(&config.topic_prefix, host);
+    let client_id = format!("{}-test", client_id(config, &topics));
+    let mut stream = connect(config, &client_id, None).map_err(|e| e.to_string())?;
+    let topic = topics.topic("test");
+    let payload = json!({ "text": summary, "host": host, "test": true, "timestamp_ms": now_ms() });
+    stream
+        .write_all(&publish_packet(&topic, &payload.to_string(), false))
+        .and_then(|()| stream.write_all(&[DISCONNECT, 0]))
+        .map_err(|e| e.to_string())?;
+    Ok(format!("published to {}", topic))
+}
+
+fn client_id(config: &MqttConfig, topics: &Topics) -> String {
+    match config.client_id.as_str() {
+        "" => format!("memory-monitor-{}", topics.node),
+        id => id.to_string(),
+    }
+}
+
+fn connect(config: &MqttConfig, client_id: &str, will: Option<&str>) -> std::io::Result<TcpStream> {
+    let addr = config.broker.to_socket_addrs()?.next().ok_or_else(|| Error::other("no address for the broker"))?;
+    let mut stream = TcpStream::connect_timeout(&addr, TIMEOUT)?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.write_all(&connect_packet(client_id, will, &config.username, &config.password))?;
+    let mut ack = [0u8; 4];
+    stream.read_exact(&mut ack)?;
+    match ack {
+        [CONNACK, 2, _, 0] => Ok(stream),
+        [CONNACK, 2, _, code] => Err(Error::other(format!("the broker refused the connection (code {})", code))),
+        _ => Err(Error::other("the broker didn't acknowledge the connection")),
+    }
+}
+
+// MQTT 3.1.1 with a clean session and, given a will topic, a retained "offline" will
+fn connect_packet(client_id: &str, will_topic: Option<&str>, username: &str, password: &str) -> Vec<u8> {
+    let mut flags = 0x02;
+    let mut body = Vec::new();
+    string(&mut body, b"MQTT");
+    body.push(4);
+    let flags_at = body.len();
+    body.push(0);
+    body.extend_from_slice(&(KEEP_ALIVE.as_secs() as u16).to_be_bytes());
+    string(&mut body, client_id.as_bytes());
+    if let Some(will_topic) = will_topic {
+        flags |= 0x04 | 0x20;
+        string(&mut body, will_topic.as_bytes());
+        string(&mut body, b"offline");
+    }
+    // A password needs a user name to go with it
+    if !username.is_empty() {
+        flags |= 0x80;
+        string(&mut body, username.as_bytes());
+        if !password.is_empty() {
+            flags |= 0x40;
+            string(&mut body, password.as_bytes());
+        }
+    }
+    body[flags_at] = flags;
+    packet(CONNECT, &body)
+}
+
+fn publish_packet(topic: &str, payload: &str, retain: bool) -> Vec<u8> {
+    let mut body = Vec::new();
+    string(&mut body, topic.as_bytes());
+    body.extend_from_slice(payload.as_bytes());
+    packet(PUBLISH | u8::from(retain), &body)
+}
+
+// A fixed header with the body length as a base-128 varint
+fn packet(header: u8, body: &[u8]) -> Vec<u8> {
+    let mut packet = vec![header];
+    let mut len = body.len();
+    loop {
+        let byte = (len % 128) as u8;
+        len /= 128;
+        packet.push(if len > 0 { byte | 0x80 } else { byte });
+        if len == 0 {
+            break;
+        }
+    }
+    packet.extend_from_slice(body);
+    packet
+}
+
+fn string(body: &mut Vec<u8>, text: &[u8]) {
+    body.extend_from_slice(&(text.len() as u16).to_be_bytes());
+    body.extend_from_slice(text);
+}
+
+// Home Assistant's discovery configs, retained so it picks them up whenever it
+// starts: the two percentages as sensors and the alarm as a problem sensor, all
+// on one device per host
+fn discovery(prefix: &str, topics: &Topics) -> Vec<(String, String)> {
+    let device = json!({ "identifiers": [topics.node], "name": format!("{} memory", topics.host) });
+    let percent = json!({ "unit_of_measurement": "%", "state_class": "measurement", "icon": "mdi:memory" });
+    let entities = [
+        ("sensor", "memory", "Memory used", percent.clone()),
+        ("sensor", "swap", "Swap used", percent),
+        ("binary_sensor", "alarm", "Memory alarm", json!({ "device_class": "problem" })),
+    ];
+    entities
+        .into_iter()
+        .map(|(component, key, name, mut entity)| {
+            entity["name"] = name.into();
+            entity["unique_id"] = format!("{}_{}", topics.node, key).into();
+            entity["state_topic"] = topics.topic(key).into();
+            entity["availability_topic"] = topics.topic("availability").into();
+            entity["device"] = device.clone();
+            let topic = format!("{}/{}/{}/{}/config", prefix.trim_end_matches('/'), component, topics.node, key);
+            (topic, entity.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packets_and_topics() {
+        let topics = Topics::new("memory_monitor/", "my.host");
+        assert_eq!(topics.topic("memory"), "memory_monitor/my_host/memory");
+
+        assert_eq!(publish_packet("a/b", "OFF", true), b"\x31\x08\x00\x03a/bOFF");
+        let long = publish_packet("t", &"x".repeat(200), false);
+        assert_eq!(long[..3], [PUBLISH, 0xcb, 0x01]);
+
+        let connect = connect_packet("id", Some("t"), "", "secret");
+        assert_eq!(connect[..10], [CONNECT, 26, 0, 4, b'M', b'Q', b'T', b'T', 4, 0x26]);
+        assert_eq!(connect_packet("id", Some("t"), "me", "secret")[9], 0xe6);
+        assert_eq!(connect_packet("id", None, "", "")[9], 0x02);
+
+        let configs = discovery("homeassistant", &topics);
+        assert_eq!(configs[2].0, "homeassistant/binary_sensor/my_host/alarm/config");
+        let alarm: serde_json::Value = serde_json::from_str(&configs[2].1).unwrap();
+        assert_eq!(alarm["state_topic"], "memory_monitor/my_host/alarm");
+    }
+
+    // Accepts one client, acknowledges its CONNECT and hands back everything it
+    // sent after that
+    fn broker() -> (MqttConfig, std::thread::JoinHandle<String>) {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let broker = listener.local_addr().unwrap().to_string();
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream.set_read_timeout(Some(TIMEOUT)).unwrap();
+            let mut header = [0u8; 2];
+            stream.read_exact(&mut header).unwrap();
+            let mut connect = vec![0u8; header[1] as usize];
+            stream.read_exact(&mut connect).unwrap();
+            stream.write_all(&[CONNACK, 2, 0, 0]).unwrap();
+            let mut rest = Vec::new();
+            stream.read_to_end(&mut rest).unwrap();
+            String::from_utf8_lossy(&rest).into_owned()
+        });
+        (MqttConfig { broker, ..MqttConfig::default() }, handle)
+    }
+
+    #[test]
+    fn test_publish_and_queued_samples_reach_the_broker() {
+        let (config, handle) = broker();
+        let published = test_publish(&config, "my.host", "TEST alert").unwrap();
+        assert_eq!(published, "published to memory_monitor/my_host/test");
+        assert!(handle.join().unwrap().contains("\"test\":true"));
+
+        let (config, handle) = broker();
+        let topics = Topics::new(&config.topic_prefix, "my.host");
+        let path = std::env::temp_dir().join(format!("rmm-mqtt-outbox-{}.jsonl", std::process::id()));
+        let outbox = Outbox::new(path.clone(), 10);
+        let missed = Sample { timestamp_ms: 1234, total_memory: 200, used_memory: 50, ..Sample::default() };
+        queue(Some(&outbox), vec![sample_delivery(&topics, &missed, Alarm::default())]);
+        let (tx, rx) = channel();
+        drop(tx);
+        session(&rx, &config, &topics, Some(&outbox), &mut 0).unwrap();
+        let sent = handle.join().unwrap();
+        assert!(sent.contains("memory_monitor/my_host/sample") && sent.contains("\"timestamp_ms\":1234"));
+        assert!(!path.exists());
+    }
+}
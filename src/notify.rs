@@ -0,0 +1,514 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, PidExt, ProcessExt, System, SystemExt};
+
+use rusty_mem_monitor::alert::{AlertRule, EscalationStage};
+use rusty_mem_monitor::sample::now_ms;
+
+use crate::config::{ActionsConfig, AlertCommand, AlertsConfig, Config};
+use crate::diag;
+#[cfg(feature = "audio")]
+use crate::i18n::tr;
+use crate::i18n::tr_args;
+#[cfg(feature = "mqtt")]
+use crate::mqtt;
+use crate::outbox::{Delivery, Outbox};
+#[cfg(feature = "audio")]
+use crate::sound;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+const WEBHOOK_OUTBOX: &str = "webhooks";
+
+pub struct AlertContext {
+    pub hostname: String,
+    pub memory_percentage: f32,
+    pub level: usize,
+}
+
+impl AlertContext {
+    fn summary(&self) -> String {
+        format!(
+            "CRITICAL memory usage on {}: {:.1}% (escalation level {})",
+            self.hostname, self.memory_percentage, self.level
+        )
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Transition {
+    Warning,
+    Critical,
+    Recovery,
+}
+
+impl Transition {
+    pub fn name(self) -> &'static str {
+        match self {
+            Transition::Warning => "warning",
+            Transition::Critical => "critical",
+            Transition::Recovery => "recovery",
+        }
+    }
+}
+
+// Runs [alerts.on_warning] / [alerts.on_critical] / [alerts.on_recovery], each with its own cooldown
+#[derive(Default)]
+pub struct AlertCommands {
+    warning_ran: Option<Instant>,
+    critical_ran: Option<Instant>,
+    recovery_ran: Option<Instant>,
+}
+
+impl AlertCommands {
+    pub fn fire(
+        &mut self,
+        transition: Transition,
+        config: &AlertsConfig,
+        dry_run: bool,
+        hostname: &str,
+        memory_percentage: f32,
+    ) {
+        let (command, last_run) = match transition {
+            Transition::Warning => (&config.on_warning, &mut self.warning_ran),
+            Transition::Critical => (&config.on_critical, &mut self.critical_ran),
+            Transition::Recovery => (&config.on_recovery, &mut self.recovery_ran),
+        };
+        let Some(command) = command.as_ref().filter(|command| !command.command.trim().is_empty()) else {
+            return;
+        };
+        let cooldown = Duration::from_secs_f64(command.cooldown_minutes.max(0.0) * 60.0);
+        if last_run.is_some_and(|at| at.elapsed() < cooldown) {
+            diag::log(format!("Skipping {} command: still cooling down", transition.name()));
+            return;
+        }
+        *last_run = Some(Instant::now());
+        if dry_run {
+            diag::info(format!("Dry run: would run {} command: {}", transition.name(), command.command));
+            return;
+        }
+
+        let command = command.clone();
+        let hostname = hostname.to_string();
+        std::thread::spawn(move || {
+            if let Err(e) = run_command(&command, transition, &hostname, memory_percentage, false) {
+                diag::log(format!("{} command failed: {}", transition.name(), e));
+            }
+        });
+    }
+}
+
+// Details go in the environment so the command line itself stays whatever the user wrote
+fn run_command(
+    command: &AlertCommand,
+    transition: Transition,
+    hostname: &str,
+    memory_percentage: f32,
+    test: bool,
+) -> Result<(), String> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    diag::info(format!("Running {} command: {}", transition.name(), command.command));
+    let status = shell
+        .arg(&command.command)
+        .env("MEMORY_MONITOR_EVENT", transition.name())
+        .env("MEMORY_MONITOR_HOST", hostname)
+        .env("MEMORY_MONITOR_PERCENT", format!("{:.1}", memory_percentage))
+        .env("MEMORY_MONITOR_TEST", if test { "1" } else { "0" })
+        .stdin(Stdio::null())
+        .status()
+        .map_err(|e| format!("could not start: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("exited with {}", status))
+    }
+}
+
+// Somewhere an alert can be delivered, as configured; listed once each however
+// many stages and rules share it
+#[derive(Clone, PartialEq)]
+pub enum AlertChannel {
+    #[cfg(feature = "audio")]
+    Sound,
+    Webhook(String),
+    Email(String),
+    Command(Transition, AlertCommand),
+    #[cfg(feature = "mqtt")]
+    Mqtt(String),
+}
+
+impl AlertChannel {
+    pub fn label(&self) -> String {
+        match self {
+            #[cfg(feature = "audio")]
+            AlertChannel::Sound => tr("alarm-sound"),
+            AlertChannel::Webhook(url) => tr_args("channel-webhook", &[("url", url)]),
+            AlertChannel::Email(to) => tr_args("channel-email", &[("to", to)]),
+            AlertChannel::Command(transition, command) => {
+                let key = format!("channel-command-{}", transition.name());
+                tr_args(&key, &[("command", &command.command)])
+            }
+            #[cfg(feature = "mqtt")]
+            AlertChannel::Mqtt(broker) => tr_args("channel-mqtt", &[("broker", broker)]),
+        }
+    }
+}
+
+pub fn channels(config: &Config) -> Vec<AlertChannel> {
+    let alerts = &config.alerts;
+    let mut channels = Vec::new();
+    #[cfg(feature = "audio")]
+    if alerts.sound.enabled {
+        channels.push(AlertChannel::Sound);
+    }
+    let webhooks = alerts.escalation.iter().filter_map(|stage| stage.webhook.clone());
+    let webhooks = webhooks.chain(alerts.rules.iter().filter_map(|rule| rule.webhook.clone()));
+    channels.extend(webhooks.map(AlertChannel::Webhook));
+    channels.extend(alerts.escalation.iter().filter_map(|stage| stage.email.clone()).map(AlertChannel::Email));
+    let commands = [
+        (Transition::Warning, &alerts.on_warning),
+        (Transition::Critical, &alerts.on_critical),
+        (Transition::Recovery, &alerts.on_recovery),
+    ];
+    for (transition, command) in commands {
+        if let Some(command) = command.as_ref().filter(|command| !command.command.trim().is_empty()) {
+            channels.push(AlertChannel::Command(transition, command.clone()));
+        }
+    }
+    #[cfg(feature = "mqtt")]
+    if config.mqtt.enabled && !config.mqtt.broker.is_empty() {
+        channels.push(AlertChannel::Mqtt(config.mqtt.broker.clone()));
+    }
+    let mut seen = Vec::new();
+    channels.retain(|channel| {
+        let new = !seen.contains(channel);
+        seen.push(channel.clone());
+        new
+    });
+    channels
+}
+
+pub enum ChannelTest {
+    Sending(Receiver<Result<String, String>>),
+    Done(Result<String, String>),
+}
+
+impl ChannelTest {
+    pub fn poll(&mut self) {
+        if let ChannelTest::Sending(rx) = self {
+            if let Ok(result) = rx.try_recv() {
+                *self = ChannelTest::Done(result);
+            }
+        }
+    }
+}
+
+// Sends a synthetic alert, marked as a test, straight to the channel: no queue,
+// cooldown, snooze or dry run, since the point is to see whether it gets there
+pub fn test_channel(channel: &AlertChannel, config: &Config, hostname: &str) -> ChannelTest {
+    let (tx, rx) = mpsc::channel();
+    let channel = channel.clone();
+    let hostname = hostname.to_string();
+    #[cfg(feature = "audio")]
+    let volume = config.alerts.sound.volume;
+    #[cfg(feature = "mqtt")]
+    let mqtt = config.mqtt.clone();
+    std::thread::spawn(move || {
+        let summary = format!("TEST alert from the memory monitor on {}; nothing is wrong", hostname);
+        let result = match &channel {
+            #[cfg(feature = "audio")]
+            AlertChannel::Sound => sound::play(volume).map(|player| format!("played through {}", player)),
+            AlertChannel::Webhook(url) => {
+                let body = serde_json::json!({
+                    "text": summary,
+                    "host": hostname,
+                    "test": true,
+                    "timestamp_ms": now_ms(),
+                });
+                send_webhook(&Delivery::new(url, body.to_string())).map(|()| "delivered".to_string())
+            }
+            AlertChannel::Email(to) => send_email(to, &summary).map(|()| "handed to sendmail".to_string()),
+            AlertChannel::Command(transition, command) => {
+                run_command(command, *transition, &hostname, 0.0, true).map(|()| "exited with 0".to_string())
+            }
+            #[cfg(feature = "mqtt")]
+            AlertChannel::Mqtt(_) => mqtt::test_publish(&mqtt, &hostname, &summary),
+        };
+        match &result {
+            Ok(message) => diag::info(format!("Test of {}: {}", channel.label(), message)),
+            Err(e) => diag::log(format!("Test of {} failed: {}", channel.label(), e)),
+        }
+        let _ = tx.send(result);
+    });
+    ChannelTest::Sending(rx)
+}
+
+// The process an escalation stage wants to kill, held while the user confirms
+#[derive(Clone)]
+pub struct KillTarget {
+    pub pid: u32,
+    pub name: String,
+    pub memory: u64,
+}
+
+// Network and mail delivery run on a background thread; the kill action needs the
+// live process table and runs inline. `sys` is None when the alarm is for a remote
+// host, whose processes we can't touch. A kill that needs confirming is returned
+// instead of carried out.
+pub fn fire_stage(
+    stage: &EscalationStage,
+    context: AlertContext,
+    sys: Option<&mut System>,
+    actions: &ActionsConfig,
+) -> Option<KillTarget> {
+    diag::info(format!("Escalating: {}", context.summary()));
+
+    let mut pending = None;
+    if stage.kill_top_offender {
+        match sys {
+            Some(sys) => match top_offender(sys, &actions.kill_exclude) {
+                None => diag::log("Emergency action: no process to kill"),
+                Some(target) if actions.dry_run => diag::info(format!(
+                    "Dry run: would kill {} (pid {}, {} bytes)",
+                    target.name, target.pid, target.memory
+                )),
+                Some(target) if actions.confirm_kill => pending = Some(target),
+                Some(target) => kill(sys, &target),
+            },
+            None => diag::log("Emergency action: skipped, processes belong to a remote host"),
+        }
+    }
+
+    let webhook = stage.webhook.clone();
+    let email = stage.email.clone();
+    if webhook.is_none() && email.is_none() {
+        return pending;
+    }
+    let outbox = webhook_outbox(actions);
+    std::thread::spawn(move || {
+        if let Some(url) = webhook {
+            deliver_webhook(Delivery::new(&url, webhook_body(&context)), outbox.as_ref());
+        }
+        if let Some(to) = email {
+            if let Err(e) = send_email(&to, &context.summary()) {
+                diag::log(format!("Email to {} failed: {}", to, e));
+            }
+        }
+    });
+    pending
+}
+
+// Posted once when a rule is raised, through the same queue as escalation webhooks
+pub fn fire_rule(rule: &AlertRule, hostname: &str, value: f32, actions: &ActionsConfig) {
+    let Some(url) = rule.webhook.clone() else { return };
+    let unit = rule.metric.unit();
+    let text = format!("Alert rule \"{}\" on {}: {:.1}{} for {}s", rule.name, hostname, value, unit, rule.for_secs);
+    let body = serde_json::json!({
+        "text": text,
+        "host": hostname,
+        "rule": rule.name,
+        "value": value,
+        "timestamp_ms": now_ms(),
+    })
+    .to_string();
+    let outbox = webhook_outbox(actions);
+    std::thread::spawn(move || deliver_webhook(Delivery::new(&url, body), outbox.as_ref()));
+}
+
+fn webhook_body(context: &AlertContext) -> String {
+    serde_json::json!({
+        "text": context.summary(),
+        "host": context.hostname,
+        "memory_percentage": context.memory_percentage,
+        "level": context.level,
+        "timestamp_ms": now_ms(),
+    })
+    .to_string()
+}
+
+fn webhook_outbox(actions: &ActionsConfig) -> Option<Outbox> {
+    (actions.webhook_queue > 0).then(|| Outbox::open(WEBHOOK_OUTBOX, actions.webhook_queue)).flatten()
+}
+
+// Deliveries go through the queue, so one to an endpoint that's still down waits
+// behind the earlier ones and the endpoint sees alerts in the order they fired
+fn deliver_webhook(delivery: Delivery, outbox: Option<&Outbox>) {
+    let Some(outbox) = outbox else {
+        if let Err(e) = send_webhook(&delivery) {
+            diag::log(format!("Webhook {} failed: {}", delivery.url, e));
+        }
+        return;
+    };
+    match outbox.push(delivery.clone()) {
+        Ok(dropped) => {
+            if dropped > 0 {
+                diag::log(format!("Webhook queue full: dropped the {} oldest", dropped));
+            }
+            flush_webhooks(outbox);
+        }
+        Err(e) => {
+            diag::log(format!("Could not queue webhook: {}", e));
+            if let Err(e) = send_webhook(&delivery) {
+                diag::log(format!("Webhook {} failed: {}", delivery.url, e));
+            }
+        }
+    }
+}
+
+fn flush_webhooks(outbox: &Outbox) {
+    let flushed = outbox.flush(|delivery| {
+        send_webhook(delivery).inspect_err(|e| {
+            diag::log(format!("Webhook {} failed, kept for retry: {}", delivery.url, e));
+        })
+    });
+    if let Err(e) = flushed {
+        diag::log(format!("Could not rewrite webhook queue: {}", e));
+    }
+}
+
+// Called periodically, so a queue left from a dropped connection (or a previous
+// session) drains once the network is back even if no new alert fires
+pub fn retry_webhooks(actions: &ActionsConfig) {
+    // Even reading the queue waits on whoever's rewriting it, so none of this
+    // happens on the UI thread
+    if let Some(outbox) = webhook_outbox(actions) {
+        std::thread::spawn(move || {
+            if outbox.len() > 0 {
+                flush_webhooks(&outbox);
+            }
+        });
+    }
+}
+
+fn send_webhook(delivery: &Delivery) -> Result<(), String> {
+    ureq::AgentBuilder::new()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .post(&delivery.url)
+        .set("Content-Type", "application/json")
+        .send_string(&delivery.body)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+// Hands the message to the local MTA rather than speaking SMTP ourselves
+fn send_email(to: &str, summary: &str) -> Result<(), String> {
+    let mut child = Command::new("sendmail")
+        .args(["-t", "-oi"])
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("could not run sendmail: {}", e))?;
+    let message = format!(
+        "To: {}\nSubject: [memory monitor] {}\n\n{}\n",
+        to, summary, summary,
+    );
+    child.stdin
+        .take()
+        .ok_or("sendmail stdin unavailable")?
+        .write_all(message.as_bytes())
+        .map_err(|e| e.to_string())?;
+    let status = child.wait().map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("sendmail exited with {}", status))
+    }
+}
+
+// The largest of our own user's processes, leaving out the excluded names. Other
+// users' processes are off limits: the biggest one on the box is as likely to
+// be the compositor, sshd or a database as the runaway.
+fn top_offender(sys: &mut System, exclude: &[String]) -> Option<KillTarget> {
+    sys.refresh_processes();
+    let own_pid = Pid::from_u32(std::process::id());
+    let own_user = sys.process(own_pid)?.user_id()?.clone();
+    sys.processes()
+        .values()
+        .filter(|process| process.pid() != own_pid && process.user_id() == Some(&own_user))
+        .filter(|process| !is_excluded(process.name(), exclude))
+        .max_by_key(|process| process.memory())
+        .map(|process| KillTarget {
+            pid: process.pid().as_u32(),
+            name: process.name().to_string(),
+            memory: process.memory(),
+        })
+}
+
+fn is_excluded(name: &str, exclude: &[String]) -> bool {
+    exclude.iter().any(|excluded| excluded.eq_ignore_ascii_case(name))
+}
+
+// By the time a confirmation comes back the pid may belong to something else,
+// so the name has to still match
+pub fn kill(sys: &mut System, target: &KillTarget) {
+    let pid = Pid::from_u32(target.pid);
+    sys.refresh_process(pid);
+    let killed = match sys.process(pid) {
+        Some(process) if process.name() == target.name => process.kill(),
+        _ => false,
+    };
+    diag::log(format!(
+        "Emergency action: kill {} (pid {}, {} bytes) {}",
+        target.name,
+        target.pid,
+        target.memory,
+        if killed { "sent" } else { "failed" },
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channels_are_listed_once() {
+        let hook = Some("https://hooks.example.org/memory".to_string());
+        let email = Some("oncall@example.org".to_string());
+        let stage = EscalationStage { webhook: hook.clone(), email, ..Default::default() };
+        let alerts = AlertsConfig {
+            escalation: vec![stage.clone(), stage],
+            rules: vec![AlertRule { webhook: hook.clone(), ..AlertRule::default() }],
+            on_critical: Some(AlertCommand { command: "true".into(), ..AlertCommand::default() }),
+            on_recovery: Some(AlertCommand::default()),
+            ..AlertsConfig::default()
+        };
+        let config = Config { alerts, ..Config::default() };
+        let labels: Vec<String> = channels(&config).iter().map(AlertChannel::label).collect();
+        let mut expected = vec![
+            "Webhook https://hooks.example.org/memory",
+            "Email oncall@example.org",
+            "critical command: true",
+        ];
+        if cfg!(feature = "audio") {
+            expected.insert(0, "Alarm sound");
+        }
+        assert_eq!(labels, expected);
+    }
+
+    #[test]
+    fn top_offender_is_one_of_our_own_and_not_excluded() {
+        let actions = ActionsConfig::default();
+        assert!(actions.confirm_kill);
+        assert!(is_excluded("xorg", &actions.kill_exclude));
+
+        let mut sys = System::new();
+        let Some(target) = top_offender(&mut sys, &actions.kill_exclude) else { return };
+        let own_user = sys.process(Pid::from_u32(std::process::id())).and_then(|process| process.user_id());
+        let process = sys.process(Pid::from_u32(target.pid)).unwrap();
+        assert_eq!(process.user_id(), own_user);
+        assert!(!is_excluded(&target.name, &actions.kill_exclude));
+
+        let exclude = vec![target.name.clone()];
+        let next = top_offender(&mut sys, &exclude);
+        assert!(next.is_none_or(|next| next.name != target.name));
+    }
+}
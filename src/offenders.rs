@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::processes::{self, ProcessGrouping, ProcessRow, ProcessSort};
+use crate::{config, diag};
+
+const FILE_NAME: &str = "offenders.json";
+const RECORD_INTERVAL: Duration = Duration::from_secs(60);
+const KEEP_DAYS: u64 = 8;
+const MS_PER_DAY: u64 = 86_400_000;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Period {
+    Day,
+    Week,
+}
+
+// Memory-hours per command name, bucketed by UTC day since the epoch. Every
+// RECORD_INTERVAL each command is credited with its RSS for that long, so a
+// browser that sat at 4 GB all afternoon outranks a build that spiked to 8 GB
+// for a minute.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Leaderboard {
+    days: BTreeMap<u64, HashMap<String, f64>>,
+    #[serde(skip)]
+    recorded: Option<Instant>,
+}
+
+impl Leaderboard {
+    pub fn load() -> Self {
+        let Some(path) = path() else {
+            return Self::default();
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                diag::log(format!("Ignoring invalid process history {}: {}", path.display(), e));
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn due(&self) -> bool {
+        self.recorded.is_none_or(|at| at.elapsed() >= RECORD_INTERVAL)
+    }
+
+    pub fn record(&mut self, rows: &[ProcessRow], now_ms: u64) -> std::io::Result<()> {
+        self.recorded = Some(Instant::now());
+        self.add(rows, now_ms);
+        self.save()
+    }
+
+    fn add(&mut self, rows: &[ProcessRow], now_ms: u64) {
+        let today = now_ms / MS_PER_DAY;
+        let hours = RECORD_INTERVAL.as_secs_f64() / 3600.0;
+        let day = self.days.entry(today).or_default();
+        for group in processes::group(rows, ProcessGrouping::Command, ProcessSort::Memory) {
+            *day.entry(group.key).or_default() += group.memory as f64 * hours;
+        }
+        self.days.retain(|&day, _| day + KEEP_DAYS > today);
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let path = path().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "no data directory")
+        })?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let text = serde_json::to_string(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(path, text)
+    }
+
+    // Byte-hours per command, largest first
+    pub fn top(&self, period: Period, now_ms: u64, count: usize) -> Vec<(String, f64)> {
+        let today = now_ms / MS_PER_DAY;
+        let first = match period {
+            Period::Day => today,
+            Period::Week => today.saturating_sub(6),
+        };
+        let mut totals: HashMap<&str, f64> = HashMap::new();
+        for (_, day) in self.days.range(first..=today) {
+            for (name, byte_hours) in day {
+                *totals.entry(name.as_str()).or_default() += byte_hours;
+            }
+        }
+        let mut top: Vec<(String, f64)> = totals.into_iter().map(|(name, total)| (name.to_string(), total)).collect();
+        top.sort_by(|a, b| b.1.total_cmp(&a.1));
+        top.truncate(count);
+        top
+    }
+}
+
+fn path() -> Option<PathBuf> {
+    config::data_dir().map(|dir| dir.join(FILE_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(pid: u32, name: &str, memory: u64) -> ProcessRow {
+        ProcessRow {
+            pid,
+            name: name.to_string(),
+            memory,
+            ..ProcessRow::default()
+        }
+    }
+
+    #[test]
+    fn ranks_memory_hours_by_period() {
+        let mut board = Leaderboard::default();
+        let yesterday = 20_000 * MS_PER_DAY;
+        let today = yesterday + MS_PER_DAY;
+        for _ in 0..60 {
+            board.add(&[row(1, "build", 8000)], yesterday);
+        }
+        for _ in 0..30 {
+            board.add(&[row(2, "browser", 4000), row(3, "browser", 2000)], today);
+        }
+        let day = board.top(Period::Day, today, 10);
+        assert_eq!(day.len(), 1);
+        assert_eq!(day[0].0, "browser");
+        assert!((day[0].1 - 3000.0).abs() < 1e-6);
+        let week: Vec<String> = board.top(Period::Week, today, 10).into_iter().map(|(name, _)| name).collect();
+        assert_eq!(week, ["build", "browser"]);
+
+        board.add(&[], today + KEEP_DAYS * MS_PER_DAY);
+        assert!(board.top(Period::Week, today + KEEP_DAYS * MS_PER_DAY, 10).is_empty());
+    }
+}
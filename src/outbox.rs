@@ -0,0 +1,206 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use rusty_mem_monitor::sample::now_ms;
+
+use crate::config;
+
+const DIR_NAME: &str = "outbox";
+
+// Senders run on their own threads; one at a time gets to rewrite a queue file.
+// Only held for the file itself, never while a delivery is on the network.
+static LOCK: Mutex<()> = Mutex::new(());
+// Queues with a flush under way, so two flushes don't send the same delivery
+static FLUSHING: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+// A payload that couldn't be delivered, waiting for its sink to come back
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Delivery {
+    pub queued_ms: u64,
+    // Where it goes: a webhook's URL, or an MQTT topic
+    pub url: String,
+    pub body: String,
+}
+
+impl Delivery {
+    pub fn new(url: &str, body: String) -> Self {
+        Self {
+            queued_ms: now_ms(),
+            url: url.to_string(),
+            body,
+        }
+    }
+}
+
+// Bounded on-disk queue, one JSON delivery per line, so data outlives a flaky
+// network and restarts. Past `capacity` the oldest deliveries are dropped.
+pub struct Outbox {
+    path: PathBuf,
+    capacity: usize,
+}
+
+impl Outbox {
+    pub fn new(path: PathBuf, capacity: usize) -> Self {
+        Self { path, capacity }
+    }
+
+    // `<data dir>/outbox/<name>.jsonl`
+    pub fn open(name: &str, capacity: usize) -> Option<Self> {
+        let path = config::data_dir()?.join(DIR_NAME).join(format!("{}.jsonl", name));
+        Some(Self::new(path, capacity))
+    }
+
+    #[cfg(any(feature = "gui", test))]
+    pub fn len(&self) -> usize {
+        let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        self.read().len()
+    }
+
+    // Returns how many old deliveries were dropped to make room
+    pub fn push(&self, delivery: Delivery) -> std::io::Result<usize> {
+        self.extend(vec![delivery])
+    }
+
+    // Several at once, for sinks that batch up while offline
+    pub fn extend(&self, deliveries: Vec<Delivery>) -> std::io::Result<usize> {
+        let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut queued = self.read();
+        queued.extend(deliveries);
+        let dropped = queued.len().saturating_sub(self.capacity);
+        queued.drain(..dropped);
+        self.write(&queued)?;
+        Ok(dropped)
+    }
+
+    // Sends what's queued, oldest first. Once a sink fails, the rest of its
+    // deliveries wait for the next flush so it still gets them in order; other
+    // sinks carry on. Returns how many were sent, or 0 if another flush of this
+    // queue is already running.
+    pub fn flush(&self, send: impl FnMut(&Delivery) -> Result<(), String>) -> std::io::Result<usize> {
+        {
+            let mut flushing = FLUSHING.lock().unwrap_or_else(|e| e.into_inner());
+            if flushing.contains(&self.path) {
+                return Ok(0);
+            }
+            flushing.push(self.path.clone());
+        }
+        let flushed = self.send_queued(send);
+        FLUSHING.lock().unwrap_or_else(|e| e.into_inner()).retain(|path| *path != self.path);
+        flushed
+    }
+
+    // The queue stays on disk while it's sent, so a crash halfway only means
+    // some deliveries go out twice
+    fn send_queued(&self, mut send: impl FnMut(&Delivery) -> Result<(), String>) -> std::io::Result<usize> {
+        let queued = {
+            let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            self.read()
+        };
+        let mut failed = HashSet::new();
+        let mut sent = Vec::new();
+        for delivery in queued {
+            if failed.contains(&delivery.url) {
+                continue;
+            }
+            match send(&delivery) {
+                Ok(()) => sent.push(delivery),
+                Err(_) => {
+                    failed.insert(delivery.url);
+                }
+            }
+        }
+        if sent.is_empty() {
+            return Ok(0);
+        }
+        // Pushes may have landed meanwhile, so only what went out is taken away
+        let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut queued = self.read();
+        for delivery in &sent {
+            if let Some(at) = queued.iter().position(|queued| queued == delivery) {
+                queued.remove(at);
+            }
+        }
+        self.write(&queued)?;
+        Ok(sent.len())
+    }
+
+    fn read(&self) -> Vec<Delivery> {
+        let Ok(text) = std::fs::read_to_string(&self.path) else {
+            return Vec::new();
+        };
+        text.lines().filter_map(|line| serde_json::from_str(line).ok()).collect()
+    }
+
+    // Written aside and renamed over, so a crash mid-write can't lose the queue
+    fn write(&self, queued: &[Delivery]) -> std::io::Result<()> {
+        if queued.is_empty() {
+            return match std::fs::remove_file(&self.path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            };
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let temp = self.path.with_extension("jsonl.tmp");
+        let mut file = std::fs::File::create(&temp)?;
+        for delivery in queued {
+            writeln!(file, "{}", serde_json::to_string(delivery)?)?;
+        }
+        file.sync_all()?;
+        std::fs::rename(&temp, &self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_newest_and_retries_failed_sinks_in_order() {
+        let path = std::env::temp_dir().join(format!("rmm-outbox-{}.jsonl", std::process::id()));
+        let outbox = Outbox::new(path.clone(), 3);
+        for (url, body) in [("a", "1"), ("b", "2"), ("a", "3"), ("b", "4")] {
+            outbox.push(Delivery::new(url, body.to_string())).unwrap();
+        }
+        assert_eq!(outbox.len(), 3);
+
+        let mut sent = Vec::new();
+        let count = outbox
+            .flush(|delivery| {
+                if delivery.url == "a" {
+                    return Err("unreachable".to_string());
+                }
+                sent.push(delivery.body.clone());
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!((count, sent), (2, vec!["2".to_string(), "4".to_string()]));
+        let left: Vec<_> = outbox.read().into_iter().map(|delivery| delivery.body).collect();
+        assert_eq!(left, ["3"]);
+
+        assert_eq!(outbox.flush(|_| Ok(())).unwrap(), 1);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn pushes_during_a_flush_are_kept() {
+        let path = std::env::temp_dir().join(format!("rmm-outbox-busy-{}.jsonl", std::process::id()));
+        let outbox = Outbox::new(path.clone(), 10);
+        outbox.push(Delivery::new("a", "1".to_string())).unwrap();
+        let count = outbox
+            .flush(|_| {
+                outbox.push(Delivery::new("a", "2".to_string())).unwrap();
+                assert_eq!(outbox.flush(|_| Ok(())).unwrap(), 0);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(count, 1);
+        let left: Vec<_> = outbox.read().into_iter().map(|delivery| delivery.body).collect();
+        assert_eq!(left, ["2"]);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,154 @@
+use serde::Deserialize;
+use std::process::Command;
+use std::sync::mpsc::{channel, Receiver};
+
+// Commit charge, pagefile and hibernation file layout on Windows, queried through
+// CIM since there's no stable Rust binding for it. Everything here is a no-op elsewhere.
+const QUERY: &str = "$p = @(Get-CimInstance Win32_PageFileUsage | \
+    Select-Object Name,AllocatedBaseSize,CurrentUsage,PeakUsage); \
+    $auto = (Get-CimInstance Win32_ComputerSystem).AutomaticManagedPagefile; \
+    $c = Get-CimInstance Win32_PerfFormattedData_PerfOS_Memory | Select-Object CommittedBytes,CommitLimit; \
+    [pscustomobject]@{ Pagefiles = $p; Automatic = $auto; Commit = $c } | ConvertTo-Json -Depth 3 -Compress";
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct Pagefile {
+    pub name: String,
+    // All sizes are in MB, as CIM reports them
+    pub allocated_base_size: u64,
+    pub current_usage: u64,
+    pub peak_usage: u64,
+}
+
+// Windows refuses allocations once the commit charge reaches the limit (RAM plus
+// pagefiles), however much RAM looks free, so this is its real "used / total"
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "PascalCase")]
+pub struct CommitCharge {
+    pub committed_bytes: u64,
+    pub commit_limit: u64,
+}
+
+impl CommitCharge {
+    pub fn percentage(&self) -> f64 {
+        self.committed_bytes as f64 / self.commit_limit.max(1) as f64 * 100.0
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct PagefileInfo {
+    pub pagefiles: Vec<Pagefile>,
+    pub automatic: bool,
+    #[serde(default)]
+    pub commit: Option<CommitCharge>,
+    #[serde(skip)]
+    pub hiberfil_bytes: Option<u64>,
+}
+
+#[derive(Clone, Copy)]
+pub enum Action {
+    EnableAutomaticPagefile,
+    DisableHibernation,
+}
+
+impl Action {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::EnableAutomaticPagefile => "Let Windows manage the pagefile (admin)",
+            Action::DisableHibernation => "Turn off hibernation to free hiberfil.sys (admin)",
+        }
+    }
+}
+
+pub fn query_async() -> Receiver<Result<PagefileInfo, String>> {
+    let (tx, rx) = channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(query());
+    });
+    rx
+}
+
+fn query() -> Result<PagefileInfo, String> {
+    if !cfg!(windows) {
+        return Err("pagefile details are only available on Windows".to_string());
+    }
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", QUERY])
+        .output()
+        .map_err(|e| format!("could not run powershell: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    let mut info: PagefileInfo = serde_json::from_slice(&output.stdout).map_err(|e| e.to_string())?;
+    let system_drive = std::env::var("SystemDrive").unwrap_or_else(|_| "C:".to_string());
+    info.hiberfil_bytes = std::fs::metadata(format!("{}\\hiberfil.sys", system_drive))
+        .ok()
+        .map(|meta| meta.len());
+    Ok(info)
+}
+
+// Plain-language advice for the configurations that most often explain
+// "out of memory" errors while RAM still looks free.
+pub fn guidance(info: &PagefileInfo, total_memory: u64) -> Vec<String> {
+    let mut advice = Vec::new();
+    if let Some(commit) = info.commit.filter(|commit| commit.percentage() >= 90.0) {
+        advice.push(format!(
+            "Commit charge is at {:.0}% of the limit; allocations start failing at 100% even \
+             with RAM free. A larger pagefile raises the limit.",
+            commit.percentage(),
+        ));
+    }
+    let allocated_mb: u64 = info.pagefiles.iter().map(|p| p.allocated_base_size).sum();
+    if info.pagefiles.is_empty() {
+        advice.push(
+            "No pagefile: the commit limit equals installed RAM, so programs can fail to \
+             allocate even while memory looks free."
+                .to_string(),
+        );
+    } else if !info.automatic && allocated_mb * 1024 * 1024 < total_memory / 4 {
+        advice.push(
+            "The fixed pagefile is smaller than a quarter of RAM; consider letting Windows \
+             manage its size."
+                .to_string(),
+        );
+    }
+    for pagefile in &info.pagefiles {
+        if pagefile.allocated_base_size > 0 && pagefile.peak_usage * 10 >= pagefile.allocated_base_size * 8 {
+            advice.push(format!(
+                "{} has peaked at {}% of its size; it may be too small.",
+                pagefile.name,
+                pagefile.peak_usage * 100 / pagefile.allocated_base_size,
+            ));
+        }
+    }
+    if info.hiberfil_bytes.is_some_and(|bytes| bytes > total_memory / 2) {
+        advice.push(
+            "hiberfil.sys is large; if you never hibernate, turning hibernation off reclaims \
+             that disk space."
+                .to_string(),
+        );
+    }
+    advice
+}
+
+// Changes need administrator rights, so they are launched through a UAC prompt
+pub fn run_elevated(action: Action) -> Result<(), String> {
+    if !cfg!(windows) {
+        return Err("only available on Windows".to_string());
+    }
+    let script = match action {
+        Action::EnableAutomaticPagefile => {
+            "Start-Process powershell -Verb RunAs -ArgumentList '-NoProfile','-Command',\
+             'Get-CimInstance Win32_ComputerSystem | Set-CimInstance -Property @{AutomaticManagedPagefile=$true}'"
+        }
+        Action::DisableHibernation => {
+            "Start-Process powercfg -Verb RunAs -ArgumentList '/hibernate','off'"
+        }
+    };
+    Command::new("powershell")
+        .args(["-NoProfile", "-Command", script])
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
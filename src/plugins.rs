@@ -0,0 +1,322 @@
+use std::path::Path;
+
+use rusty_mem_monitor::{meminfo, Sample};
+use wasmi::core::{Trap, F64};
+use wasmi::{Caller, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use crate::{config, diag};
+
+// Plugins are WebAssembly modules in <config dir>/plugins/*.wasm. A module has to
+// export
+//   rmm_abi_version() -> i32     equal to ABI_VERSION
+//   rmm_metric() -> f64          called once per sample, shown in the PLUGINS panel
+// and may export
+//   rmm_capabilities() -> i64    CAP_* bits for the host functions it calls
+// Host functions live in the "rmm" import module; calling one the plugin didn't
+// declare traps. Bump ABI_VERSION whenever any of this changes incompatibly.
+pub const ABI_VERSION: i32 = 1;
+// rmm.used_memory / total_memory / used_swap / total_swap() -> i64, in bytes
+pub const CAP_SAMPLE: i64 = 1;
+// rmm.meminfo(name_ptr: i32, name_len: i32) -> i64: a /proc/meminfo field in bytes, or -1
+pub const CAP_MEMINFO: i64 = 2;
+const CAPABILITIES: [(i64, &str); 2] = [(CAP_SAMPLE, "sample"), (CAP_MEMINFO, "meminfo")];
+
+// Interpreter instructions per call, and linear memory per plugin
+const FUEL_PER_CALL: u64 = 1_000_000;
+const MEMORY_LIMIT: usize = 16 * 1024 * 1024;
+// A plugin that traps this many times in a row is switched off until reloaded
+const MAX_FAILURES: u32 = 3;
+
+type SampleField = fn(&Sample) -> u64;
+
+struct HostState {
+    limits: StoreLimits,
+    capabilities: i64,
+    sample: Sample,
+}
+
+pub struct Plugin {
+    pub name: String,
+    pub capabilities: i64,
+    pub value: Option<f64>,
+    pub error: Option<String>,
+    failures: u32,
+    store: Store<HostState>,
+    metric: wasmi::TypedFunc<(), F64>,
+}
+
+impl Plugin {
+    pub fn load(name: &str, bytes: &[u8]) -> Result<Self, String> {
+        let mut engine_config = wasmi::Config::default();
+        engine_config.consume_fuel(true);
+        let engine = Engine::new(&engine_config);
+        let module = Module::new(&engine, bytes).map_err(|e| e.to_string())?;
+        let mut store = Store::new(
+            &engine,
+            HostState {
+                limits: StoreLimitsBuilder::new().memory_size(MEMORY_LIMIT).instances(1).build(),
+                capabilities: 0,
+                sample: Sample::default(),
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        refuel(&mut store);
+
+        let linker = host_functions(&engine).map_err(|e| e.to_string())?;
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .and_then(|pre| pre.start(&mut store))
+            .map_err(|e| e.to_string())?;
+
+        let version = instance
+            .get_typed_func::<(), i32>(&store, "rmm_abi_version")
+            .map_err(|_| "doesn't export rmm_abi_version".to_string())?
+            .call(&mut store, ())
+            .map_err(|e| e.to_string())?;
+        if version != ABI_VERSION {
+            return Err(format!("built for plugin API {}, this monitor speaks {}", version, ABI_VERSION));
+        }
+        let capabilities = match instance.get_typed_func::<(), i64>(&store, "rmm_capabilities") {
+            Ok(func) => func.call(&mut store, ()).map_err(|e| e.to_string())?,
+            Err(_) => 0,
+        };
+        let known = CAPABILITIES.iter().fold(0, |all, (bit, _)| all | bit);
+        if capabilities & !known != 0 {
+            return Err(format!("asks for unknown capabilities {:#x}", capabilities & !known));
+        }
+        store.data_mut().capabilities = capabilities;
+        let metric = instance
+            .get_typed_func::<(), F64>(&store, "rmm_metric")
+            .map_err(|_| "doesn't export rmm_metric".to_string())?;
+
+        Ok(Self {
+            name: name.to_string(),
+            capabilities,
+            value: None,
+            error: None,
+            failures: 0,
+            store,
+            metric,
+        })
+    }
+
+    pub fn disabled(&self) -> bool {
+        self.failures >= MAX_FAILURES
+    }
+
+    pub fn capability_names(&self) -> Vec<&'static str> {
+        CAPABILITIES
+            .iter()
+            .filter(|(bit, _)| self.capabilities & bit != 0)
+            .map(|(_, name)| *name)
+            .collect()
+    }
+
+    pub fn update(&mut self, sample: &Sample) {
+        if self.disabled() {
+            return;
+        }
+        self.store.data_mut().sample = *sample;
+        refuel(&mut self.store);
+        match self.metric.call(&mut self.store, ()) {
+            Ok(value) => {
+                self.value = Some(value.to_float());
+                self.error = None;
+                self.failures = 0;
+            }
+            Err(e) => {
+                self.failures += 1;
+                self.error = Some(e.to_string());
+                if self.disabled() {
+                    diag::log(format!("Plugin {} disabled after repeated errors: {}", self.name, e));
+                }
+            }
+        }
+    }
+}
+
+// Tops the store up to a full budget rather than adding to whatever is left over
+fn refuel(store: &mut Store<HostState>) {
+    let remaining = store.consume_fuel(0).unwrap_or(0);
+    let _ = store.add_fuel(FUEL_PER_CALL.saturating_sub(remaining));
+}
+
+fn require(caller: &Caller<'_, HostState>, capability: i64) -> Result<(), Trap> {
+    if caller.data().capabilities & capability != 0 {
+        return Ok(());
+    }
+    let name = CAPABILITIES.iter().find(|(bit, _)| *bit == capability).map_or("?", |(_, name)| name);
+    Err(Trap::new(format!("capability \"{}\" was not declared", name)))
+}
+
+fn host_functions(engine: &Engine) -> Result<Linker<HostState>, wasmi::errors::LinkerError> {
+    let mut linker = Linker::new(engine);
+    let fields: [(&str, SampleField); 4] = [
+        ("used_memory", |sample| sample.used_memory),
+        ("total_memory", |sample| sample.total_memory),
+        ("used_swap", |sample| sample.used_swap),
+        ("total_swap", |sample| sample.total_swap),
+    ];
+    for (name, field) in fields {
+        linker.func_wrap("rmm", name, move |caller: Caller<'_, HostState>| -> Result<i64, Trap> {
+            require(&caller, CAP_SAMPLE)?;
+            Ok(field(&caller.data().sample) as i64)
+        })?;
+    }
+    linker.func_wrap("rmm", "meminfo", |caller: Caller<'_, HostState>, ptr: i32, len: i32| -> Result<i64, Trap> {
+        require(&caller, CAP_MEMINFO)?;
+        let memory = caller
+            .get_export("memory")
+            .and_then(|export| export.into_memory())
+            .ok_or_else(|| Trap::new("meminfo needs an exported memory"))?;
+        let mut name = vec![0; len.clamp(0, 64) as usize];
+        memory
+            .read(&caller, ptr as u32 as usize, &mut name)
+            .map_err(|e| Trap::new(e.to_string()))?;
+        let name = String::from_utf8_lossy(&name);
+        Ok(meminfo::read()
+            .and_then(|info| info.get(name.as_ref()).copied())
+            .map_or(-1, |bytes| bytes as i64))
+    })?;
+    Ok(linker)
+}
+
+// Everything in the plugins directory, plus why each file that didn't load was refused
+#[derive(Default)]
+pub struct PluginHost {
+    pub plugins: Vec<Plugin>,
+    pub rejected: Vec<(String, String)>,
+}
+
+impl PluginHost {
+    pub fn load() -> Self {
+        let mut host = Self::default();
+        let Some(dir) = config::config_dir().map(|dir| dir.join("plugins")) else {
+            return host;
+        };
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            return host;
+        };
+        let mut paths: Vec<_> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+            .collect();
+        paths.sort();
+        for path in paths {
+            host.load_file(&path);
+        }
+        host
+    }
+
+    fn load_file(&mut self, path: &Path) {
+        let name = path.file_stem().map_or_else(String::new, |stem| stem.to_string_lossy().to_string());
+        let loaded = std::fs::read(path)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| Plugin::load(&name, &bytes));
+        match loaded {
+            Ok(plugin) => self.plugins.push(plugin),
+            Err(e) => {
+                diag::log(format!("Plugin {} not loaded: {}", path.display(), e));
+                self.rejected.push((name, e));
+            }
+        }
+    }
+
+    pub fn update(&mut self, sample: &Sample) {
+        for plugin in &mut self.plugins {
+            plugin.update(sample);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty() && self.rejected.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(id: u8, contents: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![id, contents.len() as u8];
+        bytes.extend_from_slice(contents);
+        bytes
+    }
+
+    // Hand-assembled module importing rmm.used_memory and exporting the three ABI
+    // functions; `metric` is the body of rmm_metric
+    fn plugin(abi: u8, capabilities: u8, metric: &[u8]) -> Vec<u8> {
+        let mut bytes = b"\0asm\x01\0\0\0".to_vec();
+        // () -> i32, () -> i64, () -> f64
+        bytes.extend(section(1, &[3, 0x60, 0, 1, 0x7f, 0x60, 0, 1, 0x7e, 0x60, 0, 1, 0x7c]));
+        let mut import = vec![1, 3];
+        import.extend_from_slice(b"rmm");
+        import.push(11);
+        import.extend_from_slice(b"used_memory");
+        import.extend_from_slice(&[0, 1]);
+        bytes.extend(section(2, &import));
+        bytes.extend(section(3, &[3, 0, 1, 2]));
+        let mut exports = vec![3];
+        for (name, index) in [("rmm_abi_version", 1), ("rmm_capabilities", 2), ("rmm_metric", 3)] {
+            exports.push(name.len() as u8);
+            exports.extend_from_slice(name.as_bytes());
+            exports.extend_from_slice(&[0, index]);
+        }
+        bytes.extend(section(7, &exports));
+        let mut code = vec![3];
+        for body in [&[0x41, abi][..], &[0x42, capabilities][..], metric] {
+            code.push(body.len() as u8 + 2);
+            code.push(0);
+            code.extend_from_slice(body);
+            code.push(0x0b);
+        }
+        bytes.extend(section(10, &code));
+        bytes
+    }
+
+    // call rmm.used_memory; f64.convert_i64_s
+    const USED_MEMORY: &[u8] = &[0x10, 0, 0xb9];
+    // loop br 0 end; f64.const 0
+    const SPIN: &[u8] = &[0x03, 0x40, 0x0c, 0, 0x0b, 0x44, 0, 0, 0, 0, 0, 0, 0, 0];
+
+    fn sample() -> Sample {
+        Sample {
+            used_memory: 4096,
+            total_memory: 8192,
+            ..Sample::default()
+        }
+    }
+
+    #[test]
+    fn calls_declared_host_functions() {
+        let mut plugin = Plugin::load("used", &plugin(1, CAP_SAMPLE as u8, USED_MEMORY)).unwrap();
+        assert_eq!(plugin.capability_names(), ["sample"]);
+        plugin.update(&sample());
+        assert_eq!(plugin.value, Some(4096.0));
+    }
+
+    #[test]
+    fn undeclared_capability_traps() {
+        let mut plugin = Plugin::load("sneaky", &plugin(1, 0, USED_MEMORY)).unwrap();
+        plugin.update(&sample());
+        assert_eq!(plugin.value, None);
+        assert!(plugin.error.as_deref().unwrap().contains("not declared"));
+    }
+
+    #[test]
+    fn rejects_other_abi_versions() {
+        let error = Plugin::load("future", &plugin(2, 0, USED_MEMORY)).err().unwrap();
+        assert!(error.contains("plugin API 2"));
+    }
+
+    #[test]
+    fn runaway_plugin_is_stopped_and_disabled() {
+        let mut plugin = Plugin::load("spin", &plugin(1, 0, SPIN)).unwrap();
+        for _ in 0..MAX_FAILURES + 1 {
+            plugin.update(&sample());
+        }
+        assert!(plugin.disabled());
+        assert_eq!(plugin.failures, MAX_FAILURES);
+    }
+}
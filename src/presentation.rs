@@ -0,0 +1,151 @@
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use crate::watchdog::Backend;
+
+// Detection runs helper tools, so the answer is reused for this long
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+// Our own "Keep awake while recording" lock, which mustn't count
+const OWN_INHIBITOR: &str = "rusty_mem_monitor";
+
+// Whether something on this desktop wants the screen left alone: a video player
+// or presentation holding an idle inhibitor, or a fullscreen window. The helper
+// tools run on a watchdog worker; callers get the last answer.
+pub struct Presentation {
+    backend: Backend<(), bool>,
+    active: bool,
+    checked: Option<Instant>,
+}
+
+impl Default for Presentation {
+    fn default() -> Self {
+        Self { backend: Backend::new("presentation"), active: false, checked: None }
+    }
+}
+
+impl Presentation {
+    // Call every frame; `wanted` starts a fresh check once the last one is old
+    pub fn poll(&mut self, wanted: bool) {
+        let due = wanted && self.checked.is_none_or(|at| at.elapsed() >= CHECK_INTERVAL);
+        if let Some(active) = self.backend.poll(due, |_| detect()) {
+            self.active = active;
+            self.checked = Some(Instant::now());
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn detect() -> bool {
+    // Browsers and players inhibit idle through logind, GNOME's session manager or
+    // both; X11 fullscreen windows catch the ones that do neither
+    let logind = run("systemd-inhibit", &["--list", "--no-pager", "--no-legend"]);
+    if logind.is_some_and(|text| blocks_idle(&text)) {
+        return true;
+    }
+    let gnome = run(
+        "gdbus",
+        &[
+            "call",
+            "--session",
+            "--dest",
+            "org.gnome.SessionManager",
+            "--object-path",
+            "/org/gnome/SessionManager",
+            "--method",
+            "org.gnome.SessionManager.IsInhibited",
+            // GSM_INHIBITOR_FLAG_IDLE
+            "8",
+        ],
+    );
+    if gnome.is_some_and(|text| text.contains("true")) {
+        return true;
+    }
+    std::env::var_os("DISPLAY").is_some() && x11_fullscreen()
+}
+
+#[cfg(target_os = "linux")]
+fn x11_fullscreen() -> bool {
+    let Some(active) = run("xprop", &["-root", "_NET_ACTIVE_WINDOW"]) else {
+        return false;
+    };
+    let Some(window) = active.split_whitespace().last().filter(|id| id.starts_with("0x") && *id != "0x0") else {
+        return false;
+    };
+    run("xprop", &["-id", window, "_NET_WM_STATE"]).is_some_and(|state| state.contains("_NET_WM_STATE_FULLSCREEN"))
+}
+
+#[cfg(target_os = "macos")]
+fn detect() -> bool {
+    run("pmset", &["-g", "assertions"]).is_some_and(|text| prevents_display_sleep(&text))
+}
+
+#[cfg(windows)]
+fn detect() -> bool {
+    // QUNS_BUSY (a fullscreen app), QUNS_RUNNING_D3D_FULL_SCREEN, QUNS_PRESENTATION_MODE
+    let mut state = 0;
+    let result = unsafe { win::SHQueryUserNotificationState(&mut state) };
+    result == 0 && (2..=4).contains(&state)
+}
+
+#[cfg(windows)]
+mod win {
+    #[link(name = "shell32")]
+    extern "system" {
+        pub fn SHQueryUserNotificationState(state: *mut i32) -> i32;
+    }
+}
+
+#[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+fn detect() -> bool {
+    false
+}
+
+#[cfg_attr(windows, allow(dead_code))]
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// `systemd-inhibit --list` rows: WHO UID USER PID COMM WHAT WHY MODE, where WHO
+// and WHY may contain spaces. WHAT is the colon-separated list holding "idle".
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn blocks_idle(list: &str) -> bool {
+    list.lines().any(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        !line.trim_start().starts_with(OWN_INHIBITOR)
+            && fields.last() == Some(&"block")
+            && fields.iter().any(|field| field.split(':').any(|what| what == "idle"))
+    })
+}
+
+// `pmset -g assertions` starts with a system-wide summary of assertion counts
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn prevents_display_sleep(assertions: &str) -> bool {
+    assertions.lines().any(|line| {
+        let mut fields = line.split_whitespace();
+        fields.next() == Some("PreventUserIdleDisplaySleep") && fields.next().is_some_and(|count| count != "0")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognises_other_apps_idle_inhibitors() {
+        let list = "\
+rusty_mem_monitor 1000 me 4242 sleep sleep:idle Recording block
+NetworkManager    0    root 812 NetworkManager sleep NetworkManager needs to turn off networks delay\n";
+        assert!(!blocks_idle(list));
+        let video = "Firefox 1000 me 5151 firefox idle video-playing block\n";
+        assert!(blocks_idle(&format!("{}{}", list, video)));
+
+        let summary = "Assertion status system-wide:\n   PreventUserIdleDisplaySleep    0\n   PreventSystemSleep 1\n";
+        assert!(!prevents_display_sleep(summary));
+        assert!(prevents_display_sleep(&summary.replace("DisplaySleep    0", "DisplaySleep    1")));
+    }
+}
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::config::{AlertsConfig, Config, FocusConfig};
+
+// The alerting part of a config as a standalone file, so a team can hand the
+// same rules to every machine without touching anyone's theme or registry.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct AlertPreset {
+    pub name: String,
+    pub alerts: AlertsConfig,
+    pub focus: FocusConfig,
+}
+
+impl AlertPreset {
+    pub fn from_config(config: &Config, name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            alerts: config.alerts.clone(),
+            focus: config.focus.clone(),
+        }
+    }
+
+    pub fn apply(self, config: &mut Config) {
+        config.alerts = self.alerts;
+        config.focus = self.focus;
+    }
+}
+
+pub fn export(path: &Path) -> Result<(), String> {
+    let name = path.file_stem().map_or_else(String::new, |stem| stem.to_string_lossy().to_string());
+    let preset = AlertPreset::from_config(&Config::load(), &name);
+    let text = toml::to_string_pretty(&preset).map_err(|e| e.to_string())?;
+    std::fs::write(path, text).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// Replaces this machine's alert rules; everything else in the config is kept
+pub fn import(path: &Path) -> Result<String, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let preset: AlertPreset = toml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let name = preset.name.clone();
+    let mut config = Config::load();
+    preset.apply(&mut config);
+    config.save().map_err(|e| format!("could not save config: {}", e))?;
+    Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusty_mem_monitor::alert::EscalationStage;
+
+    #[test]
+    fn applies_only_alerting_sections() {
+        let mut team = Config::default();
+        team.alerts.escalation.push(EscalationStage {
+            after_minutes: 5.0,
+            webhook: Some("https://hooks.example.org/ci".to_string()),
+            ..Default::default()
+        });
+        team.focus.growth_alert_percent = 20.0;
+        let text = toml::to_string_pretty(&AlertPreset::from_config(&team, "CI runner")).unwrap();
+
+        let mut mine = Config::default();
+        mine.appearance.theme = "Amber Terminal".to_string();
+        toml::from_str::<AlertPreset>(&text).unwrap().apply(&mut mine);
+        assert_eq!(mine.alerts.escalation.len(), 1);
+        assert_eq!(mine.focus.growth_alert_percent, 20.0);
+        assert_eq!(mine.appearance.theme, "Amber Terminal");
+    }
+}
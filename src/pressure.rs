@@ -0,0 +1,116 @@
+use std::process::Command;
+
+// macOS hands out "free" RAM to file cache and compresses before it swaps, so
+// used/total says little there. The kernel's own pressure level and the
+// compressor's size are what tell whether the machine is actually short.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PressureLevel {
+    Normal,
+    Warning,
+    Critical,
+}
+
+impl PressureLevel {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PressureLevel::Normal => "normal",
+            PressureLevel::Warning => "warning",
+            PressureLevel::Critical => "critical",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Compressor {
+    // Bytes of memory held in compressed form, and the RAM that takes
+    pub stored: u64,
+    pub occupied: u64,
+}
+
+impl Compressor {
+    pub fn ratio(&self) -> Option<f64> {
+        (self.stored > 0 && self.occupied > 0).then(|| self.stored as f64 / self.occupied as f64)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MacMemory {
+    pub level: Option<PressureLevel>,
+    pub compressor: Option<Compressor>,
+}
+
+pub fn query() -> Result<MacMemory, String> {
+    if !cfg!(target_os = "macos") {
+        return Err("memory pressure is only reported on macOS".to_string());
+    }
+    let level = run("sysctl", &["-n", "kern.memorystatus_vm_pressure_level"])
+        .ok()
+        .and_then(|text| parse_level(&text));
+    let compressor = run("vm_stat", &[])?;
+    Ok(MacMemory {
+        level,
+        compressor: parse_vm_stat(&compressor),
+    })
+}
+
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("could not run {}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+// The kernel's dispatch levels: 1 normal, 2 warn, 4 critical
+fn parse_level(text: &str) -> Option<PressureLevel> {
+    match text.trim() {
+        "1" => Some(PressureLevel::Normal),
+        "2" => Some(PressureLevel::Warning),
+        "4" => Some(PressureLevel::Critical),
+        _ => None,
+    }
+}
+
+// vm_stat counts pages; the page size is in its first line
+fn parse_vm_stat(text: &str) -> Option<Compressor> {
+    let page_size: u64 = text
+        .lines()
+        .next()?
+        .split("page size of ")
+        .nth(1)?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()?;
+    let pages = |name: &str| {
+        text.lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+            .and_then(|value| value.trim().trim_end_matches('.').parse::<u64>().ok())
+    };
+    Some(Compressor {
+        stored: pages("Pages stored in compressor")? * page_size,
+        occupied: pages("Pages occupied by compressor")? * page_size,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_vm_stat_and_level() {
+        let vm_stat = "Mach Virtual Memory Statistics: (page size of 16384 bytes)\n\
+                       Pages free:                               10000.\n\
+                       Pages stored in compressor:              300000.\n\
+                       Pages occupied by compressor:            100000.\n";
+        let compressor = parse_vm_stat(vm_stat).unwrap();
+        assert_eq!(compressor.occupied, 100000 * 16384);
+        assert_eq!(compressor.ratio(), Some(3.0));
+        assert_eq!(parse_vm_stat("Pages free: 1."), None);
+        assert_eq!(parse_level("4\n"), Some(PressureLevel::Critical));
+        assert_eq!(parse_level("0"), None);
+    }
+}
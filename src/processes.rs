@@ -0,0 +1,443 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, PidExt, ProcessExt, ProcessRefreshKind, System, SystemExt, UserExt};
+
+use rusty_mem_monitor::meminfo;
+
+use crate::i18n::tr;
+
+// How often a ProcessScanner walks all of /proc to find new processes
+const FULL_RESCAN: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Default)]
+pub struct ProcessRow {
+    pub pid: u32,
+    pub name: String,
+    pub user: Option<String>,
+    pub memory: u64,
+    // None where the platform doesn't expose per-process swap
+    pub swap: Option<u64>,
+    // Read from smaps_rollup for the rows on screen only, when the PSS column is shown
+    pub pss: Option<u64>,
+    // Only refreshed while the CPU column is shown
+    pub cpu: f32,
+    // Bytes read plus written per second since the previous refresh, while the I/O column is shown
+    pub io: Option<u64>,
+    pub threads: Option<u64>,
+    // Seconds since the epoch
+    pub start_time: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum ProcessColumn {
+    Pid,
+    Name,
+    User,
+    Rss,
+    Pss,
+    Swap,
+    Cpu,
+    Io,
+    Threads,
+    Started,
+}
+
+impl ProcessColumn {
+    pub const ALL: [Self; 10] = [
+        Self::Pid,
+        Self::Name,
+        Self::User,
+        Self::Rss,
+        Self::Pss,
+        Self::Swap,
+        Self::Cpu,
+        Self::Io,
+        Self::Threads,
+        Self::Started,
+    ];
+
+    pub fn header(&self) -> String {
+        tr(match self {
+            Self::Pid => "column-pid",
+            Self::Name => "column-name",
+            Self::User => "column-user",
+            Self::Rss => "column-rss",
+            Self::Pss => "column-pss",
+            Self::Swap => "column-swap",
+            Self::Cpu => "column-cpu",
+            Self::Io => "column-io",
+            Self::Threads => "column-threads",
+            Self::Started => "column-started",
+        })
+    }
+
+    fn default_width(&self) -> f32 {
+        match self {
+            Self::Name => 140.0,
+            Self::User | Self::Io => 90.0,
+            Self::Pid | Self::Cpu | Self::Threads => 50.0,
+            Self::Rss | Self::Pss | Self::Swap | Self::Started => 70.0,
+        }
+    }
+}
+
+// One column of the process table as the user arranged it; the order of the
+// list is the order on screen
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ColumnLayout {
+    pub column: ProcessColumn,
+    pub visible: bool,
+    pub width: f32,
+}
+
+pub fn default_columns() -> Vec<ColumnLayout> {
+    use ProcessColumn::*;
+    ProcessColumn::ALL
+        .iter()
+        .map(|&column| ColumnLayout {
+            column,
+            visible: matches!(column, Pid | Name | Rss | Swap),
+            width: column.default_width(),
+        })
+        .collect()
+}
+
+// Makes a saved arrangement usable: duplicates are dropped, columns it doesn't
+// know about yet are added at the end, hidden, and the name always stays visible
+// since that's where rows are picked
+pub fn complete_columns(columns: &mut Vec<ColumnLayout>) {
+    let mut seen = Vec::new();
+    columns.retain(|layout| {
+        let first = !seen.contains(&layout.column);
+        seen.push(layout.column);
+        first
+    });
+    for layout in default_columns() {
+        if !seen.contains(&layout.column) {
+            columns.push(ColumnLayout { visible: false, ..layout });
+        }
+    }
+    for layout in columns.iter_mut() {
+        layout.visible |= layout.column == ProcessColumn::Name;
+        layout.width = layout.width.clamp(30.0, 400.0);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ProcessGrouping {
+    None,
+    User,
+    Command,
+}
+
+// Sum over every process sharing a user or command name, so fifty small browser
+// renderers show up as the one big browser they are
+#[derive(Clone)]
+pub struct ProcessGroup {
+    pub key: String,
+    pub count: usize,
+    pub memory: u64,
+    pub swap: Option<u64>,
+}
+
+// A row picked in the process table, kept by key so it survives refreshes
+#[derive(Clone, PartialEq)]
+pub enum Selection {
+    Process(u32),
+    Group(String),
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ProcessSort {
+    Memory,
+    Swap,
+}
+
+// Memory and owner only: CPU and disk usage are what make a full refresh expensive
+pub fn collect(sys: &mut System) -> Vec<ProcessRow> {
+    sys.refresh_processes_specifics(ProcessRefreshKind::new().with_user());
+    let pids: Vec<Pid> = sys.processes().keys().copied().collect();
+    rows(sys, &pids, None)
+}
+
+// For the process table, refreshed every couple of seconds: in between full
+// rescans only the processes already known are re-read, and ones that exited
+// are dropped. Anything started since shows up with the next rescan.
+#[derive(Default)]
+pub struct ProcessScanner {
+    known: Vec<Pid>,
+    rescanned: Option<Instant>,
+    collected: Option<Instant>,
+}
+
+impl ProcessScanner {
+    // CPU and I/O are only read while their columns are shown
+    pub fn collect(&mut self, sys: &mut System, cpu: bool, io: bool) -> Vec<ProcessRow> {
+        let mut kind = ProcessRefreshKind::new();
+        if cpu {
+            kind = kind.with_cpu();
+        }
+        if io {
+            kind = kind.with_disk_usage();
+        }
+        let io_secs = self.collected.filter(|_| io).map(|at| at.elapsed().as_secs_f64());
+        self.collected = Some(Instant::now());
+        // CPU usage is worked out against one system-wide time delta, so it takes
+        // refreshing every process together
+        if cpu || self.rescanned.is_none_or(|at| at.elapsed() >= FULL_RESCAN) {
+            self.rescanned = Some(Instant::now());
+            sys.refresh_processes_specifics(kind.with_user());
+            self.known = sys.processes().keys().copied().collect();
+        } else {
+            self.known.retain(|pid| sys.refresh_process_specifics(*pid, kind));
+        }
+        rows(sys, &self.known, io_secs)
+    }
+
+    // The next collect does a full rescan
+    pub fn reset(&mut self) {
+        self.rescanned = None;
+    }
+}
+
+fn rows(sys: &mut System, pids: &[Pid], io_secs: Option<f64>) -> Vec<ProcessRow> {
+    // Accounts rarely change while we run; load them once for user names
+    if sys.users().is_empty() {
+        sys.refresh_users_list();
+    }
+    pids.iter()
+        .filter_map(|pid| {
+            let process = sys.process(*pid)?;
+            let (swap, threads) = read_status(pid.as_u32());
+            let disk = process.disk_usage();
+            Some(ProcessRow {
+                pid: pid.as_u32(),
+                name: process.name().to_string(),
+                user: process.user_id()
+                    .and_then(|uid| sys.get_user_by_id(uid))
+                    .map(|user| user.name().to_string()),
+                memory: process.memory(),
+                swap,
+                pss: None,
+                cpu: process.cpu_usage(),
+                io: io_secs
+                    .filter(|secs| *secs > 0.0)
+                    .map(|secs| ((disk.read_bytes + disk.written_bytes) as f64 / secs) as u64),
+                threads,
+                start_time: process.start_time(),
+            })
+        })
+        .collect()
+}
+
+pub fn sort(rows: &mut [ProcessRow], by: ProcessSort) {
+    match by {
+        ProcessSort::Memory => rows.sort_by_key(|row| std::cmp::Reverse(row.memory)),
+        ProcessSort::Swap => rows.sort_by_key(|row| std::cmp::Reverse(row.swap.unwrap_or(0))),
+    }
+}
+
+fn group_key(row: &ProcessRow, by: ProcessGrouping) -> &str {
+    match by {
+        ProcessGrouping::User => row.user.as_deref().unwrap_or("?"),
+        ProcessGrouping::None | ProcessGrouping::Command => row.name.as_str(),
+    }
+}
+
+pub fn group(rows: &[ProcessRow], by: ProcessGrouping, sort: ProcessSort) -> Vec<ProcessGroup> {
+    let mut groups: HashMap<&str, ProcessGroup> = HashMap::new();
+    for row in rows {
+        let key = group_key(row, by);
+        let group = groups.entry(key).or_insert_with(|| ProcessGroup {
+            key: key.to_string(),
+            count: 0,
+            memory: 0,
+            swap: None,
+        });
+        group.count += 1;
+        group.memory += row.memory;
+        if let Some(swap) = row.swap {
+            group.swap = Some(group.swap.unwrap_or(0) + swap);
+        }
+    }
+    let mut groups: Vec<ProcessGroup> = groups.into_values().collect();
+    match sort {
+        ProcessSort::Memory => groups.sort_by_key(|group| std::cmp::Reverse(group.memory)),
+        ProcessSort::Swap => groups.sort_by_key(|group| std::cmp::Reverse(group.swap.unwrap_or(0))),
+    }
+    groups
+}
+
+// What closing the selection would give back, and how many processes that is.
+// PSS splits shared pages between everything mapping them, so libraries other
+// processes keep using aren't counted; RSS stands in where PSS can't be read.
+pub fn reclaimable(
+    rows: &[ProcessRow],
+    by: ProcessGrouping,
+    selection: &Selection,
+    pss: impl Fn(u32) -> Option<u64>,
+) -> (usize, u64) {
+    rows.iter()
+        .filter(|row| match selection {
+            Selection::Process(pid) => row.pid == *pid,
+            Selection::Group(key) => group_key(row, by) == key,
+        })
+        .fold((0, 0), |(count, bytes), row| (count + 1, bytes + pss(row.pid).unwrap_or(row.memory)))
+}
+
+// Who we run as, and whether that's enough to read everyone's memory. Without
+// administrator rights, Windows and macOS report other users' processes with no
+// sizes at all; Linux lets anyone read RSS.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Privileges {
+    pub user: Option<String>,
+    pub restricted: bool,
+}
+
+impl Privileges {
+    pub fn of(sys: &System) -> Self {
+        let own = sys.process(Pid::from_u32(std::process::id()));
+        Self {
+            user: own
+                .and_then(|process| process.user_id())
+                .and_then(|uid| sys.get_user_by_id(uid))
+                .map(|user| user.name().to_string()),
+            restricted: !cfg!(target_os = "linux") && !is_elevated(sys),
+        }
+    }
+
+    // Another user's process that came back empty because we weren't allowed to look
+    pub fn hides(&self, row: &ProcessRow) -> bool {
+        self.restricted && row.memory == 0 && row.user != self.user
+    }
+}
+
+#[cfg(unix)]
+fn is_elevated(sys: &System) -> bool {
+    let own = sys.process(Pid::from_u32(std::process::id()));
+    own.and_then(|process| process.effective_user_id()).is_some_and(|uid| **uid == 0)
+}
+
+// `net session` needs an elevated token, which makes it the usual cheap check
+#[cfg(windows)]
+fn is_elevated(_sys: &System) -> bool {
+    std::process::Command::new("net")
+        .arg("session")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn is_elevated(_sys: &System) -> bool {
+    false
+}
+
+// Starts another copy of the monitor through a UAC prompt; the caller closes this one
+pub fn relaunch_elevated() -> Result<(), String> {
+    if !cfg!(windows) {
+        return Err("only available on Windows; run the monitor with sudo instead".to_string());
+    }
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    let script = format!("Start-Process -Verb RunAs -FilePath '{}'", exe.display().to_string().replace('\'', "''"));
+    let status = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-Command", &script])
+        .status()
+        .map_err(|e| e.to_string())?;
+    // Declining the prompt makes Start-Process fail
+    if status.success() { Ok(()) } else { Err("elevation was cancelled".to_string()) }
+}
+
+// VmSwap and Threads in /proc/<pid>/status; kernel threads and other users'
+// processes we can't read simply report nothing.
+#[cfg(target_os = "linux")]
+fn read_status(pid: u32) -> (Option<u64>, Option<u64>) {
+    let Ok(status) = std::fs::read_to_string(format!("/proc/{}/status", pid)) else {
+        return (None, None);
+    };
+    let fields = meminfo::parse(&status);
+    (fields.get("VmSwap").copied(), fields.get("Threads").copied())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_status(_pid: u32) -> (Option<u64>, Option<u64>) {
+    (None, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(pid: u32, name: &str, user: &str, memory: u64) -> ProcessRow {
+        ProcessRow {
+            pid,
+            name: name.to_string(),
+            user: Some(user.to_string()),
+            memory,
+            ..ProcessRow::default()
+        }
+    }
+
+    #[test]
+    fn restricted_views_hide_only_other_users_empty_rows() {
+        let privileges = Privileges { user: Some("alice".to_string()), restricted: true };
+        assert!(privileges.hides(&row(1, "svchost", "SYSTEM", 0)));
+        assert!(!privileges.hides(&row(2, "svchost", "SYSTEM", 4096)));
+        assert!(!privileges.hides(&row(3, "zombie", "alice", 0)));
+        let elevated = Privileges { restricted: false, ..privileges };
+        assert!(!elevated.hides(&row(1, "svchost", "SYSTEM", 0)));
+    }
+
+    #[test]
+    fn sums_by_command_and_user() {
+        let rows = [
+            row(1, "chrome", "alice", 80),
+            row(2, "chrome", "alice", 80),
+            row(3, "chrome", "bob", 80),
+            row(4, "postgres", "postgres", 200),
+        ];
+        let by_command = group(&rows, ProcessGrouping::Command, ProcessSort::Memory);
+        assert_eq!(by_command[0].key, "chrome");
+        assert_eq!((by_command[0].count, by_command[0].memory), (3, 240));
+        let by_user = group(&rows, ProcessGrouping::User, ProcessSort::Memory);
+        let keys: Vec<&str> = by_user.iter().map(|group| group.key.as_str()).collect();
+        assert_eq!(keys, ["postgres", "alice", "bob"]);
+    }
+
+    #[test]
+    fn reclaimable_prefers_pss() {
+        let rows = [row(1, "chrome", "alice", 80), row(2, "chrome", "alice", 80), row(3, "vim", "alice", 10)];
+        let pss = |pid| (pid == 1).then_some(30);
+        let chrome = Selection::Group("chrome".to_string());
+        assert_eq!(reclaimable(&rows, ProcessGrouping::Command, &chrome, pss), (2, 110));
+        assert_eq!(reclaimable(&rows, ProcessGrouping::None, &Selection::Process(3), pss), (1, 10));
+    }
+
+    #[test]
+    fn scanner_keeps_following_known_processes() {
+        let mut sys = System::new();
+        let mut scanner = ProcessScanner::default();
+        let own = std::process::id();
+        assert!(scanner.collect(&mut sys, false, false).iter().any(|row| row.pid == own && row.memory > 0));
+        // Well within FULL_RESCAN, so this one only re-reads the known pids
+        let rows = scanner.collect(&mut sys, false, false);
+        assert!(scanner.rescanned.is_some_and(|at| at.elapsed() < FULL_RESCAN));
+        assert!(rows.iter().any(|row| row.pid == own && row.memory > 0));
+    }
+
+    #[test]
+    fn saved_columns_are_completed() {
+        let mut columns = vec![
+            ColumnLayout { column: ProcessColumn::Swap, visible: true, width: 1000.0 },
+            ColumnLayout { column: ProcessColumn::Name, visible: false, width: 100.0 },
+            ColumnLayout { column: ProcessColumn::Swap, visible: false, width: 50.0 },
+        ];
+        complete_columns(&mut columns);
+        assert_eq!(columns.len(), ProcessColumn::ALL.len());
+        assert_eq!(columns[0], ColumnLayout { column: ProcessColumn::Swap, visible: true, width: 400.0 });
+        assert!(columns[1].visible);
+        assert!(columns[2..].iter().all(|layout| !layout.visible));
+    }
+}
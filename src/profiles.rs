@@ -0,0 +1,120 @@
+use std::collections::BTreeMap;
+
+use toml::Value;
+
+use crate::layout::Layout;
+
+// One setting that two profiles disagree on, with the values as TOML; None
+// where a profile doesn't have it at all
+pub struct Difference {
+    pub key: String,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+// Side-by-side comparison of what two --profile names keep. Tables are walked
+// down to their leaves and compared by dotted key; arrays count as one value.
+pub struct Comparison {
+    pub left: String,
+    pub right: String,
+    left_value: Value,
+    right_value: Value,
+    pub differences: Vec<Difference>,
+}
+
+impl Comparison {
+    pub fn new(left: (&str, &Layout), right: (&str, &Layout)) -> Result<Self, String> {
+        let left_value = Value::try_from(left.1).map_err(|e| e.to_string())?;
+        let right_value = Value::try_from(right.1).map_err(|e| e.to_string())?;
+        let differences = diff(&left_value, &right_value);
+        Ok(Self { left: left.0.to_string(), right: right.0.to_string(), left_value, right_value, differences })
+    }
+
+    // The layout `to_right` (or the left one otherwise) ends up with once `key`
+    // is copied over from the other side
+    pub fn copied(&self, key: &str, to_right: bool) -> Result<Layout, String> {
+        let (from, to) = if to_right {
+            (&self.left_value, &self.right_value)
+        } else {
+            (&self.right_value, &self.left_value)
+        };
+        let mut to = to.clone();
+        copy(key, from, &mut to);
+        to.try_into().map_err(|e: toml::de::Error| e.to_string())
+    }
+}
+
+fn flatten(prefix: &str, value: &Value, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Table(table) => {
+            for (key, value) in table {
+                let key = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten(&key, value, out);
+            }
+        }
+        leaf => {
+            out.insert(prefix.to_string(), leaf.clone());
+        }
+    }
+}
+
+fn diff(left: &Value, right: &Value) -> Vec<Difference> {
+    let (mut lefts, mut rights) = (BTreeMap::new(), BTreeMap::new());
+    flatten("", left, &mut lefts);
+    flatten("", right, &mut rights);
+    let mut keys: Vec<&String> = lefts.keys().chain(rights.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter(|key| lefts.get(*key) != rights.get(*key))
+        .map(|key| Difference {
+            key: key.clone(),
+            left: lefts.get(key).map(Value::to_string),
+            right: rights.get(key).map(Value::to_string),
+        })
+        .collect()
+}
+
+// Sets the leaf at dotted `key` in `to` to what it is in `from`, or removes it
+// when `from` doesn't have one
+fn copy(key: &str, from: &Value, to: &mut Value) {
+    let parts: Vec<&str> = key.split('.').collect();
+    let Some((leaf, tables)) = parts.split_last() else { return };
+    let source = tables.iter().try_fold(from, |value, part| value.get(part)).and_then(|table| table.get(leaf));
+    let mut target = to;
+    for part in tables {
+        let Value::Table(table) = target else { return };
+        target = table.entry(part.to_string()).or_insert_with(|| Value::Table(Default::default()));
+    }
+    let Value::Table(table) = target else { return };
+    match source {
+        Some(value) => {
+            table.insert(leaf.to_string(), value.clone());
+        }
+        None => {
+            table.remove(*leaf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn differences_copy_across() {
+        let mut work = Layout { compact: true, ..Layout::default() };
+        work.set_open("processes", true);
+        let home = Layout { window_size: Some([800.0, 600.0]), ..Layout::default() };
+        let comparison = Comparison::new(("work", &work), ("home", &home)).unwrap();
+        let keys: Vec<&str> = comparison.differences.iter().map(|difference| difference.key.as_str()).collect();
+        assert_eq!(keys, ["compact", "panels.processes", "window_size"]);
+        assert_eq!(comparison.differences[0].left.as_deref(), Some("true"));
+
+        let home = comparison.copied("panels.processes", true).unwrap();
+        assert!(home.is_open("processes", false) && !home.compact);
+        let work = comparison.copied("window_size", false).unwrap();
+        assert_eq!(work.window_size, Some([800.0, 600.0]));
+        assert!(comparison.copied("compact", false).is_ok_and(|work| !work.compact));
+    }
+}
@@ -0,0 +1,185 @@
+//! Python module `rusty_mem_monitor`, built with the `python` feature:
+//!
+//! ```text
+//! maturin develop
+//! ```
+
+// pyo3 0.20 macros trip this lint on newer compilers
+#![allow(non_local_definitions)]
+
+use pyo3::exceptions::PyIOError;
+use pyo3::prelude::*;
+
+use crate::{stats, History, Sample, Sampler};
+
+#[pyclass(name = "Sample", get_all)]
+#[derive(Clone, Copy)]
+pub struct PySample {
+    timestamp_ms: u64,
+    total_memory: u64,
+    used_memory: u64,
+    used_memory_min: u64,
+    used_memory_max: u64,
+    total_swap: u64,
+    used_swap: u64,
+    free_memory: u64,
+    buffers_memory: u64,
+    compressed_memory: u64,
+    cgroup_limited: bool,
+}
+
+impl From<&Sample> for PySample {
+    fn from(sample: &Sample) -> Self {
+        Self {
+            timestamp_ms: sample.timestamp_ms,
+            total_memory: sample.total_memory,
+            used_memory: sample.used_memory,
+            used_memory_min: sample.used_memory_min,
+            used_memory_max: sample.used_memory_max,
+            total_swap: sample.total_swap,
+            used_swap: sample.used_swap,
+            free_memory: sample.free_memory,
+            buffers_memory: sample.buffers_memory,
+            compressed_memory: sample.compressed_memory,
+            cgroup_limited: sample.cgroup_limited,
+        }
+    }
+}
+
+impl From<&PySample> for Sample {
+    fn from(sample: &PySample) -> Self {
+        Self {
+            timestamp_ms: sample.timestamp_ms,
+            total_memory: sample.total_memory,
+            used_memory: sample.used_memory,
+            used_memory_min: sample.used_memory_min,
+            used_memory_max: sample.used_memory_max,
+            total_swap: sample.total_swap,
+            used_swap: sample.used_swap,
+            free_memory: sample.free_memory,
+            buffers_memory: sample.buffers_memory,
+            compressed_memory: sample.compressed_memory,
+            cgroup_limited: sample.cgroup_limited,
+        }
+    }
+}
+
+#[pymethods]
+impl PySample {
+    fn memory_percentage(&self) -> f32 {
+        Sample::from(self).memory_percentage()
+    }
+
+    fn swap_percentage(&self) -> f32 {
+        Sample::from(self).swap_percentage()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Sample(timestamp_ms={}, used_memory={}, total_memory={}, used_swap={}, total_swap={})",
+            self.timestamp_ms, self.used_memory, self.total_memory, self.used_swap, self.total_swap
+        )
+    }
+}
+
+#[pyclass(name = "Sampler", unsendable)]
+pub struct PySampler(Sampler);
+
+#[pymethods]
+impl PySampler {
+    #[new]
+    fn new() -> Self {
+        Self(Sampler::new())
+    }
+
+    fn sample(&mut self) -> PySample {
+        PySample::from(&self.0.sample())
+    }
+
+    /// Blocks for `count * interval_ms`; see `Sampler::sample_window`.
+    fn sample_window(&mut self, count: usize, interval_ms: u64) -> PySample {
+        PySample::from(&self.0.sample_window(count, std::time::Duration::from_millis(interval_ms)))
+    }
+}
+
+#[pyclass(name = "Summary", get_all)]
+pub struct PySummary {
+    min: f64,
+    max: f64,
+    mean: f64,
+    p95: f64,
+}
+
+impl From<stats::Summary> for PySummary {
+    fn from(summary: stats::Summary) -> Self {
+        Self {
+            min: summary.min,
+            max: summary.max,
+            mean: summary.mean,
+            p95: summary.p95,
+        }
+    }
+}
+
+#[pyclass(name = "History")]
+pub struct PyHistory(History);
+
+#[pymethods]
+impl PyHistory {
+    #[new]
+    fn new(capacity: usize) -> Self {
+        Self(History::new(capacity))
+    }
+
+    fn push(&mut self, sample: &PySample) {
+        self.0.push(&Sample::from(sample));
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    fn samples(&self) -> Vec<PySample> {
+        self.0.iter().map(PySample::from).collect()
+    }
+
+    /// Samples with `start_ms <= timestamp_ms < end_ms`.
+    fn between(&self, start_ms: u64, end_ms: u64) -> Vec<PySample> {
+        self.0
+            .iter()
+            .filter(|sample| (start_ms..end_ms).contains(&sample.timestamp_ms))
+            .map(PySample::from)
+            .collect()
+    }
+
+    fn memory_summary(&self) -> Option<PySummary> {
+        stats::memory_summary(&self.0).map(PySummary::from)
+    }
+
+    fn swap_summary(&self) -> Option<PySummary> {
+        stats::swap_summary(&self.0).map(PySummary::from)
+    }
+
+    /// Bytes per minute, or None with fewer than two samples.
+    fn growth_per_minute(&self) -> Option<f64> {
+        stats::growth_per_minute(&self.0)
+    }
+}
+
+/// Reads a recording or history export. Lines that don't parse, like the header,
+/// are skipped.
+#[pyfunction]
+fn load_recording(path: &str) -> PyResult<Vec<PySample>> {
+    let text = std::fs::read_to_string(path).map_err(|e| PyIOError::new_err(format!("{}: {}", path, e)))?;
+    Ok(text.lines().filter_map(Sample::from_csv_row).map(|sample| PySample::from(&sample)).collect())
+}
+
+#[pymodule]
+fn rusty_mem_monitor(_py: Python<'_>, module: &PyModule) -> PyResult<()> {
+    module.add_class::<PySample>()?;
+    module.add_class::<PySampler>()?;
+    module.add_class::<PySummary>()?;
+    module.add_class::<PyHistory>()?;
+    module.add_function(wrap_pyfunction!(load_recording, module)?)?;
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use eframe::egui::{self, Align2, Color32, FontId, Pos2, Rect};
+use rand::Rng;
+use std::time::{Duration, Instant};
+
+const CELL: f32 = 14.0;
+const GLYPHS: &[u8] = b"0123456789ABCDEF<>/\\|=+*#$%&@";
+// Frame time while it's animating; calm rain doesn't need more, frantic rain gets twice as many
+const FRAME: Duration = Duration::from_millis(50);
+// A long pause (minimized, lean mode) shouldn't make every drop jump at once
+const MAX_STEP: f32 = 0.1;
+
+struct Drop {
+    // Head position in cells from the top; negative while it's still waiting to fall
+    head: f32,
+    // Cells per second at full intensity
+    speed: f32,
+    glyphs: Vec<u8>,
+}
+
+// "Digital rain" behind the main panel. `intensity` (0 to 1) sets how many
+// columns fall and how fast, so the pressure on the machine reads across the room.
+#[derive(Default)]
+pub struct Rain {
+    drops: Vec<Drop>,
+    painted: Option<Instant>,
+}
+
+impl Rain {
+    pub fn paint(&mut self, painter: &egui::Painter, rect: Rect, intensity: f32, color: Color32) {
+        let intensity = intensity.clamp(0.0, 1.0);
+        let dt = self.painted.map_or(0.0, |at| at.elapsed().as_secs_f32().min(MAX_STEP));
+        self.painted = Some(Instant::now());
+        let mut rng = rand::thread_rng();
+        let columns = (rect.width() / CELL).ceil() as usize;
+        let rows = rect.height() / CELL;
+        if self.drops.len() != columns {
+            // Start mid-fall rather than with an empty screen
+            self.drops = (0..columns).map(|_| spawn(&mut rng, rows)).collect();
+            for drop in &mut self.drops {
+                drop.head += rng.gen_range(0.0..rows.max(1.0));
+            }
+        }
+
+        let pace = 0.3 + 2.7 * intensity * intensity;
+        let font = FontId::monospace(CELL * 0.9);
+        for (column, drop) in self.drops.iter_mut().enumerate() {
+            drop.head += drop.speed * pace * dt;
+            if drop.head - drop.glyphs.len() as f32 > rows {
+                // Sparse at low usage: most columns wait a while before falling again
+                *drop = spawn(&mut rng, rows);
+                if rng.gen::<f32>() > 0.25 + 0.75 * intensity {
+                    drop.head -= rows;
+                }
+                continue;
+            }
+            if rng.gen::<f32>() < 0.1 + 0.4 * intensity {
+                let index = rng.gen_range(0..drop.glyphs.len());
+                drop.glyphs[index] = random_glyph(&mut rng);
+            }
+            let x = rect.left() + (column as f32 + 0.5) * CELL;
+            for (i, glyph) in drop.glyphs.iter().enumerate() {
+                let row = drop.head.floor() - i as f32;
+                if row < 0.0 || row > rows {
+                    continue;
+                }
+                // Brightest at the head, fading up the tail; faint overall so it stays a background
+                let fade = 1.0 - i as f32 / drop.glyphs.len() as f32;
+                let alpha = if i == 0 { 0.45 } else { 0.25 * fade };
+                let pos = Pos2::new(x, rect.top() + row * CELL);
+                let text = (*glyph as char).to_string();
+                painter.text(pos, Align2::CENTER_TOP, text, font.clone(), color.gamma_multiply(alpha));
+            }
+        }
+    }
+
+    pub fn frame(intensity: f32) -> Duration {
+        if intensity >= 0.9 { FRAME / 2 } else { FRAME }
+    }
+}
+
+fn spawn(rng: &mut impl Rng, rows: f32) -> Drop {
+    let length = rng.gen_range(6..20);
+    Drop {
+        head: -rng.gen_range(0.0..rows.max(1.0)),
+        speed: rng.gen_range(4.0..12.0),
+        glyphs: (0..length).map(|_| random_glyph(rng)).collect(),
+    }
+}
+
+fn random_glyph(rng: &mut impl Rng) -> u8 {
+    GLYPHS[rng.gen_range(0..GLYPHS.len())]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_the_width_and_falls_faster_under_pressure() {
+        let ctx = egui::Context::default();
+        let rect = Rect::from_min_size(Pos2::ZERO, egui::vec2(140.0, 280.0));
+        let mut fallen = [0.0; 2];
+        for (intensity, fallen) in [0.0, 1.0].into_iter().zip(&mut fallen) {
+            let mut rain = Rain::default();
+            let _ = ctx.run(egui::RawInput::default(), |ctx| {
+                let painter = ctx.layer_painter(egui::LayerId::background());
+                rain.paint(&painter, rect, intensity, Color32::GREEN);
+                assert_eq!(rain.drops.len(), 10);
+                let before: f32 = rain.drops.iter().map(|drop| drop.head).sum();
+                rain.painted = Some(Instant::now() - Duration::from_millis(50));
+                rain.paint(&painter, rect, intensity, Color32::GREEN);
+                // None is far enough down to have fallen out and respawned yet
+                *fallen = rain.drops.iter().map(|drop| drop.head).sum::<f32>() - before;
+            });
+        }
+        assert!(fallen[1] > fallen[0], "{:?}", fallen);
+    }
+}
@@ -0,0 +1,62 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use rusty_mem_monitor::sample::{self, Sample};
+use rusty_mem_monitor::History;
+
+use crate::{bookmarks, config};
+
+pub struct Recorder {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    samples: u64,
+}
+
+impl Recorder {
+    pub fn start() -> std::io::Result<Self> {
+        let path = output_path("recordings", "recording", "csv")?;
+        let mut writer = BufWriter::new(File::create(&path)?);
+        writeln!(writer, "{}", sample::CSV_HEADER)?;
+        Ok(Self { path, writer, samples: 0 })
+    }
+
+    // Flushed per sample so an overnight capture survives a crash or power loss
+    pub fn record(&mut self, sample: &Sample) -> std::io::Result<()> {
+        writeln!(self.writer, "{}", sample.csv_row())?;
+        self.writer.flush()?;
+        self.samples += 1;
+        Ok(())
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+}
+
+// One-shot dump of what's currently on the plot, in the same format as a recording
+pub fn export_history(history: &History) -> std::io::Result<PathBuf> {
+    let path = output_path("exports", "history", "csv")?;
+    let mut writer = BufWriter::new(File::create(&path)?);
+    writeln!(writer, "{}", sample::CSV_HEADER)?;
+    for sample in history.iter() {
+        writeln!(writer, "{}", sample.csv_row())?;
+    }
+    writer.flush()?;
+    if let (Some(first), Some(last)) = (history.iter().next(), history.iter().last()) {
+        bookmarks::export_range(&path, first.timestamp_ms, last.timestamp_ms)?;
+    }
+    Ok(path)
+}
+
+pub fn output_path(subdir: &str, prefix: &str, extension: &str) -> std::io::Result<PathBuf> {
+    let dir = config::data_dir()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no data directory"))?
+        .join(subdir);
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir.join(format!("{}-{}.{}", prefix, sample::now_ms(), extension)))
+}
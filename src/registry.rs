@@ -0,0 +1,280 @@
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::time::Duration;
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::config::{self, RegistryConfig};
+use crate::diag;
+use crate::i18n::{tr, tr_args};
+
+const MAX_DOWNLOAD_BYTES: u64 = 16 * 1024 * 1024;
+const HTTP_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    Plugin,
+    Theme,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct RegistryEntry {
+    pub name: String,
+    pub kind: EntryKind,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    pub url: String,
+    pub sha256: String,
+    // Plugins only; entries without it are assumed to match
+    #[serde(default)]
+    pub api_version: Option<i32>,
+}
+
+#[derive(Deserialize)]
+struct RegistryIndex {
+    entries: Vec<RegistryEntry>,
+}
+
+enum RegistryEvent {
+    Listed(Result<Vec<RegistryEntry>, String>),
+    Installed(String, Result<PathBuf, String>),
+}
+
+// Network work happens on short-lived threads; results are picked up by `poll`
+// from the UI thread so a slow registry never stalls rendering.
+pub struct RegistryClient {
+    pub entries: Vec<RegistryEntry>,
+    pub status: String,
+    pending: usize,
+    tx: Sender<RegistryEvent>,
+    rx: Receiver<RegistryEvent>,
+}
+
+impl RegistryClient {
+    pub fn new() -> Self {
+        let (tx, rx) = channel();
+        Self {
+            entries: Vec::new(),
+            status: String::new(),
+            pending: 0,
+            tx,
+            rx,
+        }
+    }
+
+    pub fn busy(&self) -> bool {
+        self.pending > 0
+    }
+
+    pub fn refresh(&mut self, config: &RegistryConfig) {
+        if !config.enabled || config.url.is_empty() {
+            return;
+        }
+        let url = config.url.clone();
+        let tx = self.tx.clone();
+        self.pending += 1;
+        self.status = tr("registry-fetching");
+        std::thread::spawn(move || {
+            let _ = tx.send(RegistryEvent::Listed(fetch_index(&url)));
+        });
+    }
+
+    pub fn install(&mut self, entry: &RegistryEntry) {
+        let Some(path) = install_path(entry) else {
+            self.status = tr("registry-no-config-dir");
+            return;
+        };
+        let entry = entry.clone();
+        let tx = self.tx.clone();
+        self.pending += 1;
+        self.status = tr_args("registry-downloading", &[("name", &entry.name)]);
+        std::thread::spawn(move || {
+            let result = download_verified(&entry, &path).map(|_| path);
+            let _ = tx.send(RegistryEvent::Installed(entry.name, result));
+        });
+    }
+
+    pub fn poll(&mut self) {
+        while let Ok(event) = self.rx.try_recv() {
+            self.pending = self.pending.saturating_sub(1);
+            match event {
+                RegistryEvent::Listed(Ok(entries)) => {
+                    self.status = tr_args("registry-available", &[("count", &entries.len())]);
+                    self.entries = entries;
+                }
+                RegistryEvent::Listed(Err(e)) => {
+                    self.status = tr_args("registry-error", &[("error", &e)]);
+                    diag::log(&self.status);
+                }
+                RegistryEvent::Installed(name, Ok(path)) => {
+                    self.status = tr_args("registry-installed-to", &[("name", &name), ("path", &path.display())]);
+                }
+                RegistryEvent::Installed(name, Err(e)) => {
+                    self.status = tr_args("registry-install-failed", &[("name", &name), ("error", &e)]);
+                    diag::log(&self.status);
+                }
+            }
+        }
+    }
+}
+
+pub fn is_installed(entry: &RegistryEntry) -> bool {
+    install_path(entry).is_some_and(|path| path.exists())
+}
+
+fn install_path(entry: &RegistryEntry) -> Option<PathBuf> {
+    config::config_dir().map(|dir| dir.join(relative_path(entry)))
+}
+
+// `<plugins|themes>/<name>-<version>.<ext>` under the config directory
+fn relative_path(entry: &RegistryEntry) -> PathBuf {
+    let subdir = match entry.kind {
+        EntryKind::Plugin => "plugins",
+        EntryKind::Theme => "themes",
+    };
+    let extension = entry.url
+        .rsplit('/')
+        .next()
+        .and_then(|file| file.rsplit_once('.'))
+        .map(|(_, ext)| sanitize(ext))
+        .unwrap_or_default();
+    let mut file_name = format!("{}-{}", sanitize(&entry.name), sanitize(&entry.version));
+    if !extension.is_empty() {
+        file_name.push('.');
+        file_name.push_str(&extension);
+    }
+    PathBuf::from(subdir).join(file_name)
+}
+
+// Registry data is untrusted: never let it pick directories or hidden files
+fn sanitize(part: &str) -> String {
+    part.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '_' })
+        .collect::<String>()
+        .trim_start_matches('.')
+        .to_string()
+}
+
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new().timeout(HTTP_TIMEOUT).build()
+}
+
+fn fetch_index(url: &str) -> Result<Vec<RegistryEntry>, String> {
+    let response = agent().get(url).call().map_err(|e| e.to_string())?;
+    let index: RegistryIndex = serde_json::from_reader(response.into_reader().take(MAX_DOWNLOAD_BYTES))
+        .map_err(|e| e.to_string())?;
+    Ok(index.entries)
+}
+
+fn download_verified(entry: &RegistryEntry, path: &PathBuf) -> Result<(), String> {
+    let response = agent().get(&entry.url).call().map_err(|e| e.to_string())?;
+    let bytes = read_verified(response.into_reader(), &entry.sha256)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(path, bytes).map_err(|e| e.to_string())
+}
+
+// Nothing reaches the disk unless it fits the size limit and matches the
+// registry's checksum
+fn read_verified(reader: impl Read, sha256: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    reader
+        .take(MAX_DOWNLOAD_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| e.to_string())?;
+    if bytes.len() as u64 > MAX_DOWNLOAD_BYTES {
+        return Err("download exceeds size limit".to_string());
+    }
+
+    let digest: String = Sha256::digest(&bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    if !digest.eq_ignore_ascii_case(sha256.trim()) {
+        return Err(format!("checksum mismatch (got {})", digest));
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use std::path::{Component, Path};
+
+    // sha256("hello")
+    const HELLO_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    fn entry(name: &str, version: &str, url: &str) -> RegistryEntry {
+        RegistryEntry {
+            name: name.to_string(),
+            kind: EntryKind::Plugin,
+            version: version.to_string(),
+            description: String::new(),
+            url: url.to_string(),
+            sha256: HELLO_SHA256.to_string(),
+            api_version: None,
+        }
+    }
+
+    fn assert_contained(path: &Path) {
+        let components: Vec<Component> = path.components().collect();
+        assert_eq!(components.len(), 2, "{}", path.display());
+        assert!(components.iter().all(|c| matches!(c, Component::Normal(_))), "{}", path.display());
+        let file_name = path.file_name().unwrap().to_string_lossy();
+        assert!(!file_name.starts_with('.'), "{}", path.display());
+    }
+
+    #[test]
+    fn sanitize_strips_separators_and_leading_dots() {
+        assert_eq!(sanitize("cpu-graph_2.wasm"), "cpu-graph_2.wasm");
+        assert_eq!(sanitize("../../etc/passwd"), "_.._etc_passwd");
+        assert_eq!(sanitize("..\\windows"), "_windows");
+        assert_eq!(sanitize(".hidden"), "hidden");
+        assert_eq!(sanitize("/abs"), "_abs");
+        assert_eq!(sanitize("a b:c"), "a_b_c");
+    }
+
+    #[test]
+    fn install_path_stays_in_its_directory() {
+        let path = relative_path(&entry("cpu-graph", "1.2.0", "https://example.org/cpu-graph.wasm"));
+        assert_eq!(path, PathBuf::from("plugins").join("cpu-graph-1.2.0.wasm"));
+
+        let hostile = [
+            entry("../../../.bashrc", "1", "https://example.org/x.wasm"),
+            entry("..", "..", "https://example.org/x.wasm"),
+            entry(".profile", "/etc/passwd", "https://example.org/x.wasm"),
+            entry("x", "1", "https://example.org/x./../../.ssh"),
+            entry("x", "1", "https://example.org/x.wasm/..\\..\\evil"),
+            entry("x", "1", "https://example.org/x.."),
+        ];
+        for entry in &hostile {
+            assert_contained(&relative_path(entry));
+        }
+        let mut theme = entry("/tmp/x", "1", "https://example.org/theme.toml");
+        theme.kind = EntryKind::Theme;
+        assert_eq!(relative_path(&theme), PathBuf::from("themes").join("_tmp_x-1.toml"));
+    }
+
+    #[test]
+    fn download_must_match_its_checksum() {
+        assert_eq!(read_verified(Cursor::new("hello"), HELLO_SHA256).unwrap(), b"hello");
+        assert!(read_verified(Cursor::new("hello"), &HELLO_SHA256.to_uppercase()).is_ok());
+        let err = read_verified(Cursor::new("hellp"), HELLO_SHA256).unwrap_err();
+        assert!(err.starts_with("checksum mismatch"), "{}", err);
+        assert!(read_verified(Cursor::new("hello"), "").is_err());
+    }
+
+    #[test]
+    fn download_over_the_size_limit_is_refused() {
+        let oversized = std::io::repeat(0).take(MAX_DOWNLOAD_BYTES + 1);
+        assert_eq!(read_verified(oversized, HELLO_SHA256).unwrap_err(), "download exceeds size limit");
+        let at_limit = std::io::repeat(0).take(MAX_DOWNLOAD_BYTES);
+        assert!(read_verified(at_limit, HELLO_SHA256).unwrap_err().starts_with("checksum mismatch"));
+    }
+}
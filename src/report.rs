@@ -0,0 +1,167 @@
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use rusty_mem_monitor::stats::{self, Summary};
+use rusty_mem_monitor::units::format_bytes;
+use rusty_mem_monitor::History;
+
+use crate::events::{Event, EventKind};
+use crate::processes::ProcessRow;
+use crate::recorder;
+use crate::screenshot::{self, escape, format_datetime, Color, Marker};
+
+const STYLE: &str = "body{font-family:monospace;background:#0b0f0b;color:#c8d8c8;margin:2em}\
+h1,h2{color:#00ff66}table{border-collapse:collapse;margin-bottom:1.5em}\
+td,th{padding:2px 12px;text-align:left;border-bottom:1px solid #223}\
+svg{background:#111611}.dim{color:#778877}";
+
+// Everything a report covers; the caller gathers it so this module never touches the GUI
+pub struct Report<'a> {
+    pub host: String,
+    pub history: &'a History,
+    pub events: Vec<&'a Event>,
+    // Empty when the samples come from another machine
+    pub processes: Vec<ProcessRow>,
+    // Byte-hours per command today, from the leaderboard
+    pub offenders: Vec<(String, f64)>,
+}
+
+// A single self-contained HTML file: inline CSS and SVG, no scripts or external
+// resources, so it can be attached to a ticket and opened anywhere
+pub fn render(report: &Report) -> String {
+    let mut html = String::new();
+    let (first, last) = match (report.history.first(), report.history.last()) {
+        (Some(first), Some(last)) => (first.timestamp_ms, last.timestamp_ms),
+        _ => (0, 0),
+    };
+    let title = format!("Memory report: {}", report.host);
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{}</title><style>{}</style></head><body>\n",
+        escape(&title),
+        STYLE
+    );
+    let _ = writeln!(html, "<h1>{}</h1>", escape(&title));
+    let _ = writeln!(
+        html,
+        "<p class=\"dim\">{} to {}, {} samples</p>",
+        format_datetime(first),
+        format_datetime(last),
+        report.history.len()
+    );
+
+    html.push_str("<h2>Usage</h2>\n");
+    let markers: Vec<_> = report
+        .events
+        .iter()
+        .map(|event| Marker {
+            timestamp_ms: event.timestamp_ms,
+            color: event_color(event.kind),
+            label: &event.message,
+        })
+        .collect();
+    html.push_str(&screenshot::svg_chart(report.history, &markers));
+
+    html.push_str("<h2>Statistics</h2>\n<table><tr><th></th><th>min</th><th>avg</th><th>p95</th><th>max</th></tr>\n");
+    let summaries = [("RAM", stats::memory_summary(report.history)), ("Swap", stats::swap_summary(report.history))];
+    for (name, summary) in summaries {
+        if let Some(Summary { min, max, mean, p95 }) = summary {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{:.1}%</td><td>{:.1}%</td><td>{:.1}%</td><td>{:.1}%</td></tr>",
+                name, min, mean, p95, max
+            );
+        }
+    }
+    html.push_str("</table>\n");
+    if let Some(growth) = stats::growth_per_minute(report.history) {
+        let sign = if growth < 0.0 { "-" } else { "+" };
+        let _ = writeln!(html, "<p>Growth: {}{}/min</p>", sign, format_bytes(growth.abs() as u64));
+    }
+
+    html.push_str("<h2>Events</h2>\n");
+    if report.events.is_empty() {
+        html.push_str("<p class=\"dim\">None</p>\n");
+    } else {
+        html.push_str("<table>\n");
+        for event in &report.events {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td style=\"color:{}\">{}</td></tr>",
+                format_datetime(event.timestamp_ms),
+                event_color(event.kind),
+                escape(&event.message)
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    if !report.processes.is_empty() {
+        html.push_str("<h2>Top processes</h2>\n<table><tr><th>PID</th><th>Name</th><th>RSS</th><th>Swap</th></tr>\n");
+        for row in &report.processes {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                row.pid,
+                escape(&row.name),
+                format_bytes(row.memory),
+                row.swap.map(format_bytes).unwrap_or_else(|| "-".to_string())
+            );
+        }
+        html.push_str("</table>\n");
+    }
+    if !report.offenders.is_empty() {
+        html.push_str("<h2>Top memory users today</h2>\n<table><tr><th>Command</th><th>Memory-hours</th></tr>\n");
+        for (name, byte_hours) in &report.offenders {
+            let byte_hours = format_bytes(*byte_hours as u64);
+            let _ = writeln!(html, "<tr><td>{}</td><td>{}h</td></tr>", escape(name), byte_hours);
+        }
+        html.push_str("</table>\n");
+    }
+    html.push_str("</body></html>\n");
+    html
+}
+
+pub fn write(report: &Report) -> std::io::Result<PathBuf> {
+    let path = recorder::output_path("reports", "report", "html")?;
+    std::fs::write(&path, render(report))?;
+    Ok(path)
+}
+
+fn event_color(kind: EventKind) -> Color {
+    match kind {
+        EventKind::Threshold => Color(0xff, 0xcc, 0x00),
+        EventKind::AlarmStart | EventKind::Oom => Color(0xff, 0x33, 0x33),
+        EventKind::AlarmEnd => screenshot::MEMORY,
+        EventKind::Snapshot | EventKind::Bookmark => Color(0x66, 0xcc, 0xff),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusty_mem_monitor::Sample;
+
+    #[test]
+    fn renders_a_self_contained_page() {
+        let mut history = History::new(10);
+        for (timestamp_ms, used_memory) in [(1_000, 40), (2_000, 60)] {
+            history.push(&Sample { timestamp_ms, total_memory: 100, used_memory, ..Sample::default() });
+        }
+        let event = Event {
+            timestamp_ms: 2_000,
+            kind: EventKind::Bookmark,
+            message: "deploy <v2>".to_string(),
+        };
+        let html = render(&Report {
+            host: "web-1".to_string(),
+            history: &history,
+            events: vec![&event],
+            processes: Vec::new(),
+            offenders: Vec::new(),
+        });
+        assert!(html.contains("<polyline"));
+        assert!(html.contains("deploy &lt;v2&gt;"));
+        assert!(!html.contains("<script") && !html.contains("Top processes"));
+    }
+}
@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Column order of [`Sample::csv_row`]; every field is an exact integer.
+pub const CSV_HEADER: &str = "timestamp_ms,total_memory,used_memory,total_swap,used_swap,\
+    free_memory,buffers_memory,compressed_memory,used_memory_min,used_memory_max";
+
+/// One reading of the system memory counters. Kept as raw bytes so it can be
+/// shipped between processes without losing anything.
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+pub struct Sample {
+    pub timestamp_ms: u64,
+    pub total_memory: u64,
+    pub used_memory: u64,
+    pub total_swap: u64,
+    pub used_swap: u64,
+    #[serde(default)]
+    pub free_memory: u64,
+    #[serde(default)]
+    pub buffers_memory: u64,
+    // Windows memory-compression store; part of used_memory, zero elsewhere
+    #[serde(default)]
+    pub compressed_memory: u64,
+    // Extremes of used_memory across the sub-samples this sample was aggregated
+    // from; zero when it's a single reading from an older source
+    #[serde(default)]
+    pub used_memory_min: u64,
+    #[serde(default)]
+    pub used_memory_max: u64,
+    // Totals describe the cgroup limit we run under rather than host RAM
+    #[serde(default)]
+    pub cgroup_limited: bool,
+}
+
+/// Percentages of total RAM that always add up to 100: what's really in use,
+/// the compressed store, kernel buffers, reclaimable page cache and completely
+/// free memory.
+#[derive(Clone, Copy, Default)]
+pub struct Composition {
+    pub used: f32,
+    pub compressed: f32,
+    pub buffers: f32,
+    pub cached: f32,
+    pub free: f32,
+}
+
+impl Composition {
+    // Bottom to top stacking order
+    pub fn bands(&self) -> [f32; 5] {
+        [self.used, self.compressed, self.buffers, self.cached, self.free]
+    }
+}
+
+impl Sample {
+    pub fn composition(&self) -> Composition {
+        let total = self.total_memory as f64;
+        if total <= 0.0 {
+            return Composition::default();
+        }
+        let available = self.total_memory.saturating_sub(self.used_memory);
+        let free = self.free_memory.min(available);
+        let reclaimable = available - free;
+        let buffers = self.buffers_memory.min(reclaimable);
+        let percent = |bytes: u64| (bytes as f64 / total * 100.0) as f32;
+        let used = self.used_memory.min(self.total_memory);
+        let compressed = self.compressed_memory.min(used);
+        Composition {
+            used: percent(used - compressed),
+            compressed: percent(compressed),
+            buffers: percent(buffers),
+            cached: percent(reclaimable - buffers),
+            free: percent(free),
+        }
+    }
+
+    pub fn csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{},{}",
+            self.timestamp_ms,
+            self.total_memory,
+            self.used_memory,
+            self.total_swap,
+            self.used_swap,
+            self.free_memory,
+            self.buffers_memory,
+            self.compressed_memory,
+            self.used_memory_min,
+            self.used_memory_max,
+        )
+    }
+
+    /// Parses a [`csv_row`](Self::csv_row) line. Recordings from older versions,
+    /// with five or eight columns, are accepted too; missing fields are zero.
+    pub fn from_csv_row(line: &str) -> Option<Self> {
+        let fields: Vec<u64> = line
+            .trim()
+            .split(',')
+            .map(|field| field.parse().ok())
+            .collect::<Option<_>>()?;
+        if ![5, 8, 10].contains(&fields.len()) {
+            return None;
+        }
+        let field = |i: usize| fields.get(i).copied().unwrap_or(0);
+        Some(Self {
+            timestamp_ms: field(0),
+            total_memory: field(1),
+            used_memory: field(2),
+            total_swap: field(3),
+            used_swap: field(4),
+            free_memory: field(5),
+            buffers_memory: field(6),
+            compressed_memory: field(7),
+            used_memory_min: field(8),
+            used_memory_max: field(9),
+            cgroup_limited: false,
+        })
+    }
+
+    /// False when the platform reported no memory at all, as a failed refresh
+    /// does in some containers and BSD jails. Percentages mean nothing then and
+    /// should be shown as unavailable rather than 0%.
+    pub fn has_memory(&self) -> bool {
+        self.total_memory > 0
+    }
+
+    /// False when there's no swap configured, or none could be read.
+    pub fn has_swap(&self) -> bool {
+        self.total_swap > 0
+    }
+
+    pub fn memory_percentage(&self) -> f32 {
+        if self.total_memory > 0 {
+            (self.used_memory as f64 / self.total_memory as f64 * 100.0) as f32
+        } else {
+            0.0
+        }
+    }
+
+    /// Lowest and highest memory percentage seen while this sample was taken;
+    /// both equal [`memory_percentage`](Self::memory_percentage) for a single reading.
+    pub fn memory_range_percentage(&self) -> (f32, f32) {
+        if self.used_memory_max == 0 || self.total_memory == 0 {
+            let percentage = self.memory_percentage();
+            return (percentage, percentage);
+        }
+        let percent = |bytes: u64| (bytes as f64 / self.total_memory as f64 * 100.0) as f32;
+        (percent(self.used_memory_min), percent(self.used_memory_max))
+    }
+
+    pub fn swap_percentage(&self) -> f32 {
+        if self.total_swap > 0 {
+            (self.used_swap as f64 / self.total_swap as f64 * 100.0) as f32
+        } else {
+            0.0
+        }
+    }
+}
+
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_round_trip_is_exact() {
+        let sample = Sample {
+            timestamp_ms: 1_700_000_000_123,
+            total_memory: 24 * 1024u64.pow(4) + 7,
+            used_memory: 24 * 1024u64.pow(4) - 3,
+            total_swap: 8 << 30,
+            used_swap: 1,
+            free_memory: 10,
+            buffers_memory: 4096,
+            compressed_memory: 0,
+            used_memory_min: 24 * 1024u64.pow(4) - 9,
+            used_memory_max: 24 * 1024u64.pow(4),
+            cgroup_limited: false,
+        };
+        let parsed = Sample::from_csv_row(&sample.csv_row()).unwrap();
+        assert_eq!(parsed.csv_row(), sample.csv_row());
+    }
+
+    #[test]
+    fn reads_old_five_column_rows() {
+        let parsed = Sample::from_csv_row("5,100,40,10,1\n").unwrap();
+        assert_eq!(parsed.used_memory, 40);
+        assert_eq!(parsed.free_memory, 0);
+        assert!(Sample::from_csv_row("5,100,40").is_none());
+        assert!(Sample::from_csv_row(CSV_HEADER).is_none());
+    }
+}
@@ -0,0 +1,291 @@
+use std::time::Duration;
+use sysinfo::{System, SystemExt};
+
+use crate::sample::{now_ms, Sample};
+use crate::cgroup::{self, CgroupMemory};
+use crate::{compressed, meminfo};
+
+/// The memory counters a [`Sampler`] reads. Implemented for sysinfo's `System`;
+/// tests and tools can supply their own scripted values instead.
+pub trait SystemProvider {
+    fn refresh_memory(&mut self);
+    fn total_memory(&self) -> u64;
+    fn used_memory(&self) -> u64;
+    fn total_swap(&self) -> u64;
+    fn used_swap(&self) -> u64;
+    fn free_memory(&self) -> u64;
+
+    fn buffers_memory(&mut self) -> u64 {
+        0
+    }
+
+    fn compressed_memory(&mut self) -> u64 {
+        0
+    }
+
+    fn cgroup_memory(&mut self) -> Option<CgroupMemory> {
+        None
+    }
+}
+
+impl SystemProvider for System {
+    fn refresh_memory(&mut self) {
+        SystemExt::refresh_memory(self)
+    }
+
+    fn total_memory(&self) -> u64 {
+        SystemExt::total_memory(self)
+    }
+
+    fn used_memory(&self) -> u64 {
+        SystemExt::used_memory(self)
+    }
+
+    fn total_swap(&self) -> u64 {
+        SystemExt::total_swap(self)
+    }
+
+    fn used_swap(&self) -> u64 {
+        SystemExt::used_swap(self)
+    }
+
+    fn free_memory(&self) -> u64 {
+        SystemExt::free_memory(self)
+    }
+
+    fn buffers_memory(&mut self) -> u64 {
+        meminfo::read()
+            .and_then(|info| info.get("Buffers").copied())
+            .unwrap_or(0)
+    }
+
+    fn compressed_memory(&mut self) -> u64 {
+        compressed::store_bytes(self)
+    }
+
+    fn cgroup_memory(&mut self) -> Option<CgroupMemory> {
+        cgroup::read()
+    }
+}
+
+/// Reads memory counters from the running system, or from any other [`SystemProvider`].
+pub struct Sampler<P = System> {
+    provider: P,
+}
+
+impl Sampler {
+    /// A sampler that only ever loads memory information.
+    pub fn new() -> Self {
+        Self::from_system(System::new())
+    }
+
+    pub fn from_system(sys: System) -> Self {
+        Self { provider: sys }
+    }
+
+    /// The underlying sysinfo handle, for callers that also need process data.
+    pub fn system_mut(&mut self) -> &mut System {
+        &mut self.provider
+    }
+}
+
+impl<P: SystemProvider> Sampler<P> {
+    pub fn with_provider(provider: P) -> Self {
+        Self { provider }
+    }
+
+    pub fn sample(&mut self) -> Sample {
+        let provider = &mut self.provider;
+        provider.refresh_memory();
+        let used_memory = provider.used_memory();
+        let sample = Sample {
+            timestamp_ms: now_ms(),
+            total_memory: provider.total_memory(),
+            used_memory,
+            used_memory_min: used_memory,
+            used_memory_max: used_memory,
+            total_swap: provider.total_swap(),
+            used_swap: provider.used_swap(),
+            free_memory: provider.free_memory(),
+            buffers_memory: provider.buffers_memory(),
+            compressed_memory: provider.compressed_memory(),
+            cgroup_limited: false,
+        };
+        match provider.cgroup_memory() {
+            Some(cgroup) if cgroup.limit < sample.total_memory => limit_to_cgroup(sample, cgroup),
+            _ => sample,
+        }
+    }
+
+    /// Takes `count` readings `interval` apart, blocking for `count * interval`, and
+    /// folds them into one sample so short spikes between samples aren't lost:
+    /// `used_memory` is the mean, `used_memory_min`/`used_memory_max` the extremes.
+    /// Everything else comes from the last reading.
+    pub fn sample_window(&mut self, count: usize, interval: Duration) -> Sample {
+        let mut min = u64::MAX;
+        let mut max = 0;
+        let mut sum = 0u128;
+        let mut last = Sample::default();
+        for _ in 0..count.max(1) {
+            std::thread::sleep(interval);
+            last = self.sample();
+            min = min.min(last.used_memory);
+            max = max.max(last.used_memory);
+            sum += last.used_memory as u128;
+        }
+        Sample {
+            used_memory: (sum / count.max(1) as u128) as u64,
+            used_memory_min: min,
+            used_memory_max: max,
+            ..last
+        }
+    }
+}
+
+// Inside a container or a limited systemd slice the host's RAM is irrelevant: the
+// OOM killer fires at the cgroup limit, so that's what percentages are taken of.
+fn limit_to_cgroup(sample: Sample, cgroup: CgroupMemory) -> Sample {
+    let usage = cgroup.usage.min(cgroup.limit);
+    let used_memory = usage.saturating_sub(cgroup.reclaimable);
+    Sample {
+        total_memory: cgroup.limit,
+        used_memory,
+        used_memory_min: used_memory,
+        used_memory_max: used_memory,
+        free_memory: cgroup.limit - usage,
+        buffers_memory: 0,
+        compressed_memory: 0,
+        cgroup_limited: true,
+        ..sample
+    }
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AlertState, History};
+    use std::time::Instant;
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    // Steps through a fixed list of used-memory readings, one per refresh
+    struct Scripted {
+        used: Vec<u64>,
+        step: usize,
+    }
+
+    impl SystemProvider for Scripted {
+        fn refresh_memory(&mut self) {
+            self.step += 1;
+        }
+
+        fn total_memory(&self) -> u64 {
+            10 * GIB
+        }
+
+        fn used_memory(&self) -> u64 {
+            self.used[(self.step - 1).min(self.used.len() - 1)]
+        }
+
+        fn total_swap(&self) -> u64 {
+            0
+        }
+
+        fn used_swap(&self) -> u64 {
+            0
+        }
+
+        fn free_memory(&self) -> u64 {
+            self.total_memory() - self.used_memory()
+        }
+    }
+
+    struct Limited;
+
+    impl SystemProvider for Limited {
+        fn refresh_memory(&mut self) {}
+
+        fn total_memory(&self) -> u64 {
+            64 * GIB
+        }
+
+        fn used_memory(&self) -> u64 {
+            8 * GIB
+        }
+
+        fn total_swap(&self) -> u64 {
+            0
+        }
+
+        fn used_swap(&self) -> u64 {
+            0
+        }
+
+        fn free_memory(&self) -> u64 {
+            56 * GIB
+        }
+
+        fn cgroup_memory(&mut self) -> Option<CgroupMemory> {
+            Some(CgroupMemory {
+                limit: 2 * GIB,
+                usage: 2 * GIB - GIB / 8,
+                reclaimable: GIB / 8,
+            })
+        }
+    }
+
+    fn scripted(used_gib: &[u64]) -> Sampler<Scripted> {
+        Sampler::with_provider(Scripted {
+            used: used_gib.iter().map(|gib| gib * GIB).collect(),
+            step: 0,
+        })
+    }
+
+    #[test]
+    fn samples_follow_the_script() {
+        let mut sampler = scripted(&[2, 5, 9]);
+        let percentages: Vec<f32> = (0..4).map(|_| sampler.sample().memory_percentage()).collect();
+        assert_eq!(percentages, [20.0, 50.0, 90.0, 90.0]);
+        assert_eq!(sampler.sample().swap_percentage(), 0.0);
+    }
+
+    #[test]
+    fn percentages_follow_the_cgroup_limit() {
+        let sample = Sampler::with_provider(Limited).sample();
+        assert!(sample.cgroup_limited);
+        assert_eq!(sample.total_memory, 2 * GIB);
+        assert_eq!(sample.memory_percentage(), 87.5);
+        assert_eq!(sample.free_memory, GIB / 8);
+    }
+
+    #[test]
+    fn window_keeps_spike_extremes() {
+        let mut sampler = scripted(&[4, 9, 2, 5]);
+        let sample = sampler.sample_window(4, Duration::ZERO);
+        assert_eq!(sample.used_memory, 5 * GIB);
+        assert_eq!(sample.memory_range_percentage(), (20.0, 90.0));
+    }
+
+    #[test]
+    fn alarm_follows_a_spike() {
+        let mut sampler = scripted(&[5, 8, 10, 10, 9, 6]);
+        let mut history = History::new(3);
+        let mut alerts = AlertState::default();
+        let mut critical = Vec::new();
+        for _ in 0..6 {
+            let sample = sampler.sample();
+            history.push(&sample);
+            alerts.update(sample.memory_percentage(), Instant::now(), &[]);
+            critical.push(alerts.critical);
+        }
+        // 90% is still inside the hysteresis band, so only the drop to 60% clears it
+        assert_eq!(critical, [false, false, true, true, true, false]);
+        assert_eq!(history.len(), 3);
+    }
+}
@@ -0,0 +1,519 @@
+use std::fmt::Write as _;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use sysinfo::{System, SystemExt};
+
+use rusty_mem_monitor::stats::{self, Summary};
+use rusty_mem_monitor::units::format_bytes;
+use rusty_mem_monitor::{History, Sampler};
+
+use crate::daemon::{self, DaemonClient};
+
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 330.0;
+const CHART_HEIGHT: f64 = 240.0;
+const TITLE_HEIGHT: f64 = 30.0;
+// Room for the axis labels around the plotting area
+const MARGIN_LEFT: f64 = 40.0;
+const MARGIN_BOTTOM: f64 = 20.0;
+// Without a daemon to take the backlog from, the CLI samples this long first
+const CAPTURE_SAMPLES: usize = 10;
+const BACKLOG_WAIT: Duration = Duration::from_secs(2);
+
+const BACKGROUND: Color = Color(0x11, 0x16, 0x11);
+const GRID: Color = Color(0x22, 0x33, 0x22);
+const DIM: Color = Color(0x77, 0x88, 0x77);
+pub const MEMORY: Color = Color(0x00, 0xff, 0x66);
+const SWAP: Color = Color(0xff, 0x99, 0x00);
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Color(pub u8, pub u8, pub u8);
+
+impl std::fmt::Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+// A dotted vertical line on the chart, e.g. an alarm or a bookmark
+pub struct Marker<'a> {
+    pub timestamp_ms: u64,
+    pub color: Color,
+    pub label: &'a str,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format {
+    Png,
+    Svg,
+}
+
+impl Format {
+    pub fn of(path: &Path) -> Result<Self, String> {
+        match path.extension().and_then(|extension| extension.to_str()).map(str::to_ascii_lowercase).as_deref() {
+            Some("png") => Ok(Self::Png),
+            Some("svg") => Ok(Self::Svg),
+            _ => Err(format!("{}: use a .png or .svg file name", path.display())),
+        }
+    }
+
+    #[cfg(feature = "gui")]
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Svg => "svg",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Anchor {
+    Start,
+    End,
+}
+
+// What the plot is drawn onto; written once so the SVG and the PNG show the same thing
+trait Canvas {
+    // `label` becomes a hover tooltip where the format has them
+    fn line(&mut self, from: (f64, f64), to: (f64, f64), color: Color, dashed: bool, label: &str);
+    fn polyline(&mut self, points: &[(f64, f64)], color: Color);
+    fn text(&mut self, at: (f64, f64), text: &str, color: Color, size: f64, anchor: Anchor);
+}
+
+// RAM and swap in percent over the history, markers as dotted vertical lines,
+// in a CHART_HEIGHT-high band starting at `top`
+fn draw_chart(canvas: &mut impl Canvas, history: &History, markers: &[Marker], top: f64) {
+    let plot_width = WIDTH - MARGIN_LEFT;
+    let plot_height = CHART_HEIGHT - MARGIN_BOTTOM;
+    let y = |percentage: f64| top + plot_height - percentage.clamp(0.0, 100.0) / 100.0 * plot_height;
+    for percentage in [0.0, 25.0, 50.0, 75.0, 100.0] {
+        canvas.line((MARGIN_LEFT, y(percentage)), (WIDTH, y(percentage)), GRID, false, "");
+        let label = format!("{}%", percentage);
+        canvas.text((2.0, y(percentage).max(top + 10.0)), &label, DIM, 10.0, Anchor::Start);
+    }
+    let (Some(first), Some(last)) = (history.first(), history.last()) else {
+        return;
+    };
+    let (start, span) = (first.timestamp_ms, last.timestamp_ms.saturating_sub(first.timestamp_ms).max(1));
+    let x = |timestamp_ms: u64| MARGIN_LEFT + timestamp_ms.saturating_sub(start) as f64 / span as f64 * plot_width;
+
+    for marker in markers.iter().filter(|marker| (start..=last.timestamp_ms).contains(&marker.timestamp_ms)) {
+        let at = x(marker.timestamp_ms);
+        canvas.line((at, top), (at, top + plot_height), marker.color, true, marker.label);
+    }
+    for (color, swap) in [(MEMORY, false), (SWAP, true)] {
+        let points: Vec<_> = history
+            .iter()
+            .map(|sample| {
+                let percentage = if swap { sample.swap_percentage() } else { sample.memory_percentage() };
+                (x(sample.timestamp_ms), y(percentage as f64))
+            })
+            .collect();
+        canvas.polyline(&points, color);
+    }
+    let bottom = top + CHART_HEIGHT - 4.0;
+    canvas.text((MARGIN_LEFT, bottom), &format_datetime(start), DIM, 10.0, Anchor::Start);
+    canvas.text((WIDTH, bottom), &format_datetime(last.timestamp_ms), DIM, 10.0, Anchor::End);
+    canvas.text((MARGIN_LEFT + 10.0, top + 14.0), "RAM", MEMORY, 11.0, Anchor::Start);
+    canvas.text((MARGIN_LEFT + 45.0, top + 14.0), "Swap", SWAP, 11.0, Anchor::Start);
+}
+
+// The chart alone, for embedding in a page
+#[cfg(feature = "gui")]
+pub fn svg_chart(history: &History, markers: &[Marker]) -> String {
+    let mut svg = Svg::new(WIDTH, CHART_HEIGHT);
+    draw_chart(&mut svg, history, markers, 0.0);
+    svg.finish()
+}
+
+// The chart with a title above and the statistics below: what the main window's
+// plot shows, as a file
+fn draw_screenshot(canvas: &mut impl Canvas, host: &str, history: &History) {
+    canvas.text((8.0, 20.0), &format!("Memory: {}", host), MEMORY, 14.0, Anchor::Start);
+    if let (Some(first), Some(last)) = (history.first(), history.last()) {
+        let (from, to) = (format_datetime(first.timestamp_ms), format_datetime(last.timestamp_ms));
+        let span = format!("{} samples, {} to {}", history.len(), from, to);
+        canvas.text((WIDTH - 8.0, 20.0), &span, DIM, 10.0, Anchor::End);
+    }
+    draw_chart(canvas, history, &[], TITLE_HEIGHT);
+    let mut y = TITLE_HEIGHT + CHART_HEIGHT + 20.0;
+    let Some(latest) = history.last() else {
+        canvas.text((8.0, y), "No samples", DIM, 11.0, Anchor::Start);
+        return;
+    };
+    let rows = [
+        ("RAM ", MEMORY, latest.used_memory, latest.total_memory, stats::memory_summary(history)),
+        ("Swap", SWAP, latest.used_swap, latest.total_swap, stats::swap_summary(history)),
+    ];
+    for (name, color, used, total, summary) in rows {
+        let mut line = format!("{}  {} / {}", name, format_bytes(used), format_bytes(total));
+        if let Some(Summary { min, max, mean, p95 }) = summary {
+            let _ = write!(line, "   min {:.1}%  avg {:.1}%  p95 {:.1}%  max {:.1}%", min, mean, p95, max);
+        }
+        canvas.text((8.0, y), &line, color, 11.0, Anchor::Start);
+        y += 18.0;
+    }
+    if let Some(growth) = stats::growth_per_minute(history) {
+        let sign = if growth < 0.0 { "-" } else { "+" };
+        let line = format!("Growth {}{}/min", sign, format_bytes(growth.abs() as u64));
+        canvas.text((8.0, y), &line, DIM, 11.0, Anchor::Start);
+    }
+}
+
+pub fn render(format: Format, host: &str, history: &History) -> Vec<u8> {
+    match format {
+        Format::Svg => {
+            let mut svg = Svg::new(WIDTH, HEIGHT);
+            draw_screenshot(&mut svg, host, history);
+            svg.finish().into_bytes()
+        }
+        Format::Png => {
+            let mut raster = Raster::new(WIDTH as usize, HEIGHT as usize);
+            draw_screenshot(&mut raster, host, history);
+            raster.png()
+        }
+    }
+}
+
+pub fn write(path: &Path, host: &str, history: &History) -> Result<(), String> {
+    let format = Format::of(path)?;
+    std::fs::write(path, render(format, host, history)).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// `--screenshot`: the running daemon's backlog if there is one, otherwise a few
+// seconds of fresh samples, so it works on a headless CI box too
+pub fn capture(path: &Path, standalone: bool) -> Result<(), String> {
+    Format::of(path)?;
+    let mut history = History::new(daemon::BACKLOG);
+    if let Some(daemon) = if standalone { None } else { DaemonClient::connect() } {
+        let started = Instant::now();
+        while started.elapsed() < BACKLOG_WAIT && daemon.is_connected() {
+            let samples = daemon.poll();
+            // The backlog arrives in one burst; once it's in, stop waiting
+            if samples.is_empty() && !history.is_empty() {
+                break;
+            }
+            samples.iter().for_each(|sample| history.push(sample));
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+    if history.is_empty() {
+        let interval = daemon::SAMPLE_INTERVAL;
+        eprintln!("Sampling for {:?}", interval * CAPTURE_SAMPLES as u32);
+        let mut sampler = Sampler::new();
+        for _ in 0..CAPTURE_SAMPLES {
+            history.push(&sampler.sample_window(daemon::SUB_SAMPLES as usize, interval / daemon::SUB_SAMPLES));
+        }
+    }
+    let host = System::new().host_name().unwrap_or_else(|| "unknown host".to_string());
+    write(path, &host, &history)
+}
+
+struct Svg {
+    body: String,
+}
+
+impl Svg {
+    fn new(width: f64, height: f64) -> Self {
+        let mut body = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+            w = width,
+            h = height
+        );
+        let _ = writeln!(body, "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>", BACKGROUND);
+        Self { body }
+    }
+
+    fn finish(mut self) -> String {
+        self.body.push_str("</svg>\n");
+        self.body
+    }
+}
+
+impl Canvas for Svg {
+    fn line(&mut self, from: (f64, f64), to: (f64, f64), color: Color, dashed: bool, label: &str) {
+        let _ = write!(
+            self.body,
+            "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{}\"{}>",
+            from.0,
+            from.1,
+            to.0,
+            to.1,
+            color,
+            if dashed { " stroke-dasharray=\"2,2\"" } else { "" }
+        );
+        if !label.is_empty() {
+            let _ = write!(self.body, "<title>{}</title>", escape(label));
+        }
+        self.body.push_str("</line>\n");
+    }
+
+    fn polyline(&mut self, points: &[(f64, f64)], color: Color) {
+        let points: Vec<String> = points.iter().map(|(x, y)| format!("{:.1},{:.1}", x, y)).collect();
+        let _ = writeln!(
+            self.body,
+            "<polyline fill=\"none\" stroke=\"{}\" stroke-width=\"1.5\" points=\"{}\"/>",
+            color,
+            points.join(" ")
+        );
+    }
+
+    fn text(&mut self, at: (f64, f64), text: &str, color: Color, size: f64, anchor: Anchor) {
+        let anchor = match anchor {
+            Anchor::Start => "start",
+            Anchor::End => "end",
+        };
+        let _ = writeln!(
+            self.body,
+            "<text x=\"{:.1}\" y=\"{:.1}\" fill=\"{}\" font-family=\"monospace\" font-size=\"{}\" \
+             text-anchor=\"{}\" xml:space=\"preserve\">{}</text>",
+            at.0,
+            at.1,
+            color,
+            size,
+            anchor,
+            escape(text)
+        );
+    }
+}
+
+// A plain RGB pixel buffer; text is drawn with the built-in bitmap font below
+struct Raster {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Raster {
+    fn new(width: usize, height: usize) -> Self {
+        let mut pixels = Vec::with_capacity(width * height * 3);
+        for _ in 0..width * height {
+            pixels.extend([BACKGROUND.0, BACKGROUND.1, BACKGROUND.2]);
+        }
+        Self { width, height, pixels }
+    }
+
+    fn set(&mut self, x: i64, y: i64, color: Color) {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return;
+        }
+        let at = (y as usize * self.width + x as usize) * 3;
+        self.pixels[at..at + 3].copy_from_slice(&[color.0, color.1, color.2]);
+    }
+
+    // Bresenham; `skip` blanks every other pair of pixels for a dotted line
+    fn segment(&mut self, from: (f64, f64), to: (f64, f64), color: Color, skip: bool) {
+        let (mut x, mut y) = (from.0.round() as i64, from.1.round() as i64);
+        let (x1, y1) = (to.0.round() as i64, to.1.round() as i64);
+        let (dx, dy) = ((x1 - x).abs(), -(y1 - y).abs());
+        let (sx, sy) = (if x < x1 { 1 } else { -1 }, if y < y1 { 1 } else { -1 });
+        let mut error = dx + dy;
+        let mut step = 0;
+        loop {
+            if !skip || step % 4 < 2 {
+                self.set(x, y, color);
+            }
+            if x == x1 && y == y1 {
+                break;
+            }
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                x += sx;
+            }
+            if doubled <= dx {
+                error += dx;
+                y += sy;
+            }
+            step += 1;
+        }
+    }
+
+    fn png(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        // Writing into a Vec only fails on a size mismatch, which can't happen here
+        if let Ok(mut writer) = encoder.write_header() {
+            let _ = writer.write_image_data(&self.pixels);
+        }
+        bytes
+    }
+}
+
+impl Canvas for Raster {
+    fn line(&mut self, from: (f64, f64), to: (f64, f64), color: Color, dashed: bool, _label: &str) {
+        self.segment(from, to, color, dashed);
+    }
+
+    fn polyline(&mut self, points: &[(f64, f64)], color: Color) {
+        // Two pixels thick, to match the SVG's 1.5 stroke
+        for pair in points.windows(2) {
+            self.segment(pair[0], pair[1], color, false);
+            self.segment((pair[0].0, pair[0].1 + 1.0), (pair[1].0, pair[1].1 + 1.0), color, false);
+        }
+    }
+
+    fn text(&mut self, at: (f64, f64), text: &str, color: Color, size: f64, anchor: Anchor) {
+        let scale = (size / 9.0).round().max(1.0) as i64;
+        let advance = (GLYPH_WIDTH + 1) * scale;
+        let width = text.chars().count() as i64 * advance;
+        let left = match anchor {
+            Anchor::Start => at.0 as i64,
+            Anchor::End => at.0 as i64 - width,
+        };
+        // `at` is the baseline, as in SVG
+        let top = at.1 as i64 - GLYPH_HEIGHT * scale;
+        for (i, c) in text.chars().enumerate() {
+            let rows = glyph(c);
+            for (row, bits) in rows.iter().enumerate() {
+                for column in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - column)) == 0 {
+                        continue;
+                    }
+                    for dy in 0..scale {
+                        for dx in 0..scale {
+                            let x = left + i as i64 * advance + column * scale + dx;
+                            self.set(x, top + row as i64 * scale + dy, color);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+const GLYPH_WIDTH: i64 = 5;
+const GLYPH_HEIGHT: i64 = 7;
+
+// 5x7 bitmaps, one row per byte, for the characters the screenshot writes;
+// lowercase is drawn as uppercase and anything else as '?'
+const GLYPHS: &[(char, [u8; 7])] = &[
+    (' ', [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000]),
+    ('0', [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110]),
+    ('1', [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110]),
+    ('2', [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111]),
+    ('3', [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110]),
+    ('4', [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010]),
+    ('5', [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110]),
+    ('6', [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110]),
+    ('7', [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000]),
+    ('8', [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110]),
+    ('9', [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100]),
+    ('A', [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001]),
+    ('B', [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110]),
+    ('C', [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110]),
+    ('D', [0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100]),
+    ('E', [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111]),
+    ('F', [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000]),
+    ('G', [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111]),
+    ('H', [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001]),
+    ('I', [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110]),
+    ('J', [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100]),
+    ('K', [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001]),
+    ('L', [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111]),
+    ('M', [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001]),
+    ('N', [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001]),
+    ('O', [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110]),
+    ('P', [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000]),
+    ('Q', [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101]),
+    ('R', [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001]),
+    ('S', [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110]),
+    ('T', [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100]),
+    ('U', [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110]),
+    ('V', [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100]),
+    ('W', [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010]),
+    ('X', [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001]),
+    ('Y', [0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100]),
+    ('Z', [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111]),
+    ('%', [0b11000, 0b11001, 0b00010, 0b00100, 0b01000, 0b10011, 0b00011]),
+    ('.', [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100]),
+    (',', [0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b00100, 0b01000]),
+    (':', [0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000]),
+    ('-', [0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000]),
+    ('+', [0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000]),
+    ('=', [0b00000, 0b00000, 0b11111, 0b00000, 0b11111, 0b00000, 0b00000]),
+    ('/', [0b00000, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b00000]),
+    ('(', [0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010]),
+    (')', [0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000]),
+    ('_', [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b11111]),
+    ('?', [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b00000, 0b00100]),
+];
+
+fn glyph(c: char) -> [u8; 7] {
+    let c = c.to_ascii_uppercase();
+    let find = |c: char| GLYPHS.iter().find(|(glyph, _)| *glyph == c).map(|(_, rows)| *rows);
+    find(c).or_else(|| find('?')).unwrap_or_default()
+}
+
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// `2024-03-01 14:05:09 UTC`; images and reports outlive the session, so they carry the date too
+pub fn format_datetime(timestamp_ms: u64) -> String {
+    let secs = timestamp_ms / 1000;
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        secs / 3600 % 24,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+// Days since 1970-01-01 to a Gregorian date (Howard Hinnant's algorithm)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusty_mem_monitor::Sample;
+
+    #[test]
+    fn renders_the_same_plot_as_svg_and_png() {
+        assert_eq!(format_datetime(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format_datetime(1_709_301_909_000), "2024-03-01 14:05:09 UTC");
+        assert!(Format::of(Path::new("plot.txt")).is_err());
+        assert!(Format::of(Path::new("plot.PNG")) == Ok(Format::Png));
+
+        let mut history = History::new(10);
+        for (timestamp_ms, used_memory) in [(1_000, 40), (2_000, 60)] {
+            history.push(&Sample { timestamp_ms, total_memory: 100, used_memory, ..Sample::default() });
+        }
+        let svg = String::from_utf8(render(Format::Svg, "web <1>", &history)).unwrap();
+        assert!(svg.contains("<polyline") && svg.contains("web &lt;1&gt;") && svg.contains("avg 50.0%"));
+
+        let png = render(Format::Png, "web-1", &history);
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        let mut raster = Raster::new(WIDTH as usize, HEIGHT as usize);
+        draw_screenshot(&mut raster, "web-1", &history);
+        assert!(raster.pixels.chunks(3).any(|pixel| pixel == [MEMORY.0, MEMORY.1, MEMORY.2]));
+    }
+}
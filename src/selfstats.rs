@@ -0,0 +1,124 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use sysinfo::{Pid, PidExt, ProcessExt, ProcessRefreshKind, SystemExt};
+
+// Frames the frame time and allocation figures are averaged over
+const FRAME_WINDOW: usize = 120;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+// The system allocator, counting calls so the overlay can show allocations per frame
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+fn allocations() -> u64 {
+    ALLOCATIONS.load(Ordering::Relaxed)
+}
+
+pub struct FrameStart {
+    at: Instant,
+    allocations: u64,
+}
+
+#[derive(Clone, Copy)]
+struct Frame {
+    time: Duration,
+    allocations: u64,
+}
+
+// The monitor's own footprint: RSS, and time and allocations spent in update()
+// over the last FRAME_WINDOW frames
+#[derive(Default)]
+pub struct SelfStats {
+    frames: VecDeque<Frame>,
+    pub rss: Option<u64>,
+    rss_refreshed: Option<Instant>,
+}
+
+impl SelfStats {
+    pub fn begin() -> FrameStart {
+        FrameStart {
+            at: Instant::now(),
+            allocations: allocations(),
+        }
+    }
+
+    pub fn end(&mut self, start: FrameStart) {
+        self.push(Frame {
+            time: start.at.elapsed(),
+            // Other threads (samplers, the daemon client) allocate too; they're counted here
+            allocations: allocations().saturating_sub(start.allocations),
+        });
+    }
+
+    fn push(&mut self, frame: Frame) {
+        self.frames.push_back(frame);
+        while self.frames.len() > FRAME_WINDOW {
+            self.frames.pop_front();
+        }
+    }
+
+    pub fn refresh_rss(&mut self, sys: &mut sysinfo::System, every: Duration) {
+        if self.rss_refreshed.is_some_and(|at| at.elapsed() < every) {
+            return;
+        }
+        let pid = Pid::from_u32(std::process::id());
+        sys.refresh_process_specifics(pid, ProcessRefreshKind::new());
+        self.rss = sys.process(pid).map(|process| process.memory());
+        self.rss_refreshed = Some(Instant::now());
+    }
+
+    // Mean and worst frame time
+    pub fn frame_time(&self) -> Option<(Duration, Duration)> {
+        let count = self.frames.len() as u32;
+        let total: Duration = self.frames.iter().map(|frame| frame.time).sum();
+        let max = self.frames.iter().map(|frame| frame.time).max()?;
+        Some((total / count, max))
+    }
+
+    pub fn allocations_per_frame(&self) -> Option<f64> {
+        let total: u64 = self.frames.iter().map(|frame| frame.allocations).sum();
+        (!self.frames.is_empty()).then(|| total as f64 / self.frames.len() as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn averages_over_a_bounded_window() {
+        let mut stats = SelfStats::default();
+        assert!(stats.frame_time().is_none());
+        for i in 0..FRAME_WINDOW as u64 + 10 {
+            let time = Duration::from_millis(if i < 10 { 100 } else { 2 + i % 3 });
+            stats.push(Frame { time, allocations: i % 2 * 10 });
+        }
+        assert_eq!(stats.frames.len(), FRAME_WINDOW);
+        assert_eq!(stats.frame_time(), Some((Duration::from_millis(3), Duration::from_millis(4))));
+        assert_eq!(stats.allocations_per_frame(), Some(5.0));
+    }
+}
@@ -0,0 +1,101 @@
+use sysinfo::{System, SystemExt};
+
+use rusty_mem_monitor::meminfo;
+
+use crate::config::{self, Config};
+use crate::daemon;
+
+pub struct Check {
+    pub name: &'static str,
+    pub ok: bool,
+    pub detail: String,
+}
+
+pub fn run() -> Vec<Check> {
+    let mut sys = System::new();
+    sys.refresh_memory();
+
+    let mut checks = vec![
+        Check {
+            name: "memory totals",
+            ok: sys.total_memory() > 0 && sys.used_memory() <= sys.total_memory(),
+            detail: format!("total={} used={}", sys.total_memory(), sys.used_memory()),
+        },
+        Check {
+            name: "swap totals",
+            ok: sys.used_swap() <= sys.total_swap(),
+            detail: format!("total={} used={}", sys.total_swap(), sys.used_swap()),
+        },
+    ];
+
+    if cfg!(target_os = "linux") {
+        let info = meminfo::read();
+        checks.push(Check {
+            name: "/proc/meminfo",
+            ok: info.as_ref().is_some_and(|info| info.contains_key("MemAvailable")),
+            detail: match &info {
+                Some(info) => format!("{} fields", info.len()),
+                None => "unreadable".to_string(),
+            },
+        });
+    }
+
+    checks.push(writable_dir_check("config dir", config::config_dir()));
+    checks.push(writable_dir_check("data dir", config::data_dir()));
+
+    let config_path = Config::path();
+    checks.push(Check {
+        name: "config file",
+        ok: config_path.as_ref().is_none_or(|path| {
+            !path.exists()
+                || std::fs::read_to_string(path)
+                    .map(|text| toml::from_str::<Config>(&text).is_ok())
+                    .unwrap_or(false)
+        }),
+        detail: config_path
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "no config directory".to_string()),
+    });
+
+    checks.push(Check {
+        name: "sampler daemon",
+        // Informational: running without a daemon is the normal case
+        ok: true,
+        detail: if daemon::DaemonClient::connect().is_some() {
+            "running".to_string()
+        } else {
+            "not running".to_string()
+        },
+    });
+
+    checks
+}
+
+fn writable_dir_check(name: &'static str, dir: Option<std::path::PathBuf>) -> Check {
+    let Some(dir) = dir else {
+        return Check { name, ok: false, detail: "could not be determined".to_string() };
+    };
+    let probe = dir.join(".selftest");
+    let result = std::fs::create_dir_all(&dir)
+        .and_then(|_| std::fs::write(&probe, b"ok"))
+        .and_then(|_| std::fs::remove_file(&probe));
+    Check {
+        name,
+        ok: result.is_ok(),
+        detail: match result {
+            Ok(()) => dir.display().to_string(),
+            Err(e) => format!("{}: {}", dir.display(), e),
+        },
+    }
+}
+
+pub fn format(checks: &[Check]) -> String {
+    checks.iter()
+        .map(|check| format!(
+            "[{}] {:<16} {}\n",
+            if check.ok { " OK " } else { "FAIL" },
+            check.name,
+            check.detail,
+        ))
+        .collect()
+}
@@ -0,0 +1,76 @@
+use sysinfo::{ComponentExt, System, SystemExt};
+
+// Where a sensor turns yellow and red when it doesn't report a critical
+// temperature of its own
+const WARNING_CELSIUS: f32 = 80.0;
+const CRITICAL_CELSIUS: f32 = 95.0;
+
+#[derive(Clone)]
+pub struct Sensor {
+    pub label: String,
+    pub celsius: f32,
+    pub critical: Option<f32>,
+}
+
+impl Sensor {
+    // 0 fine, 1 running hot, 2 at or past critical
+    pub fn level(&self) -> u8 {
+        let critical = self.critical.filter(|critical| *critical > 0.0).unwrap_or(CRITICAL_CELSIUS);
+        if self.celsius >= critical {
+            2
+        } else if self.celsius >= WARNING_CELSIUS.min(critical - 10.0) {
+            1
+        } else {
+            0
+        }
+    }
+}
+
+// CPU, SoC, GPU and drive sensors through sysinfo's component list. The reader
+// keeps a System of its own so it can run off the UI thread; the list is found
+// once, after that only the readings are refreshed.
+#[derive(Default)]
+pub struct SensorReader {
+    sys: System,
+    listed: bool,
+}
+
+impl SensorReader {
+    pub fn read(&mut self) -> Vec<Sensor> {
+        if self.listed {
+            self.sys.refresh_components();
+        } else {
+            self.sys.refresh_components_list();
+            self.listed = true;
+        }
+        let mut sensors: Vec<Sensor> = self
+            .sys
+            .components()
+            .iter()
+            .filter(|component| component.temperature().is_finite())
+            .map(|component| Sensor {
+                label: component.label().to_string(),
+                celsius: component.temperature(),
+                critical: component.critical(),
+            })
+            .collect();
+        sensors.sort_by(|a, b| a.label.cmp(&b.label));
+        sensors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_follow_the_sensors_own_critical() {
+        let sensor = |celsius, critical| Sensor { label: String::new(), celsius, critical };
+        assert_eq!(sensor(70.0, None).level(), 0);
+        assert_eq!(sensor(85.0, None).level(), 1);
+        assert_eq!(sensor(96.0, None).level(), 2);
+        assert_eq!(sensor(62.0, Some(70.0)).level(), 1);
+        assert_eq!(sensor(70.0, Some(70.0)).level(), 2);
+        assert_eq!(sensor(85.0, Some(0.0)).level(), 1);
+    }
+}
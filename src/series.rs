@@ -0,0 +1,164 @@
+use egui_plot::PlotPoint;
+
+use rusty_mem_monitor::{stats, History};
+
+use crate::config::{PlotConfig, Smoothing};
+
+#[derive(Clone, PartialEq)]
+struct Key {
+    samples: usize,
+    oldest_ms: u64,
+    newest_ms: u64,
+    latest_ms: u64,
+    absolute: bool,
+    plot: PlotConfig,
+}
+
+// The main plot's lines. The plot repaints far more often than samples arrive,
+// so they're rebuilt into the same buffers only when the history or the plot
+// settings change.
+#[derive(Default)]
+pub struct PlotSeries {
+    key: Option<Key>,
+    pub memory: Vec<PlotPoint>,
+    pub swap: Vec<PlotPoint>,
+    // Sub-sample extremes, so spikes between samples stay visible
+    pub range_min: Vec<PlotPoint>,
+    pub range_max: Vec<PlotPoint>,
+    // Empty with smoothing off
+    pub smoothed_memory: Vec<PlotPoint>,
+    pub smoothed_swap: Vec<PlotPoint>,
+    values: Vec<f64>,
+    steps: Vec<PlotPoint>,
+}
+
+impl PlotSeries {
+    // Returns whether anything was rebuilt
+    pub fn update(&mut self, history: &History, latest_ms: u64, absolute: bool, plot: &PlotConfig) -> bool {
+        let key = Key {
+            samples: history.len(),
+            oldest_ms: history.first().map_or(0, |sample| sample.timestamp_ms),
+            newest_ms: history.last().map_or(0, |sample| sample.timestamp_ms),
+            latest_ms,
+            absolute,
+            plot: plot.clone(),
+        };
+        if self.key.as_ref() == Some(&key) {
+            return false;
+        }
+        self.key = Some(key);
+
+        self.memory.clear();
+        self.swap.clear();
+        self.range_min.clear();
+        self.range_max.clear();
+        // Readings without totals would plot as a drop to zero; leave a gap instead
+        for sample in history.iter().filter(|sample| sample.has_memory()) {
+            let x = relative_secs(sample.timestamp_ms, latest_ms);
+            let (memory, swap, min, max) = if absolute {
+                let (min, max) = if sample.used_memory_max == 0 {
+                    (sample.used_memory, sample.used_memory)
+                } else {
+                    (sample.used_memory_min, sample.used_memory_max)
+                };
+                (sample.used_memory as f64, sample.used_swap as f64, min as f64, max as f64)
+            } else {
+                let (min, max) = sample.memory_range_percentage();
+                (sample.memory_percentage() as f64, sample.swap_percentage() as f64, min as f64, max as f64)
+            };
+            self.memory.push(PlotPoint::new(x, memory));
+            if sample.has_swap() {
+                self.swap.push(PlotPoint::new(x, swap));
+            }
+            self.range_min.push(PlotPoint::new(x, min));
+            self.range_max.push(PlotPoint::new(x, max));
+        }
+        smooth(&self.memory, plot, &mut self.values, &mut self.smoothed_memory);
+        smooth(&self.swap, plot, &mut self.values, &mut self.smoothed_swap);
+        // After smoothing, which wants one point per sample
+        if plot.step_memory {
+            for points in [&mut self.memory, &mut self.range_min, &mut self.range_max] {
+                stairs(points, &mut self.steps);
+            }
+        }
+        if plot.step_swap {
+            stairs(&mut self.swap, &mut self.steps);
+        }
+        true
+    }
+
+    pub fn is_smoothed(&self) -> bool {
+        !self.smoothed_memory.is_empty()
+    }
+}
+
+fn smooth(points: &[PlotPoint], plot: &PlotConfig, values: &mut Vec<f64>, smoothed: &mut Vec<PlotPoint>) {
+    smoothed.clear();
+    values.clear();
+    values.extend(points.iter().map(|point| point.y));
+    let averaged = match plot.smoothing {
+        Smoothing::Off => return,
+        Smoothing::MovingAverage => stats::moving_average(values, plot.window),
+        Smoothing::Ema => stats::exponential_moving_average(values, plot.ema_alpha),
+    };
+    smoothed.extend(points.iter().zip(averaged).map(|(point, y)| PlotPoint::new(point.x, y)));
+}
+
+// Adds a corner before every point so each value holds until the next one
+fn stairs(points: &mut Vec<PlotPoint>, scratch: &mut Vec<PlotPoint>) {
+    scratch.clear();
+    for (i, point) in points.iter().enumerate() {
+        if i > 0 {
+            scratch.push(PlotPoint::new(point.x, points[i - 1].y));
+        }
+        scratch.push(*point);
+    }
+    std::mem::swap(points, scratch);
+}
+
+pub fn relative_secs(timestamp_ms: u64, latest_ms: u64) -> f64 {
+    (timestamp_ms as f64 - latest_ms as f64) / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusty_mem_monitor::Sample;
+
+    #[test]
+    fn rebuilds_only_when_something_changed() {
+        let mut history = History::new(10);
+        let sample = |timestamp_ms| Sample {
+            timestamp_ms,
+            total_memory: 100,
+            used_memory: 50,
+            ..Sample::default()
+        };
+        history.push(&sample(1000));
+        history.push(&sample(2000));
+        let mut plot = PlotConfig::default();
+        let mut series = PlotSeries::default();
+        assert!(series.update(&history, 2000, false, &plot));
+        assert!(!series.update(&history, 2000, false, &plot));
+        assert_eq!(series.memory[0], PlotPoint::new(-1.0, 50.0));
+        assert!(!series.is_smoothed());
+
+        plot.smoothing = Smoothing::Ema;
+        assert!(series.update(&history, 2000, false, &plot));
+        assert_eq!(series.smoothed_memory.len(), 2);
+        history.push(&sample(3000));
+        assert!(series.update(&history, 3000, false, &plot));
+        assert_eq!(series.memory.len(), 3);
+
+        history.push(&Sample { timestamp_ms: 4000, ..Sample::default() });
+        assert!(series.update(&history, 4000, false, &plot));
+        assert_eq!(series.memory.len(), 3);
+        assert!(series.swap.is_empty());
+
+        plot.step_memory = true;
+        assert!(series.update(&history, 4000, false, &plot));
+        assert_eq!(series.memory.len(), 5);
+        assert_eq!(series.memory[1], PlotPoint::new(-2.0, 50.0));
+        assert_eq!(series.smoothed_memory.len(), 3);
+    }
+}
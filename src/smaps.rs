@@ -0,0 +1,134 @@
+use rusty_mem_monitor::meminfo;
+
+// What kind of memory a process holds, from /proc/<pid>/smaps_rollup plus the
+// [heap] and [stack] entries of /proc/<pid>/smaps. All values are bytes.
+#[derive(Clone, Copy, Default)]
+pub struct MemoryMap {
+    pub rss: u64,
+    pub heap: u64,
+    pub stack: u64,
+    pub anonymous: u64,
+    pub file_backed: u64,
+    pub shared: u64,
+    pub swap: u64,
+}
+
+impl MemoryMap {
+    // Stacking order for the breakdown chart; anonymous memory outside heap and
+    // stack is mostly mmap'd allocator arenas
+    pub fn bands(&self) -> [(&'static str, u64); 6] {
+        [
+            ("Heap", self.heap),
+            ("Stack", self.stack),
+            ("Other anon", self.anonymous.saturating_sub(self.heap + self.stack)),
+            ("File-backed", self.file_backed),
+            ("Shared", self.shared),
+            ("Swap", self.swap),
+        ]
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn read(pid: u32) -> Result<MemoryMap, String> {
+    let rollup = std::fs::read_to_string(format!("/proc/{}/smaps_rollup", pid)).map_err(|e| e.to_string())?;
+    // Reading smaps walks every mapping; only heap and stack are taken from it
+    let smaps = std::fs::read_to_string(format!("/proc/{}/smaps", pid)).unwrap_or_default();
+    Ok(parse(&rollup, &smaps))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read(_pid: u32) -> Result<MemoryMap, String> {
+    Err("memory maps are only available on Linux".to_string())
+}
+
+// Proportional set size: private pages plus this process's share of shared ones
+#[cfg(target_os = "linux")]
+pub fn pss(pid: u32) -> Option<u64> {
+    let rollup = std::fs::read_to_string(format!("/proc/{}/smaps_rollup", pid)).ok()?;
+    meminfo::parse(&rollup).get("Pss").copied()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn pss(_pid: u32) -> Option<u64> {
+    None
+}
+
+fn parse(rollup: &str, smaps: &str) -> MemoryMap {
+    let fields = meminfo::parse(rollup);
+    let field = |name: &str| fields.get(name).copied().unwrap_or(0);
+    let rss = field("Rss");
+    let anonymous = field("Anonymous");
+    let (heap, stack) = special_mappings(smaps);
+    MemoryMap {
+        rss,
+        heap,
+        stack,
+        anonymous,
+        file_backed: rss.saturating_sub(anonymous),
+        shared: field("Shared_Clean") + field("Shared_Dirty"),
+        swap: field("Swap"),
+    }
+}
+
+// Each mapping starts with a header like "7ffc...-7ffd... rw-p 00000000 00:00 0  [stack]"
+// followed by "Key: value kB" lines
+fn special_mappings(smaps: &str) -> (u64, u64) {
+    let mut heap = 0;
+    let mut stack = 0;
+    let mut current: Option<&str> = None;
+    for line in smaps.lines() {
+        let first = line.split_whitespace().next().unwrap_or("");
+        if first.contains('-') && !first.ends_with(':') {
+            current = line.split_whitespace().nth(5);
+            continue;
+        }
+        let Some(kb) = line.strip_prefix("Rss:") else { continue };
+        let bytes = kb.split_whitespace().next().and_then(|v| v.parse::<u64>().ok()).unwrap_or(0) * 1024;
+        match current {
+            Some("[heap]") => heap += bytes,
+            Some(name) if name.starts_with("[stack") => stack += bytes,
+            _ => {}
+        }
+    }
+    (heap, stack)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROLLUP: &str = "\
+55d0c0000000-7ffd00000000 ---p 00000000 00:00 0                          [rollup]
+Rss:                8000 kB
+Pss:                6000 kB
+Shared_Clean:       1000 kB
+Shared_Dirty:        500 kB
+Private_Clean:      2000 kB
+Private_Dirty:      4500 kB
+Anonymous:          5000 kB
+Swap:                 64 kB
+";
+
+    const SMAPS: &str = "\
+55d0c1000000-55d0c1200000 rw-p 00000000 00:00 0                          [heap]
+Size:               2048 kB
+Rss:                1800 kB
+7f00aa000000-7f00aa100000 r-xp 00000000 08:01 1234                       /usr/lib/libc.so.6
+Size:               1024 kB
+Rss:                 900 kB
+7ffd00000000-7ffd00021000 rw-p 00000000 00:00 0                          [stack]
+Size:                132 kB
+Rss:                  40 kB
+";
+
+    #[test]
+    fn breaks_down_rollup() {
+        let map = parse(ROLLUP, SMAPS);
+        assert_eq!(map.rss, 8000 * 1024);
+        assert_eq!(map.heap, 1800 * 1024);
+        assert_eq!(map.stack, 40 * 1024);
+        assert_eq!(map.file_backed, 3000 * 1024);
+        assert_eq!(map.shared, 1500 * 1024);
+        assert_eq!(map.bands()[2], ("Other anon", (5000 - 1800 - 40) * 1024));
+    }
+}
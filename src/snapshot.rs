@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::time::SystemTime;
+use sysinfo::{System, SystemExt};
+
+use rusty_mem_monitor::Sample;
+
+use crate::processes::{self, ProcessRow, ProcessSort};
+
+#[derive(Clone)]
+pub struct Snapshot {
+    pub name: String,
+    pub taken_at: SystemTime,
+    pub total_memory: u64,
+    pub used_memory: u64,
+    pub total_swap: u64,
+    pub used_swap: u64,
+    // Every process, largest first, so any two snapshots can be diffed
+    pub processes: Vec<ProcessRow>,
+}
+
+pub struct SnapshotDiff {
+    pub used_memory: i64,
+    pub used_swap: i64,
+    pub seconds: f64,
+    // Largest growth first; processes that exited shrink to nothing
+    pub processes: Vec<ProcessDiff>,
+}
+
+// One process in either snapshot, with None where it wasn't running
+pub struct ProcessDiff {
+    pub pid: u32,
+    pub name: String,
+    pub before: Option<u64>,
+    pub after: Option<u64>,
+}
+
+impl ProcessDiff {
+    pub fn delta(&self) -> i64 {
+        self.after.unwrap_or(0) as i64 - self.before.unwrap_or(0) as i64
+    }
+}
+
+impl Snapshot {
+    // Refreshes on its own: when attached to a daemon the local System is never
+    // refreshed by the sampling loop.
+    pub fn capture(sys: &mut System, name: String) -> Self {
+        sys.refresh_memory();
+
+        let mut processes = processes::collect(sys);
+        processes::sort(&mut processes, ProcessSort::Memory);
+
+        Self {
+            name,
+            taken_at: SystemTime::now(),
+            total_memory: sys.total_memory(),
+            used_memory: sys.used_memory(),
+            total_swap: sys.total_swap(),
+            used_swap: sys.used_swap(),
+            processes,
+        }
+    }
+
+    // Remote hosts only send totals, so there are no processes to list
+    pub fn from_sample(sample: &Sample, name: String) -> Self {
+        Self {
+            name,
+            taken_at: SystemTime::now(),
+            total_memory: sample.total_memory,
+            used_memory: sample.used_memory,
+            total_swap: sample.total_swap,
+            used_swap: sample.used_swap,
+            processes: Vec::new(),
+        }
+    }
+
+    pub fn diff(&self, earlier: &Snapshot) -> SnapshotDiff {
+        // Matched on start time as well, so a reused pid isn't taken for the same process
+        let key = |row: &ProcessRow| (row.pid, row.start_time);
+        let mut before: HashMap<(u32, u64), &ProcessRow> =
+            earlier.processes.iter().map(|row| (key(row), row)).collect();
+        let mut processes: Vec<ProcessDiff> = self.processes
+            .iter()
+            .map(|row| ProcessDiff {
+                pid: row.pid,
+                name: row.name.clone(),
+                before: before.remove(&key(row)).map(|earlier| earlier.memory),
+                after: Some(row.memory),
+            })
+            .collect();
+        processes.extend(before.into_values().map(|row| ProcessDiff {
+            pid: row.pid,
+            name: row.name.clone(),
+            before: Some(row.memory),
+            after: None,
+        }));
+        processes.sort_by_key(|process| (std::cmp::Reverse(process.delta()), process.pid));
+        SnapshotDiff {
+            used_memory: self.used_memory as i64 - earlier.used_memory as i64,
+            used_swap: self.used_swap as i64 - earlier.used_swap as i64,
+            seconds: self.taken_at
+                .duration_since(earlier.taken_at)
+                .map(|d| d.as_secs_f64())
+                .unwrap_or(0.0),
+            processes,
+        }
+    }
+
+    pub fn memory_percentage(&self) -> f64 {
+        if self.total_memory > 0 {
+            self.used_memory as f64 / self.total_memory as f64 * 100.0
+        } else {
+            0.0
+        }
+    }
+
+    pub fn swap_percentage(&self) -> f64 {
+        if self.total_swap > 0 {
+            self.used_swap as f64 / self.total_swap as f64 * 100.0
+        } else {
+            0.0
+        }
+    }
+
+    pub fn age_secs(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(self.taken_at)
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn processes_diff_by_growth() {
+        let row = |pid, memory| ProcessRow { pid, name: format!("p{}", pid), memory, ..ProcessRow::default() };
+        let snapshot = |used, processes| Snapshot {
+            name: String::new(),
+            taken_at: SystemTime::UNIX_EPOCH,
+            total_memory: 1000,
+            used_memory: used,
+            total_swap: 0,
+            used_swap: 0,
+            processes,
+        };
+        let before = snapshot(500, vec![row(1, 100), row(2, 50), row(3, 30)]);
+        let after = snapshot(650, vec![row(2, 250), row(1, 90), row(4, 40)]);
+        let diff = after.diff(&before);
+        assert_eq!(diff.used_memory, 150);
+        let deltas: Vec<(u32, i64)> = diff.processes.iter().map(|process| (process.pid, process.delta())).collect();
+        assert_eq!(deltas, [(2, 200), (4, 40), (1, -10), (3, -30)]);
+        assert_eq!(diff.processes[3].after, None);
+    }
+}
@@ -0,0 +1,196 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use sysinfo::{Pid, PidExt, ProcessExt, ProcessRefreshKind, System, SystemExt};
+
+use rusty_mem_monitor::sample::now_ms;
+use rusty_mem_monitor::units::format_bytes;
+
+use crate::config::{self, SoakConfig};
+use crate::diag;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const DIR_NAME: &str = "soak";
+const FOOTPRINT_FILE: &str = "footprint.csv";
+const FOOTPRINT_HEADER: &str = "timestamp_ms,rss_bytes,disk_bytes,history_samples";
+// The footprint file may take this share of the disk cap before it's rotated to
+// `<name>.1`, replacing the previous one
+const ROTATE_SHARE: u64 = 20;
+// Output the monitor writes and can do without, oldest first, once over the disk cap
+const PRUNABLE: &[&str] = &["recordings", "exports", "reports", "screenshots", "logs"];
+
+const MB: u64 = 1024 * 1024;
+
+// Endurance mode for monitors left running for weeks: every CHECK_INTERVAL it
+// checks our own RSS and the data directory's size against the caps, prunes old
+// output to stay under the disk cap, and appends our footprint to a CSV so
+// slow growth shows up. Trimming memory is up to the caller, which knows what it holds.
+pub struct Soak {
+    config: SoakConfig,
+    dir: Option<PathBuf>,
+    checked: Option<Instant>,
+    recorded: Option<Instant>,
+}
+
+#[derive(Default)]
+pub struct Footprint {
+    pub rss: u64,
+    pub disk: u64,
+    // Our RSS is over the cap: trim whatever can be rebuilt
+    pub over_memory: bool,
+}
+
+impl Soak {
+    pub fn start(config: &SoakConfig) -> Self {
+        let dir = config::data_dir().map(|dir| dir.join(DIR_NAME));
+        diag::info(format!(
+            "Soak mode: capping memory at {} MB and disk at {} MB",
+            config.max_memory_mb, config.max_disk_mb
+        ));
+        Self {
+            config: config.clone(),
+            dir,
+            checked: None,
+            recorded: None,
+        }
+    }
+
+    // None between checks. `keep` are files still being written, e.g. the current recording.
+    pub fn check(&mut self, sys: &mut System, history_samples: usize, keep: &[&Path]) -> Option<Footprint> {
+        if self.checked.is_some_and(|at| at.elapsed() < CHECK_INTERVAL) {
+            return None;
+        }
+        self.checked = Some(Instant::now());
+        let pid = Pid::from_u32(std::process::id());
+        sys.refresh_process_specifics(pid, ProcessRefreshKind::new());
+        let rss = sys.process(pid).map_or(0, |process| process.memory());
+        let disk = config::data_dir().map_or(0, |dir| self.enforce_disk_cap(&dir, keep));
+        let footprint = Footprint {
+            rss,
+            disk,
+            over_memory: rss > self.config.max_memory_mb * MB,
+        };
+        let every = Duration::from_secs(self.config.footprint_minutes.max(1) * 60);
+        if self.recorded.is_none_or(|at| at.elapsed() >= every) {
+            self.recorded = Some(Instant::now());
+            if let Err(e) = self.record(&footprint, history_samples) {
+                diag::log(format!("Could not record footprint: {}", e));
+            }
+        }
+        Some(footprint)
+    }
+
+    // Returns the size of the data directory afterwards
+    fn enforce_disk_cap(&self, root: &Path, keep: &[&Path]) -> u64 {
+        let cap = self.config.max_disk_mb * MB;
+        let (used, removed) = prune(root, cap, keep);
+        if removed > 0 {
+            diag::info(format!("Soak mode: removed {} old files to stay under {}", removed, format_bytes(cap)));
+        }
+        if used > cap {
+            diag::log(format!("Soak mode: data directory is {}, over the {} cap", format_bytes(used), format_bytes(cap)));
+        }
+        used
+    }
+
+    fn record(&self, footprint: &Footprint, history_samples: usize) -> std::io::Result<()> {
+        let Some(dir) = &self.dir else { return Ok(()) };
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(FOOTPRINT_FILE);
+        rotate(&path, self.config.max_disk_mb * MB / ROTATE_SHARE)?;
+        let new = !path.exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        if new {
+            writeln!(file, "{}", FOOTPRINT_HEADER)?;
+        }
+        writeln!(file, "{},{},{},{}", now_ms(), footprint.rss, footprint.disk, history_samples)
+    }
+}
+
+// Moves `path` to `<path>.1` once it's grown past `max_bytes`
+fn rotate(path: &Path, max_bytes: u64) -> std::io::Result<()> {
+    match std::fs::metadata(path) {
+        Ok(metadata) if metadata.len() > max_bytes => {
+            let mut rotated = path.as_os_str().to_owned();
+            rotated.push(".1");
+            std::fs::rename(path, rotated)
+        }
+        _ => Ok(()),
+    }
+}
+
+// Deletes the oldest prunable files under `root` until it fits in `cap` bytes.
+// Returns the size afterwards and how many files went.
+fn prune(root: &Path, cap: u64, keep: &[&Path]) -> (u64, usize) {
+    let mut files = Vec::new();
+    walk(root, &mut files);
+    let mut used: u64 = files.iter().map(|(_, len, _)| len).sum();
+    if used <= cap {
+        return (used, 0);
+    }
+    let prunable = |path: &Path| {
+        PRUNABLE.iter().any(|dir| path.starts_with(root.join(dir))) && !keep.contains(&path)
+    };
+    let mut candidates: Vec<_> = files.into_iter().filter(|(path, _, _)| prunable(path)).collect();
+    candidates.sort_by_key(|(_, _, modified)| *modified);
+    let mut removed = 0;
+    for (path, len, _) in candidates {
+        if used <= cap {
+            break;
+        }
+        if std::fs::remove_file(&path).is_ok() {
+            used -= len;
+            removed += 1;
+        }
+    }
+    (used, removed)
+}
+
+fn walk(dir: &Path, files: &mut Vec<(PathBuf, u64, SystemTime)>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else { continue };
+        if metadata.is_dir() {
+            walk(&entry.path(), files);
+        } else {
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            files.push((entry.path(), metadata.len(), modified));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prunes_the_oldest_output_first() {
+        let root = std::env::temp_dir().join(format!("rmm-soak-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        for dir in ["recordings", "outbox"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        let file = |name: &str, len: usize| {
+            let path = root.join(name);
+            std::fs::write(&path, vec![b'x'; len]).unwrap();
+            // Modification times need to differ for the order to be certain
+            std::thread::sleep(Duration::from_millis(20));
+            path
+        };
+        let oldest = file("recordings/a.csv", 400);
+        let queue = file("outbox/webhooks.jsonl", 400);
+        let active = file("recordings/b.csv", 400);
+        let newer = file("recordings/c.csv", 400);
+
+        assert_eq!(prune(&root, 2000, &[]), (1600, 0));
+        // The active recording is skipped even though it's older than c.csv
+        assert_eq!(prune(&root, 900, &[&active]), (800, 2));
+        assert!(!oldest.exists() && !newer.exists() && active.exists() && queue.exists());
+
+        rotate(&active, 100).unwrap();
+        assert!(!active.exists() && root.join("recordings/b.csv.1").exists());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
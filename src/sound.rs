@@ -0,0 +1,158 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::config::SoundConfig;
+use crate::diag;
+
+const RATE: u32 = 22_050;
+const PITCH: f32 = 880.0;
+// Three short beeps, 150 ms on and 100 ms off
+const BEEPS: u32 = 3;
+const BEEP_MS: u32 = 150;
+const GAP_MS: u32 = 100;
+
+// Told apart in the file name, so a test play can overlap the alarm
+static PLAYS: AtomicU32 = AtomicU32::new(0);
+
+// The alarm's beeping: starts with the critical alarm, repeats while it's up and
+// unacknowledged, and gets louder as escalation stages ask for it. Acknowledging,
+// snoozing or recovering silences it and puts the volume back.
+#[derive(Default)]
+pub struct AlarmSound {
+    // Raised by escalation stages for the rest of this alarm
+    volume: Option<f32>,
+    played: Option<Instant>,
+}
+
+impl AlarmSound {
+    pub fn update(&mut self, sounding: bool, config: &SoundConfig) {
+        if !sounding || !config.enabled {
+            *self = Self::default();
+            return;
+        }
+        let repeat = Duration::from_secs_f64(config.repeat_secs.max(1.0));
+        if self.played.is_some_and(|at| at.elapsed() < repeat) {
+            return;
+        }
+        self.played = Some(Instant::now());
+        let volume = self.volume.unwrap_or(config.volume).max(config.volume);
+        std::thread::spawn(move || {
+            if let Err(e) = play(volume) {
+                diag::log(format!("Could not play the alarm sound: {}", e));
+            }
+        });
+    }
+
+    // From an escalation stage's sound_volume; takes effect from the next beep
+    pub fn escalate(&mut self, volume: f32) {
+        self.volume = Some(self.volume.unwrap_or(0.0).max(volume.clamp(0.0, 1.0)));
+    }
+}
+
+// Plays the alarm once at `volume` (0-1) and waits for it to finish. Returns the
+// player that made the noise.
+pub fn play(volume: f32) -> Result<String, String> {
+    let play = PLAYS.fetch_add(1, Ordering::Relaxed);
+    let name = format!("rusty_mem_monitor-alarm-{}-{}.wav", std::process::id(), play);
+    let path = std::env::temp_dir().join(name);
+    std::fs::File::create(&path)
+        .and_then(|mut file| file.write_all(&tone(volume)))
+        .map_err(|e| format!("could not write {}: {}", path.display(), e))?;
+    let mut played = None;
+    let mut failures = Vec::new();
+    for (program, args) in players(&path) {
+        match Command::new(program).args(&args).stdin(Stdio::null()).stdout(Stdio::null()).status() {
+            Ok(status) if status.success() => {
+                played = Some(program.to_string());
+                break;
+            }
+            Ok(status) => failures.push(format!("{} exited with {}", program, status)),
+            Err(e) => failures.push(format!("{}: {}", program, e)),
+        }
+    }
+    let _ = std::fs::remove_file(&path);
+    played.ok_or_else(|| failures.join("; "))
+}
+
+// Whatever each platform can play a WAV file with, first choice first
+fn players(path: &Path) -> Vec<(&'static str, Vec<String>)> {
+    let path = path.display().to_string();
+    if cfg!(windows) {
+        let script = format!("(New-Object Media.SoundPlayer '{}').PlaySync()", path.replace('\'', "''"));
+        vec![("powershell", vec!["-NoProfile".to_string(), "-Command".to_string(), script])]
+    } else if cfg!(target_os = "macos") {
+        vec![("afplay", vec![path])]
+    } else {
+        // PulseAudio, PipeWire, then bare ALSA
+        vec![
+            ("paplay", vec![path.clone()]),
+            ("pw-play", vec![path.clone()]),
+            ("aplay", vec!["-q".to_string(), path]),
+        ]
+    }
+}
+
+// 16-bit mono PCM in a WAV container, with the volume baked into the samples so
+// every player sounds the same
+fn tone(volume: f32) -> Vec<u8> {
+    let amplitude = volume.clamp(0.0, 1.0) * i16::MAX as f32;
+    let beep = RATE * BEEP_MS / 1000;
+    let period = beep + RATE * GAP_MS / 1000;
+    let count = period * BEEPS;
+    let mut samples = Vec::with_capacity(count as usize * 2);
+    for i in 0..count {
+        let t = i % period;
+        let value = if t < beep {
+            // A few ms of fade at each end, so the beeps don't click
+            let fade = (t.min(beep - t) as f32 / (RATE / 200) as f32).min(1.0);
+            (2.0 * std::f32::consts::PI * PITCH * i as f32 / RATE as f32).sin() * amplitude * fade
+        } else {
+            0.0
+        };
+        samples.extend_from_slice(&(value as i16).to_le_bytes());
+    }
+    let mut wav = Vec::with_capacity(44 + samples.len());
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + samples.len() as u32).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&RATE.to_le_bytes());
+    wav.extend_from_slice(&(RATE * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+    wav.extend_from_slice(&samples);
+    wav
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tone_is_a_wav_scaled_by_volume() {
+        let quiet = tone(0.25);
+        let loud = tone(1.0);
+        assert_eq!(&quiet[..4], b"RIFF");
+        assert_eq!(&quiet[36..40], b"data");
+        assert_eq!(quiet.len(), loud.len());
+        let peak = |wav: &[u8]| {
+            wav[44..].chunks(2).map(|pair| i16::from_le_bytes([pair[0], pair[1]]).unsigned_abs()).max().unwrap()
+        };
+        assert!(peak(&loud) > 30_000);
+        assert!(peak(&quiet) < 8_200);
+
+        let mut alarm = AlarmSound::default();
+        alarm.escalate(1.5);
+        alarm.escalate(0.6);
+        assert_eq!(alarm.volume, Some(1.0));
+        alarm.update(false, &SoundConfig::default());
+        assert_eq!(alarm.volume, None);
+    }
+}
@@ -0,0 +1,59 @@
+use std::path::PathBuf;
+
+use crate::cli::Args;
+use crate::config::AgentConfig;
+use crate::daemon::DaemonClient;
+use crate::diag;
+
+// Where the window's samples come from. Only Local has this machine behind it:
+// processes, sensors and the rest of sysinfo; the others are just samples, off
+// the wire or out of a file, and the panels that need more are left out.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Source {
+    Local,
+    Remote(String),
+    Replay(PathBuf),
+}
+
+impl Source {
+    // Several --connect hosts are a dashboard, which samples nothing here itself
+    pub fn of(args: &Args) -> Self {
+        match (&args.replay, args.connect.as_slice()) {
+            (Some(path), _) => Self::Replay(PathBuf::from(path)),
+            (None, [addr]) => Self::Remote(addr.clone()),
+            _ => Self::Local,
+        }
+    }
+
+    pub fn is_local(&self) -> bool {
+        *self == Self::Local
+    }
+
+    // What the title bar and alerts name instead of our host name
+    pub fn label(&self) -> Option<String> {
+        match self {
+            Self::Local => None,
+            Self::Remote(addr) => Some(addr.clone()),
+            Self::Replay(path) => {
+                let name = path.file_name().unwrap_or(path.as_os_str()).to_string_lossy();
+                Some(format!("replay {}", name))
+            }
+        }
+    }
+
+    // The stream the window reads; None samples in process
+    pub fn client(&self, args: &Args, agent: &AgentConfig) -> Option<DaemonClient> {
+        match self {
+            Self::Remote(addr) => Some(DaemonClient::remote(addr, agent)),
+            Self::Replay(path) => match DaemonClient::replay(path) {
+                Ok(client) => Some(client),
+                Err(e) => {
+                    diag::log(format!("Could not replay {}: {}", path.display(), e));
+                    None
+                }
+            },
+            Self::Local if !args.standalone && !args.safe_mode && args.connect.is_empty() => DaemonClient::connect(),
+            Self::Local => None,
+        }
+    }
+}
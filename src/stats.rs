@@ -0,0 +1,124 @@
+use crate::history::History;
+
+/// Min, max, mean and 95th percentile of a series.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Summary {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub p95: f64,
+}
+
+impl Summary {
+    /// Returns `None` for an empty series. The percentile is nearest-rank.
+    pub fn of(values: impl IntoIterator<Item = f64>) -> Option<Self> {
+        let mut values: Vec<f64> = values.into_iter().collect();
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(f64::total_cmp);
+        let rank = (values.len() as f64 * 0.95).ceil() as usize;
+        Some(Self {
+            min: values[0],
+            max: values[values.len() - 1],
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            p95: values[rank.clamp(1, values.len()) - 1],
+        })
+    }
+}
+
+/// Memory and swap usage in percent over everything in `history`.
+pub fn memory_summary(history: &History) -> Option<Summary> {
+    Summary::of(history.iter().map(|sample| sample.memory_percentage() as f64))
+}
+
+pub fn swap_summary(history: &History) -> Option<Summary> {
+    Summary::of(history.iter().map(|sample| sample.swap_percentage() as f64))
+}
+
+/// Least-squares slope of used memory, in bytes per minute. A fitted line
+/// rather than first-to-last, so one spike at either end doesn't decide it.
+pub fn growth_per_minute(history: &History) -> Option<f64> {
+    let first_ms = history.first()?.timestamp_ms;
+    let points: Vec<(f64, f64)> = history
+        .iter()
+        .map(|sample| ((sample.timestamp_ms - first_ms) as f64 / 60_000.0, sample.used_memory as f64))
+        .collect();
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let covariance: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    (variance > 0.0).then(|| covariance / variance)
+}
+
+/// Trailing mean over up to `window` values; the first few average what's there.
+pub fn moving_average(values: &[f64], window: usize) -> Vec<f64> {
+    let window = window.max(1);
+    let mut sum = 0.0;
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            sum += value;
+            if i >= window {
+                sum -= values[i - window];
+            }
+            sum / (i + 1).min(window) as f64
+        })
+        .collect()
+}
+
+/// Exponential moving average; `alpha` near 1 follows the data, near 0 smooths hard.
+pub fn exponential_moving_average(values: &[f64], alpha: f64) -> Vec<f64> {
+    let alpha = alpha.clamp(0.0, 1.0);
+    let mut current = None;
+    values
+        .iter()
+        .map(|&value| {
+            let next = current.map_or(value, |previous: f64| previous + alpha * (value - previous));
+            current = Some(next);
+            next
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sample;
+
+    #[test]
+    fn summarizes_series() {
+        let summary = Summary::of((1..=100).map(f64::from)).unwrap();
+        assert_eq!(summary.min, 1.0);
+        assert_eq!(summary.max, 100.0);
+        assert_eq!(summary.mean, 50.5);
+        assert_eq!(summary.p95, 95.0);
+        assert_eq!(Summary::of([]), None);
+    }
+
+    #[test]
+    fn smooths_series() {
+        assert_eq!(moving_average(&[2.0, 4.0, 6.0, 8.0], 2), [2.0, 3.0, 5.0, 7.0]);
+        assert_eq!(exponential_moving_average(&[10.0, 20.0, 20.0], 0.5), [10.0, 15.0, 17.5]);
+    }
+
+    #[test]
+    fn growth_is_fitted_per_minute() {
+        let mut history = History::new(10);
+        for minute in 0..5u64 {
+            history.push(&Sample {
+                timestamp_ms: minute * 60_000,
+                total_memory: 1 << 30,
+                used_memory: 1000 + minute * 500,
+                ..Sample::default()
+            });
+        }
+        assert!((growth_per_minute(&history).unwrap() - 500.0).abs() < 1e-9);
+
+        let mut single = History::new(10);
+        single.push(&Sample::default());
+        assert_eq!(growth_per_minute(&single), None);
+    }
+}
@@ -0,0 +1,68 @@
+use std::io::Write;
+
+use rusty_mem_monitor::alert::Thresholds;
+use rusty_mem_monitor::units::format_bytes;
+use rusty_mem_monitor::{Sample, Sampler};
+
+use crate::cli::StatusbarFormat;
+use crate::config::Config;
+use crate::daemon::{self, DaemonClient};
+
+// Headless line-per-sample output for status bars. Reuses a running daemon's
+// stream when there is one so the bar doesn't add another sampler.
+pub fn run(format: StatusbarFormat, standalone: bool) -> std::io::Result<()> {
+    let daemon = if standalone { None } else { DaemonClient::connect() };
+    let mut sampler = Sampler::new();
+    let stdout = std::io::stdout();
+    let thresholds = Config::load().alerts.thresholds;
+
+    loop {
+        let sample = match &daemon {
+            Some(daemon) if daemon.is_connected() => daemon.poll().pop(),
+            _ => Some(sampler.sample()),
+        };
+        if let Some(sample) = sample {
+            let mut out = stdout.lock();
+            writeln!(out, "{}", format_line(&sample, format, &thresholds))?;
+            out.flush()?;
+        }
+        std::thread::sleep(daemon::SAMPLE_INTERVAL);
+    }
+}
+
+fn format_line(sample: &Sample, format: StatusbarFormat, thresholds: &Thresholds) -> String {
+    let memory = sample.memory_percentage();
+    let swap = if sample.has_swap() { format!("{:.1}%", sample.swap_percentage()) } else { "N/A".to_string() };
+    match format {
+        StatusbarFormat::Plain if !sample.has_memory() => format!("MEM N/A SWP {}", swap),
+        StatusbarFormat::Plain => format!("MEM {:.1}% SWP {}", memory, swap),
+        StatusbarFormat::Waybar if !sample.has_memory() => serde_json::json!({
+            "text": "MEM N/A",
+            "tooltip": "Memory totals are unavailable",
+            "class": "unavailable",
+        })
+        .to_string(),
+        StatusbarFormat::Waybar => {
+            let class = if memory > thresholds.critical {
+                "critical"
+            } else if memory > thresholds.warning {
+                "warning"
+            } else {
+                "normal"
+            };
+            serde_json::json!({
+                "text": format!("MEM {:.0}%", memory),
+                "tooltip": format!(
+                    "RAM {} / {} ({:.1}%)\nSwap {}",
+                    format_bytes(sample.used_memory),
+                    format_bytes(sample.total_memory),
+                    memory,
+                    swap,
+                ),
+                "class": class,
+                "percentage": memory.round() as u32,
+            })
+            .to_string()
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use eframe::egui::Color32;
+use serde::{Deserialize, Serialize};
+
+use rusty_mem_monitor::alert::Thresholds;
+
+use crate::config::{self, GlitchConfig};
+use crate::diag;
+
+// Every color the UI draws with. Themes are plain TOML files with "#rrggbb" values,
+// the same format the registry hands out, so user themes and presets are interchangeable.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    pub dark: bool,
+    #[serde(with = "hex")]
+    pub background: Color32,
+    #[serde(with = "hex")]
+    pub primary: Color32,
+    #[serde(with = "hex")]
+    pub dim: Color32,
+    #[serde(with = "hex")]
+    pub info: Color32,
+    #[serde(with = "hex")]
+    pub warning: Color32,
+    #[serde(with = "hex")]
+    pub critical: Color32,
+    #[serde(with = "hex")]
+    pub swap: Color32,
+    #[serde(with = "hex")]
+    pub buffers: Color32,
+    #[serde(with = "hex")]
+    pub free: Color32,
+    // Only in exported themes, so a shared look carries its glitch, CRT and rain
+    // settings too; they're applied when the file is imported
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effects: Option<Effects>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct Effects {
+    pub glitch: GlitchConfig,
+    pub crt: bool,
+    pub rain: bool,
+}
+
+impl Theme {
+    // Green / yellow / red ladder shared by every percentage readout
+    pub fn level(&self, percentage: f32, thresholds: &Thresholds) -> Color32 {
+        if percentage > thresholds.critical {
+            self.critical
+        } else if percentage > thresholds.warning {
+            self.warning
+        } else {
+            self.primary
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn preset(
+    name: &str,
+    dark: bool,
+    background: [u8; 3],
+    primary: [u8; 3],
+    dim: [u8; 3],
+    info: [u8; 3],
+    warning: [u8; 3],
+    critical: [u8; 3],
+    swap: [u8; 3],
+    buffers: [u8; 3],
+    free: [u8; 3],
+) -> Theme {
+    let rgb = |c: [u8; 3]| Color32::from_rgb(c[0], c[1], c[2]);
+    Theme {
+        name: name.to_string(),
+        dark,
+        background: rgb(background),
+        primary: rgb(primary),
+        dim: rgb(dim),
+        info: rgb(info),
+        warning: rgb(warning),
+        critical: rgb(critical),
+        swap: rgb(swap),
+        buffers: rgb(buffers),
+        free: rgb(free),
+        effects: None,
+    }
+}
+
+pub fn presets() -> Vec<Theme> {
+    vec![
+        preset(config::DEFAULT_THEME, true,
+            [0, 15, 0], [0, 255, 0], [0, 180, 0], [0, 255, 255],
+            [255, 255, 0], [255, 0, 0], [255, 100, 0], [0, 160, 255], [0, 70, 0]),
+        preset("Amber Terminal", true,
+            [20, 10, 0], [255, 176, 0], [190, 120, 0], [255, 220, 120],
+            [255, 255, 160], [255, 60, 40], [255, 120, 60], [200, 140, 40], [80, 50, 0]),
+        preset("Cyberpunk Purple", true,
+            [15, 0, 25], [200, 80, 255], [140, 60, 180], [0, 240, 255],
+            [255, 230, 0], [255, 20, 90], [255, 110, 200], [90, 120, 255], [50, 20, 70]),
+        preset("Light", false,
+            [245, 245, 240], [0, 110, 40], [70, 90, 70], [0, 90, 160],
+            [170, 110, 0], [200, 0, 0], [200, 80, 0], [60, 110, 200], [200, 215, 200]),
+        // Okabe-Ito palette: states differ in luminance as well as hue
+        preset("Colorblind Safe", true,
+            [10, 10, 10], [86, 180, 233], [170, 170, 170], [255, 255, 255],
+            [240, 228, 66], [230, 159, 0], [204, 121, 167], [0, 114, 178], [60, 60, 60]),
+    ]
+}
+
+// Presets first, then any *.toml themes found in the config dir's themes/ folder
+pub fn available() -> Vec<Theme> {
+    let mut themes = presets();
+    let Some(dir) = config::config_dir().map(|dir| dir.join("themes")) else {
+        return themes;
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return themes;
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+    for path in paths {
+        let parsed = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| toml::from_str::<Theme>(&text).map_err(|e| e.to_string()));
+        match parsed {
+            Ok(theme) if !themes.iter().any(|t| t.name == theme.name) => themes.push(theme),
+            Ok(theme) => diag::log(format!("Skipping theme {}: duplicate name {}", path.display(), theme.name)),
+            Err(e) => diag::log(format!("Skipping theme {}: {}", path.display(), e)),
+        }
+    }
+    themes
+}
+
+pub fn find(themes: &[Theme], name: &str) -> Theme {
+    themes.iter()
+        .find(|theme| theme.name == name)
+        .cloned()
+        .unwrap_or_else(|| presets().remove(0))
+}
+
+// The shareable form of the current look: the theme plus the effects it's shown with
+pub fn export(theme: &Theme, effects: Effects) -> String {
+    let theme = Theme {
+        effects: Some(effects),
+        ..theme.clone()
+    };
+    toml::to_string(&theme).expect("themes serialize")
+}
+
+// Copies a shared theme file into themes/, where `available` picks it up. A
+// theme may replace an earlier import of the same name but not a preset.
+pub fn import(path: &std::path::Path) -> Result<Theme, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    let theme: Theme = toml::from_str(&text).map_err(|e| format!("Not a theme file: {}", e))?;
+    if theme.name.trim().is_empty() {
+        return Err("The theme has no name".to_string());
+    }
+    if presets().iter().any(|preset| preset.name == theme.name) {
+        return Err(format!("\"{}\" is a built-in theme; rename it in the file first", theme.name));
+    }
+    let dir = config::config_dir().ok_or("No config directory")?.join("themes");
+    let file_name: String = theme.name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    std::fs::create_dir_all(&dir)
+        .and_then(|()| std::fs::write(dir.join(format!("{}.toml", file_name)), &text))
+        .map_err(|e| format!("Could not save the theme: {}", e))?;
+    Ok(theme)
+}
+
+// "#rrggbb", as in theme files
+pub fn parse_hex(text: &str) -> Option<Color32> {
+    let digits = text.strip_prefix('#').unwrap_or(text);
+    if digits.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| digits.get(i..i + 2).and_then(|c| u8::from_str_radix(c, 16).ok());
+    Some(Color32::from_rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+mod hex {
+    use eframe::egui::Color32;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(color: &Color32, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("#{:02x}{:02x}{:02x}", color.r(), color.g(), color.b()))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color32, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        super::parse_hex(&text).ok_or_else(|| serde::de::Error::custom(format!("invalid color {:?}", text)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exports_round_trip_with_effects() {
+        let theme = presets().remove(1);
+        let effects = Effects {
+            glitch: GlitchConfig {
+                probability: 0.2,
+                charset: "01".to_string(),
+                ..GlitchConfig::default()
+            },
+            crt: true,
+            rain: false,
+        };
+        let text = export(&theme, effects.clone());
+        let parsed: Theme = toml::from_str(&text).unwrap();
+        assert!(parsed.effects == Some(effects));
+        assert!(Theme { effects: None, ..parsed } == theme);
+        // Plain theme files, as in themes/ and the registry, still load
+        let plain = toml::to_string(&theme).unwrap();
+        assert!(!plain.contains("effects"));
+        assert!(toml::from_str::<Theme>(&plain).unwrap() == theme);
+    }
+}
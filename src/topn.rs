@@ -0,0 +1,142 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use eframe::egui;
+use egui_plot::{Bar, BarChart};
+
+use crate::processes::ProcessRow;
+use crate::series::relative_secs;
+
+// Ten minutes at the usual two-second refresh
+const MAX_POINTS: usize = 300;
+// Kept per point, more than are charted, so a process climbing into the top N
+// already has some history when it gets there
+const KEPT: usize = 20;
+
+struct Point {
+    timestamp_ms: u64,
+    used: u64,
+    // (pid, RSS), largest first
+    processes: Vec<(u32, u64)>,
+}
+
+// History of the biggest processes for the top-processes chart: which ones make
+// up the used total, and whose growth moves it
+#[derive(Default)]
+pub struct TopProcesses {
+    points: VecDeque<Point>,
+    names: HashMap<u32, String>,
+    recorded: Option<Instant>,
+}
+
+impl TopProcesses {
+    pub fn due(&self, interval: Duration) -> bool {
+        self.recorded.is_none_or(|at| at.elapsed() >= interval)
+    }
+
+    pub fn record(&mut self, rows: &[ProcessRow], used: u64, timestamp_ms: u64) {
+        self.recorded = Some(Instant::now());
+        let mut processes: Vec<(u32, u64)> = rows.iter().map(|row| (row.pid, row.memory)).collect();
+        processes.sort_by_key(|(_, memory)| std::cmp::Reverse(*memory));
+        processes.truncate(KEPT);
+        for row in rows.iter().filter(|row| processes.iter().any(|(pid, _)| *pid == row.pid)) {
+            self.names.insert(row.pid, row.name.clone());
+        }
+        self.points.push_back(Point { timestamp_ms, used, processes });
+        if self.points.len() > MAX_POINTS {
+            self.points.pop_front();
+        }
+        let points = &self.points;
+        self.names.retain(|pid, _| points.iter().any(|point| point.processes.iter().any(|(kept, _)| kept == pid)));
+    }
+
+    // The `n` processes with the most memory over the whole window, bottom band
+    // first, then everything else in use as one band: (pid, value per point)
+    fn bands(&self, n: usize) -> Vec<(Option<u32>, Vec<u64>)> {
+        let mut totals: HashMap<u32, u64> = HashMap::new();
+        for point in &self.points {
+            for (pid, memory) in &point.processes {
+                *totals.entry(*pid).or_default() += memory;
+            }
+        }
+        let mut ranked: Vec<(u32, u64)> = totals.into_iter().collect();
+        ranked.sort_by_key(|(pid, total)| (std::cmp::Reverse(*total), *pid));
+        ranked.truncate(n);
+
+        let mut bands: Vec<(Option<u32>, Vec<u64>)> = ranked
+            .iter()
+            .map(|(pid, _)| {
+                let values = self.points.iter().map(|point| {
+                    point.processes.iter().find(|(kept, _)| kept == pid).map_or(0, |(_, memory)| *memory)
+                });
+                (Some(*pid), values.collect())
+            })
+            .collect();
+        let other = self.points.iter().enumerate().map(|(i, point)| {
+            let charted: u64 = bands.iter().map(|(_, values)| values[i]).sum();
+            point.used.saturating_sub(charted)
+        });
+        bands.push((None, other.collect()));
+        bands
+    }
+
+    pub fn charts(&self, n: usize, latest_ms: u64, other: (String, egui::Color32)) -> Vec<BarChart> {
+        let width = match (self.points.front(), self.points.back()) {
+            (Some(first), Some(last)) if self.points.len() > 1 => {
+                (last.timestamp_ms - first.timestamp_ms) as f64 / 1000.0 / (self.points.len() - 1) as f64
+            }
+            _ => 1.0,
+        };
+        let mut charts: Vec<BarChart> = Vec::new();
+        for (pid, values) in self.bands(n) {
+            let (name, color) = match pid {
+                Some(pid) => {
+                    let name = self.names.get(&pid).map_or("?", String::as_str);
+                    (format!("{} ({})", name, pid), color_for(pid))
+                }
+                None => other.clone(),
+            };
+            let bars = self.points
+                .iter()
+                .zip(values)
+                .map(|(point, value)| {
+                    Bar::new(relative_secs(point.timestamp_ms, latest_ms), value as f64)
+                        .width(width)
+                        .fill(color)
+                        .stroke(egui::Stroke::NONE)
+                })
+                .collect();
+            let below: Vec<&BarChart> = charts.iter().collect();
+            let chart = BarChart::new(bars).name(name).color(color).stack_on(&below);
+            charts.push(chart);
+        }
+        charts
+    }
+}
+
+// Spread around the color wheel by the golden ratio, so a process keeps its
+// color for as long as it runs and neighbouring pids don't look alike
+fn color_for(pid: u32) -> egui::Color32 {
+    let hue = (pid as f64 * 0.618_033_988_75).fract() as f32;
+    egui::ecolor::Hsva::new(hue, 0.7, 0.85, 1.0).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn biggest_processes_get_bands_and_the_rest_is_other() {
+        let row = |pid, memory| ProcessRow { pid, name: format!("p{}", pid), memory, ..ProcessRow::default() };
+        let mut top = TopProcesses::default();
+        top.record(&[row(1, 100), row(2, 50), row(3, 10)], 200, 1000);
+        top.record(&[row(1, 100), row(2, 80), row(3, 10)], 250, 2000);
+        let bands = top.bands(2);
+        assert_eq!(bands[0], (Some(1), vec![100, 100]));
+        assert_eq!(bands[1], (Some(2), vec![50, 80]));
+        assert_eq!(bands[2], (None, vec![50, 70]));
+        assert_eq!(top.names[&2], "p2");
+        assert_eq!(color_for(7), color_for(7));
+        assert_ne!(color_for(7), color_for(8));
+    }
+}
@@ -0,0 +1,43 @@
+const UNITS: [&str; 6] = ["B", "KB", "MB", "GB", "TB", "PB"];
+
+/// Formats a byte count in the largest binary unit that keeps the value at least 1,
+/// e.g. `812.0 MB`, `15.6 GB` or `2.0 TB`.
+pub fn format_bytes(bytes: u64) -> String {
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// [`format_bytes`] with an explicit sign, for differences between two readings.
+pub fn format_bytes_delta(bytes: i64) -> String {
+    let sign = if bytes < 0 { '-' } else { '+' };
+    format!("{}{}", sign, format_bytes(bytes.unsigned_abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_unit_by_magnitude() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(16 * 1024 * 1024 * 1024), "16.0 GB");
+        assert_eq!(format_bytes(6 * 1024u64.pow(4) + 1024u64.pow(4) / 2), "6.5 TB");
+        assert_eq!(format_bytes(u64::MAX), "16384.0 PB");
+    }
+
+    #[test]
+    fn deltas_are_signed() {
+        assert_eq!(format_bytes_delta(-3 * 1024 * 1024), "-3.0 MB");
+        assert_eq!(format_bytes_delta(0), "+0 B");
+    }
+}
@@ -0,0 +1,160 @@
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::time::{Duration, Instant};
+
+use crate::diag;
+
+// How long a refresh may run before its panel is marked degraded, and how long
+// the retries back off to at most
+const TIMEOUT: Duration = Duration::from_secs(3);
+const FIRST_RETRY: Duration = Duration::from_secs(5);
+const MAX_RETRY: Duration = Duration::from_secs(300);
+
+// Runs one metric backend's refresh (a cgroup walk, a container engine query, a
+// sensor driver) on a worker thread so a hung call can't freeze the window. Its
+// state moves into the worker and back; a worker that never returns takes it
+// along, and the retry starts over from `S::default()`.
+pub struct Backend<S, T> {
+    name: &'static str,
+    state: Option<S>,
+    pending: Option<(Receiver<(S, T)>, Instant)>,
+    failures: u32,
+    retry_at: Option<Instant>,
+    // The running worker has overrun TIMEOUT and been counted as a failure
+    overran: bool,
+}
+
+impl<S: Default + Send + 'static, T: Send + 'static> Backend<S, T> {
+    pub fn new(name: &'static str) -> Self {
+        Self { name, state: Some(S::default()), pending: None, failures: 0, retry_at: None, overran: false }
+    }
+
+    // Starts a refresh when `due` and none is running, and hands back its result
+    // once there is one. A late result from a hung worker is still taken.
+    pub fn poll(&mut self, due: bool, refresh: fn(&mut S) -> T) -> Option<T> {
+        if let Some((rx, started)) = &self.pending {
+            match rx.try_recv() {
+                Ok((state, value)) => {
+                    if self.failures > 0 {
+                        diag::info(format!("{} backend recovered", self.name));
+                    }
+                    self.state = Some(state);
+                    self.pending = None;
+                    self.failures = 0;
+                    self.retry_at = None;
+                    return Some(value);
+                }
+                Err(TryRecvError::Disconnected) => {
+                    diag::log(format!("{} backend failed while refreshing", self.name));
+                    self.pending = None;
+                    self.back_off();
+                }
+                Err(TryRecvError::Empty) => {
+                    // Each worker, a retry included, gets the full TIMEOUT of its own
+                    if started.elapsed() < TIMEOUT {
+                        return None;
+                    }
+                    if !self.overran {
+                        diag::log(format!("{} backend hasn't answered in {}s", self.name, TIMEOUT.as_secs()));
+                        self.overran = true;
+                        self.back_off();
+                    }
+                    if self.retry_at.is_some_and(|at| Instant::now() < at) {
+                        return None;
+                    }
+                    // Still hung at the retry: leave that worker be and start a fresh one
+                    self.pending = None;
+                }
+            }
+        }
+        let waiting = self.retry_at.is_some_and(|at| Instant::now() < at);
+        if (due || self.retry_at.is_some()) && !waiting {
+            self.start(refresh);
+        }
+        None
+    }
+
+    // Hung or failing, and being retried with backoff
+    pub fn degraded(&self) -> Option<Duration> {
+        self.retry_at.map(|at| at.saturating_duration_since(Instant::now()))
+    }
+
+    fn start(&mut self, refresh: fn(&mut S) -> T) {
+        let mut state = self.state.take().unwrap_or_default();
+        let (tx, rx) = channel();
+        let spawned = std::thread::Builder::new().name(format!("{}-backend", self.name)).spawn(move || {
+            let value = refresh(&mut state);
+            let _ = tx.send((state, value));
+        });
+        match spawned {
+            Ok(_) => {
+                self.pending = Some((rx, Instant::now()));
+                self.overran = false;
+            }
+            Err(e) => {
+                diag::log(format!("Could not start the {} backend: {}", self.name, e));
+                self.back_off();
+            }
+        }
+    }
+
+    fn back_off(&mut self) {
+        self.retry_at = Some(Instant::now() + retry_delay(self.failures));
+        self.failures = self.failures.saturating_add(1);
+    }
+}
+
+fn retry_delay(failures: u32) -> Duration {
+    FIRST_RETRY.saturating_mul(1 << failures.min(16)).min(MAX_RETRY)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn results_come_back_and_retries_back_off() {
+        let mut backend: Backend<u32, u32> = Backend::new("counter");
+        let bump = |count: &mut u32| {
+            *count += 1;
+            *count
+        };
+        assert_eq!(backend.poll(true, bump), None);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut value = None;
+        while value.is_none() && Instant::now() < deadline {
+            value = backend.poll(false, bump);
+        }
+        assert_eq!(value, Some(1));
+        assert!(backend.pending.is_none() && backend.degraded().is_none());
+
+        assert_eq!(retry_delay(0), FIRST_RETRY);
+        assert_eq!(retry_delay(2), FIRST_RETRY * 4);
+        assert_eq!(retry_delay(40), MAX_RETRY);
+    }
+
+    #[test]
+    fn a_slow_retry_is_waited_for() {
+        static STARTED: AtomicUsize = AtomicUsize::new(0);
+        let slow = |_: &mut u32| {
+            STARTED.fetch_add(1, Ordering::Relaxed);
+            std::thread::sleep(Duration::from_millis(300));
+            7
+        };
+        // As after a failed refresh whose retry is now due
+        let mut backend: Backend<u32, u32> = Backend::new("slow");
+        backend.back_off();
+        backend.retry_at = Some(Instant::now());
+
+        let deadline = Instant::now() + Duration::from_secs(2);
+        let mut value = None;
+        while value.is_none() && Instant::now() < deadline {
+            value = backend.poll(false, slow);
+            std::thread::sleep(Duration::from_millis(16));
+        }
+        assert_eq!(value, Some(7));
+        assert_eq!(STARTED.load(Ordering::Relaxed), 1);
+        assert!(backend.degraded().is_none());
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use base64::Engine;
+use sha1::{Digest, Sha1};
+
+use rusty_mem_monitor::Sample;
+
+use crate::diag;
+
+const PAGE: &str = include_str!("web.html");
+// Five minutes at the default interval, so a freshly opened page starts with a graph
+const BACKLOG: usize = 600;
+const WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_REQUEST: usize = 8 * 1024;
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+#[derive(Default)]
+struct Shared {
+    backlog: VecDeque<Sample>,
+    clients: Vec<TcpStream>,
+}
+
+// `--web`: a page with live graphs at / and the samples behind it as a WebSocket
+// at /ws, for a browser elsewhere on the LAN. Like the agent it's readable by
+// anyone who can connect, so bind it to a trusted network.
+pub struct WebServer {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl WebServer {
+    pub fn start(addr: &str, host: &str) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let accept_shared = Arc::clone(&shared);
+        let hello = serde_json::json!({ "host": host }).to_string();
+        std::thread::Builder::new().name("web".to_string()).spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { continue };
+                let shared = Arc::clone(&accept_shared);
+                let hello = hello.clone();
+                // A slow browser mustn't hold up the next one
+                std::thread::spawn(move || {
+                    if let Err(e) = serve(stream, &shared, &hello) {
+                        diag::log(format!("Web request failed: {}", e));
+                    }
+                });
+            }
+        })?;
+        diag::info(format!("Web view on http://{}/", addr));
+        Ok(Self { shared })
+    }
+
+    pub fn push(&self, sample: &Sample) {
+        let Ok(line) = serde_json::to_string(sample) else { return };
+        let frame = text_frame(&line);
+        let mut shared = self.shared.lock().unwrap();
+        shared.backlog.push_back(*sample);
+        if shared.backlog.len() > BACKLOG {
+            shared.backlog.pop_front();
+        }
+        shared.clients.retain_mut(|client| client.write_all(&frame).is_ok());
+    }
+}
+
+fn serve(mut stream: TcpStream, shared: &Mutex<Shared>, hello: &str) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|end| end == b"\r\n\r\n") && request.len() < MAX_REQUEST {
+        let read = stream.read(&mut buf)?;
+        if read == 0 {
+            return Ok(());
+        }
+        request.extend_from_slice(&buf[..read]);
+    }
+    let request = String::from_utf8_lossy(&request);
+    let path = request.lines().next().and_then(|line| line.split_whitespace().nth(1)).unwrap_or_default();
+    let key = request.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim().eq_ignore_ascii_case("sec-websocket-key").then(|| value.trim().to_string())
+    });
+    match (path, key) {
+        ("/ws", Some(key)) => {
+            write!(
+                stream,
+                "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+                 Sec-WebSocket-Accept: {}\r\n\r\n",
+                accept_key(&key)
+            )?;
+            // Backlog and registration under one lock, so no sample falls in between
+            let mut shared = shared.lock().unwrap();
+            stream.write_all(&text_frame(hello))?;
+            for sample in &shared.backlog {
+                stream.write_all(&text_frame(&serde_json::to_string(sample)?))?;
+            }
+            shared.clients.push(stream);
+            Ok(())
+        }
+        ("/", _) | ("/index.html", _) => write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\n\
+             Connection: close\r\n\r\n{}",
+            PAGE.len(),
+            PAGE
+        ),
+        _ => write!(stream, "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"),
+    }
+}
+
+fn accept_key(key: &str) -> String {
+    let digest = Sha1::digest(format!("{}{}", key, WEBSOCKET_GUID).as_bytes());
+    base64::engine::general_purpose::STANDARD.encode(digest)
+}
+
+// One unmasked, unfragmented text message, as a server sends them
+fn text_frame(text: &str) -> Vec<u8> {
+    let len = text.len();
+    let mut frame = vec![0x81];
+    if len < 126 {
+        frame.push(len as u8);
+    } else if len <= u16::MAX as usize {
+        frame.push(126);
+        frame.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        frame.push(127);
+        frame.extend_from_slice(&(len as u64).to_be_bytes());
+    }
+    frame.extend_from_slice(text.as_bytes());
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handshake_and_frames() {
+        // The example from RFC 6455
+        assert_eq!(accept_key("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+        assert_eq!(text_frame("hi"), b"\x81\x02hi");
+        assert_eq!(text_frame(&"x".repeat(300))[..4], [0x81, 126, 1, 44]);
+    }
+}
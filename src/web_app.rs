@@ -0,0 +1,164 @@
+//! The monitor as a web page. Built for `wasm32-unknown-unknown` with the `wasm`
+//! feature, it draws a remote machine's memory from the WebSocket that an agent
+//! (or the window) started with `--web` serves at `/ws`. A browser can't open the
+//! agent's own TCP stream, and has no machine of its own to sample, so this is
+//! the usage bar and plot of [`MemoryMonitorWidget`] over that feed rather than
+//! the desktop window.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use eframe::egui;
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Event, MessageEvent, WebSocket};
+
+use crate::widget::{MemoryMonitorWidget, SamplerHandle};
+use crate::Sample;
+
+// The agent's sample interval; the page only uses it to pace repaints
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+// What --web keeps as its backlog, so a reconnect doesn't lose any of the plot
+const HISTORY_LEN: usize = 600;
+const RECONNECT_SECS: f64 = 5.0;
+
+/// Starts the page on the `<canvas>` with id `canvas_id`, streaming from `url`,
+/// e.g. `ws://homelab:8080/ws`.
+#[wasm_bindgen]
+pub async fn start(canvas_id: String, url: String) -> Result<(), JsValue> {
+    eframe::WebRunner::new()
+        .start(
+            &canvas_id,
+            eframe::WebOptions::default(),
+            Box::new(move |cc| Box::new(WebApp::new(cc, url))),
+        )
+        .await
+}
+
+// The first message on /ws names the host, ahead of the samples
+#[derive(Deserialize)]
+struct Hello {
+    host: String,
+}
+
+// What the socket's callbacks tell the app
+#[derive(Default)]
+struct Feed {
+    host: Option<String>,
+    open: bool,
+}
+
+// A socket and the callbacks it calls, which have to outlive it
+struct Connection {
+    socket: WebSocket,
+    _callbacks: Vec<Closure<dyn FnMut(Event)>>,
+}
+
+impl Connection {
+    fn open(
+        url: &str,
+        sampler: &SamplerHandle,
+        feed: &Rc<RefCell<Feed>>,
+        ctx: &egui::Context,
+    ) -> Result<Self, JsValue> {
+        let socket = WebSocket::new(url)?;
+        let on_message = {
+            let (sampler, feed, ctx) = (sampler.clone(), Rc::clone(feed), ctx.clone());
+            Closure::<dyn FnMut(Event)>::new(move |event: Event| {
+                let Some(text) = event.dyn_ref::<MessageEvent>().and_then(|event| event.data().as_string()) else {
+                    return;
+                };
+                if let Ok(sample) = serde_json::from_str::<Sample>(&text) {
+                    // A reconnect starts with the backlog again
+                    if sampler.latest().is_none_or(|latest| sample.timestamp_ms > latest.timestamp_ms) {
+                        sampler.push(&sample);
+                        ctx.request_repaint();
+                    }
+                } else if let Ok(hello) = serde_json::from_str::<Hello>(&text) {
+                    feed.borrow_mut().host = Some(hello.host);
+                }
+            })
+        };
+        let on_open = {
+            let feed = Rc::clone(feed);
+            Closure::<dyn FnMut(Event)>::new(move |_| feed.borrow_mut().open = true)
+        };
+        let on_close = {
+            let (feed, ctx) = (Rc::clone(feed), ctx.clone());
+            Closure::<dyn FnMut(Event)>::new(move |_| {
+                feed.borrow_mut().open = false;
+                ctx.request_repaint();
+            })
+        };
+        socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+        Ok(Self { socket, _callbacks: vec![on_message, on_open, on_close] })
+    }
+}
+
+impl Drop for Connection {
+    // The callbacks go with us, so the browser mustn't call them afterwards
+    fn drop(&mut self) {
+        self.socket.set_onmessage(None);
+        self.socket.set_onopen(None);
+        self.socket.set_onclose(None);
+        let _ = self.socket.close();
+    }
+}
+
+struct WebApp {
+    url: String,
+    sampler: SamplerHandle,
+    widget: MemoryMonitorWidget,
+    feed: Rc<RefCell<Feed>>,
+    connection: Option<Connection>,
+    // In egui's clock, seconds since the page started
+    last_attempt: Option<f64>,
+}
+
+impl WebApp {
+    fn new(cc: &eframe::CreationContext, url: String) -> Self {
+        cc.egui_ctx.set_visuals(egui::Visuals::dark());
+        let sampler = SamplerHandle::fed(SAMPLE_INTERVAL, HISTORY_LEN);
+        let widget = MemoryMonitorWidget::new(sampler.clone()).plot_height(300.0);
+        Self { url, sampler, widget, feed: Rc::default(), connection: None, last_attempt: None }
+    }
+
+    // Like the desktop client, keeps trying for as long as the page is open
+    fn reconnect(&mut self, ctx: &egui::Context) {
+        let now = ctx.input(|input| input.time);
+        let connecting = self.connection.as_ref().is_some_and(|c| c.socket.ready_state() == WebSocket::CONNECTING);
+        let waiting = self.last_attempt.is_some_and(|last| now - last < RECONNECT_SECS);
+        if self.feed.borrow().open || connecting || waiting {
+            return;
+        }
+        self.last_attempt = Some(now);
+        self.connection = None;
+        match Connection::open(&self.url, &self.sampler, &self.feed, ctx) {
+            Ok(connection) => self.connection = Some(connection),
+            Err(e) => web_sys::console::error_1(&e),
+        }
+    }
+}
+
+impl eframe::App for WebApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.reconnect(ctx);
+        let feed = self.feed.borrow();
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.heading(feed.host.as_deref().unwrap_or(&self.url));
+                if !feed.open {
+                    ui.colored_label(egui::Color32::from_rgb(220, 50, 50), "[OFFLINE]");
+                }
+            });
+            self.widget.ui(ui);
+        });
+        if !feed.open {
+            ctx.request_repaint_after(Duration::from_secs_f64(RECONNECT_SECS));
+        }
+    }
+}
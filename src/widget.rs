@@ -0,0 +1,190 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::Weak;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use eframe::egui;
+use egui_plot::{Legend, Line, Plot};
+
+use crate::alert::{CRITICAL_THRESHOLD, WARNING_THRESHOLD};
+use crate::units::format_bytes;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::Sampler;
+use crate::{History, Sample};
+
+const RAM_COLOR: egui::Color32 = egui::Color32::from_rgb(0, 200, 120);
+const SWAP_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 160, 0);
+
+/// A [`Sampler`](crate::Sampler) running on its own thread, or a history fed from
+/// elsewhere. Clones share the same history, so any number of widgets can show
+/// one stream of samples; the thread stops once the last handle is dropped.
+#[derive(Clone)]
+pub struct SamplerHandle {
+    history: Arc<Mutex<History>>,
+    interval: Duration,
+}
+
+impl SamplerHandle {
+    /// Samples every `interval`, keeping the most recent `capacity` samples.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn spawn(interval: Duration, capacity: usize) -> Self {
+        let history = Arc::new(Mutex::new(History::new(capacity)));
+        let weak = Arc::downgrade(&history);
+        std::thread::spawn(move || sample_loop(weak, interval));
+        Self { history, interval }
+    }
+
+    /// Samples from somewhere else, such as a remote agent, handed in with
+    /// [`push`](Self::push) about every `interval`; no thread of its own, so it
+    /// also works in a browser.
+    pub fn fed(interval: Duration, capacity: usize) -> Self {
+        Self { history: Arc::new(Mutex::new(History::new(capacity))), interval }
+    }
+
+    pub fn push(&self, sample: &Sample) {
+        self.history.lock().unwrap().push(sample);
+    }
+
+    pub fn latest(&self) -> Option<Sample> {
+        self.history.lock().unwrap().last().copied()
+    }
+
+    /// Runs `f` with the history locked; keep it short, the sampler waits for it.
+    pub fn with_history<R>(&self, f: impl FnOnce(&History) -> R) -> R {
+        f(&self.history.lock().unwrap())
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn sample_loop(history: Weak<Mutex<History>>, interval: Duration) {
+    let mut sampler = Sampler::new();
+    loop {
+        let sample = sampler.sample();
+        let Some(history) = history.upgrade() else { return };
+        history.lock().unwrap().push(&sample);
+        drop(history);
+        std::thread::sleep(interval);
+    }
+}
+
+/// The usage bar and RAM/swap plot of the monitor, for embedding in another egui
+/// application:
+///
+/// ```no_run
+/// use rusty_mem_monitor::widget::{MemoryMonitorWidget, SamplerHandle};
+///
+/// let sampler = SamplerHandle::spawn(std::time::Duration::from_secs(1), 300);
+/// let mut widget = MemoryMonitorWidget::new(sampler);
+/// # let ctx = eframe::egui::Context::default();
+/// # let _ = ctx.run(Default::default(), |ctx| {
+/// eframe::egui::CentralPanel::default().show(ctx, |ui| widget.ui(ui));
+/// # });
+/// ```
+pub struct MemoryMonitorWidget {
+    sampler: SamplerHandle,
+    id: egui::Id,
+    plot_height: f32,
+    show_plot: bool,
+    show_swap: bool,
+}
+
+impl MemoryMonitorWidget {
+    pub fn new(sampler: SamplerHandle) -> Self {
+        Self {
+            sampler,
+            id: egui::Id::new("memory_monitor_widget"),
+            plot_height: 150.0,
+            show_plot: true,
+            show_swap: true,
+        }
+    }
+
+    /// Needed when more than one widget is shown at once.
+    pub fn id_source(mut self, id_source: impl std::hash::Hash) -> Self {
+        self.id = egui::Id::new(id_source);
+        self
+    }
+
+    pub fn plot_height(mut self, height: f32) -> Self {
+        self.plot_height = height;
+        self
+    }
+
+    /// Only the usage bar, for tight spaces.
+    pub fn show_plot(mut self, show: bool) -> Self {
+        self.show_plot = show;
+        self
+    }
+
+    pub fn show_swap(mut self, show: bool) -> Self {
+        self.show_swap = show;
+        self
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> egui::Response {
+        // Samples arrive from another thread, so nothing else would trigger a repaint
+        ui.ctx().request_repaint_after(self.sampler.interval());
+        ui.push_id(self.id, |ui| {
+            let Some(latest) = self.sampler.latest() else {
+                ui.label("Waiting for the first sample...");
+                return;
+            };
+            if latest.has_memory() {
+                usage_bar(ui, "RAM", latest.memory_percentage(), latest.used_memory, latest.total_memory);
+            } else {
+                ui.add(egui::ProgressBar::new(0.0).text("RAM N/A"));
+            }
+            if self.show_swap && latest.has_swap() {
+                usage_bar(ui, "Swap", latest.swap_percentage(), latest.used_swap, latest.total_swap);
+            }
+            if self.show_plot {
+                self.plot_ui(ui, latest.timestamp_ms);
+            }
+        })
+        .response
+    }
+
+    fn plot_ui(&self, ui: &mut egui::Ui, latest_ms: u64) {
+        let relative_secs = |sample: &Sample| -((latest_ms - sample.timestamp_ms.min(latest_ms)) as f64 / 1000.0);
+        let (memory, swap): (Vec<[f64; 2]>, Vec<[f64; 2]>) = self.sampler.with_history(|history| {
+            history
+                .iter()
+                .filter(|sample| sample.has_memory())
+                .map(|sample| {
+                    let x = relative_secs(sample);
+                    ([x, sample.memory_percentage() as f64], [x, sample.swap_percentage() as f64])
+                })
+                .unzip()
+        });
+        Plot::new("plot")
+            .height(self.plot_height)
+            .legend(Legend::default())
+            .include_y(0.0)
+            .include_y(100.0)
+            .x_axis_formatter(|x, _, _| format!("{:.0}s", x))
+            .y_axis_formatter(|y, _, _| format!("{:.0}%", y))
+            .allow_scroll(false)
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new(memory).color(RAM_COLOR).name("RAM"));
+                if self.show_swap {
+                    plot_ui.line(Line::new(swap).color(SWAP_COLOR).name("Swap"));
+                }
+            });
+    }
+}
+
+fn usage_bar(ui: &mut egui::Ui, label: &str, percentage: f32, used: u64, total: u64) {
+    let fill = if percentage > CRITICAL_THRESHOLD {
+        egui::Color32::from_rgb(220, 50, 50)
+    } else if percentage > WARNING_THRESHOLD {
+        egui::Color32::from_rgb(230, 160, 0)
+    } else {
+        RAM_COLOR
+    };
+    let text = format!("{} {:.1}%  ({} / {})", label, percentage, format_bytes(used), format_bytes(total));
+    ui.add(egui::ProgressBar::new(percentage / 100.0).fill(fill).text(text));
+}
@@ -0,0 +1,135 @@
+use crate::hugepages::selected_mode;
+use crate::meminfo;
+
+const BLOCK_ROOT: &str = "/sys/block";
+const ZSWAP_ENABLED: &str = "/sys/module/zswap/parameters/enabled";
+
+/// A zram block device, usually used as swap. Pages swapped to it stay in RAM,
+/// compressed, so a "full" zram swap can cost far less memory than it holds.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ZramDevice {
+    /// e.g. `zram0`
+    pub name: String,
+    /// Uncompressed capacity, in bytes.
+    pub disk_size: u64,
+    /// Data stored, before and after compression, in bytes.
+    pub original: u64,
+    pub compressed: u64,
+    /// RAM the device actually takes, compressed data plus allocator overhead.
+    pub used: u64,
+    pub algorithm: Option<String>,
+}
+
+impl ZramDevice {
+    /// Stored bytes per byte of RAM used, overhead included; None while empty.
+    pub fn ratio(&self) -> Option<f64> {
+        ratio(self.original, self.used)
+    }
+}
+
+/// zswap, a compressed cache in front of a regular swap device.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Zswap {
+    /// RAM taken by the compressed pool, in bytes (`Zswap`, Linux 5.19+).
+    pub pool: u64,
+    /// Uncompressed size of what's in the pool (`Zswapped`).
+    pub stored: u64,
+}
+
+impl Zswap {
+    pub fn ratio(&self) -> Option<f64> {
+        ratio(self.stored, self.pool)
+    }
+}
+
+/// Compressed swap in use on this machine.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SwapCompression {
+    pub zram: Vec<ZramDevice>,
+    /// None unless zswap is enabled.
+    pub zswap: Option<Zswap>,
+}
+
+impl SwapCompression {
+    /// Linux only; elsewhere, or without zram and zswap, this is empty.
+    pub fn read() -> Self {
+        if !cfg!(target_os = "linux") {
+            return Self::default();
+        }
+        let zswap_enabled = std::fs::read_to_string(ZSWAP_ENABLED).is_ok_and(|text| text.trim() == "Y");
+        Self {
+            zram: read_zram(),
+            zswap: zswap_enabled.then(|| meminfo::read().map(|info| zswap_from_meminfo(&info)).unwrap_or_default()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.zram.is_empty() && self.zswap.is_none()
+    }
+}
+
+fn read_zram() -> Vec<ZramDevice> {
+    let Ok(entries) = std::fs::read_dir(BLOCK_ROOT) else {
+        return Vec::new();
+    };
+    let mut devices: Vec<_> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.starts_with("zram") {
+                return None;
+            }
+            let dir = entry.path();
+            let read = |file: &str| std::fs::read_to_string(dir.join(file)).ok();
+            let mut device = parse_mm_stat(&read("mm_stat")?)?;
+            device.name = name;
+            device.disk_size = read("disksize").and_then(|text| text.trim().parse().ok()).unwrap_or(0);
+            device.algorithm = read("comp_algorithm").and_then(|text| selected_mode(&text));
+            // Configured but never given a size: not a swap device yet
+            (device.disk_size > 0).then_some(device)
+        })
+        .collect();
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    devices
+}
+
+/// The first three `mm_stat` columns: original size, compressed size and total
+/// memory used, all in bytes.
+pub fn parse_mm_stat(text: &str) -> Option<ZramDevice> {
+    let mut columns = text.split_whitespace().map(|column| column.parse::<u64>().ok());
+    Some(ZramDevice {
+        original: columns.next()??,
+        compressed: columns.next()??,
+        used: columns.next()??,
+        ..ZramDevice::default()
+    })
+}
+
+pub fn zswap_from_meminfo(info: &std::collections::HashMap<String, u64>) -> Zswap {
+    Zswap {
+        pool: info.get("Zswap").copied().unwrap_or(0),
+        stored: info.get("Zswapped").copied().unwrap_or(0),
+    }
+}
+
+fn ratio(original: u64, used: u64) -> Option<f64> {
+    (original > 0 && used > 0).then(|| original as f64 / used as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_zram_and_zswap() {
+        let device = parse_mm_stat("  4194304  1048576  1310720        0  2097152      128        0        0\n").unwrap();
+        assert_eq!((device.original, device.compressed, device.used), (4194304, 1048576, 1310720));
+        assert_eq!(device.ratio(), Some(3.2));
+        assert_eq!(parse_mm_stat("12 oops"), None);
+        assert_eq!(ZramDevice::default().ratio(), None);
+
+        let zswap = zswap_from_meminfo(&meminfo::parse("Zswap:            102400 kB\nZswapped:         409600 kB\n"));
+        assert_eq!(zswap.pool, 100 * 1024 * 1024);
+        assert_eq!(zswap.ratio(), Some(4.0));
+    }
+}